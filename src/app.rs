@@ -6,22 +6,31 @@
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::layout::Rect;
 use ratatui::widgets::TableState;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use std::sync::mpsc;
 use std::time::Instant;
 
+use crate::config::NetworkChangeAction;
 use crate::constants;
+use crate::core::exporter::{self, ProfileStatus, SharedSnapshot, Snapshot};
+use crate::core::history::EndReason;
+use crate::core::hooks::{self, HookContext, HookEvent};
+use crate::core::metrics::{ChartWindow, MetricsStore, Sample};
+use crate::core::notify::{self, NotifyEvent};
 use crate::core::scanner;
 use crate::core::telemetry::{self, TelemetryUpdate};
+use crate::daemon::protocol::SessionState;
 use crate::logger::{self, LogLevel};
 use crate::message::{self, Message, ScrollMove, SelectionMove};
+use crate::platform::InterfaceBytes;
 use crate::utils;
 
 // Re-export state types for convenient access
 pub use crate::state::{
-    AuthField, ConnectionState, DetailedConnectionInfo, FocusedPanel, InputMode, Protocol, Toast,
-    ToastType, VpnProfile, DISMISS_DURATION,
+    AuthField, ConnectionState, DetailedConnectionInfo, FocusedPanel, FormField, InputMode,
+    ProfileForm, ProfileLabels, ProfileSection, Protocol, SidebarRow, Toast, ToastType, VpnProfile,
+    DISMISS_DURATION,
 };
 
 /// Main application state container.
@@ -49,20 +58,33 @@ pub struct App {
     pub session_start: Option<Instant>,
 
     // === Network Telemetry ===
-    /// Historical download throughput data points for charting.
-    pub down_history: Vec<(f64, f64)>,
-    /// Historical upload throughput data points for charting.
-    pub up_history: Vec<(f64, f64)>,
+    /// Throughput and link quality time series for charting.
+    pub metrics: MetricsStore,
+    /// Time span the chart panel shows.
+    pub chart_window: ChartWindow,
     /// Current download rate in bytes/second.
     pub current_down: u64,
     /// Current upload rate in bytes/second.
     pub current_up: u64,
+    /// Interface the throughput figures belong to: the active tunnel, or
+    /// `None` for all non-loopback interfaces combined.
+    pub chart_interface: Option<String>,
+    /// Physical interface holding the default route, if known.
+    pub uplink_interface: Option<String>,
+    /// Current uplink download rate in bytes/second.
+    pub current_uplink_down: u64,
+    /// Current uplink upload rate in bytes/second.
+    pub current_uplink_up: u64,
     pub latency_ms: u64,
     pub packet_loss: f32,
     pub jitter_ms: u64,
     pub location: String,
     pub isp: String,
     pub dns_server: String,
+    /// Servers a DNS override expects to answer (empty if none is applied).
+    pub dns_expected: Vec<String>,
+    /// Latest active DNS leak test result (`None` until one completes).
+    pub dns_leak: Option<crate::core::dns_leak::DnsLeakReport>,
     pub ipv6_leak: bool,
 
     // === System Info ===
@@ -83,24 +105,55 @@ pub struct App {
     pub action_menu_state: ratatui::widgets::ListState,
     pub config_scroll: u16,
     pub profile_list_state: TableState,
+    /// Sidebar sections folded down to their header (kept for the session only).
+    pub collapsed_sections: HashSet<ProfileSection>,
     pub panel_areas: HashMap<FocusedPanel, Rect>,
     pub toast: Option<Toast>,
     pub terminal_size: (u16, u16),
     pub is_root: bool,
+    /// Dependency check and launcher behind [`App::connect_profile`].
+    connector: crate::core::connector::Ops,
     /// User-configurable application settings.
     pub config: crate::config::AppConfig,
     /// Resolved config directory path.
     pub config_dir: std::path::PathBuf,
     /// Number of connection drops detected this session.
     pub connection_drops: u32,
+    /// Figures of the tunnel session in progress, recorded to the history
+    /// when it ends. Never set while attached to a daemon (it records).
+    session_tracker: Option<crate::core::history::SessionTracker>,
+    /// Records shown by the history overlay, loaded when it opens.
+    pub history: Vec<crate::core::history::SessionRecord>,
+    /// Results shown by the benchmark overlay, best first.
+    pub benchmark: Vec<crate::core::benchmark::ProbeResult>,
     /// Profile index queued for auto-connect after current disconnect completes.
     pub pending_connect: Option<usize>,
+    /// Automatic reconnect in progress after an unexpected drop.
+    pub reconnect: Option<crate::state::ReconnectState>,
+    /// Reason for the `post_up` hook, held until the scanner reports the
+    /// tunnel's interface (`None` = nothing pending).
+    post_up_pending: Option<&'static str>,
+    /// Tunnel details captured before a requested disconnect, for `post_down`.
+    down_hook: Option<HookContext>,
+    /// Where notifications go (`None` = notifications off).
+    notify_backend: Option<notify::Backend>,
+    /// Writes terminal notifications; tests swap it so none reach the real terminal.
+    ring_terminal: fn(NotifyEvent, &str),
 
     // === Kill Switch ===
     /// Kill switch operating mode (Off, Auto, `AlwaysOn`).
     pub killswitch_mode: crate::state::KillSwitchMode,
     /// Current kill switch state (Disabled, Armed, Blocking).
     pub killswitch_state: crate::state::KillSwitchState,
+    /// Last time the installed kill switch rules were verified.
+    killswitch_verified_at: Option<Instant>,
+    /// Kill switch state last announced by a notification.
+    killswitch_notified: crate::state::KillSwitchState,
+
+    // === Daemon ===
+    /// Control socket of the daemon we are attached to. When set, the daemon
+    /// owns the tunnel and firewall; the TUI only sends requests.
+    pub daemon_socket: Option<std::path::PathBuf>,
 
     // === Async Communication ===
     telemetry_rx: Option<mpsc::Receiver<TelemetryUpdate>>,
@@ -108,16 +161,22 @@ pub struct App {
     telemetry_nudge: Option<mpsc::Sender<()>>,
     cmd_tx: mpsc::Sender<Message>,
     cmd_rx: mpsc::Receiver<Message>,
+    /// Where to send the answer to an `OpenVPN` credential prompt the connect
+    /// thread is waiting on (`None` = cancelled).
+    auth_reply: Option<mpsc::Sender<Option<crate::core::openvpn_mgmt::Credentials>>>,
+    /// Snapshot read by the metrics exporter (`None` = not serving).
+    exporter: Option<SharedSnapshot>,
 
     // --- Spawn-on-demand background work (no long-running threads) ---
     /// Receiver for the latest scanner result. `Some` = scan in flight or result ready.
     scanner_rx: Option<mpsc::Receiver<Vec<scanner::ActiveSession>>>,
-    /// Receiver for the latest raw network byte totals. `Some` = fetch in flight.
-    netstats_rx: Option<mpsc::Receiver<(u64, u64)>>,
-    /// Last total bytes-in reading (for delta calculation).
-    last_bytes_in: u64,
-    /// Last total bytes-out reading (for delta calculation).
-    last_bytes_out: u64,
+    /// Receiver for the latest per-interface byte counters and uplink name.
+    /// `Some` = fetch in flight.
+    netstats_rx: Option<mpsc::Receiver<(InterfaceBytes, Option<String>)>>,
+    /// Receiver for the running kill switch rule check. `Some` = check in flight.
+    killswitch_check_rx: Option<mpsc::Receiver<Result<Vec<String>, String>>>,
+    /// Last per-interface counter reading (for delta calculation).
+    last_interface_bytes: InterfaceBytes,
 }
 
 impl App {
@@ -125,11 +184,11 @@ impl App {
     #[allow(clippy::too_many_lines)]
     pub fn new(config: crate::config::AppConfig, config_dir: std::path::PathBuf) -> Self {
         let (cmd_tx, cmd_rx) = mpsc::channel::<Message>();
-        let history_size = constants::NETWORK_HISTORY_SIZE;
-        #[allow(clippy::cast_precision_loss)]
-        let down_history = (0..history_size).map(|i| (i as f64, 0.0)).collect();
-        #[allow(clippy::cast_precision_loss)]
-        let up_history = (0..history_size).map(|i| (i as f64, 0.0)).collect();
+        let metrics = if config.persist_metrics {
+            MetricsStore::load(&config_dir.join(constants::METRICS_FILE_NAME))
+        } else {
+            MetricsStore::default()
+        };
         let mut app = Self {
             should_quit: false,
 
@@ -137,16 +196,22 @@ impl App {
             profiles: Vec::new(),
             session_start: None,
 
-            down_history,
-            up_history,
+            metrics,
+            chart_window: ChartWindow::default(),
             current_down: 0,
             current_up: 0,
+            chart_interface: None,
+            uplink_interface: None,
+            current_uplink_down: 0,
+            current_uplink_up: 0,
             latency_ms: 0,
             packet_loss: 0.0,
             jitter_ms: 0,
             location: "Detecting...".to_string(),
             isp: "Detecting...".to_string(),
             dns_server: "Detecting...".to_string(),
+            dns_expected: Vec::new(),
+            dns_leak: None,
             ipv6_leak: false,
 
             public_ip: "Detecting...".to_string(),
@@ -164,18 +229,34 @@ impl App {
             action_menu_state: ratatui::widgets::ListState::default(),
             config_scroll: 0,
             profile_list_state: TableState::default(),
+            collapsed_sections: HashSet::new(),
             panel_areas: HashMap::new(),
             toast: None,
             terminal_size: (0, 0),
             is_root: utils::is_root(),
+            connector: crate::core::connector::Ops::default(),
             config,
             config_dir,
             connection_drops: 0,
+            session_tracker: None,
+            history: Vec::new(),
+            benchmark: Vec::new(),
             pending_connect: None,
+            auth_reply: None,
+            exporter: None,
+            reconnect: None,
+            post_up_pending: None,
+            down_hook: None,
+            notify_backend: None,
+            ring_terminal: notify::ring_terminal,
 
             // Kill switch - load from persisted state for crash recovery
             killswitch_mode: crate::state::KillSwitchMode::default(),
             killswitch_state: crate::state::KillSwitchState::default(),
+            killswitch_verified_at: None,
+            killswitch_notified: crate::state::KillSwitchState::default(),
+
+            daemon_socket: None,
 
             telemetry_rx: None,
             telemetry_nudge: None,
@@ -183,12 +264,23 @@ impl App {
             cmd_rx,
             scanner_rx: None,
             netstats_rx: None,
-            last_bytes_in: 0,
-            last_bytes_out: 0,
+            killswitch_check_rx: None,
+            last_interface_bytes: InterfaceBytes::new(),
         };
 
-        // Recover kill switch state from crash if persisted
-        if let Some(persisted) = crate::core::killswitch::load_state() {
+        // Attach to a running daemon: it owns the kill switch, so skip crash recovery
+        let daemon = utils::get_daemon_socket_path().ok().and_then(|socket| {
+            let timeout = std::time::Duration::from_millis(constants::DAEMON_PROBE_TIMEOUT_MS);
+            let status = crate::daemon::client::probe(&socket, timeout)?;
+            Some((socket, status))
+        });
+        if let Some((socket, status)) = daemon {
+            app.killswitch_mode = status.killswitch_mode;
+            app.killswitch_state = status.killswitch_state;
+            app.spawn_daemon_subscriber(socket.clone());
+            app.daemon_socket = Some(socket);
+        } else if let Some(persisted) = crate::core::killswitch::load_state() {
+            // Recover kill switch state from crash if persisted
             app.killswitch_mode = persisted.mode;
             // If we were blocking when crashed, release it now
             if persisted.state == crate::state::KillSwitchState::Blocking {
@@ -203,6 +295,15 @@ impl App {
 
         // Load profiles from ~/.config/vortix/profiles/
         app.profiles = crate::vpn::load_profiles();
+        // Encrypted profiles are listed by name until the vault is unlocked
+        if crate::core::vault::is_enabled(&app.config_dir) && !crate::core::vault::is_unlocked() {
+            app.input_mode = InputMode::VaultUnlock {
+                passphrase: String::new(),
+                cursor: 0,
+                error: None,
+                connect: None,
+            };
+        }
 
         app.load_metadata();
         app.sort_profiles();
@@ -229,6 +330,11 @@ impl App {
             app.log(&format!("IO: Auto-logging to {}", log_path.display()));
         }
 
+        if let Some(socket) = &app.daemon_socket {
+            let msg = format!("INIT: Attached to daemon at {}", socket.display());
+            app.log(&msg);
+        }
+
         app.log("SUCCESS: System active. Press [x] for actions.");
 
         // Check for required system dependencies at startup
@@ -240,11 +346,61 @@ impl App {
         app.telemetry_rx = Some(telem_rx);
         app.telemetry_nudge = Some(telem_nudge);
 
+        // React to Wi-Fi/dock changes as they happen instead of on the next poll
+        #[cfg(target_os = "linux")]
+        app.spawn_network_watcher();
+
+        if !app.config.metrics_listen.is_empty() {
+            app.start_exporter();
+        }
+
+        // Only changes from here on are worth a notification
+        app.killswitch_notified = app.killswitch_state;
+        if app.config.notifications.enabled {
+            let backend = notify::backend(&app.config.notifications);
+            app.log(match backend {
+                notify::Backend::Desktop => "INIT: Notifications via desktop (D-Bus)",
+                notify::Backend::Terminal => "INIT: Notifications via terminal (OSC 9 and bell)",
+            });
+            app.notify_backend = Some(backend);
+        }
+
         app.process_external(); // Flush any early messages
 
         app
     }
 
+    /// Forward daemon events into the message loop until the daemon hangs up.
+    fn spawn_daemon_subscriber(&self, socket: std::path::PathBuf) {
+        let cmd_tx = self.cmd_tx.clone();
+        std::thread::spawn(move || {
+            let mut client = match crate::daemon::client::DaemonClient::connect(&socket) {
+                Ok(client) => client,
+                Err(e) => {
+                    let _ = cmd_tx.send(Message::Log(format!("WARN: {e}")));
+                    return;
+                }
+            };
+            if let Err(e) = client.subscribe() {
+                let _ = cmd_tx.send(Message::Log(format!("WARN: Daemon subscribe failed: {e}")));
+                return;
+            }
+            loop {
+                match client.next_event() {
+                    Ok(event) => {
+                        if cmd_tx.send(Message::Daemon(event)).is_err() {
+                            return;
+                        }
+                    }
+                    Err(e) => {
+                        let _ = cmd_tx.send(Message::Log(format!("WARN: Lost daemon: {e}")));
+                        return;
+                    }
+                }
+            }
+        });
+    }
+
     /// Add a log message via centralized logger
     fn log(&mut self, message: &str) {
         // Parse "PREFIX: content" — the prefix determines both the category and the level.
@@ -343,50 +499,19 @@ impl App {
                     self.input_mode = InputMode::Import { path, cursor };
                 }
             }
-            InputMode::AuthPrompt {
-                profile_idx,
-                profile_name,
-                mut username,
-                mut username_cursor,
-                mut password,
-                mut password_cursor,
-                mut focused_field,
-                mut save_credentials,
-                connect_after,
-            } => {
-                self.handle_input_auth(
-                    key,
-                    profile_idx,
-                    &profile_name,
-                    &mut username,
-                    &mut username_cursor,
-                    &mut password,
-                    &mut password_cursor,
-                    &mut focused_field,
-                    &mut save_credentials,
-                    connect_after,
-                );
-                // Update state if still in AuthPrompt mode
-                if let InputMode::AuthPrompt { .. } = self.input_mode {
-                    self.input_mode = InputMode::AuthPrompt {
-                        profile_idx,
-                        profile_name,
-                        username,
-                        username_cursor,
-                        password,
-                        password_cursor,
-                        focused_field,
-                        save_credentials,
-                        connect_after,
-                    };
-                }
-            }
+            InputMode::AuthPrompt { .. } => self.handle_input_auth(key),
+            InputMode::ProfileForm { .. } => self.handle_input_profile_form(key),
             InputMode::DependencyError { .. } | InputMode::PermissionDenied { .. } => {
                 if key.code == KeyCode::Esc {
                     self.handle_message(Message::CloseOverlay);
                 }
             }
             InputMode::ConfirmDelete { .. } => self.handle_confirm_delete_keys(key),
+            InputMode::History { .. } => self.handle_history_keys(key),
+            InputMode::Benchmark { .. } => self.handle_benchmark_keys(key),
+            InputMode::VaultUnlock { .. } => self.handle_vault_unlock_keys(key),
+            InputMode::QuickSlot { .. } => self.handle_quick_slot_keys(key),
+            InputMode::Search { .. } => self.handle_search_keys(key),
             InputMode::Normal => self.handle_normal_keys(key),
         }
     }
@@ -427,11 +552,7 @@ impl App {
         match self.focused_panel {
             FocusedPanel::Sidebar => {
                 // Scroll Profiles
-                let current = self.profile_list_state.selected().unwrap_or(0);
-                let last = self.profiles.len().saturating_sub(1);
-                if current < last {
-                    self.profile_list_state.select(Some(current + 1));
-                }
+                self.step_profile_selection(1, true, false);
             }
             FocusedPanel::Logs => {
                 // Scroll Logs
@@ -462,10 +583,7 @@ impl App {
         match self.focused_panel {
             FocusedPanel::Sidebar => {
                 // Scroll Profiles
-                let current = self.profile_list_state.selected().unwrap_or(0);
-                if current > 0 {
-                    self.profile_list_state.select(Some(current - 1));
-                }
+                self.step_profile_selection(1, false, false);
             }
             FocusedPanel::Logs => {
                 // Scroll Logs
@@ -511,6 +629,31 @@ impl App {
         }
     }
 
+    /// Handle keyboard input for the quick slot picker: a digit binds the
+    /// profile, `0`/Backspace unbinds it.
+    fn handle_quick_slot_keys(&mut self, key: KeyEvent) {
+        let InputMode::QuickSlot { profile_idx, .. } = self.input_mode else {
+            return;
+        };
+        match key.code {
+            KeyCode::Esc => self.handle_message(Message::CloseOverlay),
+            KeyCode::Char(c @ '1'..='9') => {
+                let slot = c.to_digit(10).and_then(|d| u8::try_from(d).ok());
+                self.handle_message(Message::AssignQuickSlot {
+                    idx: profile_idx,
+                    slot,
+                });
+            }
+            KeyCode::Char('0') | KeyCode::Backspace | KeyCode::Delete => {
+                self.handle_message(Message::AssignQuickSlot {
+                    idx: profile_idx,
+                    slot: None,
+                });
+            }
+            _ => {}
+        }
+    }
+
     fn handle_input_import(&mut self, key: KeyEvent, path: &mut String, cursor: &mut usize) {
         match key.code {
             KeyCode::Esc => self.handle_message(Message::CloseOverlay),
@@ -522,10 +665,8 @@ impl App {
             KeyCode::Left => {
                 *cursor = cursor.saturating_sub(1);
             }
-            KeyCode::Right => {
-                if *cursor < path.len() {
-                    *cursor += 1;
-                }
+            KeyCode::Right if *cursor < path.len() => {
+                *cursor += 1;
             }
             KeyCode::Home => {
                 *cursor = 0;
@@ -533,16 +674,12 @@ impl App {
             KeyCode::End => {
                 *cursor = path.len();
             }
-            KeyCode::Backspace => {
-                if *cursor > 0 {
-                    path.remove(*cursor - 1);
-                    *cursor -= 1;
-                }
+            KeyCode::Backspace if *cursor > 0 => {
+                path.remove(*cursor - 1);
+                *cursor -= 1;
             }
-            KeyCode::Delete => {
-                if *cursor < path.len() {
-                    path.remove(*cursor);
-                }
+            KeyCode::Delete if *cursor < path.len() => {
+                path.remove(*cursor);
             }
             KeyCode::Char(c) => {
                 path.insert(*cursor, c);
@@ -552,69 +689,445 @@ impl App {
         }
     }
 
-    /// Handle keyboard input for the auth credentials overlay.
-    #[allow(clippy::too_many_arguments)]
-    fn handle_input_auth(
-        &mut self,
-        key: KeyEvent,
-        profile_idx: usize,
-        _profile_name: &str,
-        username: &mut String,
-        username_cursor: &mut usize,
-        password: &mut String,
-        password_cursor: &mut usize,
-        focused_field: &mut AuthField,
-        save_credentials: &mut bool,
-        connect_after: bool,
-    ) {
+    /// Handle keyboard input for the session history overlay: printable keys
+    /// edit the filter, arrows move the selection.
+    fn handle_history_keys(&mut self, key: KeyEvent) {
+        if key.code == KeyCode::Esc {
+            self.handle_message(Message::CloseOverlay);
+            return;
+        }
+        let InputMode::History {
+            filter,
+            cursor,
+            selected,
+        } = &mut self.input_mode
+        else {
+            return;
+        };
+        match key.code {
+            KeyCode::Up => *selected = selected.saturating_sub(1),
+            KeyCode::Down => *selected += 1,
+            KeyCode::PageUp => {
+                *selected = selected.saturating_sub(constants::PROFILE_LIST_PAGE_SIZE);
+            }
+            KeyCode::PageDown => *selected += constants::PROFILE_LIST_PAGE_SIZE,
+            KeyCode::Left => *cursor = cursor.saturating_sub(1),
+            KeyCode::Right if *cursor < filter.len() => *cursor += 1,
+            KeyCode::Home => *cursor = 0,
+            KeyCode::End => *cursor = filter.len(),
+            KeyCode::Backspace if *cursor > 0 => {
+                filter.remove(*cursor - 1);
+                *cursor -= 1;
+                *selected = 0;
+            }
+            KeyCode::Delete if *cursor < filter.len() => {
+                filter.remove(*cursor);
+                *selected = 0;
+            }
+            KeyCode::Char(c) => {
+                filter.insert(*cursor, c);
+                *cursor += 1;
+                *selected = 0;
+            }
+            _ => {}
+        }
+        let matching = self.history.iter().filter(|r| r.matches(filter)).count();
+        *selected = (*selected).min(matching.saturating_sub(1));
+    }
+
+    /// Handle keyboard input for the benchmark overlay: printable keys edit
+    /// the scope, Enter runs the benchmark and then connects the selected
+    /// server.
+    fn handle_benchmark_keys(&mut self, key: KeyEvent) {
+        let InputMode::Benchmark {
+            scope,
+            cursor,
+            running,
+            selected,
+        } = &mut self.input_mode
+        else {
+            return;
+        };
         match key.code {
             KeyCode::Esc => self.handle_message(Message::CloseOverlay),
-            KeyCode::Tab | KeyCode::BackTab => {
-                // Cycle through fields: Username -> Password -> SaveCheckbox -> Username
-                *focused_field = match (&focused_field, key.code) {
-                    (AuthField::Username, KeyCode::Tab)
-                    | (AuthField::SaveCheckbox, KeyCode::BackTab) => AuthField::Password,
-                    (AuthField::Password, KeyCode::Tab)
-                    | (AuthField::Username, KeyCode::BackTab) => AuthField::SaveCheckbox,
-                    (AuthField::SaveCheckbox, KeyCode::Tab)
-                    | (AuthField::Password, KeyCode::BackTab) => AuthField::Username,
-                    _ => focused_field.clone(),
-                };
+            // Results arrive as one batch; nothing to do meanwhile
+            _ if *running => {}
+            KeyCode::Enter if self.benchmark.is_empty() => {
+                let scope = scope.clone();
+                self.handle_message(Message::RunBenchmark(scope));
             }
             KeyCode::Enter => {
-                // On SaveCheckbox, toggle the checkbox instead of submitting
-                if *focused_field == AuthField::SaveCheckbox {
-                    *save_credentials = !*save_credentials;
+                let Some(result) = self.benchmark.get(*selected) else {
                     return;
+                };
+                let name = result.profile.clone();
+                self.input_mode = InputMode::Normal;
+                self.connect_benchmarked(&name);
+            }
+            KeyCode::Up => *selected = selected.saturating_sub(1),
+            KeyCode::Down => {
+                *selected = (*selected + 1).min(self.benchmark.len().saturating_sub(1));
+            }
+            _ => {
+                let before = scope.clone();
+                Self::handle_text_field_input(key, scope, cursor);
+                // Results belong to the old scope
+                if *scope != before {
+                    self.benchmark.clear();
+                    *selected = 0;
                 }
-                // Require both fields to be non-empty
-                if username.is_empty() || password.is_empty() {
-                    self.show_toast(
-                        "Both username and password are required".to_string(),
-                        ToastType::Warning,
-                    );
+            }
+        }
+    }
+
+    /// Connect the profile a benchmark picked, unless it's already up.
+    fn connect_benchmarked(&mut self, name: &str) {
+        let Some(idx) = self.profiles.iter().position(|p| p.name == name) else {
+            self.show_toast(
+                format!("Profile '{name}' no longer exists"),
+                ToastType::Error,
+            );
+            return;
+        };
+        if matches!(&self.connection_state, ConnectionState::Connected { profile, .. } if profile == name)
+        {
+            self.show_toast(format!("Already connected to '{name}'"), ToastType::Info);
+            return;
+        }
+        self.log(&format!("ACTION: Connecting to fastest server '{name}'"));
+        self.toggle_connection(idx);
+    }
+
+    /// Read the endpoints of the profiles in `scope` and probe them on a
+    /// background thread; the ranking comes back as `BenchmarkResult`.
+    fn start_benchmark(&mut self, scope: &str) {
+        use crate::core::benchmark::{self, ProbeResult};
+
+        let mut targets = Vec::new();
+        let mut unreadable = Vec::new();
+        for profile in self.profiles.iter().filter(|p| p.in_scope(scope)) {
+            match crate::vpn::profile_endpoint(profile) {
+                Ok(endpoint) => targets.push((profile.name.clone(), endpoint)),
+                Err(e) => unreadable.push(ProbeResult::failed(&profile.name, String::new(), e)),
+            }
+        }
+        if targets.is_empty() && unreadable.is_empty() {
+            self.show_toast(
+                format!("No profiles match '{}'", scope.trim()),
+                ToastType::Info,
+            );
+            return;
+        }
+        if let InputMode::Benchmark { running, .. } = &mut self.input_mode {
+            *running = true;
+        }
+        let scope_label = if scope.trim().is_empty() {
+            String::new()
+        } else {
+            format!(" in '{}'", scope.trim())
+        };
+        self.log(&format!(
+            "ACTION: Benchmarking {} server(s){scope_label}...",
+            targets.len() + unreadable.len()
+        ));
+        if !matches!(self.connection_state, ConnectionState::Disconnected) {
+            self.log("INFO: Benchmark probes run through the active tunnel");
+        }
+
+        let timeout = std::time::Duration::from_secs(constants::BENCHMARK_PROBE_TIMEOUT_SECS);
+        let cmd_tx = self.cmd_tx.clone();
+        std::thread::spawn(move || {
+            let mut results = benchmark::run(&targets, timeout, constants::BENCHMARK_PARALLELISM);
+            results.append(&mut unreadable);
+            benchmark::rank(&mut results);
+            let _ = cmd_tx.send(Message::BenchmarkResult(results));
+        });
+    }
+
+    /// Handle keyboard input for the vault passphrase prompt.
+    fn handle_vault_unlock_keys(&mut self, key: KeyEvent) {
+        let InputMode::VaultUnlock {
+            passphrase, cursor, ..
+        } = &mut self.input_mode
+        else {
+            return;
+        };
+        match key.code {
+            KeyCode::Esc => {
+                self.handle_message(Message::CloseOverlay);
+                self.show_toast(
+                    "Vault locked: encrypted profiles can't connect".to_string(),
+                    ToastType::Warning,
+                );
+            }
+            KeyCode::Enter => {
+                let passphrase = std::mem::take(passphrase);
+                *cursor = 0;
+                self.handle_message(Message::UnlockVault(passphrase));
+            }
+            _ => Self::handle_text_field_input(key, passphrase, cursor),
+        }
+    }
+
+    /// Unlock the vault, reload the now readable profiles and connect the
+    /// profile that asked for it.
+    fn unlock_vault(&mut self, passphrase: &str) {
+        let connect = match &self.input_mode {
+            InputMode::VaultUnlock { connect, .. } => connect.clone(),
+            _ => None,
+        };
+        if let Err(e) = crate::core::vault::unlock(&self.config_dir, passphrase) {
+            if let InputMode::VaultUnlock { error, .. } = &mut self.input_mode {
+                *error = Some(e);
+            }
+            return;
+        }
+        self.input_mode = InputMode::Normal;
+        self.log("VAULT: Unlocked");
+        self.reload_profiles();
+        self.show_toast("Vault unlocked".to_string(), ToastType::Success);
+        if let Some(idx) =
+            connect.and_then(|name| self.profiles.iter().position(|p| p.name == name))
+        {
+            self.connect_profile(idx);
+        }
+    }
+
+    /// Read the profiles from disk again, keeping the selection.
+    fn reload_profiles(&mut self) {
+        let selected = self.selected_profile().map(|p| p.name.clone());
+        self.profiles = crate::vpn::load_profiles();
+        self.pending_connect = None;
+        self.load_metadata();
+        self.sort_profiles();
+        let idx = selected
+            .and_then(|name| self.profiles.iter().position(|p| p.name == name))
+            .or_else(|| (!self.profiles.is_empty()).then_some(0));
+        self.profile_list_state.select(idx);
+    }
+
+    /// Handle keyboard input for profile search: printable keys edit the
+    /// query, arrows move through the matches, Enter connects the selected one.
+    fn handle_search_keys(&mut self, key: KeyEvent) {
+        let InputMode::Search { query, cursor } = &mut self.input_mode else {
+            return;
+        };
+        match key.code {
+            // Leave the cursor on whatever was selected
+            KeyCode::Esc => self.handle_message(Message::CloseOverlay),
+            KeyCode::Enter => {
+                let target = self
+                    .profile_list_state
+                    .selected()
+                    .filter(|idx| self.search_matches().contains(idx));
+                self.input_mode = InputMode::Normal;
+                match target {
+                    Some(idx) => self.handle_message(Message::ToggleConnect(Some(idx))),
+                    None => {
+                        self.show_toast("No profile matches".to_string(), ToastType::Info);
+                    }
+                }
+            }
+            KeyCode::Down | KeyCode::Tab => self.step_profile_selection(1, true, true),
+            KeyCode::Up | KeyCode::BackTab => self.step_profile_selection(1, false, true),
+            _ => {
+                let before = query.clone();
+                Self::handle_text_field_input(key, query, cursor);
+                if *query != before {
+                    // The top match follows the query
+                    if let Some(&top) = self.search_matches().first() {
+                        self.profile_list_state.select(Some(top));
+                    }
+                }
+            }
+        }
+    }
+
+    /// The active search query (`None` when not searching or the query is
+    /// blank).
+    fn search_query(&self) -> Option<&str> {
+        match &self.input_mode {
+            InputMode::Search { query, .. } if !query.trim().is_empty() => Some(query),
+            _ => None,
+        }
+    }
+
+    /// Indices of the profiles matching the search query, best match first.
+    pub fn search_matches(&self) -> Vec<usize> {
+        let Some(query) = self.search_query() else {
+            return (0..self.profiles.len()).collect();
+        };
+        let mut scored: Vec<(u32, usize)> = self
+            .profiles
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, p)| p.search_score(query).map(|score| (score, idx)))
+            .collect();
+        // Best score first; ties keep sidebar order
+        scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        scored.into_iter().map(|(_, idx)| idx).collect()
+    }
+
+    /// Recorded sessions matching `filter`, newest first.
+    pub fn filtered_history(&self, filter: &str) -> Vec<&crate::core::history::SessionRecord> {
+        self.history.iter().filter(|r| r.matches(filter)).collect()
+    }
+
+    /// Handle keyboard input for the auth credentials overlay.
+    fn handle_input_auth(&mut self, key: KeyEvent) {
+        let InputMode::AuthPrompt {
+            profile_idx,
+            username,
+            username_cursor,
+            password,
+            password_cursor,
+            challenge,
+            otp,
+            otp_cursor,
+            focused_field,
+            save_credentials,
+            connect_after,
+            ..
+        } = &mut self.input_mode
+        else {
+            return;
+        };
+        let fields = AuthField::order(challenge.as_ref());
+
+        let submit = match key.code {
+            KeyCode::Esc => Some(Message::CloseOverlay),
+            KeyCode::Tab | KeyCode::BackTab => {
+                // Cycle through the shown fields, e.g. Username -> Password -> SaveCheckbox
+                let pos = fields.iter().position(|f| f == focused_field).unwrap_or(0);
+                let next = if key.code == KeyCode::Tab {
+                    (pos + 1) % fields.len()
+                } else {
+                    (pos + fields.len() - 1) % fields.len()
+                };
+                *focused_field = fields[next].clone();
+                None
+            }
+            // On SaveCheckbox, toggle the checkbox instead of submitting
+            KeyCode::Enter | KeyCode::Char(' ') if *focused_field == AuthField::SaveCheckbox => {
+                *save_credentials = !*save_credentials;
+                None
+            }
+            KeyCode::Enter => {
+                // Require every shown text field to be non-empty
+                let missing = fields.iter().any(|field| match field {
+                    AuthField::Username => username.is_empty(),
+                    AuthField::Password => password.is_empty(),
+                    AuthField::Otp => otp.is_empty(),
+                    AuthField::SaveCheckbox => false,
+                });
+                if missing {
+                    let warning = if challenge.is_some() {
+                        "All fields are required"
+                    } else {
+                        "Both username and password are required"
+                    };
+                    self.show_toast(warning.to_string(), ToastType::Warning);
                     return;
                 }
-                self.handle_message(Message::AuthSubmit {
-                    idx: profile_idx,
+                Some(Message::AuthSubmit {
+                    idx: *profile_idx,
                     username: username.clone(),
                     password: password.clone(),
-                    save: *save_credentials,
-                    connect_after,
-                });
-            }
-            KeyCode::Char(' ') if *focused_field == AuthField::SaveCheckbox => {
-                *save_credentials = !*save_credentials;
+                    response: otp.clone(),
+                    // Nothing to save when only the challenge is answered
+                    save: *save_credentials && fields.contains(&AuthField::SaveCheckbox),
+                    connect_after: *connect_after,
+                })
             }
             _ => {
                 // Route text editing to the focused field
                 let (text, cursor) = match focused_field {
                     AuthField::Username => (username, username_cursor),
                     AuthField::Password => (password, password_cursor),
+                    AuthField::Otp => (otp, otp_cursor),
                     AuthField::SaveCheckbox => return, // No text editing on checkbox
                 };
                 Self::handle_text_field_input(key, text, cursor);
+                None
+            }
+        };
+
+        if let Some(msg) = submit {
+            self.handle_message(msg);
+        }
+    }
+
+    fn handle_input_profile_form(&mut self, key: KeyEvent) {
+        let InputMode::ProfileForm {
+            form,
+            profile_idx,
+            first,
+            first_cursor,
+            second,
+            second_cursor,
+            checked,
+            focused_field,
+            ..
+        } = &mut self.input_mode
+        else {
+            return;
+        };
+
+        let submit = match key.code {
+            KeyCode::Esc => {
+                self.handle_message(Message::CloseOverlay);
+                return;
+            }
+            KeyCode::Tab | KeyCode::BackTab => {
+                // Cycle through fields: First -> Second -> Checkbox -> First
+                *focused_field =
+                    match (*focused_field, key.code) {
+                        (FormField::First, KeyCode::Tab)
+                        | (FormField::Checkbox, KeyCode::BackTab) => FormField::Second,
+                        (FormField::Second, KeyCode::Tab)
+                        | (FormField::First, KeyCode::BackTab) => FormField::Checkbox,
+                        _ => FormField::First,
+                    };
+                None
+            }
+            KeyCode::Enter => Some(match form {
+                ProfileForm::SplitTunnel => Message::SplitTunnelSubmit {
+                    idx: *profile_idx,
+                    include: first.clone(),
+                    exclude: second.clone(),
+                    bypass_lan: *checked,
+                },
+                ProfileForm::Dns => Message::DnsSubmit {
+                    idx: *profile_idx,
+                    servers: first.clone(),
+                    search: second.clone(),
+                    block_outside: *checked,
+                },
+                ProfileForm::Tags => Message::TagsSubmit {
+                    idx: *profile_idx,
+                    group: first.clone(),
+                    tags: second.clone(),
+                    favorite: *checked,
+                },
+            }),
+            KeyCode::Char(' ') if *focused_field == FormField::Checkbox => {
+                *checked = !*checked;
+                None
+            }
+            _ => {
+                match focused_field {
+                    FormField::First => Self::handle_text_field_input(key, first, first_cursor),
+                    FormField::Second => {
+                        Self::handle_text_field_input(key, second, second_cursor);
+                    }
+                    FormField::Checkbox => {}
+                }
+                None
             }
+        };
+
+        if let Some(msg) = submit {
+            self.handle_message(msg);
         }
     }
 
@@ -624,10 +1137,8 @@ impl App {
             KeyCode::Left => {
                 *cursor = cursor.saturating_sub(1);
             }
-            KeyCode::Right => {
-                if *cursor < text.len() {
-                    *cursor += 1;
-                }
+            KeyCode::Right if *cursor < text.len() => {
+                *cursor += 1;
             }
             KeyCode::Home => {
                 *cursor = 0;
@@ -635,16 +1146,12 @@ impl App {
             KeyCode::End => {
                 *cursor = text.len();
             }
-            KeyCode::Backspace => {
-                if *cursor > 0 {
-                    text.remove(*cursor - 1);
-                    *cursor -= 1;
-                }
+            KeyCode::Backspace if *cursor > 0 => {
+                text.remove(*cursor - 1);
+                *cursor -= 1;
             }
-            KeyCode::Delete => {
-                if *cursor < text.len() {
-                    text.remove(*cursor);
-                }
+            KeyCode::Delete if *cursor < text.len() => {
+                text.remove(*cursor);
             }
             KeyCode::Char(c) => {
                 text.insert(*cursor, c);
@@ -686,31 +1193,29 @@ impl App {
                 self.handle_message(Message::ProfileMove(SelectionMove::Last));
             }
             KeyCode::PageUp => {
-                let current = self.profile_list_state.selected().unwrap_or(0);
-                let next = current.saturating_sub(constants::PROFILE_LIST_PAGE_SIZE);
-                self.profile_list_state.select(Some(next));
+                self.step_profile_selection(constants::PROFILE_LIST_PAGE_SIZE, false, false);
             }
             KeyCode::PageDown => {
-                let current = self.profile_list_state.selected().unwrap_or(0);
-                let last = self.profiles.len().saturating_sub(1);
-                let next = (current + constants::PROFILE_LIST_PAGE_SIZE).min(last);
-                self.profile_list_state.select(Some(next));
+                self.step_profile_selection(constants::PROFILE_LIST_PAGE_SIZE, true, false);
             }
 
             // Quick Actions (always available)
-            KeyCode::Char('1') => self.handle_message(Message::QuickConnect(0)),
-            KeyCode::Char('2') => self.handle_message(Message::QuickConnect(1)),
-            KeyCode::Char('3') => self.handle_message(Message::QuickConnect(2)),
-            KeyCode::Char('4') => self.handle_message(Message::QuickConnect(3)),
-            KeyCode::Char('5') => self.handle_message(Message::QuickConnect(4)),
-            KeyCode::Char('6') => self.handle_message(Message::QuickConnect(5)),
-            KeyCode::Char('7') => self.handle_message(Message::QuickConnect(6)),
-            KeyCode::Char('8') => self.handle_message(Message::QuickConnect(7)),
-            KeyCode::Char('9') => self.handle_message(Message::QuickConnect(8)),
+            KeyCode::Char('1') => self.handle_message(Message::QuickConnect(1)),
+            KeyCode::Char('2') => self.handle_message(Message::QuickConnect(2)),
+            KeyCode::Char('3') => self.handle_message(Message::QuickConnect(3)),
+            KeyCode::Char('4') => self.handle_message(Message::QuickConnect(4)),
+            KeyCode::Char('5') => self.handle_message(Message::QuickConnect(5)),
+            KeyCode::Char('6') => self.handle_message(Message::QuickConnect(6)),
+            KeyCode::Char('7') => self.handle_message(Message::QuickConnect(7)),
+            KeyCode::Char('8') => self.handle_message(Message::QuickConnect(8)),
+            KeyCode::Char('9') => self.handle_message(Message::QuickConnect(9)),
             KeyCode::Char('d') => self.handle_message(Message::Disconnect),
             KeyCode::Char('r') => self.handle_message(Message::Reconnect),
             KeyCode::Char('i') => self.handle_message(Message::OpenImport),
             KeyCode::Char('y') => self.handle_message(Message::CopyIp),
+            KeyCode::Char('H') => self.handle_message(Message::OpenHistory),
+            KeyCode::Char('B') => self.handle_message(Message::OpenBenchmark),
+            KeyCode::Char('/') => self.handle_message(Message::OpenSearch),
 
             // Kill Switch toggle (Shift+K for safety)
             KeyCode::Char('K') => self.handle_message(Message::ToggleKillSwitch),
@@ -734,6 +1239,10 @@ impl App {
                 KeyCode::Delete | KeyCode::Backspace => {
                     self.handle_message(Message::OpenDelete(None));
                 }
+                // Enter on a folded section unfolds it instead of connecting
+                KeyCode::Enter if self.selection_folded() => {
+                    self.handle_message(Message::ToggleGroup);
+                }
                 KeyCode::Char('c') | KeyCode::Enter => {
                     self.handle_message(Message::ToggleConnect(None));
                 }
@@ -749,6 +1258,10 @@ impl App {
                 }
                 KeyCode::Char('a') => self.handle_message(Message::ManageAuth),
                 KeyCode::Char('A') => self.handle_message(Message::ClearAuth),
+                KeyCode::Char('f') => self.handle_message(Message::ToggleFavorite),
+                KeyCode::Char('t') => self.handle_message(Message::ManageTags),
+                KeyCode::Char('o') => self.handle_message(Message::ToggleGroup),
+                KeyCode::Char('#') => self.handle_message(Message::OpenQuickSlot),
                 _ => {}
             },
             FocusedPanel::Logs => {
@@ -784,8 +1297,13 @@ impl App {
                     _ => {}
                 }
             }
+            FocusedPanel::Chart => {
+                if key.code == KeyCode::Char('w') {
+                    self.handle_message(Message::CycleChartWindow);
+                }
+            }
             // Read-only panels
-            FocusedPanel::ConnectionDetails | FocusedPanel::Chart | FocusedPanel::Security => {}
+            FocusedPanel::ConnectionDetails | FocusedPanel::Security => {}
         }
     }
 
@@ -936,6 +1454,9 @@ impl App {
                                 username_cursor,
                                 password,
                                 password_cursor,
+                                challenge: None,
+                                otp: String::new(),
+                                otp_cursor: 0,
                                 focused_field: crate::state::AuthField::Username,
                                 save_credentials: true,
                                 connect_after: false,
@@ -944,6 +1465,72 @@ impl App {
                     }
                 }
             }
+            Message::ManageSplitTunnel => {
+                if let Some(profile) = self.selected_profile() {
+                    let policy =
+                        crate::vpn::split::for_profile(&profile.config_path).unwrap_or_default();
+                    self.open_profile_form(
+                        ProfileForm::SplitTunnel,
+                        policy.include.join(", "),
+                        policy.exclude.join(", "),
+                        policy.bypass_lan,
+                    );
+                }
+            }
+            Message::ManageDns => {
+                if let Some(profile) = self.selected_profile() {
+                    let dns =
+                        crate::core::dns::for_profile(&profile.config_path).unwrap_or_default();
+                    self.open_profile_form(
+                        ProfileForm::Dns,
+                        dns.servers.join(", "),
+                        dns.search.join(", "),
+                        dns.block_outside,
+                    );
+                }
+            }
+            Message::DnsSubmit {
+                idx,
+                servers,
+                search,
+                block_outside,
+            } => self.save_dns_override(idx, &servers, &search, block_outside),
+            Message::SplitTunnelSubmit {
+                idx,
+                include,
+                exclude,
+                bypass_lan,
+            } => self.save_split_tunnel(idx, &include, &exclude, bypass_lan),
+            Message::ManageTags => {
+                if let Some(profile) = self.selected_profile() {
+                    let labels = profile.labels.clone();
+                    self.open_profile_form(
+                        ProfileForm::Tags,
+                        labels.group.unwrap_or_default(),
+                        labels.tags.join(", "),
+                        labels.favorite,
+                    );
+                }
+            }
+            Message::TagsSubmit {
+                idx,
+                group,
+                tags,
+                favorite,
+            } => self.save_tags(idx, &group, &tags, favorite),
+            Message::ToggleFavorite => self.toggle_favorite(),
+            Message::ToggleGroup => self.toggle_section(),
+            Message::OpenQuickSlot => {
+                if let Some(idx) = self.profile_list_state.selected() {
+                    if let Some(profile) = self.profiles.get(idx) {
+                        self.input_mode = InputMode::QuickSlot {
+                            profile_idx: idx,
+                            profile_name: profile.name.clone(),
+                        };
+                    }
+                }
+            }
+            Message::AssignQuickSlot { idx, slot } => self.assign_quick_slot(idx, slot),
             Message::ClearAuth => {
                 if let Some(idx) = self.profile_list_state.selected() {
                     if let Some(profile) = self.profiles.get(idx) {
@@ -990,27 +1577,36 @@ impl App {
             Message::ProfileMove(mv) => match mv {
                 SelectionMove::Next => self.profile_next(),
                 SelectionMove::Prev => self.profile_previous(),
-                SelectionMove::First => self.profile_list_state.select(Some(0)),
-                SelectionMove::Last => {
-                    let last = self.profiles.len().saturating_sub(1);
-                    self.profile_list_state.select(Some(last));
-                }
+                SelectionMove::First => self.step_profile_selection(usize::MAX, false, false),
+                SelectionMove::Last => self.step_profile_selection(usize::MAX, true, false),
             },
 
             // Connection
             Message::Disconnect => {
-                if matches!(self.connection_state, ConnectionState::Disconnecting { .. }) {
+                if self.reconnect.is_some()
+                    && matches!(self.connection_state, ConnectionState::Disconnected)
+                {
+                    self.cancel_reconnect();
+                } else if matches!(self.connection_state, ConnectionState::Disconnecting { .. }) {
                     self.force_disconnect();
                 } else {
                     self.disconnect();
                 }
             }
             Message::Reconnect => self.reconnect(),
-            Message::QuickConnect(idx) => {
-                if idx < self.profiles.len() {
-                    self.toggle_connection(idx);
-                }
-            }
+            Message::QuickConnect(slot) => {
+                match self
+                    .profiles
+                    .iter()
+                    .position(|p| p.labels.quick_slot == Some(slot))
+                {
+                    Some(idx) => self.toggle_connection(idx),
+                    None => self.show_toast(
+                        format!("Quick slot {slot} is empty: press # on a profile to bind it"),
+                        ToastType::Info,
+                    ),
+                }
+            }
 
             // Disconnect result from background thread
             Message::DisconnectResult {
@@ -1036,6 +1632,7 @@ impl App {
                 } else {
                     let err_msg = error.unwrap_or_else(|| "unknown error".to_string());
                     self.log(&format!("ERR: Failed to disconnect '{profile}': {err_msg}"));
+                    self.end_session(EndReason::User, &err_msg);
                     // Clear pending -- don't auto-connect after a failed disconnect
                     self.pending_connect = None;
                     self.connection_state = ConnectionState::Disconnected;
@@ -1050,6 +1647,10 @@ impl App {
                 success,
                 error,
             } => {
+                // The connect ended while its credential prompt was still open
+                if self.auth_reply.take().is_some() {
+                    self.input_mode = InputMode::Normal;
+                }
                 // Ignore stale results if we're no longer in Connecting state for this profile.
                 // This prevents spurious errors when the connect polling thread outlives a
                 // disconnect (e.g., user disconnects while log polling is still running).
@@ -1062,8 +1663,8 @@ impl App {
                         "INFO: Ignoring stale ConnectResult for '{profile}' (state changed)"
                     ));
                 } else if success {
-                    // The connect thread confirmed success (e.g. OpenVPN log says
-                    // "Initialization Sequence Completed"). Transition to Connected
+                    // The connect thread confirmed success (e.g. OpenVPN reported
+                    // CONNECTED). Transition to Connected
                     // immediately. The scanner will fill in interface details on the
                     // next tick.
                     let location = self
@@ -1081,6 +1682,9 @@ impl App {
                         details: Box::new(DetailedConnectionInfo::default()),
                     };
                     self.session_start = Some(now);
+                    if let Some(tracker) = &mut self.session_tracker {
+                        tracker.connected_at(std::time::SystemTime::now());
+                    }
 
                     if let Some(p) = self.profiles.iter_mut().find(|p| p.name == profile) {
                         p.last_used = Some(std::time::SystemTime::now());
@@ -1088,7 +1692,10 @@ impl App {
                     self.save_metadata();
 
                     self.log(&format!("STATUS: Connected to '{profile}'"));
+                    self.notify(NotifyEvent::Connected, &format!("'{profile}' is up"));
                     self.refresh_telemetry();
+                    // post_up waits for the scanner to fill in the interface
+                    self.post_up_pending = Some(self.connect_reason());
 
                     // KILL SWITCH: Arm when VPN connects
                     if self.killswitch_mode != crate::state::KillSwitchMode::Off {
@@ -1098,6 +1705,7 @@ impl App {
                 } else {
                     let err_msg = error.unwrap_or_else(|| "unknown error".to_string());
                     self.log(&format!("ERR: Failed to connect '{profile}': {err_msg}"));
+                    self.end_session(EndReason::Error, &err_msg);
                     // Kill any leftover process and clean up run files
                     self.cleanup_vpn_resources(&profile);
                     self.connection_state = ConnectionState::Disconnected;
@@ -1116,7 +1724,14 @@ impl App {
                     self.zoomed_panel = Some(self.focused_panel.clone());
                 }
             }
+            Message::CycleChartWindow => {
+                self.chart_window = self.chart_window.next();
+            }
             Message::CloseOverlay => {
+                if let Some(reply) = self.auth_reply.take() {
+                    // Cancelled: the connect thread stops the daemon
+                    let _ = reply.send(None);
+                }
                 self.show_config = false;
                 self.show_action_menu = false;
                 self.show_bulk_menu = false;
@@ -1135,6 +1750,70 @@ impl App {
                 self.show_bulk_menu = true;
                 self.action_menu_state.select(Some(0));
             }
+            Message::OpenHistory => match crate::core::history::load(&self.config_dir) {
+                Ok(records) => {
+                    self.history = records.into_iter().rev().collect();
+                    self.input_mode = InputMode::History {
+                        filter: String::new(),
+                        cursor: 0,
+                        selected: 0,
+                    };
+                }
+                Err(e) => self.show_toast(e, ToastType::Error),
+            },
+            Message::OpenBenchmark => {
+                if self.profiles.is_empty() {
+                    self.show_toast("No profiles to benchmark".to_string(), ToastType::Info);
+                } else {
+                    self.benchmark.clear();
+                    self.input_mode = InputMode::Benchmark {
+                        scope: String::new(),
+                        cursor: 0,
+                        running: false,
+                        selected: 0,
+                    };
+                }
+            }
+            Message::RunBenchmark(scope) => self.start_benchmark(&scope),
+            Message::UnlockVault(passphrase) => self.unlock_vault(&passphrase),
+            Message::BenchmarkResult(results) => {
+                // Closed while probing: nobody is waiting for the ranking
+                let InputMode::Benchmark {
+                    running, selected, ..
+                } = &mut self.input_mode
+                else {
+                    return;
+                };
+                *running = false;
+                *selected = 0;
+                match results.first().filter(|r| r.is_reachable()) {
+                    Some(best) => {
+                        let rtt = best
+                            .rtt_ms
+                            .map_or_else(|| "no round trip".to_string(), |ms| format!("{ms}ms"));
+                        self.log(&format!(
+                            "NET: Fastest server: '{}' ({}, {rtt})",
+                            best.profile, best.endpoint
+                        ));
+                    }
+                    None => self.log("WARN: Benchmark found no reachable server"),
+                }
+                self.benchmark = results;
+            }
+            Message::OpenSearch => {
+                if self.profiles.is_empty() {
+                    self.show_toast("No profiles to search".to_string(), ToastType::Info);
+                } else {
+                    self.focused_panel = FocusedPanel::Sidebar;
+                    if self.zoomed_panel.is_some() {
+                        self.zoomed_panel = Some(FocusedPanel::Sidebar);
+                    }
+                    self.input_mode = InputMode::Search {
+                        query: String::new(),
+                        cursor: 0,
+                    };
+                }
+            }
             Message::OpenImport => {
                 self.input_mode = InputMode::Import {
                     path: String::new(),
@@ -1163,6 +1842,7 @@ impl App {
                 idx,
                 username,
                 password,
+                response,
                 save,
                 connect_after,
             } => {
@@ -1181,9 +1861,35 @@ impl App {
                     return;
                 }
 
+                // Answer for a connect that is waiting on OpenVPN
+                if let Some(reply) = self.auth_reply.take() {
+                    if save {
+                        match utils::write_openvpn_auth_file(&profile_name, &username, &password) {
+                            Ok(()) => {
+                                self.log(&format!("AUTH: Saved credentials for '{profile_name}'"));
+                            }
+                            Err(e) => self.log(&format!("WARN: Failed to save credentials: {e}")),
+                        }
+                    }
+                    let credentials = crate::core::openvpn_mgmt::Credentials {
+                        username,
+                        password,
+                        response,
+                    };
+                    if reply.send(Some(credentials)).is_ok() {
+                        // The connect timeout starts over now that it has them
+                        if let ConnectionState::Connecting { started, .. } =
+                            &mut self.connection_state
+                        {
+                            *started = Instant::now();
+                        }
+                    }
+                    return;
+                }
+
                 // Write credentials to auth file
                 match utils::write_openvpn_auth_file(&profile_name, &username, &password) {
-                    Ok(_) => {
+                    Ok(()) => {
                         if save {
                             self.log(&format!("AUTH: Saved credentials for '{profile_name}'"));
                         } else {
@@ -1220,6 +1926,63 @@ impl App {
                 }
             }
 
+            Message::OpenVpnAuth {
+                profile,
+                request,
+                reply,
+            } => {
+                let idx = self.profiles.iter().position(|p| p.name == profile);
+                let (Some(idx), true) = (idx, request.username) else {
+                    self.log(&format!(
+                        "WARN: '{profile}' asks for {} credentials, which cannot be entered here",
+                        request.kind
+                    ));
+                    let _ = reply.send(None);
+                    return;
+                };
+                self.log(&format!(
+                    "AUTH: '{profile}' asks for {} credentials",
+                    request.kind
+                ));
+                // Keep the username if only the password was rejected. A
+                // static challenge is asked up front, so its saved password
+                // is still good and only the code is missing.
+                let saved = utils::read_openvpn_saved_auth(&profile).unwrap_or_default();
+                let (username, password) = match &request.challenge {
+                    Some(c) if c.is_dynamic() => (String::new(), String::new()),
+                    Some(_) => saved,
+                    None => (saved.0, String::new()),
+                };
+                let (username_cursor, password_cursor) = (username.len(), password.len());
+                let focused_field = if request
+                    .challenge
+                    .as_ref()
+                    .is_some_and(crate::core::openvpn_mgmt::Challenge::is_dynamic)
+                    || !password.is_empty()
+                {
+                    AuthField::Otp
+                } else if username.is_empty() {
+                    AuthField::Username
+                } else {
+                    AuthField::Password
+                };
+                self.auth_reply = Some(reply);
+                self.input_mode = InputMode::AuthPrompt {
+                    profile_idx: idx,
+                    profile_name: profile,
+                    username,
+                    username_cursor,
+                    password,
+                    password_cursor,
+                    challenge: request.challenge,
+                    otp: String::new(),
+                    otp_cursor: 0,
+                    focused_field,
+                    save_credentials: true,
+                    connect_after: false,
+                };
+            }
+
             Message::ToggleKillSwitch => {
                 use crate::state::KillSwitchMode;
 
@@ -1251,17 +2014,25 @@ impl App {
                     }
                 }
 
-                // Save state for recovery
-                let _ = crate::core::killswitch::save_state(
-                    self.killswitch_mode,
-                    self.killswitch_state,
-                    None,
-                    None,
-                );
+                // Save state for recovery (the daemon persists its own)
+                if self.daemon_socket.is_none() {
+                    let _ = crate::core::killswitch::save_state(
+                        self.killswitch_mode,
+                        self.killswitch_state,
+                        None,
+                        None,
+                    );
+                }
             }
 
             // System
             Message::Quit => {
+                self.save_metrics();
+                // The daemon keeps the tunnel and kill switch running without us
+                if self.daemon_socket.is_some() {
+                    self.should_quit = true;
+                    return;
+                }
                 // Clean up VPN resources before exiting so we don't leave
                 // dangling processes, PID files, or firewall rules behind.
                 match &self.connection_state {
@@ -1269,7 +2040,16 @@ impl App {
                     | ConnectionState::Connecting { profile, .. }
                     | ConnectionState::Disconnecting { profile, .. } => {
                         let profile_name = profile.clone();
+                        // Quitting can't wait for a background thread: run the hooks inline
+                        let ctx = self.hook_context("quit");
+                        if let Some(ctx) = &ctx {
+                            self.run_hooks_inline(HookEvent::PreDown, ctx);
+                        }
                         self.cleanup_vpn_resources(&profile_name);
+                        self.end_session(EndReason::User, "");
+                        if let Some(ctx) = &ctx {
+                            self.run_hooks_inline(HookEvent::PostDown, ctx);
+                        }
                     }
                     ConnectionState::Disconnected => {}
                 }
@@ -1281,6 +2061,22 @@ impl App {
                 self.should_quit = true;
             }
             Message::Log(msg) => self.log(&msg),
+            Message::NotifyFailed { event, body, error } => {
+                self.log(&format!(
+                    "WARN: Desktop notification failed: {error}; using the terminal"
+                ));
+                (self.ring_terminal)(event, &body);
+            }
+            Message::NetworkChanged(interfaces) => self.on_network_change(&interfaces),
+            Message::Daemon(event) => match event {
+                crate::daemon::protocol::Event::State { status } => {
+                    self.killswitch_mode = status.killswitch_mode;
+                    self.killswitch_state = status.killswitch_state;
+                }
+                crate::daemon::protocol::Event::Log { message } => {
+                    self.log(&format!("DAEMON: {message}"));
+                }
+            },
             Message::Toast(msg, t_type) => self.show_toast(msg, t_type),
             Message::CopyIp => self.copy_ip_to_clipboard(),
             Message::ClearLogs => {
@@ -1320,10 +2116,25 @@ impl App {
                                 }
                             }
                         }
+                        if is_connected {
+                            if let Some(tracker) = &mut self.session_tracker {
+                                tracker.record_exit_ip(&ip);
+                            }
+                        }
                         self.public_ip = ip;
                     }
-                    TelemetryUpdate::Latency(ms) => self.latency_ms = ms,
+                    TelemetryUpdate::Latency(ms) => {
+                        if let Some(tracker) = self.connected_tracker() {
+                            if ms > 0 {
+                                tracker.record_latency(ms);
+                            }
+                        }
+                        self.latency_ms = ms;
+                    }
                     TelemetryUpdate::PacketLoss(loss) => {
+                        if let Some(tracker) = self.connected_tracker() {
+                            tracker.record_loss(loss);
+                        }
                         self.packet_loss = loss;
                         self.log(&format!("NET: Packet loss: {loss:.1}%"));
                     }
@@ -1335,6 +2146,9 @@ impl App {
                         if self.location != loc && self.location != constants::MSG_DETECTING {
                             self.log(&format!("NET: Location: {loc}"));
                         }
+                        if let Some(tracker) = self.connected_tracker() {
+                            tracker.record_location(&loc);
+                        }
                         self.location = loc;
                     }
                     TelemetryUpdate::Isp(isp) => {
@@ -1344,11 +2158,16 @@ impl App {
                         self.isp = isp;
                     }
                     TelemetryUpdate::Dns(dns) => {
+                        self.dns_expected = crate::core::dns::applied_servers();
                         if self.dns_server != dns && self.dns_server != constants::MSG_NO_DATA {
-                            if utils::is_private_ip(&dns) {
+                            if self.is_dns_leaking(&dns) {
                                 self.log(&format!(
                                     "WARN: DNS server {dns} is a private IP — possible DNS leak"
                                 ));
+                                self.notify(
+                                    NotifyEvent::Leak,
+                                    &format!("DNS server {dns} may be outside the tunnel"),
+                                );
                             } else {
                                 self.log(&format!("SEC: DNS server: {dns}"));
                             }
@@ -1361,12 +2180,47 @@ impl App {
                                 self.log(
                                     "WARN: IPv6 leak detected — traffic may bypass VPN tunnel",
                                 );
+                                self.notify(
+                                    NotifyEvent::Leak,
+                                    "IPv6 traffic may bypass the tunnel",
+                                );
                             } else {
                                 self.log("SEC: IPv6 secure (blocked)");
                             }
                         }
                         self.ipv6_leak = leak;
                     }
+                    TelemetryUpdate::DnsLeakTest(report) => {
+                        let changed = self.dns_leak.as_ref().map_or(true, |prev| {
+                            prev.is_leaking() != report.is_leaking()
+                                || prev.resolvers != report.resolvers
+                        });
+                        if changed {
+                            let resolvers = report
+                                .resolvers
+                                .iter()
+                                .filter(|r| r.leaking || !report.is_leaking())
+                                .map(|r| format!("{} ({})", r.ip, r.asn))
+                                .collect::<Vec<_>>()
+                                .join(", ");
+                            if report.resolvers.is_empty() {
+                                self.log(
+                                    "SEC: DNS leak test: no resolver reached the probe domain",
+                                );
+                            } else if report.is_leaking() {
+                                self.log(&format!(
+                                    "WARN: DNS leak test: queries answered outside the VPN by {resolvers}"
+                                ));
+                                self.notify(
+                                    NotifyEvent::Leak,
+                                    &format!("DNS queries answered outside the VPN by {resolvers}"),
+                                );
+                            } else {
+                                self.log(&format!("SEC: DNS leak test passed: {resolvers}"));
+                            }
+                        }
+                        self.dns_leak = Some(report);
+                    }
                     TelemetryUpdate::Log(level, msg) => {
                         // Log through central logging system
                         logger::log(level, "TELEMETRY", msg);
@@ -1395,6 +2249,7 @@ impl App {
                             self.config.disconnect_timeout
                         ));
                         self.cleanup_vpn_resources(&profile_name);
+                        self.end_session(EndReason::User, "");
                         // Clear pending -- don't auto-connect when teardown was forced
                         self.pending_connect = None;
                         self.connection_state = ConnectionState::Disconnected;
@@ -1456,6 +2311,14 @@ impl App {
                         self.log(&format!(
                             "STATUS: Connection established to '{profile_name}'"
                         ));
+                        self.notify(NotifyEvent::Connected, &format!("'{profile_name}' is up"));
+                        if let Some(tracker) = &mut self.session_tracker {
+                            tracker.connected_at(
+                                session
+                                    .started_at
+                                    .unwrap_or_else(std::time::SystemTime::now),
+                            );
+                        }
 
                         // KILL SWITCH: Arm when VPN connects
                         if self.killswitch_mode != crate::state::KillSwitchMode::Off {
@@ -1470,6 +2333,9 @@ impl App {
                         }
                         self.save_metadata();
                         self.session_start = Some(start_time);
+                        if let Some(ctx) = self.hook_context(self.connect_reason()) {
+                            self.spawn_hooks(HookEvent::PostUp, ctx);
+                        }
                     } else {
                         // Tunnel not detected yet — log periodically so the user
                         // can see the scanner is still trying.
@@ -1526,6 +2392,11 @@ impl App {
                             details.mtu.clone_from(&session.mtu);
                             details.listen_port.clone_from(&session.listen_port);
                             details.public_key.clone_from(&session.public_key);
+                            if let Some(reason) = self.post_up_pending.take() {
+                                if let Some(ctx) = self.hook_context(reason) {
+                                    self.spawn_hooks(HookEvent::PostUp, ctx);
+                                }
+                            }
                             return;
                         }
                     }
@@ -1536,6 +2407,19 @@ impl App {
                         .find(|p| p.name == active_name)
                         .map_or_else(|| "Unknown".to_string(), |p| p.location.clone());
 
+                    // Adopted (or replaced) tunnel: whatever we tracked is gone
+                    if self
+                        .session_tracker
+                        .as_ref()
+                        .map_or(true, |t| t.profile() != active_name)
+                    {
+                        self.end_session(EndReason::Drop, "");
+                        self.begin_session(
+                            &active_name,
+                            real_start.unwrap_or_else(std::time::SystemTime::now),
+                        );
+                    }
+
                     let start_time = if let Some(real) = real_start {
                         if let Ok(duration) = std::time::SystemTime::now().duration_since(real) {
                             Instant::now()
@@ -1585,7 +2469,7 @@ impl App {
                         } => Some((
                             profile.clone(),
                             details.interface.clone(),
-                            details.endpoint.split(':').next().unwrap_or("").to_string(),
+                            utils::endpoint_host(&details.endpoint).to_string(),
                         )),
                         ConnectionState::Disconnecting { profile, .. }
                         | ConnectionState::Connecting { profile, .. } => {
@@ -1594,17 +2478,26 @@ impl App {
                         ConnectionState::Disconnected => None,
                     };
 
-                    if let Some((profile_name, _, _)) = drop_info {
+                    if let Some((profile_name, interface, server_ip)) = drop_info {
                         // Check if this was an unexpected drop from Connected state
                         let was_connected =
                             matches!(self.connection_state, ConnectionState::Connected { .. });
 
                         if was_connected {
+                            self.post_up_pending = None;
+                            if let Some(ctx) = self.hook_context("drop") {
+                                self.spawn_hooks(HookEvent::OnDrop, ctx);
+                            }
+                            self.end_session(EndReason::Drop, "");
                             self.connection_drops += 1;
                             self.log(&format!(
                                 "WARN: Connection dropped from '{}' (#{} this session)",
                                 profile_name, self.connection_drops
                             ));
+                            self.notify(
+                                NotifyEvent::Dropped,
+                                &format!("'{profile_name}' went down unexpectedly"),
+                            );
 
                             // KILL SWITCH: Activate on unexpected VPN drop
                             if self.killswitch_mode != crate::state::KillSwitchMode::Off
@@ -1618,11 +2511,20 @@ impl App {
                                     ToastType::Error,
                                 );
                             }
+
+                            // The daemon reconnects on its own schedule
+                            if self.config.auto_reconnect
+                                && self.config.reconnect_max_attempts > 0
+                                && self.daemon_socket.is_none()
+                            {
+                                self.schedule_reconnect(&profile_name, &interface, &server_ip);
+                            }
                         } else if matches!(
                             self.connection_state,
                             ConnectionState::Disconnecting { .. }
                         ) {
                             self.log(&format!("STATUS: Disconnected from '{profile_name}'"));
+                            self.end_session(EndReason::User, "");
                         } else if matches!(
                             self.connection_state,
                             ConnectionState::Connecting { .. }
@@ -1630,6 +2532,7 @@ impl App {
                             self.log(&format!(
                                 "WARN: Connection to '{profile_name}' failed or was cancelled"
                             ));
+                            self.end_session(EndReason::Error, "failed or was cancelled");
                         }
 
                         // Clean up any leftover run files (process is already gone)
@@ -1647,6 +2550,7 @@ impl App {
                 self.session_start = None;
                 self.pending_connect = None;
                 self.log(&format!("ERR: Connection timed out for '{profile_name}'"));
+                self.end_session(EndReason::Timeout, "");
                 self.show_toast(
                     format!("Connection timed out for '{profile_name}'"),
                     ToastType::Warning,
@@ -1654,11 +2558,13 @@ impl App {
                 self.sync_killswitch();
                 self.refresh_telemetry();
             }
+            Message::KillSwitchChecked(result) => self.on_killswitch_checked(result),
             Message::Tick => {
-                // 1. Connection Timeout Safeguard
+                // 1. Connection Timeout Safeguard (paused while asking for credentials)
                 if let ConnectionState::Connecting { started, profile } = &self.connection_state {
-                    if started.elapsed()
-                        > std::time::Duration::from_secs(constants::DEFAULT_CONNECT_TIMEOUT)
+                    if self.auth_reply.is_none()
+                        && started.elapsed()
+                            > std::time::Duration::from_secs(constants::DEFAULT_CONNECT_TIMEOUT)
                     {
                         let p = profile.clone();
                         self.handle_message(Message::ConnectionTimeout(p));
@@ -1679,17 +2585,23 @@ impl App {
                 // 5. Poll network stats (spawn-on-demand, non-blocking)
                 self.poll_network_stats();
 
-                // 6. Update network stats history
-                let last = constants::NETWORK_HISTORY_SIZE - 1;
-                for i in 0..last {
-                    self.down_history[i].1 = self.down_history[i + 1].1;
-                    self.up_history[i].1 = self.up_history[i + 1].1;
-                }
-                #[allow(clippy::cast_precision_loss)]
-                {
-                    self.down_history[last].1 = self.current_down as f64;
-                    self.up_history[last].1 = self.current_up as f64;
-                }
+                // 6. Drive automatic reconnect after a drop
+                self.supervise_reconnect();
+
+                // 7. Make sure nothing removed the kill switch rules
+                self.verify_killswitch();
+
+                // 8. Record this tick's throughput and link quality
+                let now = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map_or(0, |d| d.as_secs());
+                self.record_metrics(now);
+
+                // 9. Announce kill switch changes, whichever path made them
+                self.notify_killswitch();
+
+                // 10. Hand the exporter a fresh snapshot
+                self.publish_snapshot();
             }
             Message::Resize(width, height) => {
                 self.terminal_size = (width, height);
@@ -1697,6 +2609,18 @@ impl App {
         }
     }
 
+    /// Whether `dns` looks like a leak.
+    ///
+    /// With a DNS override applied, anything other than its servers is a leak.
+    /// Otherwise a private resolver (typically the LAN router) is the best hint.
+    pub fn is_dns_leaking(&self, dns: &str) -> bool {
+        if self.dns_expected.is_empty() {
+            utils::is_private_ip(dns)
+        } else {
+            !self.dns_expected.iter().any(|s| s == dns)
+        }
+    }
+
     /// Check if a specific panel should be drawn as focused (visually)
     pub fn should_draw_focus(&self, panel: &FocusedPanel) -> bool {
         // If an overlay is active, no background panel has focus
@@ -1720,7 +2644,7 @@ impl App {
     fn get_config_max_scroll(&self) -> u16 {
         if let Some(idx) = self.profile_list_state.selected() {
             if let Some(profile) = self.profiles.get(idx) {
-                if let Ok(content) = std::fs::read_to_string(&profile.config_path) {
+                if let Ok(content) = crate::core::vault::read_to_string(&profile.config_path) {
                     #[allow(clippy::cast_possible_truncation)]
                     let total_lines = content.lines().count() as u16;
                     // Viewport height: percentage of terminal height minus chrome (borders, title, etc.)
@@ -1735,31 +2659,135 @@ impl App {
     }
 
     fn profile_next(&mut self) {
-        let i = match self.profile_list_state.selected() {
-            Some(i) => {
-                if i >= self.profiles.len().saturating_sub(1) {
-                    0
-                } else {
-                    i + 1
-                }
+        self.step_profile_selection(1, true, true);
+    }
+
+    fn profile_previous(&mut self) {
+        self.step_profile_selection(1, false, true);
+    }
+
+    /// Move the sidebar selection `by` stops forward or back, wrapping
+    /// around the ends if `wrap` is set.
+    fn step_profile_selection(&mut self, by: usize, forward: bool, wrap: bool) {
+        let stops = self.profile_stops();
+        if stops.is_empty() {
+            return;
+        }
+        // A profile hidden in a folded section sits on its header's stop.
+        // Search results aren't in index order, so look for the exact stop first.
+        let Some(pos) = self.profile_list_state.selected().and_then(|sel| {
+            stops
+                .iter()
+                .position(|&stop| stop == sel)
+                .or_else(|| stops.iter().rposition(|&stop| stop <= sel))
+        }) else {
+            self.profile_list_state.select(Some(stops[0]));
+            return;
+        };
+        let last = stops.len() - 1;
+        let next = if forward {
+            if wrap && pos == last {
+                0
+            } else {
+                pos.saturating_add(by).min(last)
             }
-            None => 0,
+        } else if wrap && pos == 0 {
+            last
+        } else {
+            pos.saturating_sub(by)
         };
-        self.profile_list_state.select(Some(i));
+        self.profile_list_state.select(Some(stops[next]));
     }
 
-    fn profile_previous(&mut self) {
-        let i = match self.profile_list_state.selected() {
-            Some(i) => {
-                if i == 0 {
-                    self.profiles.len().saturating_sub(1)
-                } else {
-                    i - 1
-                }
+    /// Whether any profile is a favorite or in a group, i.e. the sidebar
+    /// shows section headers.
+    fn profiles_grouped(&self) -> bool {
+        self.profiles
+            .iter()
+            .any(|p| p.section() != ProfileSection::Ungrouped)
+    }
+
+    /// Rows of the profile sidebar, in display order: search matches while
+    /// searching, otherwise sections with their headers. Profiles are sorted
+    /// by section, so each section is one contiguous run of indices.
+    pub fn sidebar_rows(&self) -> Vec<SidebarRow> {
+        if self.search_query().is_some() {
+            return self
+                .search_matches()
+                .into_iter()
+                .map(SidebarRow::Profile)
+                .collect();
+        }
+        if !self.profiles_grouped() {
+            return (0..self.profiles.len()).map(SidebarRow::Profile).collect();
+        }
+        let mut rows = Vec::new();
+        let mut first = 0;
+        while let Some(profile) = self.profiles.get(first) {
+            let section = profile.section();
+            let count = self.profiles[first..]
+                .iter()
+                .take_while(|p| p.section() == section)
+                .count();
+            let collapsed = self.collapsed_sections.contains(&section);
+            rows.push(SidebarRow::Header {
+                section,
+                first,
+                count,
+                collapsed,
+            });
+            if !collapsed {
+                rows.extend((first..first + count).map(SidebarRow::Profile));
             }
-            None => 0,
+            first += count;
+        }
+        rows
+    }
+
+    /// Profile indices the sidebar cursor can rest on: every visible profile,
+    /// plus the first profile of each folded section (drawn as its header).
+    fn profile_stops(&self) -> Vec<usize> {
+        self.sidebar_rows()
+            .into_iter()
+            .filter_map(|row| match row {
+                SidebarRow::Header {
+                    first,
+                    collapsed: true,
+                    ..
+                } => Some(first),
+                SidebarRow::Header { .. } => None,
+                SidebarRow::Profile(idx) => Some(idx),
+            })
+            .collect()
+    }
+
+    /// Whether the selected profile is hidden in a folded section.
+    pub fn selection_folded(&self) -> bool {
+        self.search_query().is_none()
+            && self.profiles_grouped()
+            && self
+                .selected_profile()
+                .is_some_and(|p| self.collapsed_sections.contains(&p.section()))
+    }
+
+    /// Fold or unfold the selected profile's section.
+    fn toggle_section(&mut self) {
+        let Some(section) = self.selected_profile().map(VpnProfile::section) else {
+            return;
         };
-        self.profile_list_state.select(Some(i));
+        if !self.profiles_grouped() {
+            self.show_toast(
+                "Add a group or favorite first (t / f)".to_string(),
+                ToastType::Info,
+            );
+            return;
+        }
+        if !self.collapsed_sections.remove(&section) {
+            // Rest the cursor on the header
+            let first = self.profiles.iter().position(|p| p.section() == section);
+            self.profile_list_state.select(first);
+            self.collapsed_sections.insert(section);
+        }
     }
 
     /// Request deletion of a profile (Safety Check)
@@ -1811,6 +2839,7 @@ impl App {
         // Clean up OpenVPN auth and runtime files
         if matches!(protocol, Protocol::OpenVPN) {
             utils::delete_openvpn_auth_file(&profile_name);
+            crate::core::totp::delete(&profile_name);
             utils::cleanup_openvpn_run_files(&profile_name);
         }
 
@@ -1834,6 +2863,7 @@ impl App {
                 let key = profile.config_path.to_string_lossy().to_string();
                 if let Some(meta) = metadata.get(&key) {
                     profile.last_used = meta.last_used;
+                    profile.labels = meta.labels.clone();
                 }
             }
         }
@@ -1842,25 +2872,220 @@ impl App {
     fn save_metadata(&self) {
         use std::collections::HashMap;
 
+        // Start from the stored entries so fields edited elsewhere (split tunnel) survive
+        let mut stored = utils::load_profile_metadata().unwrap_or_default();
         let mut metadata = HashMap::new();
         for profile in &self.profiles {
             let key = profile.config_path.to_string_lossy().to_string();
-            metadata.insert(
-                key,
-                utils::ProfileMetadata {
-                    last_used: profile.last_used,
-                },
-            );
+            let mut meta = stored.remove(&key).unwrap_or_default();
+            meta.last_used = profile.last_used;
+            meta.labels = profile.labels.clone();
+            metadata.insert(key, meta);
         }
 
         let _ = utils::save_profile_metadata(&metadata);
     }
 
-    /// Sort profiles alphabetically by name, updating quick slots
+    fn selected_profile(&self) -> Option<&VpnProfile> {
+        self.profile_list_state
+            .selected()
+            .and_then(|idx| self.profiles.get(idx))
+    }
+
+    /// Open a per-profile settings form for the selected profile.
+    fn open_profile_form(
+        &mut self,
+        form: ProfileForm,
+        first: String,
+        second: String,
+        checked: bool,
+    ) {
+        let Some(profile_idx) = self.profile_list_state.selected() else {
+            return;
+        };
+        let Some(profile) = self.profiles.get(profile_idx) else {
+            return;
+        };
+        self.input_mode = InputMode::ProfileForm {
+            form,
+            profile_idx,
+            profile_name: profile.name.clone(),
+            first_cursor: first.len(),
+            first,
+            second_cursor: second.len(),
+            second,
+            checked,
+            focused_field: FormField::First,
+        };
+    }
+
+    /// Validate and store a split tunnel policy. Keeps the editor open on error.
+    fn save_split_tunnel(&mut self, idx: usize, include: &str, exclude: &str, bypass_lan: bool) {
+        match crate::vpn::split::SplitTunnel::from_fields(include, exclude, bypass_lan) {
+            Ok(policy) => {
+                let summary = policy.summary();
+                self.save_profile_form(idx, "Split tunnel", "SPLIT", &summary, |meta| {
+                    meta.split_tunnel = (!policy.is_empty()).then_some(policy);
+                });
+            }
+            Err(e) => self.show_toast(e, ToastType::Warning),
+        }
+    }
+
+    /// Validate and store a DNS override. Keeps the editor open on error.
+    fn save_dns_override(&mut self, idx: usize, servers: &str, search: &str, block_outside: bool) {
+        match crate::core::dns::DnsOverride::from_fields(servers, search, block_outside) {
+            Ok(dns) => {
+                let summary = dns.summary();
+                self.save_profile_form(idx, "DNS override", "DNS", &summary, |meta| {
+                    meta.dns = (!dns.is_empty()).then_some(dns);
+                });
+            }
+            Err(e) => self.show_toast(e, ToastType::Warning),
+        }
+    }
+
+    /// Write a profile form's result to the metadata file and close the form.
+    fn save_profile_form(
+        &mut self,
+        idx: usize,
+        what: &str,
+        log_prefix: &str,
+        summary: &str,
+        update: impl FnOnce(&mut utils::ProfileMetadata),
+    ) {
+        let Some(profile) = self.profiles.get(idx) else {
+            self.show_toast("Invalid profile index".to_string(), ToastType::Error);
+            return;
+        };
+        let name = profile.name.clone();
+        let key = profile.config_path.to_string_lossy().to_string();
+
+        let mut metadata = utils::load_profile_metadata().unwrap_or_default();
+        update(metadata.entry(key).or_default());
+        if let Err(e) = utils::save_profile_metadata(&metadata) {
+            self.show_toast(e, ToastType::Error);
+            return;
+        }
+
+        self.input_mode = InputMode::Normal;
+        self.log(&format!("{log_prefix}: '{name}' set to {summary}"));
+        let active = match &self.connection_state {
+            ConnectionState::Connected { profile, .. } => profile == &name,
+            _ => false,
+        };
+        let note = if active {
+            " (applies on next connect)"
+        } else {
+            ""
+        };
+        self.show_toast(
+            format!("{what} saved for '{name}'{note}"),
+            ToastType::Success,
+        );
+    }
+
+    /// Sort profiles by sidebar section, then alphabetically by name. The
+    /// selection and any queued connect follow their profile.
     fn sort_profiles(&mut self) {
-        self.profiles.sort_by(|a, b| a.name.cmp(&b.name));
+        let name_at = |idx: Option<usize>| {
+            idx.and_then(|i| self.profiles.get(i))
+                .map(|p| p.name.clone())
+        };
+        let selected = name_at(self.profile_list_state.selected());
+        let pending = name_at(self.pending_connect);
+
+        self.profiles
+            .sort_by_cached_key(|p| (p.section(), p.name.clone()));
+
+        let index_of = |name: Option<String>| {
+            name.and_then(|n| self.profiles.iter().position(|p| p.name == n))
+        };
+        if let Some(idx) = index_of(selected) {
+            self.profile_list_state.select(Some(idx));
+        }
+        self.pending_connect = index_of(pending);
+    }
+
+    /// Change a profile's labels, persist them to `metadata.json` and re-sort
+    /// the sidebar. Returns the profile's name.
+    fn update_labels(
+        &mut self,
+        idx: usize,
+        update: impl FnOnce(&mut ProfileLabels),
+    ) -> Option<String> {
+        let Some(profile) = self.profiles.get_mut(idx) else {
+            self.show_toast("Invalid profile index".to_string(), ToastType::Error);
+            return None;
+        };
+        update(&mut profile.labels);
+        let name = profile.name.clone();
+        self.save_metadata();
+        self.sort_profiles();
+        Some(name)
+    }
+
+    /// Store the group, tags and favorite flag from the labels form.
+    fn save_tags(&mut self, idx: usize, group: &str, tags: &str, favorite: bool) {
+        let group = group.trim();
+        let tags = ProfileLabels::parse_tags(tags);
+        self.input_mode = InputMode::Normal;
+        let Some(name) = self.update_labels(idx, |labels| {
+            labels.group = (!group.is_empty()).then(|| group.to_string());
+            labels.tags = tags;
+            labels.favorite = favorite;
+        }) else {
+            return;
+        };
+        let summary = self
+            .profiles
+            .iter()
+            .find(|p| p.name == name)
+            .map(|p| p.labels.summary())
+            .unwrap_or_default();
+        self.log(&format!("TAGS: '{name}' set to {summary}"));
+        self.show_toast(format!("Labels saved for '{name}'"), ToastType::Success);
+    }
+
+    fn toggle_favorite(&mut self) {
+        let Some(idx) = self.profile_list_state.selected() else {
+            return;
+        };
+        let mut favorite = false;
+        let Some(name) = self.update_labels(idx, |labels| {
+            labels.favorite = !labels.favorite;
+            favorite = labels.favorite;
+        }) else {
+            return;
+        };
+        let message = if favorite {
+            format!("'{name}' added to favorites")
+        } else {
+            format!("'{name}' removed from favorites")
+        };
+        self.show_toast(message, ToastType::Success);
+    }
 
-        // Update quick slots logic removed - key `1` now maps to index `0` dynamically
+    /// Bind a profile to number key `slot` (1-9), taking the slot from any
+    /// other profile, or unbind it (`None`).
+    fn assign_quick_slot(&mut self, idx: usize, slot: Option<u8>) {
+        self.input_mode = InputMode::Normal;
+        if slot.is_some() {
+            for profile in &mut self.profiles {
+                if profile.labels.quick_slot == slot {
+                    profile.labels.quick_slot = None;
+                }
+            }
+        }
+        let Some(name) = self.update_labels(idx, |labels| labels.quick_slot = slot) else {
+            return;
+        };
+        let message = match slot {
+            Some(n) => format!("'{name}' bound to key {n}"),
+            None => format!("'{name}' unbound from its quick slot"),
+        };
+        self.log(&format!("SLOT: {message}"));
+        self.show_toast(message, ToastType::Success);
     }
 
     /// Smart connection toggle: Connect, Disconnect, or Switch.
@@ -1906,47 +3131,15 @@ impl App {
                         self.disconnect();
                     }
                 }
-                // If disconnected -> Connect immediately
+                // If disconnected -> Connect immediately (a manual pick overrides auto-reconnect)
                 ConnectionState::Disconnected => {
+                    self.reconnect = None;
                     self.connect_profile(idx);
                 }
             }
         }
     }
 
-    /// Check if required binaries are available for a given protocol
-    fn check_dependencies(protocol: Protocol) -> Vec<String> {
-        let mut missing = Vec::new();
-        match protocol {
-            Protocol::WireGuard => {
-                if std::process::Command::new("wg-quick")
-                    .arg("--version")
-                    .output()
-                    .is_err()
-                {
-                    missing.push("wg-quick".to_string());
-                }
-                if std::process::Command::new("wg")
-                    .arg("--version")
-                    .output()
-                    .is_err()
-                {
-                    missing.push("wireguard-tools".to_string());
-                }
-            }
-            Protocol::OpenVPN => {
-                if std::process::Command::new("openvpn")
-                    .arg("--version")
-                    .output()
-                    .is_err()
-                {
-                    missing.push("openvpn".to_string());
-                }
-            }
-        }
-        missing
-    }
-
     /// Check for system-wide dependencies at startup and warn the user.
     fn check_system_dependencies(&mut self) {
         let mut missing: Vec<&str> = Vec::new();
@@ -1971,12 +3164,9 @@ impl App {
             missing.push("openvpn");
         }
 
-        if std::process::Command::new("wg-quick")
-            .arg("--version")
-            .stdout(std::process::Stdio::null())
-            .stderr(std::process::Stdio::null())
-            .status()
-            .is_err()
+        if crate::core::connector::check_dependencies(Protocol::WireGuard)
+            .iter()
+            .any(|tool| tool == "wg-quick")
         {
             missing.push("wg-quick");
         }
@@ -2004,53 +3194,129 @@ impl App {
 
     /// Connect to a profile
     #[allow(clippy::too_many_lines)]
-    fn connect_profile(&mut self, idx: usize) {
-        // Clone needed data to release borrow on self
-        let (name, protocol, config_path, cmd_tx) = if let Some(profile) = self.profiles.get(idx) {
-            (
-                profile.name.clone(),
-                profile.protocol,
-                profile.config_path.clone(),
-                self.cmd_tx.clone(),
-            )
+    /// Why a tunnel is being brought up, as told to the hooks.
+    fn connect_reason(&self) -> &'static str {
+        if self.reconnect.is_some() {
+            "reconnect"
         } else {
-            return;
-        };
+            "connect"
+        }
+    }
+
+    /// What the hooks are told about the current tunnel (`None` when disconnected).
+    fn hook_context(&self, reason: &str) -> Option<HookContext> {
+        let (profile, details) = match &self.connection_state {
+            ConnectionState::Connected {
+                profile, details, ..
+            } => (profile, Some(details)),
+            ConnectionState::Connecting { profile, .. }
+            | ConnectionState::Disconnecting { profile, .. } => (profile, None),
+            ConnectionState::Disconnected => return None,
+        };
+        let mut ctx = HookContext::new(profile, reason);
+        if let Some(details) = details {
+            ctx.interface.clone_from(&details.interface);
+            ctx.internal_ip.clone_from(&details.internal_ip);
+            ctx.endpoint.clone_from(&details.endpoint);
+        }
+        Some(ctx)
+    }
+
+    /// Run the `event` hooks on a background thread; their output arrives as
+    /// log messages. An attached daemon runs its own hooks instead.
+    fn spawn_hooks(&self, event: HookEvent, ctx: HookContext) {
+        if self.daemon_socket.is_some()
+            || !hooks::has_hooks(&self.config.hooks, event, &ctx.profile)
+        {
+            return;
+        }
+        let config = self.config.hooks.clone();
+        let cmd_tx = self.cmd_tx.clone();
+        std::thread::spawn(move || {
+            for line in hooks::run(&config, event, &ctx) {
+                let _ = cmd_tx.send(Message::Log(line));
+            }
+        });
+    }
+
+    /// Run the `event` hooks and wait for them.
+    fn run_hooks_inline(&mut self, event: HookEvent, ctx: &HookContext) {
+        for line in hooks::run(&self.config.hooks, event, ctx) {
+            self.log(&line);
+        }
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn connect_profile(&mut self, idx: usize) {
+        // Clone needed data to release borrow on self
+        let (name, protocol, config_path, cmd_tx) = if let Some(profile) = self.profiles.get(idx) {
+            (
+                profile.name.clone(),
+                profile.protocol,
+                profile.config_path.clone(),
+                self.cmd_tx.clone(),
+            )
+        } else {
+            return;
+        };
 
-        // Check dependencies FIRST (no point asking for root if tool is missing)
-        let missing = Self::check_dependencies(protocol);
+        // Check dependencies FIRST (no point asking for root if tool is missing).
+        // With a daemon attached, it runs the tools as root and checks for itself.
+        let missing = if self.daemon_socket.is_some() {
+            Vec::new()
+        } else {
+            (self.connector.check_dependencies)(protocol)
+        };
         if !missing.is_empty() {
             self.input_mode = InputMode::DependencyError { protocol, missing };
             return;
         }
 
         // Check root second
-        if !self.is_root {
+        if !self.is_root && self.daemon_socket.is_none() {
             self.input_mode = InputMode::PermissionDenied {
                 action: format!("Manage {protocol}"),
             };
             return;
         }
 
-        // Check if OpenVPN config needs auth credentials
-        if matches!(protocol, Protocol::OpenVPN) && utils::openvpn_config_needs_auth(&config_path) {
-            // Check for saved credentials first
-            if utils::read_openvpn_saved_auth(&name).is_none() {
-                // No saved creds -- show the auth prompt overlay
-                self.input_mode = InputMode::AuthPrompt {
-                    profile_idx: idx,
-                    profile_name: name,
-                    username: String::new(),
-                    username_cursor: 0,
-                    password: String::new(),
-                    password_cursor: 0,
-                    focused_field: crate::state::AuthField::Username,
-                    save_credentials: true,
-                    connect_after: true,
-                };
-                return;
-            }
-            // Saved creds exist -- they'll be picked up in the thread below
+        // An encrypted profile can't be read until the vault is unlocked.
+        // A daemon unlocks its own vault.
+        if self.daemon_socket.is_none()
+            && crate::core::vault::is_sealed(&config_path)
+            && !crate::core::vault::is_unlocked()
+        {
+            self.input_mode = InputMode::VaultUnlock {
+                passphrase: String::new(),
+                cursor: 0,
+                error: None,
+                connect: Some(name),
+            };
+            return;
+        }
+
+        // OpenVPN config needs auth credentials and none are saved yet --
+        // show the auth prompt overlay. Saved creds are picked up by the launcher.
+        // A `static-challenge` code is only good for a moment, so those
+        // profiles are asked once OpenVPN wants the credentials.
+        if crate::core::connector::needs_auth_prompt(&name, protocol, &config_path)
+            && utils::openvpn_static_challenge(&config_path).is_none()
+        {
+            self.input_mode = InputMode::AuthPrompt {
+                profile_idx: idx,
+                profile_name: name,
+                username: String::new(),
+                username_cursor: 0,
+                password: String::new(),
+                password_cursor: 0,
+                challenge: None,
+                otp: String::new(),
+                otp_cursor: 0,
+                focused_field: crate::state::AuthField::Username,
+                save_credentials: true,
+                connect_after: true,
+            };
+            return;
         }
 
         // Start connecting
@@ -2058,227 +3324,89 @@ impl App {
             started: Instant::now(),
             profile: name.clone(),
         };
+        self.begin_session(&name, std::time::SystemTime::now());
         self.log(&format!("ACTION: Connecting to '{name}' [{protocol}]..."));
 
+        if let Some(socket) = self.daemon_socket.clone() {
+            std::thread::spawn(move || {
+                let request = crate::daemon::protocol::Request::Connect {
+                    profile: name.clone(),
+                };
+                let result = crate::daemon::client::call(&socket, &request, None);
+                let _ = cmd_tx.send(Message::ConnectResult {
+                    profile: name,
+                    success: result.is_ok(),
+                    error: result.err(),
+                });
+            });
+            return;
+        }
+
         let connect_timeout_secs = self.config.connect_timeout;
         let ovpn_verbosity = self.config.openvpn_verbosity.clone();
+        let hooks = self.config.hooks.clone();
+        let hook_ctx = HookContext::new(&name, self.connect_reason());
+        let bring_up = self.connector.bring_up;
 
         // Execute command in background to prevent TUI freeze
-        std::thread::spawn(move || match protocol {
-            Protocol::WireGuard => {
-                // wg-quick is a one-shot command: sets up interface and exits
-                match std::process::Command::new("wg-quick")
-                    .args(["up", config_path.to_str().unwrap_or("")])
-                    .stdout(std::process::Stdio::piped())
-                    .stderr(std::process::Stdio::piped())
-                    .output()
-                {
-                    Ok(out) if out.status.success() => {
-                        let _ = cmd_tx.send(Message::ConnectResult {
-                            profile: name,
-                            success: true,
-                            error: None,
-                        });
-                    }
-                    Ok(out) => {
-                        let stderr = String::from_utf8_lossy(&out.stderr).to_string();
-                        let _ = cmd_tx.send(Message::ConnectResult {
-                            profile: name,
-                            success: false,
-                            error: Some(format!("WireGuard: {stderr}")),
-                        });
-                    }
-                    Err(e) => {
-                        let _ = cmd_tx.send(Message::ConnectResult {
-                            profile: name,
-                            success: false,
-                            error: Some(format!("Failed to execute wg-quick: {e}")),
-                        });
-                    }
-                }
-            }
-            Protocol::OpenVPN => {
-                // OpenVPN is designed to run as a daemon. We use --daemon with
-                // --writepid and --log so we can track the process and poll the
-                // log for definitive success/failure markers.
-                let run_paths = crate::utils::get_openvpn_run_paths(&name);
-                let (pid_path, log_path) = match run_paths {
-                    Ok(paths) => paths,
-                    Err(e) => {
-                        let _ = cmd_tx.send(Message::ConnectResult {
-                            profile: name,
-                            success: false,
-                            error: Some(format!("Failed to create run directory: {e}")),
-                        });
-                        return;
-                    }
-                };
-
-                // Clean up stale files from previous runs
-                let _ = std::fs::remove_file(&pid_path);
-                let _ = std::fs::remove_file(&log_path);
-
-                // Build openvpn args
-                let mut args = vec![
-                    "--config".to_string(),
-                    config_path.to_str().unwrap_or("").to_string(),
-                    "--daemon".to_string(),
-                    format!("vortix-{name}"),
-                    "--writepid".to_string(),
-                    pid_path.to_str().unwrap_or("").to_string(),
-                    "--log".to_string(),
-                    log_path.to_str().unwrap_or("").to_string(),
-                    "--verb".to_string(),
-                    ovpn_verbosity,
-                ];
-
-                // If auth credentials exist, pass them via --auth-user-pass
-                if let Ok(auth_path) = crate::utils::get_openvpn_auth_path(&name) {
-                    if auth_path.exists() {
-                        args.push("--auth-user-pass".to_string());
-                        args.push(auth_path.to_str().unwrap_or("").to_string());
-                    }
-                }
-
-                let output = std::process::Command::new("openvpn")
-                    .args(&args)
-                    .stdout(std::process::Stdio::piped())
-                    .stderr(std::process::Stdio::piped())
-                    .output();
-
-                // --daemon: parent forks and exits. A non-zero exit here means
-                // the config failed basic validation before the fork.
-                match output {
-                    Ok(out) if !out.status.success() => {
-                        let stderr = String::from_utf8_lossy(&out.stderr).to_string();
-                        let _ = cmd_tx.send(Message::ConnectResult {
-                            profile: name,
-                            success: false,
-                            error: Some(format!("OpenVPN: {}", stderr.trim())),
-                        });
-                        return;
-                    }
-                    Err(e) => {
-                        let _ = cmd_tx.send(Message::ConnectResult {
-                            profile: name,
-                            success: false,
-                            error: Some(format!("Failed to start OpenVPN: {e}")),
-                        });
-                        return;
-                    }
-                    Ok(_) => {} // Fork succeeded, daemon is running
-                }
-
-                // Chown the run files so normal users can read PID/log status.
-                // OpenVPN creates them as root; we fix ownership after a brief
-                // delay to let the daemon write them.
-                std::thread::sleep(std::time::Duration::from_millis(
-                    constants::OVPN_CHOWN_DELAY_MS,
-                ));
-                crate::config::fix_ownership(
-                    pid_path.parent().unwrap_or(std::path::Path::new("/")),
-                );
-
-                // Poll the log file for definitive success/failure from the daemon.
-                let timeout = std::time::Duration::from_secs(connect_timeout_secs);
-                let poll_interval = std::time::Duration::from_millis(constants::OVPN_LOG_POLL_MS);
-                let start = std::time::Instant::now();
-
-                loop {
-                    std::thread::sleep(poll_interval);
-
-                    // Check if the daemon died (pid file gone or process not running)
-                    if start.elapsed()
-                        > std::time::Duration::from_secs(constants::OVPN_HEALTH_CHECK_DELAY_SECS)
-                    {
-                        if let Ok(content) = std::fs::read_to_string(&pid_path) {
-                            if let Ok(pid) = content.trim().parse::<u32>() {
-                                // Check if process is still alive
-                                let alive = std::process::Command::new("kill")
-                                    .args(["-0", &pid.to_string()])
-                                    .output()
-                                    .is_ok_and(|o| o.status.success());
-                                if !alive {
-                                    // Daemon died -- read log for the reason
-                                    let log =
-                                        std::fs::read_to_string(&log_path).unwrap_or_default();
-                                    let last_lines: String = log
-                                        .lines()
-                                        .rev()
-                                        .take(constants::OVPN_ERROR_LOG_TAIL_LINES)
-                                        .collect::<Vec<_>>()
-                                        .into_iter()
-                                        .rev()
-                                        .collect::<Vec<_>>()
-                                        .join("\n");
-                                    let _ = cmd_tx.send(Message::ConnectResult {
-                                        profile: name,
-                                        success: false,
-                                        error: Some(format!(
-                                            "OpenVPN daemon exited:\n{last_lines}"
-                                        )),
-                                    });
-                                    return;
-                                }
-                            }
-                        } else if start.elapsed()
-                            > std::time::Duration::from_secs(constants::OVPN_PID_FILE_TIMEOUT_SECS)
-                        {
-                            // No pid file — daemon likely failed to start
-                            let log = std::fs::read_to_string(&log_path)
-                                .unwrap_or_else(|_| "No log output".to_string());
-                            let _ = cmd_tx.send(Message::ConnectResult {
-                                profile: name,
-                                success: false,
-                                error: Some(format!("OpenVPN: no PID file. Log:\n{log}")),
-                            });
-                            return;
-                        }
-                    }
+        std::thread::spawn(move || {
+            use crate::core::connector::LaunchOutcome;
 
-                    // Read the log file and check for markers
-                    if let Ok(log_content) = std::fs::read_to_string(&log_path) {
-                        // Success marker
-                        if log_content.contains(constants::OVPN_LOG_SUCCESS) {
-                            let _ = cmd_tx.send(Message::ConnectResult {
-                                profile: name,
-                                success: true,
-                                error: None,
-                            });
-                            return;
-                        }
+            for line in hooks::run(&hooks, HookEvent::PreUp, &hook_ctx) {
+                let _ = cmd_tx.send(Message::Log(line));
+            }
 
-                        // Error markers
-                        for pattern in constants::OVPN_LOG_ERRORS {
-                            if log_content.contains(pattern) {
-                                // Extract the line containing the error for context
-                                let error_line = log_content
-                                    .lines()
-                                    .find(|l| l.contains(pattern))
-                                    .unwrap_or(pattern);
-                                let _ = cmd_tx.send(Message::ConnectResult {
-                                    profile: name,
-                                    success: false,
-                                    error: Some(format!("OpenVPN: {error_line}")),
-                                });
-                                return;
-                            }
-                        }
-                    }
+            // Credential prompts are answered through the auth overlay
+            let ask = |request: &crate::core::openvpn_mgmt::AuthRequest| {
+                let (reply, answer) = mpsc::channel();
+                cmd_tx
+                    .send(Message::OpenVpnAuth {
+                        profile: name.clone(),
+                        request: request.clone(),
+                        reply,
+                    })
+                    .ok()?;
+                answer
+                    .recv_timeout(std::time::Duration::from_secs(
+                        constants::OVPN_AUTH_PROMPT_TIMEOUT_SECS,
+                    ))
+                    .ok()
+                    .flatten()
+            };
 
+            match bring_up(
+                &name,
+                protocol,
+                &config_path,
+                connect_timeout_secs,
+                &ovpn_verbosity,
+                &ask,
+            ) {
+                Ok(LaunchOutcome::Confirmed) => {
+                    let _ = cmd_tx.send(Message::ConnectResult {
+                        profile: name,
+                        success: true,
+                        error: None,
+                    });
+                }
+                Ok(LaunchOutcome::Unconfirmed) => {
                     // Timeout -- let the scanner take over
-                    if start.elapsed() >= timeout {
-                        let _ = cmd_tx.send(Message::ConnectResult {
-                            profile: name.clone(),
-                            success: true,
-                            error: None,
-                        });
-                        let _ = cmd_tx.send(Message::Log(format!(
-                            "WARN: OpenVPN log confirmation timed out for '{name}' \
-                             after {connect_timeout_secs}s — scanner will confirm tunnel status"
-                        )));
-                        return;
-                    }
+                    let _ = cmd_tx.send(Message::ConnectResult {
+                        profile: name.clone(),
+                        success: true,
+                        error: None,
+                    });
+                    let _ = cmd_tx.send(Message::Log(format!(
+                        "WARN: OpenVPN connection confirmation timed out for '{name}' \
+                         after {connect_timeout_secs}s — scanner will confirm tunnel status"
+                    )));
+                }
+                Err(e) => {
+                    let _ = cmd_tx.send(Message::ConnectResult {
+                        profile: name,
+                        success: false,
+                        error: Some(e),
+                    });
                 }
             }
         });
@@ -2288,49 +3416,45 @@ impl App {
     /// Synchronizes the kill switch state with the current mode and connection status.
     /// This is the single source of truth for kill switch state transitions and firewall control.
     fn sync_killswitch(&mut self) {
-        use crate::state::{KillSwitchMode, KillSwitchState};
+        use crate::state::KillSwitchState;
+
+        if let Some(socket) = &self.daemon_socket {
+            // The daemon decides the state and owns the firewall
+            let request = crate::daemon::protocol::Request::KillSwitch {
+                mode: self.killswitch_mode,
+            };
+            let timeout = std::time::Duration::from_millis(constants::DAEMON_PROBE_TIMEOUT_MS);
+            match crate::daemon::client::call(socket, &request, Some(timeout)) {
+                Ok(status) => self.killswitch_state = status.killswitch_state,
+                Err(e) => self.log(&format!("WARN: Daemon kill switch update failed: {e}")),
+            }
+            return;
+        }
 
         let old_state = self.killswitch_state;
 
         // 1. Determine the target state
-        self.killswitch_state = match self.killswitch_mode {
-            KillSwitchMode::Off => KillSwitchState::Disabled,
-            KillSwitchMode::Auto => {
-                if matches!(self.connection_state, ConnectionState::Connected { .. }) {
-                    KillSwitchState::Armed
-                } else if old_state == KillSwitchState::Blocking {
-                    // Stay blocking if we were already blocking in Auto mode
-                    KillSwitchState::Blocking
-                } else {
-                    KillSwitchState::Armed // Show intent even if disconnected
-                }
-            }
-            KillSwitchMode::AlwaysOn => {
-                if matches!(self.connection_state, ConnectionState::Connected { .. }) {
-                    KillSwitchState::Armed
-                } else {
-                    KillSwitchState::Blocking
-                }
-            }
-        };
+        self.killswitch_state = KillSwitchState::target(
+            self.killswitch_mode,
+            matches!(self.connection_state, ConnectionState::Connected { .. }),
+            old_state,
+        );
 
         // 2. Sync physical firewall state if target state changed or if forcing sync
         if self.killswitch_state != old_state || self.killswitch_state == KillSwitchState::Blocking
         {
             if self.killswitch_state.is_blocking() {
-                // Determine VPN interface and server IP if connected
-                let (interface, server_ip) = match &self.connection_state {
-                    ConnectionState::Connected { details, .. } => (
-                        details.interface.as_str(),
-                        Some(details.endpoint.split(':').next().unwrap_or("")),
-                    ),
-                    _ => (crate::platform::DEFAULT_VPN_INTERFACE, None),
-                };
+                let (interface, server_ip) = self.killswitch_target();
 
                 if self.is_root {
-                    if let Err(e) = crate::core::killswitch::enable_blocking(interface, server_ip) {
+                    if let Err(e) = crate::core::killswitch::enable_blocking(
+                        &interface,
+                        server_ip.as_deref(),
+                        &self.config.killswitch,
+                    ) {
                         self.log(&format!("WARN: Failed to enable kill switch: {e}"));
                     }
+                    self.killswitch_verified_at = Some(Instant::now());
                 }
             } else if old_state.is_blocking() {
                 // Target is not blocking, but we were blocking - release
@@ -2349,92 +3473,490 @@ impl App {
         );
     }
 
-    /// Kill any running VPN process and remove run files for a profile.
-    ///
-    /// Handles both protocols:
-    /// - **`OpenVPN`**: sends SIGTERM to the daemon (via PID file) and removes pid/log files.
-    /// - **`WireGuard`**: runs `wg-quick down` to tear down the interface.
-    ///
-    /// Safe to call even if the process is already gone — all operations are best-effort.
-    fn cleanup_vpn_resources(&self, profile_name: &str) {
-        if let Some(profile) = self.profiles.iter().find(|p| p.name == profile_name) {
-            match profile.protocol {
-                Protocol::OpenVPN => {
-                    if let Some(pid) = utils::read_openvpn_pid(profile_name) {
-                        let _ = std::process::Command::new("kill")
-                            .arg(pid.to_string())
-                            .stdout(std::process::Stdio::null())
-                            .stderr(std::process::Stdio::null())
-                            .output();
-                    }
-                    utils::cleanup_openvpn_run_files(profile_name);
-                }
-                Protocol::WireGuard => {
-                    let _ = std::process::Command::new("wg-quick")
-                        .args(["down", profile.config_path.to_str().unwrap_or("")])
-                        .stdout(std::process::Stdio::null())
-                        .stderr(std::process::Stdio::null())
-                        .output();
-                }
-            }
+    /// VPN interface and server IP the kill switch rules are built for.
+    fn killswitch_target(&self) -> (String, Option<String>) {
+        match (&self.connection_state, &self.reconnect) {
+            (ConnectionState::Connected { details, .. }, _) => (
+                details.interface.clone(),
+                Some(utils::endpoint_host(&details.endpoint).to_string()),
+            ),
+            // Keep the dropped server reachable so reconnect attempts get through
+            (_, Some(rc)) => (rc.interface.clone(), rc.server_ip.clone()),
+            _ => (crate::platform::DEFAULT_VPN_INTERFACE.to_string(), None),
         }
     }
 
-    /// Finalize a disconnect: transition to `Disconnected`, sync kill switch,
-    /// and drain `pending_connect` (auto-connect to the queued profile, if any).
-    ///
-    /// When switching profiles (`pending_connect` is set), the `Disconnected` state
-    /// is never visible — we go straight from `Disconnecting` to `Connecting`.
-    fn complete_disconnect(&mut self, profile_name: &str) {
-        self.session_start = None;
+    /// Periodically check that the kill switch rules we installed are still
+    /// in place. Firewall managers (firewalld, docker, `ufw reload`) flush
+    /// them without notice. The check shells out to the firewall tools, so it
+    /// runs on a worker and reports back as `Message::KillSwitchChecked`.
+    fn verify_killswitch(&mut self) {
+        // 1. Collect the result of the previous check
+        if let Some(rx) = &self.killswitch_check_rx {
+            match rx.try_recv() {
+                Ok(result) => {
+                    self.killswitch_check_rx = None;
+                    self.handle_message(Message::KillSwitchChecked(result));
+                }
+                Err(mpsc::TryRecvError::Empty) => return,
+                Err(mpsc::TryRecvError::Disconnected) => self.killswitch_check_rx = None,
+            }
+        }
 
-        // Clean up OpenVPN runtime files if this was an OpenVPN profile
+        // 2. Start a new one when due
+        if !self.is_root || self.daemon_socket.is_some() || !self.killswitch_state.is_blocking() {
+            return;
+        }
+        let interval = std::time::Duration::from_secs(constants::KILLSWITCH_VERIFY_INTERVAL_SECS);
         if self
-            .profiles
-            .iter()
-            .any(|p| p.name == profile_name && matches!(p.protocol, Protocol::OpenVPN))
+            .killswitch_verified_at
+            .is_some_and(|at| at.elapsed() < interval)
         {
-            crate::utils::cleanup_openvpn_run_files(profile_name);
+            return;
         }
 
-        // Drain pending_connect: switch directly to the next profile
-        // without flashing the Disconnected state in the UI.
-        if let Some(idx) = self.pending_connect.take() {
-            if idx < self.profiles.len() {
-                let next_name = self.profiles[idx].name.clone();
+        let (interface, server_ip) = self.killswitch_target();
+        let config = self.config.killswitch.clone();
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let result =
+                crate::core::killswitch::repair_blocking(&interface, server_ip.as_deref(), &config)
+                    .map_err(|e| e.to_string());
+            let _ = tx.send(result);
+        });
+        self.killswitch_check_rx = Some(rx);
+    }
+
+    /// Handle the outcome of a kill switch rule check: `Ok` lists the drift
+    /// that was found and repaired.
+    fn on_killswitch_checked(&mut self, result: Result<Vec<String>, String>) {
+        if !self.killswitch_state.is_blocking() {
+            // Released while the check ran; undo anything it re-applied
+            if matches!(&result, Ok(drift) if !drift.is_empty()) {
+                if let Err(e) = crate::core::killswitch::disable_blocking() {
+                    self.log(&format!("WARN: Failed to release kill switch: {e}"));
+                }
+            }
+            return;
+        }
+        match result {
+            Ok(drift) => {
+                self.killswitch_verified_at = Some(Instant::now());
+                if !drift.is_empty() {
+                    self.log(&format!(
+                        "ERR: Kill switch rules were modified externally ({}); re-applied",
+                        drift.join("; ")
+                    ));
+                    self.show_toast(
+                        "Kill switch rules were tampered with - re-applied".to_string(),
+                        ToastType::Error,
+                    );
+                }
+            }
+            Err(e) => self.log(&format!(
+                "WARN: Kill switch rules could not be verified: {e}"
+            )),
+        }
+    }
+
+    /// Start the reconnect supervisor for a profile that just dropped.
+    fn schedule_reconnect(&mut self, profile_name: &str, interface: &str, server_ip: &str) {
+        let delay = self.reconnect_delay(1);
+        self.reconnect = Some(crate::state::ReconnectState {
+            profile: profile_name.to_string(),
+            attempt: 1,
+            next_at: Some(Instant::now() + delay),
+            interface: if interface.is_empty() {
+                crate::platform::DEFAULT_VPN_INTERFACE.to_string()
+            } else {
+                interface.to_string()
+            },
+            server_ip: (!server_ip.is_empty()).then(|| server_ip.to_string()),
+        });
+        self.log(&format!(
+            "ACTION: Reconnecting to '{profile_name}' in {}s (attempt 1/{})",
+            delay.as_secs(),
+            self.config.reconnect_max_attempts
+        ));
+    }
+
+    /// Backoff delay for a reconnect attempt, jittered per the config.
+    fn reconnect_delay(&self, attempt: u32) -> std::time::Duration {
+        let entropy = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| u64::from(d.subsec_nanos()));
+        crate::state::backoff_delay(
+            attempt,
+            std::time::Duration::from_secs(self.config.reconnect_base_delay),
+            std::time::Duration::from_secs(self.config.reconnect_max_delay),
+            self.config.reconnect_jitter,
+            entropy,
+        )
+    }
+
+    /// Drive the reconnect supervisor (called every tick).
+    ///
+    /// Fires due attempts and inspects the outcome of the one in flight:
+    /// a tunnel means success, falling back to `Disconnected` means failure.
+    fn supervise_reconnect(&mut self) {
+        let Some(rc) = &self.reconnect else {
+            return;
+        };
+
+        match &self.connection_state {
+            ConnectionState::Connected { profile, .. } => {
+                let profile = profile.clone();
+                let attempt = rc.attempt;
+                self.reconnect = None;
                 self.log(&format!(
-                    "STATUS: Disconnected from '{profile_name}', connecting to '{next_name}'..."
+                    "STATUS: Reconnected to '{profile}' (attempt {attempt})"
                 ));
-                self.connection_state = ConnectionState::Disconnected;
-                self.sync_killswitch();
-                self.connect_profile(idx);
-                return;
+                self.show_toast(format!("Reconnected to '{profile}'"), ToastType::Success);
             }
+            ConnectionState::Disconnected => match rc.next_at {
+                None => self.reconnect_failed(),
+                Some(at) if Instant::now() >= at => self.reconnect_attempt(),
+                Some(_) => {}
+            },
+            // Attempt in flight, or the user is tearing things down
+            ConnectionState::Connecting { .. } | ConnectionState::Disconnecting { .. } => {}
         }
+    }
 
-        // Normal disconnect (no pending switch)
-        self.log(&format!("STATUS: Disconnected from '{profile_name}'"));
-        self.connection_state = ConnectionState::Disconnected;
-        self.sync_killswitch();
-        self.refresh_telemetry();
+    /// Fire the current reconnect attempt.
+    fn reconnect_attempt(&mut self) {
+        let Some(rc) = &mut self.reconnect else {
+            return;
+        };
+        rc.next_at = None;
+        let (profile_name, attempt) = (rc.profile.clone(), rc.attempt);
+
+        let Some(idx) = self.profiles.iter().position(|p| p.name == profile_name) else {
+            self.reconnect = None;
+            self.log(&format!(
+                "ERR: Reconnect cancelled: profile '{profile_name}' no longer exists"
+            ));
+            return;
+        };
+
+        self.log(&format!(
+            "ACTION: Reconnect attempt {attempt}/{} for '{profile_name}'",
+            self.config.reconnect_max_attempts
+        ));
+        self.connect_profile(idx);
+
+        // connect_profile opened an overlay (auth prompt, missing tool, no root)
+        if !matches!(self.connection_state, ConnectionState::Connecting { .. }) {
+            self.reconnect = None;
+            self.log("WARN: Reconnect stopped: manual action required");
+        }
     }
 
-    #[allow(clippy::too_many_lines)]
-    fn disconnect(&mut self) {
-        // Extract connection info from Connected or Connecting state
-        let connection_info = match &self.connection_state {
-            ConnectionState::Connected {
-                profile: ref profile_name,
-                details,
-                ..
-            } => self
-                .profiles
-                .iter()
-                .find(|p| p.name == *profile_name)
-                .map(|profile| {
-                    (
-                        profile.name.clone(),
-                        profile.protocol,
+    /// The attempt in flight failed: schedule the next one or give up.
+    fn reconnect_failed(&mut self) {
+        let Some(rc) = &self.reconnect else {
+            return;
+        };
+        let (profile_name, attempt) = (rc.profile.clone(), rc.attempt);
+        let max = self.config.reconnect_max_attempts;
+
+        if attempt >= max {
+            self.reconnect = None;
+            self.log(&format!(
+                "ERR: Giving up reconnecting to '{profile_name}' after {attempt} attempts"
+            ));
+            self.show_toast(
+                format!("Could not reconnect to '{profile_name}'"),
+                ToastType::Error,
+            );
+            return;
+        }
+
+        let delay = self.reconnect_delay(attempt + 1);
+        if let Some(rc) = &mut self.reconnect {
+            rc.attempt += 1;
+            rc.next_at = Some(Instant::now() + delay);
+        }
+        self.log(&format!(
+            "ACTION: Retrying '{profile_name}' in {}s (attempt {}/{max})",
+            delay.as_secs(),
+            attempt + 1
+        ));
+    }
+
+    /// Stop the reconnect supervisor at the user's request.
+    fn cancel_reconnect(&mut self) {
+        if let Some(rc) = self.reconnect.take() {
+            self.log(&format!("ACTION: Reconnect to '{}' cancelled", rc.profile));
+            self.show_toast("Reconnect cancelled".to_string(), ToastType::Info);
+        }
+    }
+
+    /// Kill any running VPN process and remove run files for a profile.
+    ///
+    /// Handles both protocols:
+    /// - **`OpenVPN`**: sends SIGTERM to the daemon (via PID file) and removes pid/log files.
+    /// - **`WireGuard`**: runs `wg-quick down` to tear down the interface.
+    ///
+    /// Safe to call even if the process is already gone — all operations are best-effort.
+    fn cleanup_vpn_resources(&self, profile_name: &str) {
+        // The daemon cleans up after its own tunnels
+        if self.daemon_socket.is_some() {
+            return;
+        }
+        if let Some(profile) = self.profiles.iter().find(|p| p.name == profile_name) {
+            crate::core::connector::cleanup(profile_name, profile.protocol, &profile.config_path);
+        }
+    }
+
+    /// Start tracking a session of `profile_name` for the history.
+    fn begin_session(&mut self, profile_name: &str, started_at: std::time::SystemTime) {
+        // The daemon owns (and records) the session
+        if self.daemon_socket.is_some() {
+            return;
+        }
+        let protocol = self
+            .profiles
+            .iter()
+            .find(|p| p.name == profile_name)
+            .map(|p| p.protocol)
+            .unwrap_or_default();
+        self.session_tracker = Some(crate::core::history::SessionTracker::new(
+            profile_name,
+            protocol,
+            started_at,
+        ));
+    }
+
+    /// Send a notification for `event` if it is switched on. Desktop
+    /// notifications go out on a worker; the terminal one is written inline,
+    /// between frames.
+    fn notify(&self, event: NotifyEvent, body: &str) {
+        let Some(backend) = self.notify_backend else {
+            return;
+        };
+        if !event.enabled(&self.config.notifications) {
+            return;
+        }
+        match backend {
+            notify::Backend::Desktop => {
+                let body = body.to_string();
+                let cmd_tx = self.cmd_tx.clone();
+                std::thread::spawn(move || {
+                    send_desktop_or_fallback(notify::send_desktop, event, body, &cmd_tx);
+                });
+            }
+            notify::Backend::Terminal => (self.ring_terminal)(event, body),
+        }
+    }
+
+    /// Notify when the kill switch started or stopped blocking since last time.
+    fn notify_killswitch(&mut self) {
+        let now = self.killswitch_state;
+        let was_blocking = std::mem::replace(&mut self.killswitch_notified, now).is_blocking();
+        if was_blocking == now.is_blocking() {
+            return;
+        }
+        if now.is_blocking() {
+            self.notify(
+                NotifyEvent::KillSwitchEngaged,
+                "Traffic outside the tunnel is blocked",
+            );
+        } else {
+            self.notify(
+                NotifyEvent::KillSwitchReleased,
+                "Traffic is no longer blocked",
+            );
+        }
+    }
+
+    /// Record the tracked session, if any, as ended for `reason`.
+    fn end_session(&mut self, reason: EndReason, error: &str) {
+        let Some(tracker) = self.session_tracker.take() else {
+            return;
+        };
+        let record = tracker.finish(reason, error);
+        if let Err(e) = crate::core::history::append(&self.config_dir, &record) {
+            self.log(&format!("WARN: {e}"));
+        }
+    }
+
+    /// The session tracker, while the tunnel is up (telemetry taken while
+    /// connecting still describes the old route).
+    fn connected_tracker(&mut self) -> Option<&mut crate::core::history::SessionTracker> {
+        if matches!(self.connection_state, ConnectionState::Connected { .. }) {
+            self.session_tracker.as_mut()
+        } else {
+            None
+        }
+    }
+
+    /// Fold the current throughput and link quality into the metrics store.
+    /// Latency, jitter and loss count only once a probe has answered.
+    fn record_metrics(&mut self, now: u64) {
+        let probed = self.latency_ms > 0;
+        self.metrics.record(
+            now,
+            &Sample {
+                down: self.current_down,
+                up: self.current_up,
+                uplink_down: self.current_uplink_down,
+                uplink_up: self.current_uplink_up,
+                latency_ms: probed.then_some(self.latency_ms),
+                jitter_ms: probed.then_some(self.jitter_ms),
+                loss_pct: (probed || self.packet_loss > 0.0).then_some(self.packet_loss),
+            },
+        );
+    }
+
+    /// Serve `OpenMetrics` on `metrics_listen`.
+    fn start_exporter(&mut self) {
+        let shared = SharedSnapshot::default();
+        match exporter::serve(&self.config.metrics_listen, shared.clone()) {
+            Ok(addr) => {
+                self.log(&format!("INIT: Serving metrics on http://{addr}/metrics"));
+                self.exporter = Some(shared);
+                self.publish_snapshot();
+            }
+            Err(e) => self.log(&format!("WARN: {e}")),
+        }
+    }
+
+    fn publish_snapshot(&self) {
+        if let Some(shared) = &self.exporter {
+            let snapshot = self.exporter_snapshot();
+            if let Ok(mut current) = shared.lock() {
+                *current = snapshot;
+            }
+        }
+    }
+
+    /// The connection, link quality and leak figures the exporter serves.
+    fn exporter_snapshot(&self) -> Snapshot {
+        let (state, active, handshake) = match &self.connection_state {
+            ConnectionState::Disconnected => (SessionState::Disconnected, None, None),
+            ConnectionState::Connecting { profile, .. } => {
+                (SessionState::Connecting, Some(profile), None)
+            }
+            ConnectionState::Connected {
+                profile, details, ..
+            } => (
+                SessionState::Connected,
+                Some(profile),
+                crate::vpn::wireguard::parse_handshake_age(&details.latest_handshake),
+            ),
+            ConnectionState::Disconnecting { profile, .. } => {
+                (SessionState::Disconnecting, Some(profile), None)
+            }
+        };
+        let probed = self.latency_ms > 0;
+        let dns_leak = match &self.dns_leak {
+            Some(report) => Some(report.is_leaking()),
+            None if self.dns_server.parse::<std::net::IpAddr>().is_ok() => {
+                Some(self.is_dns_leaking(&self.dns_server))
+            }
+            None => None,
+        };
+        Snapshot {
+            state,
+            profiles: self
+                .profiles
+                .iter()
+                .map(|p| ProfileStatus {
+                    name: p.name.clone(),
+                    protocol: p.protocol,
+                    up: state == SessionState::Connected && active == Some(&p.name),
+                })
+                .collect(),
+            rx_rate: self.current_down,
+            tx_rate: self.current_up,
+            handshake_age_secs: handshake,
+            latency_ms: probed.then_some(self.latency_ms),
+            jitter_ms: probed.then_some(self.jitter_ms),
+            loss_pct: (probed || self.packet_loss > 0.0).then_some(self.packet_loss),
+            killswitch_mode: self.killswitch_mode,
+            killswitch_state: self.killswitch_state,
+            dns_leak,
+            ipv6_leak: Some(self.ipv6_leak),
+        }
+    }
+
+    /// Write the metrics store to disk when `persist_metrics` is enabled.
+    fn save_metrics(&mut self) {
+        if !self.config.persist_metrics {
+            return;
+        }
+        let path = self.config_dir.join(constants::METRICS_FILE_NAME);
+        if let Err(e) = self.metrics.save(&path) {
+            self.log(&format!("WARN: Failed to save metrics: {e}"));
+        }
+    }
+
+    /// Finalize a disconnect: transition to `Disconnected`, sync kill switch,
+    /// and drain `pending_connect` (auto-connect to the queued profile, if any).
+    ///
+    /// When switching profiles (`pending_connect` is set), the `Disconnected` state
+    /// is never visible — we go straight from `Disconnecting` to `Connecting`.
+    fn complete_disconnect(&mut self, profile_name: &str) {
+        self.session_start = None;
+        self.end_session(EndReason::User, "");
+        let ctx = self
+            .down_hook
+            .take()
+            .unwrap_or_else(|| HookContext::new(profile_name, "user"));
+        self.spawn_hooks(HookEvent::PostDown, ctx);
+
+        // Clean up OpenVPN runtime files if this was an OpenVPN profile
+        if self
+            .profiles
+            .iter()
+            .any(|p| p.name == profile_name && matches!(p.protocol, Protocol::OpenVPN))
+        {
+            crate::utils::cleanup_openvpn_run_files(profile_name);
+        }
+
+        // Drain pending_connect: switch directly to the next profile
+        // without flashing the Disconnected state in the UI.
+        if let Some(idx) = self.pending_connect.take() {
+            if idx < self.profiles.len() {
+                let next_name = self.profiles[idx].name.clone();
+                self.log(&format!(
+                    "STATUS: Disconnected from '{profile_name}', connecting to '{next_name}'..."
+                ));
+                self.connection_state = ConnectionState::Disconnected;
+                self.sync_killswitch();
+                self.connect_profile(idx);
+                return;
+            }
+        }
+
+        // Normal disconnect (no pending switch)
+        self.log(&format!("STATUS: Disconnected from '{profile_name}'"));
+        self.notify(
+            NotifyEvent::Disconnected,
+            &format!("'{profile_name}' is down"),
+        );
+        self.connection_state = ConnectionState::Disconnected;
+        self.sync_killswitch();
+        self.refresh_telemetry();
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn disconnect(&mut self) {
+        // Extract connection info from Connected or Connecting state
+        let connection_info = match &self.connection_state {
+            ConnectionState::Connected {
+                profile: ref profile_name,
+                details,
+                ..
+            } => self
+                .profiles
+                .iter()
+                .find(|p| p.name == *profile_name)
+                .map(|profile| {
+                    (
+                        profile.name.clone(),
+                        profile.protocol,
                         profile.config_path.clone(),
                         details.pid,
                         self.cmd_tx.clone(),
@@ -2462,6 +3984,12 @@ impl App {
         if let Some((profile_name, protocol, config_path, pid, cmd_tx)) = connection_info {
             self.log(&format!("ACTION: Disconnecting from '{profile_name}'..."));
 
+            // Capture the tunnel details while they're still known
+            let hook_ctx = self.hook_context("user");
+            self.down_hook.clone_from(&hook_ctx);
+            self.post_up_pending = None;
+            let hooks = self.config.hooks.clone();
+
             // Set disconnecting state
             self.connection_state = ConnectionState::Disconnecting {
                 started: Instant::now(),
@@ -2478,60 +4006,24 @@ impl App {
                 );
             }
 
+            let daemon_socket = self.daemon_socket.clone();
             std::thread::spawn(move || {
-                let output = match protocol {
-                    Protocol::WireGuard => std::process::Command::new("wg-quick")
-                        .args(["down", config_path.to_str().unwrap_or("")])
-                        .stdout(std::process::Stdio::piped())
-                        .stderr(std::process::Stdio::piped())
-                        .output(),
-                    Protocol::OpenVPN => {
-                        // Try PID file first (most reliable), then scanner PID, then pkill
-                        let target_pid = crate::utils::read_openvpn_pid(&profile_name).or(pid);
-                        if let Some(p) = target_pid {
-                            std::process::Command::new("kill")
-                                .arg(p.to_string())
-                                .stdout(std::process::Stdio::piped())
-                                .stderr(std::process::Stdio::piped())
-                                .output()
-                        } else {
-                            std::process::Command::new("pkill")
-                                .arg("openvpn")
-                                .stdout(std::process::Stdio::piped())
-                                .stderr(std::process::Stdio::piped())
-                                .output()
+                let result = if let Some(socket) = daemon_socket {
+                    let request = crate::daemon::protocol::Request::Disconnect;
+                    crate::daemon::client::call(&socket, &request, None).map(|_| ())
+                } else {
+                    if let Some(ctx) = hook_ctx {
+                        for line in hooks::run(&hooks, HookEvent::PreDown, &ctx) {
+                            let _ = cmd_tx.send(Message::Log(line));
                         }
                     }
+                    crate::core::connector::bring_down(&profile_name, protocol, &config_path, pid)
                 };
-
-                match output {
-                    Ok(out) if out.status.success() => {
-                        // Clean up OpenVPN runtime files
-                        if matches!(protocol, Protocol::OpenVPN) {
-                            crate::utils::cleanup_openvpn_run_files(&profile_name);
-                        }
-                        let _ = cmd_tx.send(Message::DisconnectResult {
-                            profile: profile_name,
-                            success: true,
-                            error: None,
-                        });
-                    }
-                    Ok(out) => {
-                        let stderr = String::from_utf8_lossy(&out.stderr).to_string();
-                        let _ = cmd_tx.send(Message::DisconnectResult {
-                            profile: profile_name,
-                            success: false,
-                            error: Some(format!("{protocol}: {stderr}")),
-                        });
-                    }
-                    Err(e) => {
-                        let _ = cmd_tx.send(Message::DisconnectResult {
-                            profile: profile_name,
-                            success: false,
-                            error: Some(format!("Failed to execute: {e}")),
-                        });
-                    }
-                }
+                let _ = cmd_tx.send(Message::DisconnectResult {
+                    profile: profile_name,
+                    success: result.is_ok(),
+                    error: result.err(),
+                });
             });
         }
     }
@@ -2539,7 +4031,8 @@ impl App {
     /// Force-disconnect: escalates a stuck disconnect.
     ///
     /// For `OpenVPN`, sends SIGKILL instead of SIGTERM.
-    /// For `WireGuard`, retries `wg-quick down`.
+    /// For `WireGuard`, retries the native teardown or `wg-quick down`.
+    #[allow(clippy::too_many_lines)]
     fn force_disconnect(&mut self) {
         let profile_name =
             if let ConnectionState::Disconnecting { profile, .. } = &self.connection_state {
@@ -2548,6 +4041,14 @@ impl App {
                 return;
             };
 
+        // The daemon owns the teardown; we can only wait for its result
+        if self.daemon_socket.is_some() {
+            self.log(&format!(
+                "INFO: Waiting for daemon to finish disconnecting '{profile_name}'"
+            ));
+            return;
+        }
+
         // Look up protocol and config from the profile
         let force_info = self
             .profiles
@@ -2576,6 +4077,21 @@ impl App {
             };
 
             std::thread::spawn(move || {
+                // A native tunnel has nothing to escalate to: retry its teardown
+                #[cfg(target_os = "linux")]
+                if matches!(protocol, Protocol::WireGuard)
+                    && crate::core::connector::is_native_wireguard(&config_path)
+                {
+                    let result =
+                        crate::core::connector::bring_down(&name, protocol, &config_path, None);
+                    let _ = cmd_tx.send(Message::DisconnectResult {
+                        profile: name,
+                        success: result.is_ok(),
+                        error: result.err().map(|e| format!("Force {protocol}: {e}")),
+                    });
+                    return;
+                }
+
                 let output = match protocol {
                     Protocol::WireGuard => {
                         // Retry wg-quick down
@@ -2805,10 +4321,116 @@ impl App {
         }
     }
 
+    /// Watch for link/address/route changes on a dedicated thread.
+    #[cfg(target_os = "linux")]
+    fn spawn_network_watcher(&self) {
+        use crate::platform::NetworkWatcher;
+
+        let cmd_tx = self.cmd_tx.clone();
+        std::thread::spawn(move || {
+            let result =
+                crate::platform::linux::netwatch::RtnetlinkWatcher::watch(&mut |interfaces| {
+                    cmd_tx
+                        .send(Message::NetworkChanged(interfaces.to_vec()))
+                        .is_ok()
+                });
+            if let Err(e) = result {
+                let _ = cmd_tx.send(Message::Log(format!(
+                    "WARN: Network change detection unavailable: {e}"
+                )));
+            }
+        });
+    }
+
+    /// React to a network change: refresh telemetry and the scanner right away,
+    /// hurry a pending reconnect, and apply `network_change_action` to the tunnel.
+    fn on_network_change(&mut self, interfaces: &[String]) {
+        let tunnel = match &self.connection_state {
+            // The VPN tool is still installing its own routes and rules
+            ConnectionState::Connected { since, .. }
+                if since.elapsed()
+                    < std::time::Duration::from_secs(constants::NETWORK_CHANGE_SETTLE_SECS) =>
+            {
+                return;
+            }
+            ConnectionState::Connected { details, .. } => Some(details.interface.as_str()),
+            // Our own tunnel coming up or going down is not a network change
+            ConnectionState::Connecting { .. } | ConnectionState::Disconnecting { .. } => return,
+            ConnectionState::Disconnected => None,
+        };
+        let changed: Vec<&str> = interfaces
+            .iter()
+            .map(String::as_str)
+            .filter(|i| Some(*i) != tunnel)
+            .collect();
+        // Empty means the watcher could not name the link (e.g. it was removed)
+        if changed.is_empty() && !interfaces.is_empty() {
+            return;
+        }
+
+        let what = if changed.is_empty() {
+            "link removed".to_string()
+        } else {
+            changed.join(", ")
+        };
+        self.log(&format!("NET: Network change detected ({what})"));
+        self.refresh_telemetry();
+        self.poll_scanner();
+
+        // The network may be back: don't sit out the rest of the backoff
+        if let Some(rc) = &mut self.reconnect {
+            if rc.next_at.is_some() {
+                rc.next_at = Some(Instant::now());
+            }
+        }
+
+        // The daemon owns the tunnel and applies the action itself
+        if self.daemon_socket.is_some() {
+            return;
+        }
+        let ConnectionState::Connected {
+            profile, details, ..
+        } = &self.connection_state
+        else {
+            return;
+        };
+
+        match self.config.network_change_action {
+            NetworkChangeAction::None => {}
+            NetworkChangeAction::Rehandshake => {
+                let Some(p) = self.profiles.iter().find(|p| p.name == *profile) else {
+                    return;
+                };
+                let (name, protocol) = (p.name.clone(), p.protocol);
+                let (interface, pid) = (details.interface.clone(), details.pid);
+                let cmd_tx = self.cmd_tx.clone();
+                self.log(&format!(
+                    "ACTION: Re-handshaking '{name}' on the new network"
+                ));
+                std::thread::spawn(move || {
+                    let msg =
+                        match crate::core::connector::rehandshake(&name, protocol, &interface, pid)
+                        {
+                            Ok(()) => format!("NET: Re-handshake sent for '{name}'"),
+                            Err(e) => format!("WARN: Re-handshake failed for '{name}': {e}"),
+                        };
+                    let _ = cmd_tx.send(Message::Log(msg));
+                });
+            }
+            NetworkChangeAction::Reconnect => {
+                let name = profile.clone();
+                self.log(&format!("ACTION: Reconnecting '{name}' on the new network"));
+                self.reconnect();
+            }
+        }
+    }
+
     /// Wake the telemetry worker so it refreshes IP/ISP/latency immediately.
     /// Called after connect, disconnect, or profile switch so the user never
     /// sees stale data.
-    fn refresh_telemetry(&self) {
+    fn refresh_telemetry(&mut self) {
+        // The previous leak test described a different path
+        self.dns_leak = None;
         if let Some(nudge) = &self.telemetry_nudge {
             let _ = nudge.send(());
         }
@@ -2881,20 +4503,16 @@ impl App {
 
     /// Poll the network stats channel and kick off a new fetch if idle.
     ///
-    /// The background thread just reads raw byte totals from the OS.
-    /// Delta calculation (bytes/sec) stays here in the App, keeping state local.
+    /// The background thread just reads raw per-interface counters from the OS.
+    /// Delta calculation (bytes/sec) stays here in the App, keeping state local:
+    /// the active tunnel's interface while connected, every interface otherwise,
+    /// plus the physical uplink for the chart overlay.
     fn poll_network_stats(&mut self) {
         // 1. Try to collect a result from the previous fetch
         if let Some(rx) = &self.netstats_rx {
             match rx.try_recv() {
-                Ok((total_in, total_out)) => {
-                    // Calculate rate (delta since last reading)
-                    if self.last_bytes_in > 0 {
-                        self.current_down = total_in.saturating_sub(self.last_bytes_in);
-                        self.current_up = total_out.saturating_sub(self.last_bytes_out);
-                    }
-                    self.last_bytes_in = total_in;
-                    self.last_bytes_out = total_out;
+                Ok((counters, uplink)) => {
+                    self.apply_network_counters(counters, uplink);
                     self.netstats_rx = None; // Ready for next fetch
                 }
                 Err(mpsc::TryRecvError::Empty) => {
@@ -2909,23 +4527,48 @@ impl App {
         // 2. Kick off a new fetch
         let (tx, rx) = mpsc::channel();
         std::thread::spawn(move || {
-            let totals = {
-                #[cfg(target_os = "macos")]
-                {
-                    use crate::platform::NetworkStatsProvider;
-                    crate::platform::macos::network::MacNetworkStats::get_total_bytes()
-                }
-                #[cfg(target_os = "linux")]
-                {
-                    use crate::platform::NetworkStatsProvider;
-                    crate::platform::linux::network::LinuxNetworkStats::get_total_bytes()
-                }
-            };
-            let _ = tx.send(totals);
+            use crate::platform::NetworkStatsProvider;
+            #[cfg(target_os = "macos")]
+            type Stats = crate::platform::macos::network::MacNetworkStats;
+            #[cfg(target_os = "linux")]
+            type Stats = crate::platform::linux::network::LinuxNetworkStats;
+
+            let _ = tx.send((Stats::get_interface_bytes(), Stats::get_uplink_interface()));
         });
         self.netstats_rx = Some(rx);
     }
 
+    /// Turn a counter reading into rates (delta since the last reading).
+    fn apply_network_counters(&mut self, counters: InterfaceBytes, uplink: Option<String>) {
+        let tunnel = match &self.connection_state {
+            ConnectionState::Connected { details, .. } if !details.interface.is_empty() => {
+                Some(details.interface.clone())
+            }
+            _ => None,
+        }
+        .filter(|iface| counters.contains_key(iface));
+        let uplink = uplink.filter(|iface| Some(iface) != tunnel.as_ref());
+
+        let previous = &self.last_interface_bytes;
+        (self.current_down, self.current_up) =
+            counter_delta(previous, &counters, tunnel.as_deref());
+        (self.current_uplink_down, self.current_uplink_up) = match (&tunnel, &uplink) {
+            (Some(_), Some(iface)) => counter_delta(previous, &counters, Some(iface)),
+            _ => (0, 0),
+        };
+
+        if let (Some(tracker), Some((rx, tx))) = (
+            &mut self.session_tracker,
+            tunnel.as_ref().and_then(|iface| counters.get(iface)),
+        ) {
+            tracker.record_bytes(*rx, *tx);
+        }
+
+        self.chart_interface = tunnel;
+        self.uplink_interface = uplink;
+        self.last_interface_bytes = counters;
+    }
+
     /// Called when terminal is resized
     /// Handle terminal resize.
     /// In TEA, this dispatches a Resize message.
@@ -3085,8 +4728,43 @@ impl Default for App {
     }
 }
 
-#[cfg(test)]
-mod tests {
+/// Show `event` on the desktop with `send` (on a worker thread). When that
+/// fails, e.g. a bus without a notification daemon, hand it back to the UI
+/// thread to ring the terminal, which only it may write to.
+fn send_desktop_or_fallback(
+    send: impl FnOnce(NotifyEvent, &str) -> Result<(), String>,
+    event: NotifyEvent,
+    body: String,
+    cmd_tx: &mpsc::Sender<Message>,
+) {
+    if let Err(error) = send(event, &body) {
+        let _ = cmd_tx.send(Message::NotifyFailed { event, body, error });
+    }
+}
+
+/// Bytes (in, out) moved between two counter readings on `interface`, or
+/// across all non-loopback interfaces for `None`. Interfaces missing from
+/// either reading (just created or removed) count as idle.
+fn counter_delta(
+    previous: &InterfaceBytes,
+    current: &InterfaceBytes,
+    interface: Option<&str>,
+) -> (u64, u64) {
+    current
+        .iter()
+        .filter(|(name, _)| match interface {
+            Some(wanted) => name.as_str() == wanted,
+            None => !crate::platform::is_loopback(name),
+        })
+        .filter_map(|(name, (rx, tx))| {
+            let (prev_rx, prev_tx) = previous.get(name)?;
+            Some((rx.saturating_sub(*prev_rx), tx.saturating_sub(*prev_tx)))
+        })
+        .fold((0, 0), |(down, up), (rx, tx)| (down + rx, up + tx))
+}
+
+#[cfg(test)]
+mod tests {
     use super::*;
     use crate::core::scanner::ActiveSession;
 
@@ -3098,16 +4776,22 @@ mod tests {
             connection_state: ConnectionState::Disconnected,
             profiles: Vec::new(),
             session_start: None,
-            down_history: vec![(0.0, 0.0)],
-            up_history: vec![(0.0, 0.0)],
+            metrics: MetricsStore::default(),
+            chart_window: ChartWindow::default(),
             current_down: 0,
             current_up: 0,
+            chart_interface: None,
+            uplink_interface: None,
+            current_uplink_down: 0,
+            current_uplink_up: 0,
             latency_ms: 0,
             packet_loss: 0.0,
             jitter_ms: 0,
             location: String::new(),
             isp: String::new(),
             dns_server: String::new(),
+            dns_expected: Vec::new(),
+            dns_leak: None,
             ipv6_leak: false,
             public_ip: String::new(),
             real_ip: None,
@@ -3122,27 +4806,52 @@ mod tests {
             action_menu_state: ratatui::widgets::ListState::default(),
             config_scroll: 0,
             profile_list_state: TableState::default(),
+            collapsed_sections: HashSet::new(),
             panel_areas: HashMap::new(),
             toast: None,
             terminal_size: (80, 24),
             is_root: false,
+            connector: crate::core::connector::Ops::default(),
             config: crate::config::AppConfig::default(),
             config_dir: std::env::temp_dir().join("vortix_test"),
             connection_drops: 0,
+            session_tracker: None,
+            history: Vec::new(),
+            benchmark: Vec::new(),
             pending_connect: None,
+            auth_reply: None,
+            exporter: None,
+            reconnect: None,
+            post_up_pending: None,
+            down_hook: None,
+            notify_backend: None,
+            ring_terminal: |_, _| {},
             killswitch_mode: crate::state::KillSwitchMode::Off,
             killswitch_state: crate::state::KillSwitchState::Disabled,
+            killswitch_verified_at: None,
+            killswitch_notified: crate::state::KillSwitchState::default(),
+            daemon_socket: None,
             telemetry_rx: None,
             telemetry_nudge: None,
             cmd_tx,
             cmd_rx,
             scanner_rx: None,
             netstats_rx: None,
-            last_bytes_in: 0,
-            last_bytes_out: 0,
+            killswitch_check_rx: None,
+            last_interface_bytes: InterfaceBytes::new(),
         }
     }
 
+    /// Helper: let `connect_profile` through its root and tool checks without
+    /// touching the host; launches fail at once, reported on `cmd_rx`.
+    fn stub_connector(app: &mut App) {
+        app.is_root = true;
+        app.connector = crate::core::connector::Ops {
+            check_dependencies: |_| Vec::new(),
+            bring_up: |_, _, _, _, _, _| Err("stubbed launch".to_string()),
+        };
+    }
+
     /// Helper: put app into a Connected state for a given profile name.
     fn set_connected(app: &mut App, name: &str) {
         app.session_start = Some(Instant::now());
@@ -3395,6 +5104,7 @@ mod tests {
                 config_path: std::path::PathBuf::from(format!("/tmp/{name}.conf")),
                 location: "Test".to_string(),
                 last_used: None,
+                labels: ProfileLabels::default(),
             });
         }
     }
@@ -3716,10 +5426,11 @@ mod tests {
     fn test_quick_connect_while_connected_switches_vpn() {
         let mut app = test_app();
         add_profiles(&mut app, &["vpn-a", "vpn-b", "vpn-c"]);
+        app.profiles[1].labels.quick_slot = Some(2);
         set_connected(&mut app, "vpn-a");
 
         // Press '2' to switch to vpn-b
-        app.handle_message(Message::QuickConnect(1));
+        app.handle_message(Message::QuickConnect(2));
 
         assert_eq!(app.pending_connect, Some(1));
         assert!(matches!(
@@ -3732,11 +5443,12 @@ mod tests {
     fn test_quick_connect_while_disconnecting_updates_pending() {
         let mut app = test_app();
         add_profiles(&mut app, &["vpn-a", "vpn-b", "vpn-c"]);
+        app.profiles[2].labels.quick_slot = Some(3);
         set_disconnecting(&mut app, "vpn-a");
         app.pending_connect = Some(1); // originally queued vpn-b
 
         // User changes mind, presses '3' for vpn-c
-        app.handle_message(Message::QuickConnect(2));
+        app.handle_message(Message::QuickConnect(3));
 
         assert_eq!(
             app.pending_connect,
@@ -3749,9 +5461,10 @@ mod tests {
     fn test_quick_connect_from_disconnected() {
         let mut app = test_app();
         add_profiles(&mut app, &["vpn-a"]);
+        app.profiles[0].labels.quick_slot = Some(1);
         app.is_root = true;
 
-        app.handle_message(Message::QuickConnect(0));
+        app.handle_message(Message::QuickConnect(1));
 
         // Should go directly to Connecting (no pending)
         assert!(
@@ -3761,6 +5474,174 @@ mod tests {
         assert_eq!(app.pending_connect, None);
     }
 
+    #[test]
+    fn test_quick_connect_empty_slot_ignores_list_order() {
+        let mut app = test_app();
+        add_profiles(&mut app, &["vpn-a", "vpn-b"]);
+        app.is_root = true;
+
+        app.handle_message(Message::QuickConnect(1));
+
+        assert!(matches!(
+            app.connection_state,
+            ConnectionState::Disconnected
+        ));
+        assert!(app
+            .toast
+            .as_ref()
+            .is_some_and(|t| t.message.contains("slot 1 is empty")));
+    }
+
+    // ====================================================================
+    // Groups, favorites and folding
+    // ====================================================================
+
+    /// Helper: profiles spread over Favorites, two groups and Ungrouped.
+    fn add_grouped_profiles(app: &mut App) {
+        add_profiles(app, &["alpha", "bravo", "charlie", "delta", "echo"]);
+        app.profiles[0].labels.group = Some("Work".to_string());
+        app.profiles[1].labels.group = Some("Home".to_string());
+        app.profiles[3].labels.group = Some("Work".to_string());
+        app.profiles[4].labels.favorite = true;
+        app.sort_profiles();
+    }
+
+    fn names(app: &App) -> Vec<&str> {
+        app.profiles.iter().map(|p| p.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_sort_profiles_by_section_keeps_selection() {
+        let mut app = test_app();
+        add_profiles(&mut app, &["alpha", "bravo", "charlie", "delta", "echo"]);
+        app.profile_list_state.select(Some(3)); // delta
+        app.pending_connect = Some(0); // alpha
+        app.profiles[0].labels.group = Some("Work".to_string());
+        app.profiles[1].labels.group = Some("Home".to_string());
+        app.profiles[3].labels.group = Some("Work".to_string());
+        app.profiles[4].labels.favorite = true;
+
+        app.sort_profiles();
+
+        assert_eq!(names(&app), ["echo", "bravo", "alpha", "delta", "charlie"]);
+        assert_eq!(app.profile_list_state.selected(), Some(3));
+        assert_eq!(app.pending_connect, Some(2));
+    }
+
+    #[test]
+    fn test_sidebar_rows_headers_only_when_grouped() {
+        let mut app = test_app();
+        add_profiles(&mut app, &["alpha", "bravo"]);
+        assert_eq!(
+            app.sidebar_rows(),
+            [SidebarRow::Profile(0), SidebarRow::Profile(1)]
+        );
+
+        let mut app = test_app();
+        add_grouped_profiles(&mut app);
+        let rows = app.sidebar_rows();
+        assert_eq!(rows.len(), 9); // 4 headers + 5 profiles
+        assert_eq!(
+            rows[4],
+            SidebarRow::Header {
+                section: ProfileSection::Group("Work".to_string()),
+                first: 2,
+                count: 2,
+                collapsed: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_folded_section_is_one_stop() {
+        let mut app = test_app();
+        add_grouped_profiles(&mut app);
+        app.profile_list_state.select(Some(3)); // delta, in Work
+
+        app.handle_message(Message::ToggleGroup);
+        // Cursor rests on the folded header (Work's first profile)
+        assert_eq!(app.profile_list_state.selected(), Some(2));
+        assert!(app.selection_folded());
+
+        app.handle_message(Message::ProfileMove(SelectionMove::Next));
+        assert_eq!(app.profile_list_state.selected(), Some(4)); // charlie
+        app.handle_message(Message::ProfileMove(SelectionMove::Prev));
+        app.handle_message(Message::ProfileMove(SelectionMove::Prev));
+        assert_eq!(app.profile_list_state.selected(), Some(1)); // bravo
+
+        // Enter on the folded header unfolds it instead of connecting
+        app.handle_message(Message::ProfileMove(SelectionMove::Next));
+        app.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        assert!(app.collapsed_sections.is_empty());
+        assert!(matches!(
+            app.connection_state,
+            ConnectionState::Disconnected
+        ));
+    }
+
+    fn type_text(app: &mut App, text: &str) {
+        for c in text.chars() {
+            app.handle_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+    }
+
+    #[test]
+    fn test_search_narrows_sidebar_and_connects_top_match() {
+        let mut app = test_app();
+        add_grouped_profiles(&mut app);
+        app.profiles[3].labels.tags = vec!["streaming".to_string()]; // delta
+        stub_connector(&mut app);
+
+        app.handle_key(KeyEvent::new(KeyCode::Char('/'), KeyModifiers::NONE));
+        assert!(matches!(app.input_mode, InputMode::Search { .. }));
+
+        // Tags are searched; headers disappear while filtering
+        type_text(&mut app, "strm");
+        assert_eq!(app.sidebar_rows(), [SidebarRow::Profile(3)]);
+        assert_eq!(app.profile_list_state.selected(), Some(3));
+
+        app.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        assert_eq!(app.input_mode, InputMode::Normal);
+        assert!(matches!(
+            &app.connection_state,
+            ConnectionState::Connecting { profile, .. } if profile == "delta"
+        ));
+    }
+
+    #[test]
+    fn test_search_without_match_does_not_connect() {
+        let mut app = test_app();
+        add_profiles(&mut app, &["alpha", "bravo"]);
+        app.profile_list_state.select(Some(0));
+        app.handle_message(Message::OpenSearch);
+
+        type_text(&mut app, "zz");
+        assert!(app.sidebar_rows().is_empty());
+        app.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+        assert!(matches!(
+            app.connection_state,
+            ConnectionState::Disconnected
+        ));
+        assert_eq!(app.input_mode, InputMode::Normal);
+    }
+
+    #[test]
+    fn test_quick_slot_picker_binds_digit() {
+        let mut app = test_app();
+        add_profiles(&mut app, &["vortix-test-slot-a", "vortix-test-slot-b"]);
+        app.profiles[0].labels.quick_slot = Some(4);
+        app.profile_list_state.select(Some(1));
+
+        app.handle_message(Message::OpenQuickSlot);
+        app.handle_key(KeyEvent::new(KeyCode::Char('4'), KeyModifiers::NONE));
+
+        assert_eq!(app.input_mode, InputMode::Normal);
+        // The slot moved: each key connects exactly one profile
+        assert_eq!(app.profiles[0].labels.quick_slot, None);
+        assert_eq!(app.profiles[1].labels.quick_slot, Some(4));
+    }
+
     // ====================================================================
     // Auth prompt tests
     // ====================================================================
@@ -3782,6 +5663,7 @@ mod tests {
                 config_path,
                 location: "Test".to_string(),
                 last_used: None,
+                labels: ProfileLabels::default(),
             });
         }
     }
@@ -3803,6 +5685,7 @@ mod tests {
                 config_path,
                 location: "Test".to_string(),
                 last_used: None,
+                labels: ProfileLabels::default(),
             });
         }
     }
@@ -3901,6 +5784,7 @@ mod tests {
             idx: 0,
             username: "testuser".to_string(),
             password: "testpass".to_string(),
+            response: String::new(),
             save: true,
             connect_after: true,
         });
@@ -3956,6 +5840,9 @@ mod tests {
             username_cursor: 0,
             password: String::new(),
             password_cursor: 0,
+            challenge: None,
+            otp: String::new(),
+            otp_cursor: 0,
             focused_field: AuthField::Username,
             save_credentials: true,
             connect_after: true,
@@ -3986,6 +5873,48 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_auth_dynamic_challenge_sends_only_the_code() {
+        use crate::core::openvpn_mgmt::{AuthRequest, Challenge, ChallengeKind};
+
+        let mut app = test_app();
+        add_openvpn_profiles_with_auth(&mut app, &["otp-vpn"]);
+        let (reply, answer) = mpsc::channel();
+        app.handle_message(Message::OpenVpnAuth {
+            profile: "otp-vpn".to_string(),
+            request: AuthRequest {
+                kind: "Auth".to_string(),
+                username: true,
+                challenge: Some(Challenge {
+                    text: "Enter code".to_string(),
+                    echo: true,
+                    kind: ChallengeKind::Dynamic {
+                        state_id: "abc".to_string(),
+                        username: "alice".to_string(),
+                    },
+                }),
+            },
+            reply,
+        });
+        let InputMode::AuthPrompt { focused_field, .. } = &app.input_mode else {
+            panic!("Expected AuthPrompt");
+        };
+        assert_eq!(*focused_field, AuthField::Otp);
+
+        // The code is the only field; Tab stays on it
+        app.handle_key(KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE));
+        for c in "123456".chars() {
+            app.handle_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        app.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+        let credentials = answer.try_recv().unwrap().unwrap();
+        assert_eq!(credentials.response, "123456");
+        assert!(credentials.password.is_empty());
+        assert_eq!(app.input_mode, InputMode::Normal);
+        assert!(utils::read_openvpn_saved_auth("otp-vpn").is_none());
+    }
+
     #[test]
     fn test_auth_delete_profile_cleans_auth_file() {
         let mut app = test_app();
@@ -3993,7 +5922,8 @@ mod tests {
         app.profile_list_state.select(Some(0));
 
         // Pre-save credentials
-        let auth_path = utils::write_openvpn_auth_file("del-vpn", "user", "pass").unwrap();
+        utils::write_openvpn_auth_file("del-vpn", "user", "pass").unwrap();
+        let auth_path = utils::get_openvpn_auth_path("del-vpn").unwrap();
         assert!(auth_path.exists());
 
         // Delete the profile
@@ -4005,4 +5935,552 @@ mod tests {
             "Auth file should be deleted when profile is deleted"
         );
     }
+
+    #[test]
+    fn test_split_tunnel_editor_opens_and_edits() {
+        let mut app = test_app();
+        add_profiles(&mut app, &["split-vpn"]);
+        app.profile_list_state.select(Some(0));
+
+        app.handle_message(Message::ManageSplitTunnel);
+        assert!(matches!(
+            app.input_mode,
+            InputMode::ProfileForm {
+                form: ProfileForm::SplitTunnel,
+                focused_field: FormField::First,
+                ..
+            }
+        ));
+
+        for c in "10.0.0.0/8".chars() {
+            app.handle_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        app.handle_key(KeyEvent::new(KeyCode::BackTab, KeyModifiers::NONE));
+        app.handle_key(KeyEvent::new(KeyCode::Char(' '), KeyModifiers::NONE));
+
+        if let InputMode::ProfileForm {
+            first,
+            checked,
+            focused_field,
+            ..
+        } = &app.input_mode
+        {
+            assert_eq!(first, "10.0.0.0/8");
+            assert_eq!(*focused_field, FormField::Checkbox);
+            assert!(*checked);
+        } else {
+            panic!("Expected ProfileForm");
+        }
+
+        app.handle_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+        assert_eq!(app.input_mode, InputMode::Normal);
+    }
+
+    fn profile_form(form: ProfileForm, first: &str, second: &str) -> InputMode {
+        InputMode::ProfileForm {
+            form,
+            profile_idx: 0,
+            profile_name: "form-vpn".to_string(),
+            first: first.to_string(),
+            first_cursor: first.len(),
+            second: second.to_string(),
+            second_cursor: second.len(),
+            checked: false,
+            focused_field: FormField::First,
+        }
+    }
+
+    #[test]
+    fn test_split_tunnel_invalid_entry_keeps_editor_open() {
+        let mut app = test_app();
+        add_profiles(&mut app, &["form-vpn"]);
+        app.input_mode = profile_form(ProfileForm::SplitTunnel, "10.0.0.0/40", "");
+
+        app.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+        assert!(matches!(app.input_mode, InputMode::ProfileForm { .. }));
+        assert!(app
+            .toast
+            .as_ref()
+            .is_some_and(|t| t.toast_type == ToastType::Warning));
+    }
+
+    #[test]
+    fn test_dns_form_rejects_hostname_server() {
+        let mut app = test_app();
+        add_profiles(&mut app, &["form-vpn"]);
+        app.input_mode = profile_form(ProfileForm::Dns, "dns.example.com", "corp.example");
+
+        app.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+        assert!(matches!(
+            app.input_mode,
+            InputMode::ProfileForm {
+                form: ProfileForm::Dns,
+                ..
+            }
+        ));
+        assert!(app
+            .toast
+            .as_ref()
+            .is_some_and(|t| t.message.contains("Invalid DNS server")));
+    }
+
+    #[test]
+    fn test_dns_leak_uses_override_servers() {
+        let mut app = test_app();
+        assert!(app.is_dns_leaking("192.168.1.1"));
+        assert!(!app.is_dns_leaking("1.1.1.1"));
+
+        app.dns_expected = vec!["10.8.0.1".to_string()];
+        assert!(!app.is_dns_leaking("10.8.0.1"));
+        assert!(app.is_dns_leaking("1.1.1.1"));
+    }
+
+    // ====================================================================
+    // Reconnect supervisor tests
+    // ====================================================================
+
+    /// Helper: reconnect config with no delay so attempts are due immediately.
+    fn immediate_reconnect(app: &mut App, max_attempts: u32) {
+        app.config.reconnect_base_delay = 0;
+        app.config.reconnect_jitter = 0;
+        app.config.reconnect_max_attempts = max_attempts;
+    }
+
+    #[test]
+    fn test_drop_schedules_reconnect() {
+        let mut app = test_app();
+        add_profiles(&mut app, &["vpn-a"]);
+        set_connected(&mut app, "vpn-a");
+
+        app.handle_message(Message::SyncSystemState(vec![]));
+
+        let rc = app
+            .reconnect
+            .as_ref()
+            .expect("reconnect should be scheduled");
+        assert_eq!(rc.profile, "vpn-a");
+        assert_eq!(rc.attempt, 1);
+        assert_eq!(rc.interface, "wg0");
+        assert!(rc.next_at.is_some());
+    }
+
+    #[test]
+    fn test_dropped_session_lands_in_history() {
+        let mut app = test_app();
+        app.config_dir =
+            std::env::temp_dir().join(format!("vortix-app-history-{}", std::process::id()));
+        std::fs::create_dir_all(&app.config_dir).unwrap();
+        add_profiles(&mut app, &["vpn-a"]);
+
+        // Adopt a running tunnel, measure it, then lose it
+        app.handle_message(Message::SyncSystemState(vec![fake_session("vpn-a")]));
+        app.handle_message(Message::Telemetry(TelemetryUpdate::Latency(40)));
+        app.handle_message(Message::Telemetry(TelemetryUpdate::PublicIp(
+            "203.0.113.5".to_string(),
+        )));
+        app.handle_message(Message::SyncSystemState(vec![]));
+
+        app.handle_message(Message::OpenHistory);
+        let _ = std::fs::remove_dir_all(&app.config_dir);
+        assert!(matches!(app.input_mode, InputMode::History { .. }));
+        assert_eq!(app.history.len(), 1);
+        let record = &app.history[0];
+        assert_eq!(record.profile, "vpn-a");
+        assert_eq!(record.end_reason, crate::core::history::EndReason::Drop);
+        assert_eq!(record.avg_latency_ms, Some(40));
+        assert_eq!(record.exit_ip, "203.0.113.5");
+
+        // Typing filters; nothing matches "zz"
+        app.handle_key(KeyEvent::from(KeyCode::Char('z')));
+        app.handle_key(KeyEvent::from(KeyCode::Char('z')));
+        assert!(app.filtered_history("zz").is_empty());
+        assert!(matches!(
+            &app.input_mode,
+            InputMode::History { filter, selected: 0, .. } if filter == "zz"
+        ));
+        app.handle_key(KeyEvent::from(KeyCode::Esc));
+        assert_eq!(app.input_mode, InputMode::Normal);
+    }
+
+    #[test]
+    fn test_metrics_sample_and_chart_window() {
+        use crate::core::metrics::Metric;
+
+        let mut app = test_app();
+        app.current_down = 2048;
+        app.packet_loss = 2.5;
+        app.record_metrics(1_700_000_000);
+        // Latency counts only once a probe answered
+        app.latency_ms = 30;
+        app.record_metrics(1_700_000_001);
+
+        let at = |window, metric| app.metrics.series(window, metric, 1_700_000_001);
+        let latency = at(ChartWindow::Minute, Metric::Latency);
+        assert_eq!(latency[58..], [None, Some(30.0)]);
+        assert_eq!(at(ChartWindow::Minute, Metric::Loss)[58], Some(2.5));
+        assert_eq!(at(ChartWindow::Hour, Metric::Down)[59], Some(2048.0));
+
+        app.focused_panel = FocusedPanel::Chart;
+        app.handle_key(KeyEvent::from(KeyCode::Char('w')));
+        assert_eq!(app.chart_window, ChartWindow::Hour);
+        app.handle_key(KeyEvent::from(KeyCode::Char('w')));
+        app.handle_key(KeyEvent::from(KeyCode::Char('w')));
+        assert_eq!(app.chart_window, ChartWindow::Minute);
+    }
+
+    #[test]
+    fn test_post_up_hook_waits_for_scanner_details() {
+        let mut app = test_app();
+        add_profiles(&mut app, &["vpn-a"]);
+        app.connection_state = ConnectionState::Connecting {
+            started: Instant::now(),
+            profile: "vpn-a".to_string(),
+        };
+        app.handle_message(Message::ConnectResult {
+            profile: "vpn-a".to_string(),
+            success: true,
+            error: None,
+        });
+        assert_eq!(app.post_up_pending, Some("connect"));
+        assert_eq!(app.hook_context("connect").unwrap().interface, "");
+
+        app.handle_message(Message::SyncSystemState(vec![fake_session("vpn-a")]));
+        assert_eq!(app.post_up_pending, None);
+        let ctx = app.hook_context("drop").unwrap();
+        assert_eq!(
+            (
+                ctx.profile.as_str(),
+                ctx.interface.as_str(),
+                ctx.internal_ip.as_str()
+            ),
+            ("vpn-a", "wg0", "10.0.0.2")
+        );
+        assert_eq!(ctx.endpoint, "1.2.3.4:51820");
+    }
+
+    #[test]
+    fn test_benchmark_connects_selected_server() {
+        use crate::core::benchmark::{ProbeMethod, ProbeResult};
+
+        let mut app = test_app();
+        add_profiles(&mut app, &["vpn-a", "vpn-b"]);
+        set_connected(&mut app, "vpn-a");
+
+        app.handle_message(Message::OpenBenchmark);
+        // No profile has this tag or location: nothing starts
+        for c in "zz".chars() {
+            app.handle_key(KeyEvent::from(KeyCode::Char(c)));
+        }
+        app.handle_key(KeyEvent::from(KeyCode::Enter));
+        assert!(matches!(
+            app.input_mode,
+            InputMode::Benchmark { running: false, .. }
+        ));
+
+        app.handle_key(KeyEvent::from(KeyCode::Backspace));
+        app.handle_key(KeyEvent::from(KeyCode::Backspace));
+        app.handle_key(KeyEvent::from(KeyCode::Enter));
+        assert!(matches!(
+            app.input_mode,
+            InputMode::Benchmark { running: true, .. }
+        ));
+
+        let fastest = ProbeResult {
+            method: Some(ProbeMethod::Icmp),
+            rtt_ms: Some(12),
+            loss_pct: 0.0,
+            ..ProbeResult::failed("vpn-b", "10.0.0.2:51820/udp".to_string(), String::new())
+        };
+        let slower = ProbeResult {
+            profile: "vpn-a".to_string(),
+            rtt_ms: Some(40),
+            ..fastest.clone()
+        };
+        app.handle_message(Message::BenchmarkResult(vec![fastest, slower]));
+        assert!(matches!(
+            app.input_mode,
+            InputMode::Benchmark {
+                running: false,
+                selected: 0,
+                ..
+            }
+        ));
+
+        // Enter connects the winner: switch away from vpn-a
+        app.handle_key(KeyEvent::from(KeyCode::Enter));
+        assert_eq!(app.input_mode, InputMode::Normal);
+        assert_eq!(app.pending_connect, Some(1));
+        assert!(matches!(
+            app.connection_state,
+            ConnectionState::Disconnecting { .. }
+        ));
+    }
+
+    #[test]
+    fn test_drop_without_auto_reconnect() {
+        let mut app = test_app();
+        app.config.auto_reconnect = false;
+        add_profiles(&mut app, &["vpn-a"]);
+        set_connected(&mut app, "vpn-a");
+
+        app.handle_message(Message::SyncSystemState(vec![]));
+
+        assert!(app.reconnect.is_none());
+    }
+
+    #[test]
+    fn test_reconnect_attempt_then_retry() {
+        let mut app = test_app();
+        stub_connector(&mut app);
+        immediate_reconnect(&mut app, 3);
+        add_profiles(&mut app, &["vpn-a"]);
+        set_connected(&mut app, "vpn-a");
+        app.handle_message(Message::SyncSystemState(vec![]));
+
+        // Due immediately: fires the first attempt
+        app.supervise_reconnect();
+        assert!(matches!(
+            app.connection_state,
+            ConnectionState::Connecting { ref profile, .. } if profile == "vpn-a"
+        ));
+        assert_eq!(app.reconnect.as_ref().unwrap().next_at, None);
+
+        // Attempt fails: the next one is scheduled
+        app.handle_message(Message::ConnectResult {
+            profile: "vpn-a".to_string(),
+            success: false,
+            error: Some("handshake failed".to_string()),
+        });
+        app.supervise_reconnect();
+        let rc = app.reconnect.as_ref().unwrap();
+        assert_eq!(rc.attempt, 2);
+        assert!(rc.next_at.is_some());
+    }
+
+    #[test]
+    fn test_reconnect_gives_up_after_max_attempts() {
+        let mut app = test_app();
+        app.is_root = true;
+        immediate_reconnect(&mut app, 1);
+        add_profiles(&mut app, &["vpn-a"]);
+        set_connected(&mut app, "vpn-a");
+        app.handle_message(Message::SyncSystemState(vec![]));
+
+        app.supervise_reconnect();
+        app.handle_message(Message::ConnectResult {
+            profile: "vpn-a".to_string(),
+            success: false,
+            error: Some("handshake failed".to_string()),
+        });
+        app.supervise_reconnect();
+
+        assert!(app.reconnect.is_none());
+        assert!(matches!(
+            app.connection_state,
+            ConnectionState::Disconnected
+        ));
+    }
+
+    #[test]
+    fn test_reconnect_success_clears_state() {
+        let mut app = test_app();
+        app.is_root = true;
+        immediate_reconnect(&mut app, 3);
+        add_profiles(&mut app, &["vpn-a"]);
+        set_connected(&mut app, "vpn-a");
+        app.handle_message(Message::SyncSystemState(vec![]));
+
+        app.supervise_reconnect();
+        app.handle_message(Message::ConnectResult {
+            profile: "vpn-a".to_string(),
+            success: true,
+            error: None,
+        });
+        app.supervise_reconnect();
+
+        assert!(app.reconnect.is_none());
+    }
+
+    #[test]
+    fn test_disconnect_key_cancels_reconnect() {
+        let mut app = test_app();
+        add_profiles(&mut app, &["vpn-a"]);
+        set_connected(&mut app, "vpn-a");
+        app.handle_message(Message::SyncSystemState(vec![]));
+        assert!(app.reconnect.is_some());
+
+        app.handle_message(Message::Disconnect);
+
+        assert!(app.reconnect.is_none());
+    }
+
+    // ====================================================================
+    // Network change tests
+    // ====================================================================
+
+    /// Helper: move the connect past the network change settle window.
+    fn settle_connection(app: &mut App) {
+        if let ConnectionState::Connected { since, .. } = &mut app.connection_state {
+            *since -= std::time::Duration::from_secs(constants::NETWORK_CHANGE_SETTLE_SECS + 1);
+        }
+    }
+
+    #[test]
+    fn test_network_change_right_after_connect_ignored() {
+        let mut app = test_app();
+        app.config.network_change_action = NetworkChangeAction::Reconnect;
+        add_profiles(&mut app, &["vpn-a"]);
+        set_connected(&mut app, "vpn-a");
+
+        // The VPN tool's own host routes and rules on the uplink
+        app.handle_message(Message::NetworkChanged(vec!["eth0".to_string()]));
+
+        assert!(matches!(
+            app.connection_state,
+            ConnectionState::Connected { .. }
+        ));
+        assert_eq!(app.pending_connect, None);
+    }
+
+    #[test]
+    fn test_network_change_on_own_tunnel_ignored() {
+        let mut app = test_app();
+        app.config.network_change_action = NetworkChangeAction::Reconnect;
+        add_profiles(&mut app, &["vpn-a"]);
+        set_connected(&mut app, "vpn-a");
+        settle_connection(&mut app);
+
+        app.handle_message(Message::NetworkChanged(vec!["wg0".to_string()]));
+
+        assert!(matches!(
+            app.connection_state,
+            ConnectionState::Connected { .. }
+        ));
+        assert_eq!(app.pending_connect, None);
+    }
+
+    #[test]
+    fn test_network_change_reconnect_action() {
+        let mut app = test_app();
+        app.config.network_change_action = NetworkChangeAction::Reconnect;
+        add_profiles(&mut app, &["vpn-a"]);
+        set_connected(&mut app, "vpn-a");
+        settle_connection(&mut app);
+
+        app.handle_message(Message::NetworkChanged(vec!["wlan0".to_string()]));
+
+        assert!(matches!(
+            app.connection_state,
+            ConnectionState::Disconnecting { .. }
+        ));
+        assert_eq!(app.pending_connect, Some(0));
+    }
+
+    #[test]
+    fn test_network_change_hurries_pending_reconnect() {
+        let mut app = test_app();
+        add_profiles(&mut app, &["vpn-a"]);
+        set_connected(&mut app, "vpn-a");
+        app.config.reconnect_base_delay = 60;
+        app.handle_message(Message::SyncSystemState(vec![]));
+        assert!(app.reconnect.as_ref().unwrap().remaining_secs() > 1);
+
+        app.handle_message(Message::NetworkChanged(vec!["wlan0".to_string()]));
+
+        assert_eq!(app.reconnect.as_ref().unwrap().remaining_secs(), 0);
+    }
+
+    #[test]
+    fn test_throughput_follows_tunnel_interface() {
+        let mut app = test_app();
+        let reading = |eth0: (u64, u64), wg0: (u64, u64)| -> InterfaceBytes {
+            [
+                ("lo".to_string(), (500, 500)),
+                ("eth0".to_string(), eth0),
+                ("wg0".to_string(), wg0),
+            ]
+            .into_iter()
+            .collect()
+        };
+
+        // Disconnected: all non-loopback interfaces combined
+        app.apply_network_counters(reading((1000, 1000), (0, 0)), Some("eth0".to_string()));
+        app.apply_network_counters(reading((3000, 1500), (0, 0)), Some("eth0".to_string()));
+        assert_eq!((app.current_down, app.current_up), (2000, 500));
+        assert_eq!(app.chart_interface, None);
+        assert_eq!(app.current_uplink_down, 0);
+
+        // Connected: the tunnel alone, with the uplink alongside
+        set_connected(&mut app, "vpn");
+        app.apply_network_counters(reading((7000, 3000), (1000, 400)), Some("eth0".to_string()));
+        assert_eq!(app.chart_interface.as_deref(), Some("wg0"));
+        assert_eq!((app.current_down, app.current_up), (1000, 400));
+        assert_eq!(
+            (app.current_uplink_down, app.current_uplink_up),
+            (4000, 1500)
+        );
+    }
+
+    #[test]
+    fn test_killswitch_check_only_counts_when_successful() {
+        let mut app = test_app();
+        app.killswitch_mode = crate::state::KillSwitchMode::AlwaysOn;
+        app.killswitch_state = crate::state::KillSwitchState::Blocking;
+
+        app.handle_message(Message::KillSwitchChecked(
+            Err("iptables: busy".to_string()),
+        ));
+        assert!(app.killswitch_verified_at.is_none());
+
+        app.handle_message(Message::KillSwitchChecked(Ok(vec![
+            "missing rule".to_string()
+        ])));
+        assert!(app.killswitch_verified_at.is_some());
+        assert!(app.toast.is_some());
+    }
+
+    thread_local! {
+        /// Terminal notifications written by [`record_ring`] on this test's thread.
+        static RUNG: std::cell::RefCell<Vec<(NotifyEvent, String)>> =
+            const { std::cell::RefCell::new(Vec::new()) };
+    }
+
+    fn record_ring(event: NotifyEvent, body: &str) {
+        RUNG.with(|r| r.borrow_mut().push((event, body.to_string())));
+    }
+
+    #[test]
+    fn test_failed_desktop_notification_falls_back_to_terminal() {
+        let mut app = test_app();
+        app.ring_terminal = record_ring;
+        let cmd_tx = app.cmd_tx.clone();
+        send_desktop_or_fallback(
+            |_, _| Err("no notification daemon".to_string()),
+            NotifyEvent::Dropped,
+            "'work' dropped".to_string(),
+            &cmd_tx,
+        );
+        let message = app.cmd_rx.try_recv().unwrap();
+        assert!(matches!(
+            &message,
+            Message::NotifyFailed { event: NotifyEvent::Dropped, body, .. } if body == "'work' dropped"
+        ));
+        // Rings the terminal from the UI thread
+        app.handle_message(message);
+        assert_eq!(
+            RUNG.with(std::cell::RefCell::take),
+            vec![(NotifyEvent::Dropped, "'work' dropped".to_string())]
+        );
+
+        // Delivered: nothing comes back
+        send_desktop_or_fallback(
+            |_, _| Ok(()),
+            NotifyEvent::Connected,
+            String::new(),
+            &cmd_tx,
+        );
+        assert!(app.cmd_rx.try_recv().is_err());
+    }
 }
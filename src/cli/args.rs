@@ -1,8 +1,12 @@
 //! Command-line argument definitions.
 
+use std::net::SocketAddr;
 use std::path::PathBuf;
 
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
+
+use crate::core::history::EndReason;
+use crate::core::secrets::CredentialStore;
 
 /// Terminal UI for `WireGuard` and `OpenVPN` with real-time telemetry and leak guarding
 #[derive(Parser, Debug)]
@@ -18,6 +22,10 @@ pub struct Args {
     )]
     pub config_dir: Option<PathBuf>,
 
+    /// Serve `OpenMetrics` (tunnel state, throughput, latency, leaks) on this address
+    #[arg(long, value_name = "ADDR", global = true)]
+    pub metrics_listen: Option<SocketAddr>,
+
     /// Subcommand to execute
     #[command(subcommand)]
     pub command: Option<Commands>,
@@ -37,6 +45,113 @@ pub enum Commands {
     Update,
     /// Emergency release of kill switch (use if locked out)
     ReleaseKillSwitch,
+    /// Inspect the kill switch firewall rules
+    Killswitch {
+        #[command(subcommand)]
+        action: KillswitchCommand,
+    },
     /// Generate a pre-filled bug report with system diagnostics
     Report,
+    /// Connect to a profile without starting the TUI
+    Connect {
+        /// Profile name (as shown in the sidebar)
+        profile: String,
+    },
+    /// Disconnect active VPN session(s) without starting the TUI
+    Disconnect {
+        /// Only disconnect this profile (default: every active session)
+        profile: Option<String>,
+    },
+    /// Show active VPN sessions
+    Status {
+        /// Print machine-readable JSON instead of text
+        #[arg(long)]
+        json: bool,
+    },
+    /// List past VPN sessions, newest first
+    History {
+        /// Only sessions of this profile
+        #[arg(long, value_name = "PROFILE")]
+        profile: Option<String>,
+        /// Only sessions that ended this way (user, drop, timeout, error)
+        #[arg(long, value_name = "REASON")]
+        reason: Option<EndReason>,
+        /// Show at most this many sessions
+        #[arg(short = 'n', long, value_name = "N")]
+        limit: Option<usize>,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = HistoryFormat::Table)]
+        format: HistoryFormat,
+    },
+    /// Run in the background, owning the connection and kill switch
+    Daemon {
+        /// Connect to this profile as soon as the daemon starts
+        #[arg(long, value_name = "PROFILE")]
+        connect: Option<String>,
+    },
+    /// Manage saved `OpenVPN` credentials
+    Auth {
+        #[command(subcommand)]
+        action: AuthCommand,
+    },
+    /// Encrypt profiles and saved credentials with a passphrase
+    Vault {
+        #[command(subcommand)]
+        action: VaultCommand,
+    },
+}
+
+/// `vortix auth` subcommands
+#[derive(Subcommand, Debug)]
+pub enum AuthCommand {
+    /// Move saved credentials between auth files and the system keyring
+    Migrate {
+        /// Destination store, `file` or `keyring` (default: `credential_store` from config.toml)
+        #[arg(long, value_name = "STORE")]
+        to: Option<CredentialStore>,
+    },
+    /// Store the authenticator seed that answers a profile's OTP challenge
+    Totp {
+        /// Profile name (as shown in the sidebar)
+        profile: String,
+        /// Remove the stored seed instead
+        #[arg(long)]
+        clear: bool,
+    },
+}
+
+/// `vortix vault` subcommands
+#[derive(Subcommand, Debug)]
+pub enum VaultCommand {
+    /// Choose a passphrase and encrypt every profile and saved credential
+    Init,
+    /// Show whether the vault is enabled and what it covers
+    Status,
+    /// Decrypt every file and turn the vault off
+    Disable,
+}
+
+/// `vortix killswitch` subcommands
+#[derive(Subcommand, Debug)]
+pub enum KillswitchCommand {
+    /// Print the exact firewall commands the kill switch would run
+    Plan {
+        /// Tunnel interface (default: the active session's, else the platform default)
+        #[arg(long, value_name = "IFACE")]
+        interface: Option<String>,
+        /// VPN server address to exempt (default: the active session's endpoint)
+        #[arg(long, value_name = "IP")]
+        server: Option<String>,
+    },
+}
+
+/// Output format of `vortix history`
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HistoryFormat {
+    /// Aligned columns for reading
+    Table,
+    /// A JSON array of records
+    Json,
+    /// Comma-separated values with a header row
+    Csv,
 }
@@ -1,6 +1,7 @@
 //! CLI command handlers.
 
-use crate::cli::args::Commands;
+use crate::cli::args::{AuthCommand, Commands, KillswitchCommand, VaultCommand};
+use crate::config::AppConfig;
 use color_eyre::Result;
 use std::path::Path;
 
@@ -9,9 +10,16 @@ use std::path::Path;
 /// Returns `true` if the command was handled and the program should exit,
 /// or `false` if the TUI should be started.
 #[allow(clippy::unnecessary_wraps)]
-pub fn handle_command(command: &Commands, config_dir: &Path, config_source: &str) -> Result<bool> {
+pub fn handle_command(
+    command: &Commands,
+    config: &AppConfig,
+    config_dir: &Path,
+    config_source: &str,
+) -> Result<bool> {
     match command {
         Commands::Import { file } => {
+            // Imported profiles are stored encrypted
+            super::vault::unlock_or_exit(config_dir);
             handle_import(file);
             Ok(true)
         }
@@ -27,10 +35,61 @@ pub fn handle_command(command: &Commands, config_dir: &Path, config_source: &str
             handle_release_killswitch();
             Ok(true)
         }
+        Commands::Killswitch {
+            action: KillswitchCommand::Plan { interface, server },
+        } => {
+            super::session::killswitch_plan(interface.as_deref(), server.as_deref(), config);
+            Ok(true)
+        }
         Commands::Report => {
             super::report::run(config_dir, config_source);
             Ok(true)
         }
+        Commands::Connect { profile } => {
+            super::session::connect(profile, config);
+            Ok(true)
+        }
+        Commands::Disconnect { profile } => {
+            super::session::disconnect(profile.as_deref(), config);
+            Ok(true)
+        }
+        Commands::Status { json } => {
+            super::session::status(*json);
+            Ok(true)
+        }
+        Commands::History {
+            profile,
+            reason,
+            limit,
+            format,
+        } => {
+            super::session::history(config_dir, profile.as_deref(), *reason, *limit, *format);
+            Ok(true)
+        }
+        Commands::Daemon { connect } => {
+            super::session::daemon(connect.clone(), config);
+            Ok(true)
+        }
+        Commands::Auth {
+            action: AuthCommand::Migrate { to },
+        } => {
+            handle_auth_migrate(config_dir, to.unwrap_or_else(crate::core::secrets::store));
+            Ok(true)
+        }
+        Commands::Auth {
+            action: AuthCommand::Totp { profile, clear },
+        } => {
+            handle_auth_totp(config_dir, profile, *clear);
+            Ok(true)
+        }
+        Commands::Vault { action } => {
+            match action {
+                VaultCommand::Init => super::vault::init(config_dir),
+                VaultCommand::Status => super::vault::status(config_dir),
+                VaultCommand::Disable => super::vault::disable(config_dir),
+            }
+            Ok(true)
+        }
     }
 }
 
@@ -191,6 +250,135 @@ fn handle_info(config_dir: &Path, source: &str) {
     );
 }
 
+/// Moves the saved credentials of every `OpenVPN` profile into `to`.
+///
+/// Each pair is written to the destination and read back before it is
+/// removed from the source, so a failed or interrupted run leaves nothing lost.
+fn handle_auth_migrate(config_dir: &Path, to: crate::core::secrets::CredentialStore) {
+    use crate::core::secrets::{self, CredentialStore};
+    use crate::state::Protocol;
+
+    // Auth files may be encrypted
+    super::vault::unlock_or_exit(config_dir);
+
+    let from = match to {
+        CredentialStore::File => CredentialStore::Keyring,
+        CredentialStore::Keyring => CredentialStore::File,
+    };
+    let mut moved = 0;
+    let mut failed = 0;
+    for profile in crate::vpn::load_profiles()
+        .iter()
+        .filter(|p| p.protocol == Protocol::OpenVPN)
+    {
+        let name = profile.name.as_str();
+        let saved = match from {
+            CredentialStore::File => Ok(crate::utils::read_openvpn_auth_from_file(name)),
+            CredentialStore::Keyring => secrets::load(name),
+        };
+        let result = saved.and_then(|saved| {
+            let Some((username, password)) = saved else {
+                return Ok(false);
+            };
+            let expected = Some((username.clone(), password.clone()));
+            match to {
+                CredentialStore::Keyring => {
+                    secrets::save(name, &username, &password)?;
+                    if secrets::load(name)? != expected {
+                        return Err("the keyring did not return the saved credentials".into());
+                    }
+                    if let Ok(path) = crate::utils::get_openvpn_auth_path(name) {
+                        let _ = std::fs::remove_file(path);
+                    }
+                }
+                CredentialStore::File => {
+                    crate::utils::write_openvpn_auth_to_file(name, &username, &password)
+                        .map_err(|e| e.to_string())?;
+                    if crate::utils::read_openvpn_auth_from_file(name) != expected {
+                        return Err("the auth file did not return the saved credentials".into());
+                    }
+                    secrets::delete(name)?;
+                }
+            }
+            Ok(true)
+        });
+        match result {
+            Ok(true) => {
+                println!("  {name}: moved to {to}");
+                moved += 1;
+            }
+            Ok(false) => {}
+            Err(e) => {
+                eprintln!("  {name}: {e}");
+                failed += 1;
+            }
+        }
+    }
+
+    println!("Moved {moved} credential(s) from {from} to {to}.");
+    if secrets::store() != to {
+        println!("Set credential_store = \"{to}\" in config.toml to use them.");
+    }
+    if failed > 0 {
+        std::process::exit(constants::EXIT_FAILURE);
+    }
+}
+
+/// Stores (or with `clear`, removes) the TOTP seed of an `OpenVPN` profile.
+///
+/// The seed is read without echo on a terminal, or as the first line of
+/// piped stdin.
+fn handle_auth_totp(config_dir: &Path, profile_name: &str, clear: bool) {
+    use crate::state::Protocol;
+
+    let profiles = crate::vpn::load_profiles();
+    let Some(profile) = super::session::find_profile(&profiles, profile_name) else {
+        eprintln!("{}{profile_name}", constants::CLI_MSG_PROFILE_NOT_FOUND);
+        std::process::exit(constants::EXIT_FAILURE);
+    };
+    let name = profile.name.as_str();
+    if profile.protocol != Protocol::OpenVPN {
+        eprintln!(
+            "{}'{name}' is not an OpenVPN profile",
+            constants::CLI_MSG_ERROR
+        );
+        std::process::exit(constants::EXIT_FAILURE);
+    }
+
+    if clear {
+        crate::core::totp::delete(name);
+        println!("Removed the TOTP seed of '{name}'.");
+        return;
+    }
+
+    // Written encrypted when the vault is enabled
+    super::vault::unlock_or_exit(config_dir);
+    let seed = if super::session::stdin_is_terminal() {
+        use std::io::Write;
+        print!("Authenticator seed (base32) for '{name}': ");
+        let _ = std::io::stdout().flush();
+        let seed = super::session::read_password();
+        println!();
+        seed
+    } else {
+        let mut line = String::new();
+        std::io::stdin().read_line(&mut line).ok().map(|_| line)
+    };
+    let seed = zeroize::Zeroizing::new(seed.unwrap_or_default());
+
+    if let Err(e) = crate::core::totp::save(name, &seed) {
+        eprintln!("{}{e}", constants::CLI_MSG_ERROR);
+        std::process::exit(constants::EXIT_FAILURE);
+    }
+    if crate::utils::openvpn_static_challenge(&profile.config_path).is_none() {
+        println!("Note: '{name}' has no static-challenge; the seed answers CRV1 challenges only.");
+    }
+    // Lets the user check the seed against their authenticator app
+    if let Some(totp) = crate::core::totp::load(name) {
+        println!("Saved. Current code: {}", totp.now());
+    }
+}
+
 /// Handles the update command by running cargo install.
 fn handle_update() {
     println!("{}", constants::CLI_MSG_UPDATE_START);
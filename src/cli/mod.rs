@@ -5,3 +5,5 @@
 pub mod args;
 pub mod commands;
 pub mod report;
+pub mod session;
+pub mod vault;
@@ -251,8 +251,16 @@ fn collect_tool_statuses() -> Vec<ToolStatus> {
 
     #[cfg(target_os = "linux")]
     {
+        use crate::platform::linux::wireguard::NetlinkWireGuard;
+        use crate::platform::WireGuardControl;
+
         tools.push(check_tool("iptables", &["--version"]));
         tools.push(check_tool("nft", &["--version"]));
+        tools.push(ToolStatus {
+            name: "wireguard",
+            path: NetlinkWireGuard::is_available().then(|| "kernel module (netlink)".to_string()),
+            version: None,
+        });
     }
 
     tools
@@ -0,0 +1,731 @@
+//! Headless session commands: `connect`, `disconnect`, `status`, `history`,
+//! `daemon` and `killswitch plan`.
+//!
+//! These reuse the exact launch/teardown path of the TUI
+//! (`core::connector`) and the scanner's session detection, but never touch
+//! the terminal beyond plain stdout/stderr and, on an interactive terminal,
+//! the credential prompt `OpenVPN` asks for. Failures exit with one of the
+//! `constants::EXIT_*` codes so scripts can tell the causes apart.
+
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+use crate::app::{Protocol, VpnProfile};
+use crate::cli::args::HistoryFormat;
+use crate::config::AppConfig;
+use crate::constants;
+use crate::core::connector::{self, LaunchOutcome};
+use crate::core::history::{self, EndReason, SessionRecord, SessionTracker};
+use crate::core::hooks::{self, HookContext, HookEvent};
+use crate::core::openvpn_mgmt::{AuthRequest, Challenge, Credentials};
+use crate::core::scanner::{self, ActiveSession};
+use crate::utils;
+
+// ── Status report ───────────────────────────────────────────────────────────
+
+/// Machine-readable output of `vortix status --json`.
+#[derive(Debug, Serialize)]
+struct StatusReport {
+    connected: bool,
+    sessions: Vec<SessionStatus>,
+    killswitch_mode: Option<String>,
+    killswitch_state: Option<String>,
+}
+
+/// One active tunnel, as reported by the scanner.
+#[derive(Debug, Serialize)]
+struct SessionStatus {
+    profile: String,
+    protocol: Option<String>,
+    interface: String,
+    internal_ip: String,
+    endpoint: String,
+    pid: Option<u32>,
+    /// Unix timestamp (seconds) when the tunnel came up, if known.
+    connected_since: Option<u64>,
+    transfer_rx: String,
+    transfer_tx: String,
+    latest_handshake: String,
+}
+
+// ── Public entry points ─────────────────────────────────────────────────────
+
+/// Connect to `profile_name` and block until the tunnel is confirmed.
+pub fn connect(profile_name: &str, config: &AppConfig) {
+    let profiles = load_profiles();
+    let Some(profile) = find_profile(&profiles, profile_name) else {
+        eprintln!("{}{profile_name}", constants::CLI_MSG_PROFILE_NOT_FOUND);
+        std::process::exit(constants::EXIT_FAILURE);
+    };
+    let (name, protocol) = (profile.name.as_str(), profile.protocol);
+
+    // Same order as the TUI: dependencies, then root, then vault, then credentials
+    let missing = connector::check_dependencies(protocol);
+    if !missing.is_empty() {
+        let pkg = if protocol == Protocol::WireGuard {
+            "wireguard-tools"
+        } else {
+            "openvpn"
+        };
+        eprintln!(
+            "Missing system tools required for {protocol} sessions: {}",
+            missing.join(", ")
+        );
+        eprintln!("   {}", crate::platform::install_hint(pkg));
+        std::process::exit(constants::EXIT_MISSING_DEPENDENCY);
+    }
+
+    require_root("connect");
+
+    if crate::core::vault::is_sealed(&profile.config_path) {
+        if let Ok(config_dir) = utils::get_app_config_dir() {
+            super::vault::unlock_or_exit(&config_dir);
+        }
+    }
+
+    // Without a terminal there is nobody to answer the credential prompt
+    if connector::needs_auth_prompt(name, protocol, &profile.config_path) && !stdin_is_terminal() {
+        eprintln!("'{name}' needs a username and password.");
+        eprintln!("{}", constants::CLI_MSG_AUTH_REQUIRED);
+        std::process::exit(constants::EXIT_AUTH_REQUIRED);
+    }
+
+    let active = scanner::get_active_profiles(&profiles);
+    if active.iter().any(|s| s.name == name) {
+        println!("Already connected to '{name}'.");
+        return;
+    }
+    if let Some(other) = active.first() {
+        eprintln!(
+            "Already connected to '{}'. Run 'vortix disconnect' first.",
+            other.name
+        );
+        std::process::exit(constants::EXIT_FAILURE);
+    }
+
+    println!("Connecting to '{name}' [{protocol}]...");
+    let started_at = SystemTime::now();
+    run_hooks(config, HookEvent::PreUp, &HookContext::new(name, "connect"));
+
+    match connector::bring_up(
+        name,
+        protocol,
+        &profile.config_path,
+        config.connect_timeout,
+        &config.openvpn_verbosity,
+        &|request| prompt_credentials(name, request),
+    ) {
+        Ok(LaunchOutcome::Confirmed) => {}
+        Ok(LaunchOutcome::Unconfirmed) => {
+            // The TUI hands this over to the scanner; we ask it once, right now.
+            let confirmed = scanner::get_active_profiles(std::slice::from_ref(profile))
+                .iter()
+                .any(|s| s.name == name);
+            if !confirmed {
+                connector::cleanup(name, protocol, &profile.config_path);
+                record_session(
+                    SessionTracker::new(name, protocol, started_at),
+                    EndReason::Timeout,
+                    "",
+                );
+                eprintln!(
+                    "Timed out after {}s waiting for '{name}' to come up.",
+                    config.connect_timeout
+                );
+                std::process::exit(constants::EXIT_TIMEOUT);
+            }
+        }
+        Err(e) => {
+            // Kill any leftover process and clean up run files
+            connector::cleanup(name, protocol, &profile.config_path);
+            record_session(
+                SessionTracker::new(name, protocol, started_at),
+                EndReason::Error,
+                &e,
+            );
+            eprintln!("Failed to connect '{name}': {e}");
+            std::process::exit(connector::exit_code_for_error(&e));
+        }
+    }
+
+    record_last_used(profile);
+    println!("Connected to '{name}'.");
+
+    let ctx = scanner::get_active_profiles(std::slice::from_ref(profile))
+        .first()
+        .map_or_else(
+            || HookContext::new(name, "connect"),
+            |s| HookContext::from_session(s, "connect"),
+        );
+    run_hooks(config, HookEvent::PostUp, &ctx);
+}
+
+/// Run the `event` hooks and print what they logged.
+fn run_hooks(config: &AppConfig, event: HookEvent, ctx: &HookContext) {
+    for line in hooks::run(&config.hooks, event, ctx) {
+        eprintln!("{line}");
+    }
+}
+
+pub(super) fn stdin_is_terminal() -> bool {
+    crossterm::tty::IsTty::is_tty(&std::io::stdin())
+}
+
+/// Ask for the credentials `OpenVPN` requested on the terminal, and the
+/// answer to its challenge if it has one. Returns `None` when stdin is not
+/// a terminal or the user enters nothing.
+fn prompt_credentials(name: &str, request: &AuthRequest) -> Option<Credentials> {
+    use std::io::Write;
+
+    if !stdin_is_terminal() {
+        return None;
+    }
+    let prompt = |label: &str, echo: bool| -> Option<String> {
+        print!("{label}: ");
+        let _ = std::io::stdout().flush();
+        let line = if echo {
+            let mut line = String::new();
+            std::io::stdin().read_line(&mut line).ok()?;
+            line.trim().to_string()
+        } else {
+            let line = read_password()?;
+            println!();
+            line
+        };
+        (!line.is_empty()).then_some(line)
+    };
+
+    let mut credentials = Credentials::default();
+    // A dynamic challenge follows accepted credentials; only the answer is new
+    if !request
+        .challenge
+        .as_ref()
+        .is_some_and(Challenge::is_dynamic)
+    {
+        if request.username {
+            credentials.username =
+                prompt(&format!("{} username for '{name}'", request.kind), true)?;
+        }
+        credentials.password =
+            prompt(&format!("{} password for '{name}'", request.kind), false).unwrap_or_default();
+    }
+    if let Some(challenge) = &request.challenge {
+        credentials.response = prompt(&challenge.text, challenge.echo)?;
+    }
+    Some(credentials)
+}
+
+/// Read a line from stdin with terminal echo turned off.
+#[allow(unsafe_code)]
+pub(super) fn read_password() -> Option<String> {
+    let fd = libc::STDIN_FILENO;
+    // SAFETY: termios is plain data filled in by tcgetattr; the saved
+    // settings are restored before returning.
+    let saved = unsafe {
+        let mut term: libc::termios = std::mem::zeroed();
+        if libc::tcgetattr(fd, &mut term) != 0 {
+            return None;
+        }
+        let saved = term;
+        term.c_lflag &= !libc::ECHO;
+        libc::tcsetattr(fd, libc::TCSANOW, &term);
+        saved
+    };
+    let mut line = String::new();
+    let read = std::io::stdin().read_line(&mut line);
+    // SAFETY: restores the settings read above.
+    unsafe {
+        libc::tcsetattr(fd, libc::TCSANOW, &saved);
+    }
+    read.ok()?;
+    Some(line.trim_end_matches(&['\r', '\n'][..]).to_string())
+}
+
+/// Disconnect `profile_name`, or every active session when `None`.
+///
+/// Disconnecting a profile that is not connected is not an error.
+pub fn disconnect(profile_name: Option<&str>, config: &AppConfig) {
+    require_root("disconnect");
+
+    // wg-quick reads the profile again on the way down; never prompt for it
+    if let Ok(config_dir) = utils::get_app_config_dir() {
+        let _ = super::vault::unlock(&config_dir, false);
+    }
+
+    let profiles = load_profiles();
+    let active: Vec<ActiveSession> = scanner::get_active_profiles(&profiles)
+        .into_iter()
+        .filter(|s| profile_name.map_or(true, |n| s.name == n))
+        .collect();
+
+    if active.is_empty() {
+        match profile_name {
+            Some(n) => println!("'{n}' is not connected."),
+            None => println!("{}", constants::CLI_MSG_NOT_CONNECTED),
+        }
+        return;
+    }
+
+    // Read before teardown: the counters vanish with the interface
+    let counters = crate::platform::interface_bytes();
+    let mut failed = 0;
+    for session in &active {
+        let Some(profile) = profiles.iter().find(|p| p.name == session.name) else {
+            continue;
+        };
+        println!("Disconnecting from '{}'...", profile.name);
+        let ctx = HookContext::from_session(session, "user");
+        run_hooks(config, HookEvent::PreDown, &ctx);
+        match connector::bring_down(
+            &profile.name,
+            profile.protocol,
+            &profile.config_path,
+            session.pid,
+        ) {
+            Ok(()) => {
+                let mut tracker = SessionTracker::new(
+                    &profile.name,
+                    profile.protocol,
+                    session.started_at.unwrap_or_else(SystemTime::now),
+                );
+                if let Some((rx, tx)) = counters.get(&session.interface) {
+                    tracker.record_bytes(*rx, *tx);
+                }
+                record_session(tracker, EndReason::User, "");
+                println!("Disconnected from '{}'.", profile.name);
+                run_hooks(config, HookEvent::PostDown, &ctx);
+            }
+            Err(e) => {
+                eprintln!("Failed to disconnect '{}': {}", profile.name, e.trim());
+                failed += 1;
+            }
+        }
+    }
+
+    if failed > 0 {
+        std::process::exit(constants::EXIT_FAILURE);
+    }
+}
+
+/// Print the active sessions as text or JSON.
+pub fn status(json: bool) {
+    let profiles = load_profiles();
+    let active = scanner::get_active_profiles(&profiles);
+    let report = build_status(
+        &profiles,
+        &active,
+        crate::core::killswitch::load_state().as_ref(),
+    );
+
+    if json {
+        match serde_json::to_string_pretty(&report) {
+            Ok(out) => println!("{out}"),
+            Err(e) => {
+                eprintln!("{}{e}", constants::CLI_MSG_ERROR);
+                std::process::exit(constants::EXIT_FAILURE);
+            }
+        }
+        return;
+    }
+
+    if report.sessions.is_empty() {
+        println!("{}", constants::CLI_MSG_NOT_CONNECTED);
+    }
+    for s in &report.sessions {
+        println!(
+            "Connected: {} [{}]",
+            s.profile,
+            s.protocol.as_deref().unwrap_or("?")
+        );
+        println!("  Interface: {}", or_dash(&s.interface));
+        println!("  VPN IP:    {}", or_dash(&s.internal_ip));
+        println!("  Endpoint:  {}", or_dash(&s.endpoint));
+        if let Some(since) = s.connected_since {
+            let since = UNIX_EPOCH + std::time::Duration::from_secs(since);
+            println!("  Since:     {}", utils::format_relative_time(since));
+        }
+        println!(
+            "  Transfer:  ↓{} ↑{}",
+            or_dash(&s.transfer_rx),
+            or_dash(&s.transfer_tx)
+        );
+    }
+    if let (Some(mode), Some(state)) = (&report.killswitch_mode, &report.killswitch_state) {
+        println!("Kill switch: {mode} ({state})");
+    }
+}
+
+/// Print recorded sessions, newest first, as a table, JSON or CSV.
+pub fn history(
+    config_dir: &Path,
+    profile: Option<&str>,
+    reason: Option<EndReason>,
+    limit: Option<usize>,
+    format: HistoryFormat,
+) {
+    let records = match history::load(config_dir) {
+        Ok(records) => filter_history(records, profile, reason, limit),
+        Err(e) => {
+            eprintln!("{}{e}", constants::CLI_MSG_ERROR);
+            std::process::exit(constants::EXIT_FAILURE);
+        }
+    };
+
+    match format {
+        HistoryFormat::Json => match serde_json::to_string_pretty(&records) {
+            Ok(out) => println!("{out}"),
+            Err(e) => {
+                eprintln!("{}{e}", constants::CLI_MSG_ERROR);
+                std::process::exit(constants::EXIT_FAILURE);
+            }
+        },
+        HistoryFormat::Csv => print!("{}", history::to_csv(&records)),
+        HistoryFormat::Table if records.is_empty() => {
+            println!("{}", constants::CLI_MSG_NO_HISTORY);
+        }
+        HistoryFormat::Table => print_history_table(&records),
+    }
+}
+
+/// Run the daemon in the foreground, optionally connecting to a profile at startup.
+pub fn daemon(auto_connect: Option<String>, config: &AppConfig) {
+    require_root("daemon");
+
+    // The daemon connects encrypted profiles for every client
+    if let Ok(config_dir) = utils::get_app_config_dir() {
+        if let Err(e) = super::vault::unlock(&config_dir, true) {
+            eprintln!("Warning: {e}; encrypted profiles can't connect.");
+        }
+    }
+
+    let auto_connect = auto_connect.map(|name| {
+        let profiles = load_profiles();
+        let Some(profile) = find_profile(&profiles, &name) else {
+            eprintln!("{}{name}", constants::CLI_MSG_PROFILE_NOT_FOUND);
+            std::process::exit(constants::EXIT_FAILURE);
+        };
+        profile.name.clone()
+    });
+
+    let result = utils::get_daemon_socket_path()
+        .map_err(|e| format!("Cannot create run directory: {e}"))
+        .and_then(|socket| crate::daemon::server::run(config.clone(), &socket, auto_connect));
+    if let Err(e) = result {
+        eprintln!("{}{e}", constants::CLI_MSG_ERROR);
+        std::process::exit(constants::EXIT_FAILURE);
+    }
+}
+
+/// Print the kill switch rule plan for the current config as a shell script.
+///
+/// Interface and server default to the first active session. Nothing is
+/// applied, so no root is needed.
+pub fn killswitch_plan(interface: Option<&str>, server: Option<&str>, config: &AppConfig) {
+    let session = scanner::get_active_profiles(&load_profiles())
+        .into_iter()
+        .next();
+    let interface = interface
+        .map(str::to_string)
+        .or_else(|| session.as_ref().map(|s| s.interface.clone()))
+        .filter(|i| !i.is_empty())
+        .unwrap_or_else(|| crate::platform::DEFAULT_VPN_INTERFACE.to_string());
+    let server = server.map(str::to_string).or_else(|| {
+        session
+            .as_ref()
+            .map(|s| utils::endpoint_host(&s.endpoint).to_string())
+            .filter(|h| !h.is_empty())
+    });
+
+    match crate::core::killswitch::plan_blocking(&interface, server.as_deref(), &config.killswitch)
+    {
+        Ok(plan) => {
+            println!(
+                "# {} kill switch plan ({}) for interface {interface}, server {}",
+                constants::APP_NAME,
+                plan.backend,
+                server.as_deref().unwrap_or("none")
+            );
+            print!("{plan}");
+        }
+        Err(e) => {
+            eprintln!("{}{e}", constants::CLI_MSG_ERROR);
+            std::process::exit(constants::EXIT_FAILURE);
+        }
+    }
+}
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+/// Load profiles with their persisted metadata, like the TUI does on startup.
+fn load_profiles() -> Vec<VpnProfile> {
+    let mut profiles = crate::vpn::load_profiles();
+    if let Ok(metadata) = utils::load_profile_metadata() {
+        for profile in &mut profiles {
+            let key = profile.config_path.to_string_lossy().to_string();
+            if let Some(meta) = metadata.get(&key) {
+                profile.last_used = meta.last_used;
+                profile.labels = meta.labels.clone();
+            }
+        }
+    }
+    profiles
+}
+
+/// Find a profile by exact name, falling back to a unique case-insensitive match.
+pub(super) fn find_profile<'a>(profiles: &'a [VpnProfile], name: &str) -> Option<&'a VpnProfile> {
+    if let Some(p) = profiles.iter().find(|p| p.name == name) {
+        return Some(p);
+    }
+    let mut matches = profiles
+        .iter()
+        .filter(|p| p.name.eq_ignore_ascii_case(name));
+    match (matches.next(), matches.next()) {
+        (Some(p), None) => Some(p),
+        _ => None,
+    }
+}
+
+/// Exit with `EXIT_PERMISSION_DENIED` unless running as root.
+fn require_root(action: &str) {
+    if !utils::is_root() {
+        eprintln!(
+            "{} needs root privileges to {action}. Run: sudo {} {action}",
+            constants::APP_NAME,
+            constants::APP_NAME
+        );
+        std::process::exit(constants::EXIT_PERMISSION_DENIED);
+    }
+}
+
+/// Update the profile's `last_used` timestamp in the metadata file.
+fn record_last_used(profile: &VpnProfile) {
+    let mut metadata = utils::load_profile_metadata().unwrap_or_default();
+    let key = profile.config_path.to_string_lossy().to_string();
+    metadata.entry(key).or_default().last_used = Some(SystemTime::now());
+    let _ = utils::save_profile_metadata(&metadata);
+}
+
+/// Append a session to the history. A failure is not worth failing the
+/// command over, so it only gets a warning.
+fn record_session(tracker: SessionTracker, reason: EndReason, error: &str) {
+    let result = utils::get_app_config_dir()
+        .map_err(|e| e.to_string())
+        .and_then(|dir| history::append(&dir, &tracker.finish(reason, error)));
+    if let Err(e) = result {
+        eprintln!("Warning: {e}");
+    }
+}
+
+/// Newest first, narrowed to `profile` (case-insensitive) and `reason`.
+fn filter_history(
+    records: Vec<SessionRecord>,
+    profile: Option<&str>,
+    reason: Option<EndReason>,
+    limit: Option<usize>,
+) -> Vec<SessionRecord> {
+    records
+        .into_iter()
+        .rev()
+        .filter(|r| profile.map_or(true, |p| r.profile.eq_ignore_ascii_case(p)))
+        .filter(|r| reason.map_or(true, |reason| r.end_reason == reason))
+        .take(limit.unwrap_or(usize::MAX))
+        .collect()
+}
+
+fn print_history_table(records: &[SessionRecord]) {
+    let width = records
+        .iter()
+        .map(|r| r.profile.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max("PROFILE".len());
+    println!(
+        "{:<16}  {:<width$}  {:>8}  {:<7}  {:>10}  {:>10}  {:>7}  {:>6}  {:<15}  LOCATION",
+        "STARTED", "PROFILE", "DURATION", "ENDED", "DOWN", "UP", "LATENCY", "LOSS", "EXIT IP"
+    );
+    for r in records {
+        let started = UNIX_EPOCH + std::time::Duration::from_secs(r.started_at);
+        println!(
+            "{:<16}  {:<width$}  {:>8}  {:<7}  {:>10}  {:>10}  {:>7}  {:>6}  {:<15}  {}",
+            utils::format_system_datetime_local(started),
+            r.profile,
+            utils::format_duration_secs(r.duration_secs()),
+            r.end_reason,
+            utils::format_bytes(r.rx_bytes),
+            utils::format_bytes(r.tx_bytes),
+            r.avg_latency_ms
+                .map_or_else(|| "-".to_string(), |ms| format!("{ms}ms")),
+            r.avg_loss_pct
+                .map_or_else(|| "-".to_string(), |pct| format!("{pct:.1}%")),
+            or_dash(&r.exit_ip),
+            or_dash(&r.location)
+        );
+    }
+}
+
+fn build_status(
+    profiles: &[VpnProfile],
+    active: &[ActiveSession],
+    killswitch: Option<&crate::core::killswitch::PersistedState>,
+) -> StatusReport {
+    let sessions = active
+        .iter()
+        .map(|s| SessionStatus {
+            profile: s.name.clone(),
+            protocol: profiles
+                .iter()
+                .find(|p| p.name == s.name)
+                .map(|p| p.protocol.to_string()),
+            interface: s.interface.clone(),
+            internal_ip: s.internal_ip.clone(),
+            endpoint: s.endpoint.clone(),
+            pid: s.pid,
+            connected_since: s
+                .started_at
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_secs()),
+            transfer_rx: s.transfer_rx.clone(),
+            transfer_tx: s.transfer_tx.clone(),
+            latest_handshake: s.latest_handshake.clone(),
+        })
+        .collect::<Vec<_>>();
+
+    StatusReport {
+        connected: !sessions.is_empty(),
+        sessions,
+        killswitch_mode: killswitch.map(|k| format!("{:?}", k.mode)),
+        killswitch_state: killswitch.map(|k| format!("{:?}", k.state)),
+    }
+}
+
+fn or_dash(s: &str) -> &str {
+    if s.is_empty() {
+        "-"
+    } else {
+        s
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn profile(name: &str, protocol: Protocol) -> VpnProfile {
+        VpnProfile {
+            name: name.to_string(),
+            protocol,
+            location: "Unknown".to_string(),
+            config_path: PathBuf::from(format!("/tmp/{name}.conf")),
+            last_used: None,
+            labels: crate::state::ProfileLabels::default(),
+        }
+    }
+
+    #[test]
+    fn test_find_profile_exact_match_wins() {
+        let profiles = vec![
+            profile("Work", Protocol::WireGuard),
+            profile("work", Protocol::OpenVPN),
+        ];
+        let found = find_profile(&profiles, "work").unwrap();
+        assert_eq!(found.protocol, Protocol::OpenVPN);
+    }
+
+    #[test]
+    fn test_find_profile_case_insensitive_fallback() {
+        let profiles = vec![profile("Home-NL", Protocol::WireGuard)];
+        assert_eq!(find_profile(&profiles, "home-nl").unwrap().name, "Home-NL");
+    }
+
+    #[test]
+    fn test_find_profile_ambiguous_case_insensitive_is_none() {
+        let profiles = vec![
+            profile("Work", Protocol::WireGuard),
+            profile("WORK", Protocol::OpenVPN),
+        ];
+        assert!(find_profile(&profiles, "work").is_none());
+    }
+
+    #[test]
+    fn test_exit_codes_are_distinct() {
+        let codes = [
+            constants::EXIT_FAILURE,
+            constants::EXIT_AUTH_REQUIRED,
+            constants::EXIT_MISSING_DEPENDENCY,
+            constants::EXIT_TIMEOUT,
+            constants::EXIT_PERMISSION_DENIED,
+        ];
+        for (i, a) in codes.iter().enumerate() {
+            assert_ne!(*a, 0);
+            assert_ne!(*a, 2, "2 is reserved for clap usage errors");
+            for b in &codes[i + 1..] {
+                assert_ne!(a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_filter_history_newest_first() {
+        let records: Vec<SessionRecord> = [
+            ("Work", EndReason::User),
+            ("home", EndReason::Drop),
+            ("work", EndReason::Drop),
+            ("work", EndReason::Timeout),
+        ]
+        .iter()
+        .map(|(name, reason)| {
+            SessionTracker::new(name, Protocol::WireGuard, SystemTime::now()).finish(*reason, "")
+        })
+        .collect();
+
+        let work = filter_history(records.clone(), Some("WORK"), None, None);
+        assert_eq!(work.len(), 3);
+        assert_eq!(work[0].end_reason, EndReason::Timeout);
+
+        let drops = filter_history(records.clone(), None, Some(EndReason::Drop), Some(1));
+        assert_eq!(drops.len(), 1);
+        assert_eq!(drops[0].profile, "work");
+
+        assert!(filter_history(records, Some("office"), None, None).is_empty());
+    }
+
+    #[test]
+    fn test_build_status_disconnected() {
+        let report = build_status(&[profile("wg", Protocol::WireGuard)], &[], None);
+        assert!(!report.connected);
+        assert!(report.sessions.is_empty());
+        assert!(report.killswitch_mode.is_none());
+    }
+
+    #[test]
+    fn test_build_status_json_shape() {
+        let profiles = vec![profile("wg", Protocol::WireGuard)];
+        let active = vec![ActiveSession {
+            name: "wg".to_string(),
+            interface: "wg0".to_string(),
+            internal_ip: "10.0.0.2".to_string(),
+            endpoint: "1.2.3.4:51820".to_string(),
+            started_at: Some(UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000)),
+            ..ActiveSession::default()
+        }];
+        let killswitch = crate::core::killswitch::PersistedState {
+            mode: crate::state::KillSwitchMode::Auto,
+            state: crate::state::KillSwitchState::Armed,
+            vpn_interface: None,
+            vpn_server_ip: None,
+        };
+        let report = build_status(&profiles, &active, Some(&killswitch));
+        let json: serde_json::Value = serde_json::to_value(&report).unwrap();
+
+        assert_eq!(json["connected"], true);
+        assert_eq!(json["sessions"][0]["profile"], "wg");
+        assert_eq!(json["sessions"][0]["protocol"], "WireGuard");
+        assert_eq!(json["sessions"][0]["interface"], "wg0");
+        assert_eq!(json["sessions"][0]["connected_since"], 1_700_000_000);
+        assert_eq!(json["killswitch_mode"], "Auto");
+        assert_eq!(json["killswitch_state"], "Armed");
+    }
+}
@@ -0,0 +1,133 @@
+//! `vortix vault` subcommands and the passphrase prompt of headless commands.
+//!
+//! The passphrase comes from `VORTIX_VAULT_PASSPHRASE` when set (services,
+//! scripts), otherwise from the terminal with echo turned off.
+
+use std::path::Path;
+
+use crate::constants;
+use crate::core::vault;
+
+use super::session::{read_password, stdin_is_terminal};
+
+/// Encrypt the profiles and saved credentials under a new passphrase.
+pub fn init(config_dir: &Path) {
+    if vault::is_enabled(config_dir) {
+        eprintln!("The vault is already enabled.");
+        std::process::exit(constants::EXIT_FAILURE);
+    }
+    let Some(passphrase) = new_passphrase() else {
+        std::process::exit(constants::EXIT_FAILURE);
+    };
+    let result =
+        vault::create(config_dir, &passphrase).and_then(|key| vault::seal_all(config_dir, &key));
+    match result {
+        Ok(count) => {
+            println!("Vault enabled: {count} file(s) encrypted.");
+            println!("Keep the passphrase safe; the files can't be recovered without it.");
+        }
+        Err(e) => {
+            eprintln!("{}{e}", constants::CLI_MSG_ERROR);
+            std::process::exit(constants::EXIT_FAILURE);
+        }
+    }
+}
+
+/// Print whether the vault is enabled and how many files it covers.
+pub fn status(config_dir: &Path) {
+    if !vault::is_enabled(config_dir) {
+        println!("Vault: disabled (profiles and credentials are stored as plain files)");
+        return;
+    }
+    let files = vault::vault_files(config_dir);
+    let sealed = files.iter().filter(|f| vault::is_sealed(f)).count();
+    println!("Vault: enabled");
+    println!("  Encrypted:  {sealed} of {} file(s)", files.len());
+    println!("  Decrypted copies at: {}", vault::runtime_dir().display());
+}
+
+/// Decrypt every file and remove the vault.
+pub fn disable(config_dir: &Path) {
+    if !vault::is_enabled(config_dir) {
+        println!("The vault is not enabled.");
+        return;
+    }
+    let Some(passphrase) = passphrase("Vault passphrase: ") else {
+        eprintln!("{}", constants::CLI_MSG_VAULT_LOCKED);
+        std::process::exit(constants::EXIT_AUTH_REQUIRED);
+    };
+    let result = vault::open(config_dir, &passphrase).and_then(|key| {
+        let count = vault::open_all(config_dir, &key)?;
+        vault::remove(config_dir)?;
+        Ok(count)
+    });
+    match result {
+        Ok(count) => println!("Vault disabled: {count} file(s) decrypted."),
+        Err(e) => {
+            eprintln!("{}{e}", constants::CLI_MSG_ERROR);
+            std::process::exit(constants::EXIT_FAILURE);
+        }
+    }
+}
+
+/// Unlock the vault for this process if it is enabled. `prompt` allows
+/// asking on the terminal when the environment has no passphrase.
+///
+/// # Errors
+///
+/// Returns an error if no passphrase is available or it is wrong.
+pub fn unlock(config_dir: &Path, prompt: bool) -> Result<(), String> {
+    if !vault::is_enabled(config_dir) || vault::is_unlocked() {
+        return Ok(());
+    }
+    let passphrase = if prompt {
+        passphrase("Vault passphrase: ")
+    } else {
+        std::env::var(constants::VAULT_PASSPHRASE_ENV).ok()
+    };
+    let passphrase = passphrase.ok_or_else(|| constants::CLI_MSG_VAULT_LOCKED.to_string())?;
+    vault::unlock(config_dir, &passphrase)
+}
+
+/// Unlock the vault or exit with `EXIT_AUTH_REQUIRED`.
+pub fn unlock_or_exit(config_dir: &Path) {
+    if let Err(e) = unlock(config_dir, true) {
+        eprintln!("{}{e}", constants::CLI_MSG_ERROR);
+        std::process::exit(constants::EXIT_AUTH_REQUIRED);
+    }
+}
+
+/// The passphrase from the environment, else asked on the terminal.
+fn passphrase(label: &str) -> Option<String> {
+    use std::io::Write;
+
+    if let Ok(passphrase) = std::env::var(constants::VAULT_PASSPHRASE_ENV) {
+        return Some(passphrase);
+    }
+    if !stdin_is_terminal() {
+        return None;
+    }
+    eprint!("{label}");
+    let _ = std::io::stderr().flush();
+    let passphrase = read_password();
+    eprintln!();
+    passphrase.filter(|p| !p.is_empty())
+}
+
+/// A new passphrase, entered twice on the terminal.
+fn new_passphrase() -> Option<String> {
+    if let Ok(passphrase) = std::env::var(constants::VAULT_PASSPHRASE_ENV) {
+        return Some(passphrase).filter(|p| !p.is_empty());
+    }
+    if !stdin_is_terminal() {
+        eprintln!("{}", constants::CLI_MSG_VAULT_LOCKED);
+        return None;
+    }
+    let first = passphrase("New vault passphrase: ")?;
+    let second = passphrase("Repeat passphrase: ")?;
+    if first != second {
+        eprintln!("Passphrases don't match.");
+        return None;
+    }
+    Some(first)
+}
@@ -7,11 +7,15 @@
 //! at startup, so that all utility functions (profile loading, auth, metadata, killswitch)
 //! use the correct path without requiring a parameter change on every call site.
 
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use std::sync::OnceLock;
 
 use serde::{Deserialize, Serialize};
 
+use crate::core::killswitch::AllowedPort;
+use crate::vpn::split::Cidr;
+
 /// Process-wide resolved config directory, set once at startup.
 static CONFIG_DIR: OnceLock<PathBuf> = OnceLock::new();
 
@@ -61,6 +65,8 @@ pub struct AppConfig {
     pub ip_api_primary: String,
     /// Fallback API endpoints for IP lookup (tried in order).
     pub ip_api_fallbacks: Vec<String>,
+    /// DNS leak test endpoint (`bash.ws` protocol). Empty disables the test.
+    pub dns_leak_api: String,
     /// Maximum number of log entries kept in the TUI event log.
     pub max_log_entries: usize,
     /// Minimum log level shown in the event log (`"debug"`, `"info"`, `"warning"`, `"error"`).
@@ -73,6 +79,200 @@ pub struct AppConfig {
     pub disconnect_timeout: u64,
     /// `OpenVPN` daemon verbosity level (`--verb`). Range 0–11 (default: 3).
     pub openvpn_verbosity: String,
+    /// Where saved `OpenVPN` credentials live: `"file"` (mode-600 files
+    /// under `auth/`) or `"keyring"` (freedesktop Secret Service) (default: `"file"`).
+    pub credential_store: String,
+    /// Reconnect automatically after an unexpected drop (default: true).
+    pub auto_reconnect: bool,
+    /// Reconnect attempts before giving up (default: 5).
+    pub reconnect_max_attempts: u32,
+    /// Delay before the first reconnect attempt in seconds, doubled per attempt (default: 2).
+    pub reconnect_base_delay: u64,
+    /// Upper bound for the reconnect delay in seconds (default: 60).
+    pub reconnect_max_delay: u64,
+    /// Random spread applied to each reconnect delay, in percent (default: 20).
+    pub reconnect_jitter: u64,
+    /// What to do with the active tunnel when the network changes (Linux):
+    /// `"none"`, `"rehandshake"` or `"reconnect"` (default: `"none"`).
+    pub network_change_action: NetworkChangeAction,
+    /// Overlay the physical uplink's throughput on the tunnel chart (default: true).
+    pub uplink_overlay: bool,
+    /// Keep the chart's metrics (up to 24 hours) across restarts (default: false).
+    pub persist_metrics: bool,
+    /// Serve `OpenMetrics` on this address, e.g. `"127.0.0.1:9477"`
+    /// (default: empty, disabled). `--metrics-listen` overrides it.
+    pub metrics_listen: String,
+    /// Kill switch rule options (`[killswitch]` table).
+    pub killswitch: KillSwitchConfig,
+    /// Lifecycle hook commands (`[hooks]` table).
+    pub hooks: HooksConfig,
+    /// Desktop notification options (`[notifications]` table).
+    pub notifications: NotificationsConfig,
+}
+
+/// What to do with the active tunnel after a network change.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NetworkChangeAction {
+    /// Only refresh telemetry and the scanner.
+    #[default]
+    None,
+    /// Ask the tunnel to handshake again from the new address.
+    Rehandshake,
+    /// Tear the tunnel down and bring it back up.
+    Reconnect,
+}
+
+/// How notifications are delivered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotifyMethod {
+    /// Desktop when a session bus is found, terminal otherwise.
+    #[default]
+    Auto,
+    /// Desktop notification over D-Bus.
+    Desktop,
+    /// OSC 9 escape plus bell on the controlling terminal.
+    Terminal,
+}
+
+/// Desktop notification options, set in the `[notifications]` table of
+/// `config.toml`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct NotificationsConfig {
+    /// Send notifications at all (default: false).
+    pub enabled: bool,
+    /// `"desktop"` (D-Bus), `"terminal"` (OSC 9 and bell) or `"auto"`
+    /// (desktop when a session bus is found) (default: `"auto"`).
+    pub method: NotifyMethod,
+    /// Notify when a tunnel comes up (default: true).
+    pub connect: bool,
+    /// Notify when a tunnel is brought down (default: true).
+    pub disconnect: bool,
+    /// Notify when a tunnel drops unexpectedly (default: true).
+    pub drop: bool,
+    /// Notify when the kill switch starts blocking (default: true).
+    pub killswitch_engage: bool,
+    /// Notify when the kill switch stops blocking (default: true).
+    pub killswitch_release: bool,
+    /// Notify when a DNS or IPv6 leak is detected (default: true).
+    pub leak: bool,
+}
+
+impl Default for NotificationsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            method: NotifyMethod::default(),
+            connect: true,
+            disconnect: true,
+            drop: true,
+            killswitch_engage: true,
+            killswitch_release: true,
+            leak: true,
+        }
+    }
+}
+
+/// Shell commands run on tunnel transitions, set in the `[hooks]` table of
+/// `config.toml`. Empty commands are skipped.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HooksConfig {
+    /// Run before a tunnel is brought up.
+    pub pre_up: String,
+    /// Run once a tunnel is up and its interface is known.
+    pub post_up: String,
+    /// Run before a tunnel is brought down on request.
+    pub pre_down: String,
+    /// Run after a tunnel has been brought down on request.
+    pub post_down: String,
+    /// Run when a tunnel drops without being asked to.
+    pub on_drop: String,
+    /// Seconds a hook may run before it is killed (default: 30).
+    pub timeout: u64,
+    /// Per-profile commands (`[hooks.profile."<name>"]`), run after the global ones.
+    pub profile: BTreeMap<String, ProfileHooks>,
+}
+
+impl Default for HooksConfig {
+    fn default() -> Self {
+        Self {
+            pre_up: String::new(),
+            post_up: String::new(),
+            pre_down: String::new(),
+            post_down: String::new(),
+            on_drop: String::new(),
+            timeout: crate::constants::DEFAULT_HOOK_TIMEOUT,
+            profile: BTreeMap::new(),
+        }
+    }
+}
+
+/// Hook commands for a single profile.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProfileHooks {
+    pub pre_up: String,
+    pub post_up: String,
+    pub pre_down: String,
+    pub post_down: String,
+    pub on_drop: String,
+}
+
+/// Kill switch rule options, set in the `[killswitch]` table of `config.toml`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KillSwitchConfig {
+    /// Drop all IPv6 traffic while the kill switch is active, including
+    /// through the tunnel, link-local and neighbor discovery (default: false).
+    pub block_ipv6: bool,
+    /// Don't exempt the private LAN ranges (RFC1918, IPv6 link-local
+    /// addresses) from the kill switch (default: false).
+    pub block_lan: bool,
+    /// Extra destinations reachable outside the tunnel, as CIDRs or addresses
+    /// (e.g. `"100.64.0.0/10"`).
+    pub allow_cidrs: Vec<String>,
+    /// Outbound exceptions by protocol and destination port
+    /// (e.g. `"udp/5353"`, `"tcp/8000-8100"`, `"icmp"`).
+    pub allow_ports: Vec<String>,
+}
+
+impl KillSwitchConfig {
+    /// Check every `allow_cidrs` and `allow_ports` entry.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first entry that cannot be parsed.
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(bad) = self.allow_cidrs.iter().find(|c| Cidr::parse(c).is_none()) {
+            return Err(format!("invalid allow_cidrs entry '{bad}'"));
+        }
+        for entry in &self.allow_ports {
+            AllowedPort::parse(entry)?;
+        }
+        Ok(())
+    }
+
+    /// Parsed `allow_cidrs` (invalid entries, rejected by [`Self::validate`], are skipped).
+    #[must_use]
+    pub fn allowed_cidrs(&self) -> Vec<Cidr> {
+        self.allow_cidrs
+            .iter()
+            .filter_map(|c| Cidr::parse(c))
+            .collect()
+    }
+
+    /// Parsed `allow_ports` (invalid entries, rejected by [`Self::validate`], are skipped).
+    #[must_use]
+    pub fn allowed_ports(&self) -> Vec<AllowedPort> {
+        self.allow_ports
+            .iter()
+            .filter_map(|p| AllowedPort::parse(p).ok())
+            .collect()
+    }
 }
 
 impl Default for AppConfig {
@@ -99,12 +299,26 @@ impl Default for AppConfig {
                 constants::DEFAULT_IP_API_FALLBACK_2.to_string(),
                 constants::DEFAULT_IP_API_FALLBACK_3.to_string(),
             ],
+            dns_leak_api: constants::DEFAULT_DNS_LEAK_API.to_string(),
             max_log_entries: constants::DEFAULT_MAX_LOG_ENTRIES,
             log_level: constants::DEFAULT_LOG_LEVEL.to_string(),
             log_rotation_size: constants::DEFAULT_LOG_ROTATION_SIZE,
             log_retention_days: constants::DEFAULT_LOG_RETENTION_DAYS,
             disconnect_timeout: constants::DEFAULT_DISCONNECT_TIMEOUT,
             openvpn_verbosity: constants::DEFAULT_OVPN_VERBOSITY.to_string(),
+            credential_store: constants::DEFAULT_CREDENTIAL_STORE.to_string(),
+            auto_reconnect: constants::DEFAULT_AUTO_RECONNECT,
+            reconnect_max_attempts: constants::DEFAULT_RECONNECT_MAX_ATTEMPTS,
+            reconnect_base_delay: constants::DEFAULT_RECONNECT_BASE_DELAY,
+            reconnect_max_delay: constants::DEFAULT_RECONNECT_MAX_DELAY,
+            reconnect_jitter: constants::DEFAULT_RECONNECT_JITTER,
+            network_change_action: NetworkChangeAction::default(),
+            uplink_overlay: constants::DEFAULT_UPLINK_OVERLAY,
+            persist_metrics: constants::DEFAULT_PERSIST_METRICS,
+            metrics_listen: String::new(),
+            killswitch: KillSwitchConfig::default(),
+            hooks: HooksConfig::default(),
+            notifications: NotificationsConfig::default(),
         }
     }
 }
@@ -230,8 +444,33 @@ pub fn load_config(config_dir: &Path) -> Result<AppConfig, String> {
     let content = std::fs::read_to_string(&config_path)
         .map_err(|e| format!("Failed to read {}: {e}", config_path.display()))?;
 
-    toml::from_str(&content)
-        .map_err(|e| format!("Invalid config at {}: {e}", config_path.display()))
+    let config: AppConfig = toml::from_str(&content)
+        .map_err(|e| format!("Invalid config at {}: {e}", config_path.display()))?;
+    config.killswitch.validate().map_err(|e| {
+        format!(
+            "Invalid config at {}: [killswitch] {e}",
+            config_path.display()
+        )
+    })?;
+    if !config.metrics_listen.is_empty()
+        && config
+            .metrics_listen
+            .parse::<std::net::SocketAddr>()
+            .is_err()
+    {
+        return Err(format!(
+            "Invalid config at {}: metrics_listen must be an address like \"127.0.0.1:9477\"",
+            config_path.display()
+        ));
+    }
+    if let Err(e) = config
+        .credential_store
+        .parse::<crate::core::secrets::CredentialStore>()
+        .and_then(crate::core::secrets::check_store)
+    {
+        return Err(format!("Invalid config at {}: {e}", config_path.display()));
+    }
+    Ok(config)
 }
 
 // ======================== Migration ========================
@@ -275,18 +514,15 @@ pub fn check_migration(new_dir: &Path) -> Option<PathBuf> {
     if !old_dir.join("profiles").is_dir() {
         return None;
     }
-    let has_old_data = std::fs::read_dir(old_dir.join("profiles"))
-        .map(|mut d| d.next().is_some())
-        .unwrap_or(false);
+    let has_old_data =
+        std::fs::read_dir(old_dir.join("profiles")).is_ok_and(|mut d| d.next().is_some());
     if !has_old_data {
         return None;
     }
 
     // New path must be empty or nonexistent
     let new_has_profiles = new_dir.join("profiles").is_dir()
-        && std::fs::read_dir(new_dir.join("profiles"))
-            .map(|mut d| d.next().is_some())
-            .unwrap_or(false);
+        && std::fs::read_dir(new_dir.join("profiles")).is_ok_and(|mut d| d.next().is_some());
     if new_has_profiles {
         return None;
     }
@@ -328,10 +564,8 @@ pub fn migrate_data(old_dir: &Path, new_dir: &Path) -> std::io::Result<()> {
         // into it -- empty dirs are leftovers from a previous incomplete migration
         // or from get_profiles_dir() auto-creating directories.
         if dst.exists() {
-            let dst_is_empty_dir = dst.is_dir()
-                && std::fs::read_dir(&dst)
-                    .map(|mut d| d.next().is_none())
-                    .unwrap_or(false);
+            let dst_is_empty_dir =
+                dst.is_dir() && std::fs::read_dir(&dst).is_ok_and(|mut d| d.next().is_none());
             if !dst_is_empty_dir {
                 eprintln!("  Skipping {item} (already has data at destination)");
                 continue;
@@ -482,6 +716,16 @@ mod tests {
         assert_eq!(config.ping_targets.len(), 4);
         assert_eq!(config.ipv6_check_apis.len(), 3);
         assert_eq!(config.ip_api_fallbacks.len(), 3);
+        assert!(config.auto_reconnect);
+        assert!(config.uplink_overlay);
+        assert!(!config.persist_metrics);
+        assert!(config.metrics_listen.is_empty());
+        assert_eq!(config.hooks.timeout, crate::constants::DEFAULT_HOOK_TIMEOUT);
+        assert!(config.hooks.profile.is_empty());
+        assert!(!config.notifications.enabled);
+        assert_eq!(config.notifications.method, NotifyMethod::Auto);
+        assert!(config.notifications.drop);
+        assert_eq!(config.reconnect_max_attempts, 5);
     }
 
     // ---- load_config ----
@@ -527,6 +771,13 @@ ping_targets = ["4.4.4.4", "8.8.4.4"]
 ipv6_check_apis = ["https://example.com/v6"]
 ip_api_primary = "https://custom-api.example.com/json"
 ip_api_fallbacks = ["https://fallback1.example.com"]
+dns_leak_api = "http://127.0.0.1:8053"
+
+[killswitch]
+block_ipv6 = true
+block_lan = true
+allow_cidrs = ["100.64.0.0/10"]
+allow_ports = ["udp/5353"]
 "#;
         std::fs::write(dir.join("config.toml"), toml_content).unwrap();
 
@@ -543,6 +794,11 @@ ip_api_fallbacks = ["https://fallback1.example.com"]
             config.ip_api_fallbacks,
             vec!["https://fallback1.example.com"]
         );
+        assert_eq!(config.dns_leak_api, "http://127.0.0.1:8053");
+        assert!(config.killswitch.block_ipv6);
+        assert!(config.killswitch.block_lan);
+        assert_eq!(config.killswitch.allow_cidrs, vec!["100.64.0.0/10"]);
+        assert_eq!(config.killswitch.allow_ports, vec!["udp/5353"]);
 
         let _ = std::fs::remove_dir_all(&dir);
     }
@@ -558,6 +814,100 @@ ip_api_fallbacks = ["https://fallback1.example.com"]
         let _ = std::fs::remove_dir_all(&dir);
     }
 
+    #[test]
+    fn test_load_config_invalid_killswitch_entry() {
+        let dir = std::env::temp_dir().join("vortix_test_invalid_killswitch");
+        let _ = std::fs::create_dir_all(&dir);
+        std::fs::write(
+            dir.join("config.toml"),
+            "[killswitch]\nallow_cidrs = [\"100.64.0.0/10\", \"office\"]\n",
+        )
+        .unwrap();
+
+        let err = load_config(&dir).unwrap_err();
+        assert!(err.contains("allow_cidrs entry 'office'"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_load_config_invalid_metrics_listen() {
+        let dir = std::env::temp_dir().join("vortix_test_invalid_metrics_listen");
+        let _ = std::fs::create_dir_all(&dir);
+        std::fs::write(dir.join("config.toml"), "metrics_listen = \"localhost\"\n").unwrap();
+        assert!(load_config(&dir).unwrap_err().contains("metrics_listen"));
+
+        std::fs::write(dir.join("config.toml"), "metrics_listen = \"[::1]:9477\"\n").unwrap();
+        assert_eq!(load_config(&dir).unwrap().metrics_listen, "[::1]:9477");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_load_config_credential_store() {
+        let dir = std::env::temp_dir().join("vortix_test_credential_store");
+        let _ = std::fs::create_dir_all(&dir);
+        std::fs::write(dir.join("config.toml"), "credential_store = \"wallet\"\n").unwrap();
+        assert!(load_config(&dir).unwrap_err().contains("credential store"));
+
+        std::fs::write(dir.join("config.toml"), "credential_store = \"keyring\"\n").unwrap();
+        // Accepted only where secret-tool is installed
+        match crate::core::secrets::check_store(crate::core::secrets::CredentialStore::Keyring) {
+            Ok(()) => assert_eq!(load_config(&dir).unwrap().credential_store, "keyring"),
+            Err(_) => assert!(load_config(&dir).unwrap_err().contains("secret-tool")),
+        }
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_load_config_network_change_action() {
+        let dir = std::env::temp_dir().join("vortix_test_network_change_action");
+        let _ = std::fs::create_dir_all(&dir);
+        std::fs::write(
+            dir.join("config.toml"),
+            "network_change_action = \"reconect\"\n",
+        )
+        .unwrap();
+        assert!(load_config(&dir).unwrap_err().contains("reconect"));
+
+        std::fs::write(
+            dir.join("config.toml"),
+            "network_change_action = \"reconnect\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            load_config(&dir).unwrap().network_change_action,
+            NetworkChangeAction::Reconnect
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_load_config_notify_method() {
+        let dir = std::env::temp_dir().join("vortix_test_notify_method");
+        let _ = std::fs::create_dir_all(&dir);
+        std::fs::write(
+            dir.join("config.toml"),
+            "[notifications]\nmethod = \"termnial\"\n",
+        )
+        .unwrap();
+        assert!(load_config(&dir).unwrap_err().contains("termnial"));
+
+        std::fs::write(
+            dir.join("config.toml"),
+            "[notifications]\nmethod = \"terminal\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            load_config(&dir).unwrap().notifications.method,
+            NotifyMethod::Terminal
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn test_load_config_unknown_field() {
         let dir = std::env::temp_dir().join("vortix_test_unknown_field");
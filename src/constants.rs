@@ -34,6 +34,88 @@ pub const CMD_TIMEOUT_SECS: u64 = 5;
 /// Default maximum seconds to wait for a VPN disconnect before force-killing.
 pub const DEFAULT_DISCONNECT_TIMEOUT: u64 = 30;
 
+// === Network Change Detection ===
+
+/// Quiet period after the last link/address/route notification before a
+/// network change is reported (milliseconds). Roams arrive as bursts.
+pub const NETWORK_CHANGE_DEBOUNCE_MS: u64 = 1000;
+/// Time after a connect during which network changes are put down to the
+/// tunnel's own routes and rules rather than a roam (seconds).
+pub const NETWORK_CHANGE_SETTLE_SECS: u64 = 5;
+
+// === Reconnect Defaults ===
+
+/// Reconnect automatically after an unexpected drop by default.
+pub const DEFAULT_AUTO_RECONNECT: bool = true;
+/// Default number of reconnect attempts before giving up.
+pub const DEFAULT_RECONNECT_MAX_ATTEMPTS: u32 = 5;
+/// Default delay before the first reconnect attempt (seconds); doubles per attempt.
+pub const DEFAULT_RECONNECT_BASE_DELAY: u64 = 2;
+/// Default upper bound for the reconnect delay (seconds).
+pub const DEFAULT_RECONNECT_MAX_DELAY: u64 = 60;
+/// Default random spread applied to each reconnect delay (percent, ±).
+pub const DEFAULT_RECONNECT_JITTER: u64 = 20;
+
+// === Hook Defaults ===
+
+/// Default time limit for one hook command in seconds.
+pub const DEFAULT_HOOK_TIMEOUT: u64 = 30;
+/// Lines of hook output copied into the event log per command.
+pub const HOOK_OUTPUT_MAX_LINES: usize = 20;
+/// Bytes of each hook output stream kept; the rest is read and dropped.
+pub const HOOK_OUTPUT_MAX_BYTES: usize = 64 * 1024;
+/// How long to wait for output after a hook exits, in case something it
+/// started in the background still holds the pipes (milliseconds).
+pub const HOOK_OUTPUT_GRACE_MS: u64 = 200;
+
+// === Notification Defaults ===
+
+/// Time limit for one desktop notification call in seconds.
+pub const NOTIFY_TIMEOUT_SECS: u64 = 3;
+
+// === Credential Store ===
+
+/// Default store for saved `OpenVPN` credentials: `"file"` or `"keyring"`.
+pub const DEFAULT_CREDENTIAL_STORE: &str = "file";
+/// libsecret's command-line client for the Secret Service.
+pub const SECRET_TOOL_BIN: &str = "secret-tool";
+/// Value of the `service` attribute on keyring items.
+pub const SECRET_SERVICE_ATTRIBUTE: &str = "vortix";
+/// Time limit for one keyring call in seconds (long enough to type the
+/// keyring password into an unlock prompt).
+pub const SECRET_TOOL_TIMEOUT_SECS: u64 = 30;
+
+// === Server Benchmark ===
+
+/// Port a `WireGuard` endpoint without one listens on.
+pub const WIREGUARD_DEFAULT_PORT: u16 = 51820;
+/// Port an `OpenVPN` remote without one listens on.
+pub const OPENVPN_DEFAULT_PORT: u16 = 1194;
+/// Seconds each reachability check of a server may take.
+pub const BENCHMARK_PROBE_TIMEOUT_SECS: u64 = 2;
+/// Servers probed at the same time.
+pub const BENCHMARK_PARALLELISM: usize = 16;
+/// Milliseconds added to a server's score per percent of lost pings.
+pub const BENCHMARK_LOSS_PENALTY_MS: u64 = 20;
+
+// === Vault ===
+
+/// Vault parameters (salt, key derivation cost, passphrase check), in the
+/// config dir. Its presence means profiles and credentials are encrypted.
+pub const VAULT_FILE_NAME: &str = "vault.json";
+/// First bytes of every file the vault encrypted.
+pub const VAULT_MAGIC: &[u8] = b"VORTIXV1";
+/// Argon2id memory cost in KiB.
+pub const VAULT_KDF_MEMORY_KIB: u32 = 65536;
+/// Argon2id passes over memory.
+pub const VAULT_KDF_ITERATIONS: u32 = 3;
+/// Argon2id lanes.
+pub const VAULT_KDF_PARALLELISM: u32 = 1;
+/// Directory under the runtime dir (tmpfs) holding decrypted profile copies.
+pub const VAULT_RUNTIME_DIR: &str = "vortix";
+/// Environment variable headless commands read the vault passphrase from.
+pub const VAULT_PASSPHRASE_ENV: &str = "VORTIX_VAULT_PASSPHRASE";
+
 // === Telemetry API Endpoint Defaults ===
 // Same principle: single source of truth, overridable via config.toml.
 
@@ -53,6 +135,9 @@ pub const DEFAULT_IPV6_CHECK_APIS: [&str; 3] = [
     "https://api6.ipify.org",
 ];
 
+/// Default DNS leak test endpoint (`bash.ws` protocol, empty = disabled).
+pub const DEFAULT_DNS_LEAK_API: &str = "https://bash.ws";
+
 /// Default ping targets for latency measurement (tried in order).
 pub const DEFAULT_PING_TARGETS: [&str; 4] = [
     "1.1.1.1",        // Cloudflare
@@ -69,20 +154,40 @@ pub const PROFILES_DIR_NAME: &str = "profiles";
 pub const LOGS_DIR_NAME: &str = "logs";
 /// Name of the profile metadata file.
 pub const METADATA_FILE_NAME: &str = "metadata.json";
+/// Session history file (one JSON record per line).
+pub const HISTORY_FILE_NAME: &str = "history.jsonl";
+/// Persisted chart metrics (see `persist_metrics`).
+pub const METRICS_FILE_NAME: &str = "metrics.json";
 /// Kill switch state persistence filename.
 pub const KILLSWITCH_STATE_FILE: &str = "killswitch.state";
+/// DNS override persistence filename (what to restore on disconnect).
+pub const DNS_STATE_FILE: &str = "dns.state";
 
 // === Platform-Specific Paths ===
 
 /// macOS pf configuration file path (temp file for kill switch rules).
 #[cfg(target_os = "macos")]
 pub const PF_CONF_PATH: &str = "/tmp/vortix_killswitch.conf";
+/// macOS pf anchor holding the outside-DNS block. Lives under `com.apple/*`,
+/// which the stock `/etc/pf.conf` already evaluates.
+#[cfg(target_os = "macos")]
+pub const PF_DNS_ANCHOR: &str = "com.apple/vortix_dns";
 /// macOS `WireGuard` runtime directory.
 #[cfg(target_os = "macos")]
 pub const WIREGUARD_RUN_DIR: &str = "/var/run/wireguard";
+/// Routing table and fwmark for tunnels whose peers route `0.0.0.0/0` or
+/// `::/0` (same default as `wg-quick`).
+#[cfg(target_os = "linux")]
+pub const WIREGUARD_ROUTE_TABLE: u32 = 51820;
+/// MTU of natively created `WireGuard` interfaces without an `MTU` directive.
+#[cfg(target_os = "linux")]
+pub const WIREGUARD_DEFAULT_MTU: u32 = 1420;
 /// Linux network device statistics pseudo-file.
 #[cfg(target_os = "linux")]
 pub const PROC_NET_DEV_PATH: &str = "/proc/net/dev";
+/// Linux IPv4 routing table pseudo-file.
+#[cfg(target_os = "linux")]
+pub const PROC_NET_ROUTE_PATH: &str = "/proc/net/route";
 /// System DNS resolver configuration file (both platforms).
 pub const RESOLV_CONF_PATH: &str = "/etc/resolv.conf";
 /// Linux iptables custom chain name for kill switch.
@@ -91,6 +196,12 @@ pub const IPTABLES_CHAIN_NAME: &str = "VORTIX_KILLSWITCH";
 /// Linux nftables table name for kill switch.
 #[cfg(target_os = "linux")]
 pub const NFT_TABLE_NAME: &str = "vortix_killswitch";
+/// Linux iptables custom chain name for the outside-DNS block.
+#[cfg(target_os = "linux")]
+pub const IPTABLES_DNS_CHAIN_NAME: &str = "VORTIX_DNS";
+/// Linux nftables table name for the outside-DNS block.
+#[cfg(target_os = "linux")]
+pub const NFT_DNS_TABLE_NAME: &str = "vortix_dns";
 
 // === Logging Defaults ===
 // These are the compiled-in defaults. Users can override them via config.toml.
@@ -120,6 +231,12 @@ pub const HTTP_TIMEOUT_SECS: u64 = 10;
 pub const RETRY_DELAY_MS: u64 = 500;
 /// Number of retry attempts per API/target.
 pub const RETRY_ATTEMPTS: u8 = 2;
+/// Number of probe names resolved per DNS leak test.
+pub const DNS_LEAK_PROBES: usize = 6;
+/// Minimum interval between DNS leak tests (seconds) unless telemetry is nudged.
+pub const DNS_LEAK_TEST_INTERVAL_SECS: u64 = 300;
+/// Interval between checks that the kill switch rules are still installed (seconds).
+pub const KILLSWITCH_VERIFY_INTERVAL_SECS: u64 = 10;
 
 // === UI Layout & Tuning Constants ===
 
@@ -128,8 +245,10 @@ pub const LOG_CATEGORY_WIDTH: usize = 6;
 /// Total character width of the structured log prefix (`[HH:MM:SS] LEVEL  CAT   `).
 /// Used to calculate how much space is left for the message text.
 pub const LOG_PREFIX_WIDTH: usize = 25;
-/// Number of data points in the network throughput chart (1 point per tick).
-pub const NETWORK_HISTORY_SIZE: usize = 60;
+/// Overlay the physical uplink on the tunnel throughput chart by default.
+pub const DEFAULT_UPLINK_OVERLAY: bool = true;
+/// Keep chart metrics across restarts by default.
+pub const DEFAULT_PERSIST_METRICS: bool = false;
 /// Lines from the bottom at which the log panel re-enables auto-scroll.
 pub const LOGS_AUTO_SCROLL_THRESHOLD: u16 = 5;
 /// Number of profiles to jump when pressing Page Up / Page Down.
@@ -169,6 +288,16 @@ pub const KILLSWITCH_EMERGENCY_MSG: &str =
 
 /// Subdirectory under the Vortix config dir for `OpenVPN` runtime files (pid, log).
 pub const OPENVPN_RUN_DIR: &str = "run";
+/// Control socket of `vortix daemon`, created inside [`OPENVPN_RUN_DIR`].
+pub const DAEMON_SOCKET_NAME: &str = "daemon.sock";
+/// Subdirectory of [`OPENVPN_RUN_DIR`] holding profiles rendered with a split tunnel policy.
+pub const SPLIT_TUNNEL_DIR: &str = "split";
+/// Subdirectory of [`OPENVPN_RUN_DIR`] recording `WireGuard` tunnels brought up
+/// natively, and the routing rules to remove on teardown.
+#[cfg(target_os = "linux")]
+pub const WIREGUARD_NATIVE_DIR: &str = "wireguard";
+/// How long the TUI waits for a daemon to answer before running standalone.
+pub const DAEMON_PROBE_TIMEOUT_MS: u64 = 500;
 /// `OpenVPN` log line indicating successful tunnel establishment.
 pub const OVPN_LOG_SUCCESS: &str = "Initialization Sequence Completed";
 /// `OpenVPN` log patterns indicating definitive failure.
@@ -181,14 +310,10 @@ pub const OVPN_LOG_ERRORS: &[&str] = &[
     "ERROR:",
     "Exiting due to fatal error",
 ];
-/// Polling interval for `OpenVPN` log file (milliseconds).
+/// Polling interval (ms) while waiting for `OpenVPN` to open its management socket.
 pub const OVPN_LOG_POLL_MS: u64 = 500;
 /// Delay (ms) after `OpenVPN` fork before chowning pid/log files to the real user.
 pub const OVPN_CHOWN_DELAY_MS: u64 = 200;
-/// Seconds to wait before checking if the `OpenVPN` daemon is still alive.
-pub const OVPN_HEALTH_CHECK_DELAY_SECS: u64 = 2;
-/// Seconds to wait for the `OpenVPN` PID file to appear before declaring failure.
-pub const OVPN_PID_FILE_TIMEOUT_SECS: u64 = 3;
 /// Number of tail log lines to include in error messages when the daemon dies.
 pub const OVPN_ERROR_LOG_TAIL_LINES: usize = 5;
 /// Default `OpenVPN` `--verb` (verbosity) level passed to the daemon.
@@ -197,6 +322,22 @@ pub const DEFAULT_OVPN_VERBOSITY: &str = "3";
 pub const OPENVPN_AUTH_DIR: &str = "auth";
 /// `OpenVPN` config directive that triggers interactive auth prompts.
 pub const OVPN_AUTH_USER_PASS: &str = "auth-user-pass";
+/// Seconds to wait for a freshly started `OpenVPN` to open its management socket.
+pub const OVPN_MGMT_CONNECT_TIMEOUT_SECS: u64 = 5;
+/// Milliseconds to wait for the management interface to answer a command.
+pub const OVPN_MGMT_REPLY_TIMEOUT_MS: u64 = 2000;
+/// Interval (seconds) at which `OpenVPN` pushes `>BYTECOUNT` updates.
+pub const OVPN_BYTECOUNT_INTERVAL_SECS: u64 = 2;
+/// Seconds a connect waits for the user to answer a credential prompt.
+pub const OVPN_AUTH_PROMPT_TIMEOUT_SECS: u64 = 300;
+/// `OpenVPN` config directive that asks for a second factor with the password.
+pub const OVPN_STATIC_CHALLENGE: &str = "static-challenge";
+/// Extension of a stored TOTP seed next to a profile's auth file.
+pub const TOTP_SEED_EXTENSION: &str = "totp";
+/// TOTP time step in seconds (RFC 6238 default, used by authenticator apps).
+pub const TOTP_STEP_SECS: u64 = 30;
+/// Digits in a generated TOTP code.
+pub const TOTP_DIGITS: u32 = 6;
 
 // === Auth UI Labels ===
 
@@ -208,6 +349,23 @@ pub const TITLE_AUTH_MANAGE: &str = " Edit Auth Credentials ";
 pub const TITLE_AUTH_FOOTER: &str = " [Tab] Switch  [Enter] Connect  [Esc] Cancel ";
 /// Footer keybindings for the auth overlay (manage/edit flow).
 pub const TITLE_AUTH_MANAGE_FOOTER: &str = " [Tab] Switch  [Enter] Save  [Esc] Cancel ";
+/// Title for the split tunnel editor overlay.
+pub const TITLE_SPLIT_TUNNEL: &str = " Split Tunnel ";
+/// Title for the DNS override editor overlay.
+pub const TITLE_DNS_OVERRIDE: &str = " DNS Override ";
+/// Title for the group/tags editor overlay.
+pub const TITLE_PROFILE_LABELS: &str = " Group & Tags ";
+/// Title for the quick slot picker overlay.
+pub const TITLE_QUICK_SLOT: &str = " Quick Slot ";
+/// Footer keybindings for the quick slot picker.
+pub const TITLE_QUICK_SLOT_FOOTER: &str = " [1-9] Bind  [0] Unbind  [Esc] Cancel ";
+/// Title for the vault passphrase overlay.
+pub const TITLE_VAULT_UNLOCK: &str = " Unlock Vault ";
+/// Footer keybindings for the vault passphrase overlay.
+pub const TITLE_VAULT_UNLOCK_FOOTER: &str = " [Enter] Unlock  [Esc] Skip ";
+/// Footer keybindings for the per-profile settings overlays.
+pub const TITLE_PROFILE_FORM_FOOTER: &str =
+    " [Tab] Switch  [Space] Toggle  [Enter] Save  [Esc] Cancel ";
 
 // === Import & Download Configuration ===
 
@@ -262,6 +420,29 @@ pub const CLI_MSG_UPDATE_CMD: &str = "   cargo install vortix --force";
 pub const CLI_MSG_UPDATE_FAIL_CARGO: &str = "Failed to run cargo: ";
 pub const CLI_MSG_UPDATE_PATH_HINT: &str = "   Make sure cargo is installed and in your PATH.";
 
+pub const CLI_MSG_PROFILE_NOT_FOUND: &str = "No profile named: ";
+pub const CLI_MSG_NOT_CONNECTED: &str = "No active VPN sessions.";
+pub const CLI_MSG_NO_HISTORY: &str = "No recorded sessions.";
+pub const CLI_MSG_AUTH_REQUIRED: &str =
+    "   Credentials are required. Save them once from the TUI (Profiles → a), then retry.";
+pub const CLI_MSG_VAULT_LOCKED: &str =
+    "Vault is locked: set VORTIX_VAULT_PASSPHRASE or run from a terminal";
+
+// === CLI Exit Codes ===
+// Distinct codes so scripts can tell failure causes apart.
+// 2 is skipped: clap already uses it for usage errors.
+
+/// Generic failure (unknown profile, tool error, rejected by the server).
+pub const EXIT_FAILURE: i32 = 1;
+/// `OpenVPN` profile needs credentials that are missing or were rejected.
+pub const EXIT_AUTH_REQUIRED: i32 = 3;
+/// `wg-quick`/`openvpn` is not installed.
+pub const EXIT_MISSING_DEPENDENCY: i32 = 4;
+/// The tunnel did not come up within `connect_timeout`.
+pub const EXIT_TIMEOUT: i32 = 5;
+/// The command needs root privileges.
+pub const EXIT_PERMISSION_DENIED: i32 = 6;
+
 // === Bug Report ===
 
 /// GitHub repository URL for constructing issue links (from Cargo.toml `repository`).
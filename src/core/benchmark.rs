@@ -0,0 +1,273 @@
+//! Server benchmark for picking the fastest profile.
+//!
+//! Each profile's endpoint is probed from the current route without bringing
+//! a tunnel up:
+//! 1. ICMP echo (`ping`) gives round-trip time and loss when the server
+//!    answers pings
+//! 2. Otherwise a TCP endpoint is timed with a plain connect
+//! 3. A UDP endpoint gets one datagram: an ICMP port-unreachable reply proves
+//!    the port is closed, silence only that nothing refused it
+//!
+//! Servers are ranked by round-trip time plus a penalty per percent of loss;
+//! servers without a measured round trip go last.
+
+use crate::constants;
+use crate::vpn::Endpoint;
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs, UdpSocket};
+use std::time::{Duration, Instant};
+
+/// How a server was found reachable.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProbeMethod {
+    /// Answered ICMP echo requests.
+    Icmp,
+    /// Accepted a TCP connection.
+    Tcp,
+    /// Didn't refuse a UDP datagram (no round trip measured).
+    Udp,
+}
+
+impl std::fmt::Display for ProbeMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProbeMethod::Icmp => write!(f, "icmp"),
+            ProbeMethod::Tcp => write!(f, "tcp"),
+            ProbeMethod::Udp => write!(f, "udp"),
+        }
+    }
+}
+
+/// Outcome of probing one profile's server.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProbeResult {
+    /// Profile the server belongs to.
+    pub profile: String,
+    /// Server as `host:port/transport` (empty if the config names none).
+    pub endpoint: String,
+    /// How the server answered (`None` = unreachable).
+    pub method: Option<ProbeMethod>,
+    /// Average round-trip time in milliseconds.
+    pub rtt_ms: Option<u64>,
+    /// Percentage of ICMP echo requests without a reply.
+    pub loss_pct: f32,
+    /// Why the server is unreachable (empty if it isn't).
+    pub error: String,
+}
+
+impl ProbeResult {
+    /// A result for a server that couldn't be probed.
+    #[must_use]
+    pub fn failed(profile: &str, endpoint: String, error: String) -> Self {
+        Self {
+            profile: profile.to_string(),
+            endpoint,
+            method: None,
+            rtt_ms: None,
+            loss_pct: 100.0,
+            error,
+        }
+    }
+
+    /// Ranking score, lower is better: round-trip time plus the loss
+    /// penalty. `None` when no round trip was measured.
+    #[must_use]
+    pub fn score(&self) -> Option<u64> {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let loss = self.loss_pct.clamp(0.0, 100.0).round() as u64;
+        self.rtt_ms
+            .map(|rtt| rtt + loss * constants::BENCHMARK_LOSS_PENALTY_MS)
+    }
+
+    /// Whether the server answered at all.
+    #[must_use]
+    pub fn is_reachable(&self) -> bool {
+        self.method.is_some()
+    }
+}
+
+/// Sort results best first: measured servers by score, then servers that
+/// only didn't refuse a datagram, then unreachable ones (each by name).
+pub fn rank(results: &mut [ProbeResult]) {
+    results.sort_by(|a, b| {
+        let key = |r: &ProbeResult| (r.score().is_none(), !r.is_reachable(), r.score());
+        key(a).cmp(&key(b)).then_with(|| a.profile.cmp(&b.profile))
+    });
+}
+
+/// Probe every `(profile, endpoint)` pair, `parallelism` at a time, and
+/// return the results ranked best first.
+#[must_use]
+pub fn run(
+    targets: &[(String, Endpoint)],
+    timeout: Duration,
+    parallelism: usize,
+) -> Vec<ProbeResult> {
+    let mut results = Vec::with_capacity(targets.len());
+    for chunk in targets.chunks(parallelism.max(1)) {
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = chunk
+                .iter()
+                .map(|(profile, endpoint)| scope.spawn(move || probe(profile, endpoint, timeout)))
+                .collect();
+            results.extend(handles.into_iter().filter_map(|h| h.join().ok()));
+        });
+    }
+    rank(&mut results);
+    results
+}
+
+/// Probe one server: ICMP first, then the tunnel's own transport.
+#[must_use]
+pub fn probe(profile: &str, endpoint: &Endpoint, timeout: Duration) -> ProbeResult {
+    let shown = endpoint.to_string();
+    let Some(addr) = (endpoint.host.as_str(), endpoint.port)
+        .to_socket_addrs()
+        .ok()
+        .and_then(|mut addrs| addrs.next())
+    else {
+        return ProbeResult::failed(profile, shown, format!("Can't resolve {}", endpoint.host));
+    };
+
+    let mut result = ProbeResult::failed(profile, shown, String::new());
+    if let Some(stats) = ping(&addr, timeout) {
+        result.method = Some(ProbeMethod::Icmp);
+        result.rtt_ms = Some(stats.latency_ms);
+        result.loss_pct = stats.packet_loss;
+        return result;
+    }
+
+    let outcome = if endpoint.tcp {
+        tcp_connect(&addr, timeout).map(|rtt| (ProbeMethod::Tcp, Some(rtt)))
+    } else {
+        udp_poke(&addr, timeout).map(|()| (ProbeMethod::Udp, None))
+    };
+    match outcome {
+        Ok((method, rtt)) => {
+            result.method = Some(method);
+            result.rtt_ms = rtt;
+            result.loss_pct = 0.0;
+        }
+        Err(e) => result.error = e,
+    }
+    result
+}
+
+/// Ping `addr` a few times; `None` if no reply came back.
+fn ping(addr: &SocketAddr, timeout: Duration) -> Option<crate::core::telemetry::PingStats> {
+    // macOS ping -W takes milliseconds; Linux ping -W takes seconds
+    #[cfg(target_os = "macos")]
+    let wait = timeout.as_millis().to_string();
+    #[cfg(not(target_os = "macos"))]
+    let wait = timeout.as_secs().max(1).to_string();
+
+    let mut cmd = std::process::Command::new("ping");
+    if addr.is_ipv6() {
+        cmd.arg("-6");
+    }
+    let output = cmd
+        .args(["-c", "3", "-i", "0.2", "-W", &wait, &addr.ip().to_string()])
+        .output()
+        .ok()?;
+    let stats = crate::core::telemetry::parse_ping_output(&String::from_utf8_lossy(&output.stdout));
+    (stats.packet_loss < 100.0 && output.status.success()).then_some(stats)
+}
+
+/// Time a TCP handshake with `addr`, in milliseconds.
+fn tcp_connect(addr: &SocketAddr, timeout: Duration) -> Result<u64, String> {
+    let started = Instant::now();
+    TcpStream::connect_timeout(addr, timeout).map_err(|e| format!("TCP connect failed: {e}"))?;
+    Ok(u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX))
+}
+
+/// Send one datagram to `addr`; fails only if the host actively refuses it.
+fn udp_poke(addr: &SocketAddr, timeout: Duration) -> Result<(), String> {
+    let bind: SocketAddr = if addr.is_ipv6() {
+        "[::]:0"
+            .parse()
+            .map_err(|_| "Invalid bind address".to_string())?
+    } else {
+        "0.0.0.0:0"
+            .parse()
+            .map_err(|_| "Invalid bind address".to_string())?
+    };
+    let socket = UdpSocket::bind(bind).map_err(|e| format!("UDP socket: {e}"))?;
+    socket
+        .connect(addr)
+        .map_err(|e| format!("UDP connect failed: {e}"))?;
+    socket
+        .set_read_timeout(Some(timeout))
+        .map_err(|e| format!("UDP socket: {e}"))?;
+    socket
+        .send(&[0])
+        .map_err(|e| format!("UDP send failed: {e}"))?;
+    let mut buf = [0u8; 64];
+    match socket.recv(&mut buf) {
+        Err(e) if e.kind() == std::io::ErrorKind::ConnectionRefused => {
+            Err("UDP port closed".to_string())
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn measured(profile: &str, rtt: u64, loss: f32) -> ProbeResult {
+        ProbeResult {
+            profile: profile.to_string(),
+            endpoint: String::new(),
+            method: Some(ProbeMethod::Icmp),
+            rtt_ms: Some(rtt),
+            loss_pct: loss,
+            error: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_score_penalizes_loss() {
+        assert_eq!(measured("a", 30, 0.0).score(), Some(30));
+        assert_eq!(
+            measured("a", 30, 10.0).score(),
+            Some(30 + 10 * constants::BENCHMARK_LOSS_PENALTY_MS)
+        );
+        assert_eq!(
+            ProbeResult::failed("a", String::new(), "x".into()).score(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_rank_orders_measured_silent_unreachable() {
+        let silent = ProbeResult {
+            method: Some(ProbeMethod::Udp),
+            loss_pct: 0.0,
+            ..ProbeResult::failed("silent", String::new(), String::new())
+        };
+        let mut results = vec![
+            ProbeResult::failed("down", String::new(), "UDP port closed".into()),
+            silent,
+            measured("lossy", 20, 5.0),
+            measured("slow", 80, 0.0),
+            measured("fast", 15, 0.0),
+        ];
+        rank(&mut results);
+        let order: Vec<&str> = results.iter().map(|r| r.profile.as_str()).collect();
+        assert_eq!(order, ["fast", "slow", "lossy", "silent", "down"]);
+    }
+
+    #[test]
+    fn test_probe_closed_tcp_port_on_loopback() {
+        // Bind and drop a listener to find a port nothing listens on
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .and_then(|l| l.local_addr())
+            .map(|a| a.port())
+            .unwrap();
+        let addr: SocketAddr = format!("127.0.0.1:{port}").parse().unwrap();
+        assert!(tcp_connect(&addr, Duration::from_secs(1)).is_err());
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        assert!(tcp_connect(&addr, Duration::from_secs(1)).is_ok());
+    }
+}
@@ -0,0 +1,763 @@
+//! Terminal-free VPN launch and teardown.
+//!
+//! The TUI runs these functions in background threads and reports the result
+//! back as a `Message`; the headless CLI subcommands call them directly and
+//! block until they return. Keeping a single implementation guarantees that
+//! `vortix connect` behaves exactly like pressing Enter on a profile.
+
+use crate::app::Protocol;
+use crate::constants;
+use crate::core::openvpn_mgmt::{self, AuthRequest, Credentials};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+/// Result of a launch that did not fail outright.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LaunchOutcome {
+    /// The tunnel is confirmed up (the `WireGuard` device is configured, or
+    /// `OpenVPN` reported the `CONNECTED` state).
+    Confirmed,
+    /// The `OpenVPN` daemon is still running but did not confirm the tunnel
+    /// within the connect timeout. The scanner is expected to take over.
+    Unconfirmed,
+}
+
+/// Signature of [`bring_up`].
+pub type BringUpFn = fn(
+    &str,
+    Protocol,
+    &Path,
+    u64,
+    &str,
+    &dyn Fn(&AuthRequest) -> Option<Credentials>,
+) -> Result<LaunchOutcome, String>;
+
+/// The steps of a TUI connect that touch the host. Tests swap in stand-ins
+/// so a connect neither depends on the installed tools nor launches them.
+#[derive(Clone, Copy)]
+pub struct Ops {
+    pub check_dependencies: fn(Protocol) -> Vec<String>,
+    pub bring_up: BringUpFn,
+}
+
+impl Default for Ops {
+    fn default() -> Self {
+        Self {
+            check_dependencies,
+            bring_up,
+        }
+    }
+}
+
+/// Check if required binaries are available for a given protocol.
+///
+/// Returns the names of the missing tools (empty when everything is installed).
+pub fn check_dependencies(protocol: Protocol) -> Vec<String> {
+    let mut missing = Vec::new();
+    match protocol {
+        Protocol::WireGuard => {
+            // The native backend needs neither; wg-quick is then only a fallback
+            #[cfg(target_os = "linux")]
+            {
+                use crate::platform::WireGuardControl;
+                if NativeWireGuard::is_available() {
+                    return missing;
+                }
+            }
+            if Command::new("wg-quick").arg("--version").output().is_err() {
+                missing.push("wg-quick".to_string());
+            }
+            if Command::new("wg").arg("--version").output().is_err() {
+                missing.push("wireguard-tools".to_string());
+            }
+        }
+        Protocol::OpenVPN => {
+            if Command::new("openvpn").arg("--version").output().is_err() {
+                missing.push("openvpn".to_string());
+            }
+        }
+    }
+    missing
+}
+
+/// Whether the profile needs credentials that have not been saved yet. A
+/// `static-challenge` code counts as saved only through a TOTP seed.
+pub fn needs_auth_prompt(name: &str, protocol: Protocol, config_path: &Path) -> bool {
+    matches!(protocol, Protocol::OpenVPN)
+        && crate::utils::openvpn_config_needs_auth(config_path)
+        && (crate::utils::read_openvpn_saved_auth(name).is_none()
+            || (crate::utils::openvpn_static_challenge(config_path).is_some()
+                && !crate::core::totp::has_seed(name)))
+}
+
+/// Bring a tunnel up. Blocks until the outcome is known.
+///
+/// `ask` is called when `OpenVPN` wants credentials that are not saved or
+/// were rejected; it returns `None` to cancel the connect.
+///
+/// # Errors
+///
+/// Returns a human-readable error if the VPN tool failed to start or the
+/// daemon reported a definitive failure.
+pub fn bring_up(
+    name: &str,
+    protocol: Protocol,
+    config_path: &Path,
+    connect_timeout_secs: u64,
+    ovpn_verbosity: &str,
+    ask: &dyn Fn(&AuthRequest) -> Option<Credentials>,
+) -> Result<LaunchOutcome, String> {
+    // A split tunnel policy is applied to a rendered copy or extra arguments;
+    // the stored profile is never touched.
+    let split = crate::vpn::split::for_profile(config_path);
+    let outcome = match (protocol, split) {
+        (Protocol::WireGuard, None) => wireguard_up(name, config_path),
+        (Protocol::WireGuard, Some(policy)) => {
+            let rendered = crate::vpn::split::prepare_wireguard(config_path, &policy)?;
+            let result = wireguard_up(name, &rendered);
+            // It has been read; `down` only needs the stored profile
+            let _ = std::fs::remove_file(&rendered);
+            result
+        }
+        (Protocol::OpenVPN, split) => {
+            let extra_args = match split {
+                Some(policy) => crate::vpn::split::prepare_openvpn(config_path, &policy)?,
+                None => Vec::new(),
+            };
+            openvpn_up(
+                name,
+                config_path,
+                connect_timeout_secs,
+                ovpn_verbosity,
+                &extra_args,
+                ask,
+            )
+            .map_err(|e| {
+                crate::core::vault::remove_runtime_copy(config_path);
+                e
+            })
+        }
+    }?;
+    apply_dns_override(name, protocol, config_path);
+    Ok(outcome)
+}
+
+/// Apply the profile's DNS override once its tunnel interface exists.
+///
+/// Failures are logged but do not fail the connect: the tunnel is up and
+/// the VPN's own DNS settings (if any) are still in place.
+fn apply_dns_override(name: &str, protocol: Protocol, config_path: &Path) {
+    let Some(dns) = crate::core::dns::for_profile(config_path) else {
+        return;
+    };
+    // Applying replaces the DNS the native backend set from the profile
+    #[cfg(target_os = "linux")]
+    let dns = match native_tunnel_dns(protocol, config_path) {
+        Some(tunnel) => with_tunnel_dns(dns, tunnel),
+        None => dns,
+    };
+    let profile = crate::app::VpnProfile {
+        name: name.to_string(),
+        protocol,
+        location: String::new(),
+        config_path: config_path.to_path_buf(),
+        last_used: None,
+        labels: crate::state::ProfileLabels::default(),
+    };
+    let Some(session) = crate::core::scanner::get_active_profiles(std::slice::from_ref(&profile))
+        .into_iter()
+        .next()
+    else {
+        crate::logger::log(
+            crate::logger::LogLevel::Warning,
+            "DNS",
+            format!("DNS override for '{name}' skipped: tunnel interface not found"),
+        );
+        return;
+    };
+    if let Err(e) = crate::core::dns::apply(name, &session.interface, &dns) {
+        crate::logger::log(crate::logger::LogLevel::Error, "DNS", e);
+    }
+}
+
+/// Keep the tunnel's own servers and search domains under an override that
+/// sets neither (a block-only override), so they survive its re-apply.
+#[cfg(target_os = "linux")]
+fn with_tunnel_dns(
+    mut dns: crate::core::dns::DnsOverride,
+    tunnel: crate::core::dns::DnsOverride,
+) -> crate::core::dns::DnsOverride {
+    if dns.servers.is_empty() && dns.search.is_empty() {
+        dns.servers = tunnel.servers;
+        dns.search = tunnel.search;
+    }
+    dns
+}
+
+/// The `DNS =` settings [`native_wireguard_up`] applied for a profile, if
+/// its tunnel is run natively.
+#[cfg(target_os = "linux")]
+fn native_tunnel_dns(
+    protocol: Protocol,
+    config_path: &Path,
+) -> Option<crate::core::dns::DnsOverride> {
+    if !matches!(protocol, Protocol::WireGuard) || !is_native_wireguard(config_path) {
+        return None;
+    }
+    let content = crate::core::vault::read_to_string(config_path).ok()?;
+    let config = crate::vpn::wireguard::WgConfig::parse(&content).ok()?;
+    Some(tunnel_dns(config))
+}
+
+/// DNS settings carried by a parsed `WireGuard` config.
+#[cfg(target_os = "linux")]
+fn tunnel_dns(config: crate::vpn::wireguard::WgConfig) -> crate::core::dns::DnsOverride {
+    crate::core::dns::DnsOverride {
+        servers: config.dns_servers,
+        search: config.dns_search,
+        block_outside: false,
+    }
+}
+
+/// `wg-quick` is a one-shot command: sets up the interface and exits.
+/// On Linux the kernel is configured directly when possible.
+fn wireguard_up(name: &str, config_path: &Path) -> Result<LaunchOutcome, String> {
+    #[cfg(target_os = "linux")]
+    if let Some(result) = native_wireguard_up(name, config_path) {
+        return result;
+    }
+    #[cfg(not(target_os = "linux"))]
+    let _ = name;
+
+    match wg_quick("up", config_path) {
+        Ok(out) if out.status.success() => Ok(LaunchOutcome::Confirmed),
+        Ok(out) => {
+            let stderr = String::from_utf8_lossy(&out.stderr).to_string();
+            Err(format!("WireGuard: {stderr}"))
+        }
+        Err(e) => Err(format!("Failed to execute wg-quick: {e}")),
+    }
+}
+
+/// Run `wg-quick <action>` on a profile. An encrypted profile is handed over
+/// as a decrypted copy in the vault's runtime dir, removed once it returns.
+fn wg_quick(action: &str, config_path: &Path) -> std::io::Result<std::process::Output> {
+    let plain = crate::core::vault::plaintext_path(config_path).map_err(std::io::Error::other)?;
+    let output = Command::new("wg-quick")
+        .args([action, plain.to_str().unwrap_or("")])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output();
+    if plain != config_path {
+        let _ = std::fs::remove_file(&plain);
+    }
+    output
+}
+
+#[cfg(target_os = "linux")]
+type NativeWireGuard = crate::platform::linux::wireguard::NetlinkWireGuard;
+
+/// Interface `wg-quick` would create for a profile: the config file name.
+#[cfg(target_os = "linux")]
+fn wireguard_interface(config_path: &Path) -> String {
+    config_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// Bring the tunnel up over netlink. Returns `None` to fall back to
+/// `wg-quick`: the kernel module is missing or the profile uses directives
+/// only `wg-quick` implements (`PostUp`, custom tables, ...).
+#[cfg(target_os = "linux")]
+fn native_wireguard_up(name: &str, config_path: &Path) -> Option<Result<LaunchOutcome, String>> {
+    use crate::logger::{log, LogLevel};
+    use crate::platform::WireGuardControl;
+
+    if !NativeWireGuard::is_available() {
+        return None;
+    }
+    let content = crate::core::vault::read_to_string(config_path).ok()?;
+    let config = match crate::vpn::wireguard::WgConfig::parse(&content) {
+        Ok(config) => config,
+        Err(e) => {
+            log(
+                LogLevel::Warning,
+                "WIREGUARD",
+                format!("Using wg-quick for '{name}': {e}"),
+            );
+            return None;
+        }
+    };
+    if !config.unsupported.is_empty() {
+        log(
+            LogLevel::Info,
+            "WIREGUARD",
+            format!(
+                "Using wg-quick for '{name}': {} not supported natively",
+                config.unsupported.join(", ")
+            ),
+        );
+        return None;
+    }
+
+    let interface = wireguard_interface(config_path);
+    if let Err(e) = NativeWireGuard::up(&interface, &config) {
+        return Some(Err(format!("WireGuard: {e}")));
+    }
+    // What wg-quick hands to resolvconf; a profile override replaces it later
+    let dns = tunnel_dns(config);
+    if !dns.is_empty() {
+        if let Err(e) = crate::core::dns::apply(name, &interface, &dns) {
+            log(LogLevel::Error, "DNS", e);
+        }
+    }
+    Some(Ok(LaunchOutcome::Confirmed))
+}
+
+/// Whether the profile's tunnel was brought up natively rather than by `wg-quick`.
+#[cfg(target_os = "linux")]
+pub fn is_native_wireguard(config_path: &Path) -> bool {
+    use crate::platform::WireGuardControl;
+    NativeWireGuard::is_managed(&wireguard_interface(config_path))
+}
+
+/// Tear down a tunnel brought up natively; `None` if `wg-quick` owns it.
+#[cfg(target_os = "linux")]
+fn native_wireguard_down(config_path: &Path) -> Option<Result<(), String>> {
+    use crate::platform::WireGuardControl;
+
+    is_native_wireguard(config_path).then(|| {
+        NativeWireGuard::down(&wireguard_interface(config_path))
+            .map_err(|e| format!("WireGuard: {e}"))
+    })
+}
+
+/// `OpenVPN` is designed to run as a daemon. We use `--daemon` with
+/// `--writepid` and `--log` so we can track the process, and drive it over
+/// its management socket: it holds until we connect, reports its phases as
+/// they happen and asks us for credentials, which come from the saved auth
+/// file first (with a code from the profile's TOTP seed, if it has one) and
+/// then from `ask`.
+fn openvpn_up(
+    name: &str,
+    config_path: &Path,
+    connect_timeout_secs: u64,
+    ovpn_verbosity: &str,
+    extra_args: &[String],
+    ask: &dyn Fn(&AuthRequest) -> Option<Credentials>,
+) -> Result<LaunchOutcome, String> {
+    let (pid_path, log_path) = crate::utils::get_openvpn_run_paths(name)
+        .map_err(|e| format!("Failed to create run directory: {e}"))?;
+    let mgmt_path = crate::utils::get_openvpn_mgmt_path(name)
+        .map_err(|e| format!("Failed to create run directory: {e}"))?;
+
+    // Clean up stale files from previous runs
+    let _ = std::fs::remove_file(&pid_path);
+    let _ = std::fs::remove_file(&log_path);
+    let _ = std::fs::remove_file(&mgmt_path);
+
+    // An encrypted profile runs from a decrypted copy kept until teardown
+    let plain = crate::core::vault::plaintext_path(config_path)?;
+
+    // Build openvpn args
+    let mut args = vec![
+        "--config".to_string(),
+        plain.to_str().unwrap_or("").to_string(),
+        "--daemon".to_string(),
+        format!("vortix-{name}"),
+        "--writepid".to_string(),
+        pid_path.to_str().unwrap_or("").to_string(),
+        "--log".to_string(),
+        log_path.to_str().unwrap_or("").to_string(),
+        "--verb".to_string(),
+        ovpn_verbosity.to_string(),
+        "--management".to_string(),
+        mgmt_path.to_str().unwrap_or("").to_string(),
+        "unix".to_string(),
+        "--management-hold".to_string(),
+        "--management-query-passwords".to_string(),
+        // Ask again over the socket when the server rejects the credentials
+        "--auth-retry".to_string(),
+        "interact".to_string(),
+    ];
+    args.extend_from_slice(extra_args);
+
+    let output = Command::new("openvpn")
+        .args(&args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output();
+
+    // --daemon: parent forks and exits. A non-zero exit here means
+    // the config failed basic validation before the fork.
+    match output {
+        Ok(out) if !out.status.success() => {
+            let stderr = String::from_utf8_lossy(&out.stderr).to_string();
+            return Err(format!("OpenVPN: {}", stderr.trim()));
+        }
+        Err(e) => return Err(format!("Failed to start OpenVPN: {e}")),
+        Ok(_) => {} // Fork succeeded, daemon is running
+    }
+
+    // Chown the run files so normal users can read PID/log status.
+    // OpenVPN creates them as root; we fix ownership after a brief
+    // delay to let the daemon write them.
+    std::thread::sleep(Duration::from_millis(constants::OVPN_CHOWN_DELAY_MS));
+    crate::config::fix_ownership(pid_path.parent().unwrap_or(Path::new("/")));
+
+    let mut client = openvpn_mgmt::Client::connect(
+        &mgmt_path,
+        Duration::from_secs(constants::OVPN_MGMT_CONNECT_TIMEOUT_SECS),
+    )
+    .map_err(|_| openvpn_exit_error(&log_path))?;
+
+    let saved = crate::utils::read_openvpn_saved_auth(name);
+    let totp = crate::core::totp::load(name);
+    let timeout = Duration::from_secs(connect_timeout_secs);
+    match openvpn_mgmt::await_connected(&mut client, name, timeout, saved, totp.as_ref(), ask) {
+        Ok(connected) => {
+            // Keeps phases and byte counters current for the scanner
+            openvpn_mgmt::spawn_monitor(name, client);
+            Ok(if connected {
+                LaunchOutcome::Confirmed
+            } else {
+                // Timeout -- let the caller decide how to confirm the tunnel
+                LaunchOutcome::Unconfirmed
+            })
+        }
+        Err(e) if e.contains("AUTH_FAILED") => Err(e),
+        // The log usually names the cause (TLS error, missing TUN device, ...)
+        Err(_) => Err(openvpn_exit_error(&log_path)),
+    }
+}
+
+/// Error for a daemon that exited during the connect: the first failure
+/// marker in its log, or the last few log lines.
+fn openvpn_exit_error(log_path: &Path) -> String {
+    let log = std::fs::read_to_string(log_path).unwrap_or_default();
+    if let Some(Err(e)) = scan_openvpn_log(&log) {
+        return e;
+    }
+    if log.trim().is_empty() {
+        return "OpenVPN daemon exited without log output".to_string();
+    }
+    let last_lines: String = log
+        .lines()
+        .rev()
+        .take(constants::OVPN_ERROR_LOG_TAIL_LINES)
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!("OpenVPN daemon exited:\n{last_lines}")
+}
+
+/// Look for a definitive success or failure marker in an `OpenVPN` log.
+///
+/// Returns `None` while the daemon is still negotiating.
+fn scan_openvpn_log(log_content: &str) -> Option<Result<LaunchOutcome, String>> {
+    if log_content.contains(constants::OVPN_LOG_SUCCESS) {
+        return Some(Ok(LaunchOutcome::Confirmed));
+    }
+
+    for pattern in constants::OVPN_LOG_ERRORS {
+        if log_content.contains(pattern) {
+            // Extract the line containing the error for context
+            let error_line = log_content
+                .lines()
+                .find(|l| l.contains(pattern))
+                .unwrap_or(pattern);
+            return Some(Err(format!("OpenVPN: {error_line}")));
+        }
+    }
+
+    None
+}
+
+/// Map a launch error to one of the `constants::EXIT_*` codes.
+///
+/// Rejected credentials count as "auth required"; everything else is a
+/// generic failure.
+pub fn exit_code_for_error(error: &str) -> i32 {
+    if error.contains("AUTH_FAILED") {
+        constants::EXIT_AUTH_REQUIRED
+    } else {
+        constants::EXIT_FAILURE
+    }
+}
+
+/// Tear a tunnel down gracefully. Blocks until the tool returns.
+///
+/// For `OpenVPN` a `signal SIGTERM` over the management socket is tried first,
+/// then killing the PID file's process, then the scanner-provided `pid`,
+/// then `pkill openvpn` as a last resort. Runtime files are removed on success.
+/// A DNS override applied on connect is restored first.
+///
+/// # Errors
+///
+/// Returns a human-readable error if the teardown command failed.
+pub fn bring_down(
+    name: &str,
+    protocol: Protocol,
+    config_path: &Path,
+    pid: Option<u32>,
+) -> Result<(), String> {
+    crate::core::dns::restore();
+    #[cfg(target_os = "linux")]
+    if matches!(protocol, Protocol::WireGuard) {
+        if let Some(result) = native_wireguard_down(config_path) {
+            return result;
+        }
+    }
+    if matches!(protocol, Protocol::OpenVPN) {
+        // The daemon read its decrypted config at startup
+        crate::core::vault::remove_runtime_copy(config_path);
+    }
+    if matches!(protocol, Protocol::OpenVPN) && openvpn_mgmt::signal(name, "SIGTERM").is_ok() {
+        crate::utils::cleanup_openvpn_run_files(name);
+        return Ok(());
+    }
+    let output = match protocol {
+        Protocol::WireGuard => wg_quick("down", config_path),
+        Protocol::OpenVPN => {
+            // Try PID file first (most reliable), then scanner PID, then pkill
+            let target_pid = crate::utils::read_openvpn_pid(name).or(pid);
+            if let Some(p) = target_pid {
+                Command::new("kill")
+                    .arg(p.to_string())
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::piped())
+                    .output()
+            } else {
+                Command::new("pkill")
+                    .arg("openvpn")
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::piped())
+                    .output()
+            }
+        }
+    };
+
+    match output {
+        Ok(out) if out.status.success() => {
+            // Clean up OpenVPN runtime files
+            if matches!(protocol, Protocol::OpenVPN) {
+                crate::utils::cleanup_openvpn_run_files(name);
+            }
+            Ok(())
+        }
+        Ok(out) => {
+            let stderr = String::from_utf8_lossy(&out.stderr).to_string();
+            Err(format!("{protocol}: {stderr}"))
+        }
+        Err(e) => Err(format!("Failed to execute: {e}")),
+    }
+}
+
+/// Nudge a running tunnel onto a new underlying network without tearing it down.
+///
+/// - **`WireGuard`**: re-applies each peer's endpoint (over netlink, or with `wg set`),
+///   which starts a fresh handshake over the new route.
+/// - **`OpenVPN`**: sends `SIGUSR1`, the `OpenVPN` soft restart (over the
+///   management socket when possible).
+///
+/// # Errors
+///
+/// Returns a human-readable error if the tool failed or the process is unknown.
+pub fn rehandshake(
+    name: &str,
+    protocol: Protocol,
+    interface: &str,
+    pid: Option<u32>,
+) -> Result<(), String> {
+    let run = |cmd: &mut Command| -> Result<(), String> {
+        let out = cmd
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output()
+            .map_err(|e| format!("Failed to execute: {e}"))?;
+        if out.status.success() {
+            Ok(())
+        } else {
+            Err(String::from_utf8_lossy(&out.stderr).trim().to_string())
+        }
+    };
+
+    match protocol {
+        Protocol::WireGuard => {
+            #[cfg(target_os = "linux")]
+            {
+                use crate::platform::WireGuardControl;
+                if NativeWireGuard::is_available() {
+                    return NativeWireGuard::rehandshake(interface);
+                }
+            }
+            let out = Command::new("wg")
+                .args(["show", interface, "endpoints"])
+                .output()
+                .map_err(|e| format!("Failed to execute: {e}"))?;
+            let peers = parse_wg_endpoints(&String::from_utf8_lossy(&out.stdout));
+            if peers.is_empty() {
+                return Err(format!("No peer endpoints on {interface}"));
+            }
+            for (peer, endpoint) in peers {
+                run(Command::new("wg")
+                    .args(["set", interface, "peer", &peer, "endpoint", &endpoint]))?;
+            }
+            Ok(())
+        }
+        Protocol::OpenVPN => {
+            if openvpn_mgmt::signal(name, "SIGUSR1").is_ok() {
+                return Ok(());
+            }
+            let pid = crate::utils::read_openvpn_pid(name)
+                .or(pid)
+                .ok_or_else(|| "OpenVPN PID unknown".to_string())?;
+            run(Command::new("kill").args(["-USR1", &pid.to_string()]))
+        }
+    }
+}
+
+/// Parse `wg show <iface> endpoints` output into `(public key, endpoint)` pairs,
+/// skipping peers without an endpoint.
+fn parse_wg_endpoints(output: &str) -> Vec<(String, String)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (peer, endpoint) = line.split_once('\t')?;
+            let endpoint = endpoint.trim();
+            (endpoint != "(none)" && !endpoint.is_empty())
+                .then(|| (peer.trim().to_string(), endpoint.to_string()))
+        })
+        .collect()
+}
+
+/// Kill any running VPN process and remove run files for a profile.
+///
+/// - **`OpenVPN`**: sends SIGTERM to the daemon (via PID file) and removes pid/log files.
+/// - **`WireGuard`**: removes a natively created interface, or runs `wg-quick down`.
+///
+/// Safe to call even if the process is already gone — all operations are best-effort.
+pub fn cleanup(name: &str, protocol: Protocol, config_path: &Path) {
+    crate::core::dns::restore();
+    match protocol {
+        Protocol::OpenVPN => {
+            if let Some(pid) = crate::utils::read_openvpn_pid(name) {
+                let _ = Command::new("kill")
+                    .arg(pid.to_string())
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .output();
+            }
+            crate::utils::cleanup_openvpn_run_files(name);
+            crate::core::vault::remove_runtime_copy(config_path);
+        }
+        Protocol::WireGuard => {
+            #[cfg(target_os = "linux")]
+            if native_wireguard_down(config_path).is_some() {
+                return;
+            }
+            let _ = wg_quick("down", config_path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_openvpn_log_success() {
+        let log = "Mon Jan 1 TUN/TAP device tun0 opened\n\
+                   Mon Jan 1 Initialization Sequence Completed\n";
+        assert_eq!(scan_openvpn_log(log), Some(Ok(LaunchOutcome::Confirmed)));
+    }
+
+    #[test]
+    fn test_scan_openvpn_log_auth_failure_returns_line() {
+        let log = "Mon Jan 1 TLS: Initial packet\n\
+                   Mon Jan 1 AUTH: Received control message: AUTH_FAILED\n";
+        assert_eq!(
+            scan_openvpn_log(log),
+            Some(Err(
+                "OpenVPN: Mon Jan 1 AUTH: Received control message: AUTH_FAILED".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_scan_openvpn_log_still_negotiating() {
+        let log = "Mon Jan 1 TCP/UDP: Preserving recently used remote address\n";
+        assert_eq!(scan_openvpn_log(log), None);
+    }
+
+    #[test]
+    fn test_exit_code_for_auth_failure() {
+        assert_eq!(
+            exit_code_for_error("OpenVPN: AUTH: Received control message: AUTH_FAILED"),
+            constants::EXIT_AUTH_REQUIRED
+        );
+        assert_eq!(
+            exit_code_for_error("WireGuard: RTNETLINK answers: File exists"),
+            constants::EXIT_FAILURE
+        );
+    }
+
+    #[test]
+    fn test_needs_auth_prompt_wireguard_never() {
+        let path = Path::new("/nonexistent/vortix_test_wg.conf");
+        assert!(!needs_auth_prompt("wg", Protocol::WireGuard, path));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_native_wireguard_block_only_override_keeps_config_dns() {
+        let config = crate::vpn::wireguard::WgConfig::parse(
+            "[Interface]\n\
+             PrivateKey = AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=\n\
+             Address = 10.64.0.2/32\n\
+             DNS = 10.64.0.1, corp.example\n",
+        )
+        .unwrap();
+        let block_only = crate::core::dns::DnsOverride {
+            block_outside: true,
+            ..Default::default()
+        };
+        let dns = with_tunnel_dns(block_only, tunnel_dns(config));
+        assert_eq!(dns.servers, vec!["10.64.0.1"]);
+        assert_eq!(dns.search, vec!["corp.example"]);
+        assert!(dns.block_outside);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_override_servers_replace_tunnel_dns() {
+        let custom = crate::core::dns::DnsOverride {
+            servers: vec!["1.1.1.1".to_string()],
+            search: Vec::new(),
+            block_outside: true,
+        };
+        let tunnel = crate::core::dns::DnsOverride {
+            servers: vec!["10.64.0.1".to_string()],
+            search: vec!["corp.example".to_string()],
+            block_outside: false,
+        };
+        assert_eq!(with_tunnel_dns(custom.clone(), tunnel), custom);
+    }
+
+    #[test]
+    fn test_parse_wg_endpoints() {
+        let output = "abc=\t203.0.113.5:51820\ndef=\t(none)\nghi=\t[2001:db8::1]:51820\n";
+        assert_eq!(
+            parse_wg_endpoints(output),
+            vec![
+                ("abc=".to_string(), "203.0.113.5:51820".to_string()),
+                ("ghi=".to_string(), "[2001:db8::1]:51820".to_string()),
+            ]
+        );
+        assert!(parse_wg_endpoints("").is_empty());
+    }
+}
@@ -0,0 +1,315 @@
+//! Per-profile DNS override.
+//!
+//! Points the system resolver at the profile's DNS servers and search domains
+//! while connected, and restores the previous configuration on disconnect.
+//! Uses platform-specific implementations:
+//! - macOS: `networksetup` on every network service
+//! - Linux: `resolvectl` (systemd-resolved), falling back to `/etc/resolv.conf`
+//!
+//! Optionally, port 53 traffic that does not leave through the tunnel is
+//! blocked with a firewall rule separate from the kill switch.
+//!
+//! What has to be undone is persisted to `dns.state`, so a disconnect from
+//! another process (CLI, daemon) or after a crash still restores it.
+
+use crate::constants;
+use crate::logger::{self, LogLevel};
+use crate::platform::{DnsConfigurator, Firewall};
+use crate::utils;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[cfg(target_os = "linux")]
+type PlatformDns = crate::platform::linux::dns::LinuxDns;
+#[cfg(target_os = "macos")]
+type PlatformDns = crate::platform::macos::dns::MacDns;
+#[cfg(target_os = "linux")]
+type PlatformFirewall = crate::platform::linux::firewall::IptablesFirewall;
+#[cfg(target_os = "macos")]
+type PlatformFirewall = crate::platform::macos::firewall::PfFirewall;
+
+/// DNS settings for a single profile.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DnsOverride {
+    /// Resolver addresses used while connected.
+    pub servers: Vec<String>,
+    /// Search domains used while connected.
+    pub search: Vec<String>,
+    /// Drop port 53 traffic that does not go through the tunnel.
+    pub block_outside: bool,
+}
+
+impl DnsOverride {
+    /// Build an override from the comma/whitespace separated editor fields.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first server that is not an IP address or search domain
+    /// that is not a valid DNS name.
+    pub fn from_fields(servers: &str, search: &str, block_outside: bool) -> Result<Self, String> {
+        let servers = utils::split_list(servers)
+            .map(|s| {
+                s.parse::<std::net::IpAddr>()
+                    .map(|ip| ip.to_string())
+                    .map_err(|_| format!("Invalid DNS server: '{s}'"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let search = utils::split_list(search)
+            .map(|d| {
+                let d = d.trim_end_matches('.');
+                if utils::is_hostname(d) {
+                    Ok(d.to_string())
+                } else {
+                    Err(format!("Invalid search domain: '{d}'"))
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            servers,
+            search,
+            block_outside,
+        })
+    }
+
+    /// Whether the override leaves DNS unchanged.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.servers.is_empty() && self.search.is_empty() && !self.block_outside
+    }
+
+    /// One-line summary for logs.
+    #[must_use]
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.servers.is_empty() {
+            parts.push(format!("servers {}", self.servers.join(", ")));
+        }
+        if !self.search.is_empty() {
+            parts.push(format!("search {}", self.search.join(", ")));
+        }
+        if self.block_outside {
+            parts.push("block outside DNS".to_string());
+        }
+        if parts.is_empty() {
+            "VPN default".to_string()
+        } else {
+            parts.join("; ")
+        }
+    }
+}
+
+/// How to undo a resolver change.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum DnsRestore {
+    /// Per-link settings on the tunnel interface, reverted with `resolvectl revert`.
+    Resolvectl {
+        /// Tunnel interface the settings were applied to.
+        interface: String,
+    },
+    /// `/etc/resolv.conf` was replaced.
+    ResolvConf {
+        /// Previous file content (`None` if it did not exist or was a symlink).
+        original: Option<String>,
+        /// Previous symlink target, if it was a symlink.
+        link: Option<PathBuf>,
+    },
+    /// macOS network services were reconfigured.
+    Networksetup {
+        /// Previous settings of every service.
+        services: Vec<ServiceDns>,
+    },
+}
+
+/// DNS settings of one macOS network service. Empty lists mean "from DHCP".
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServiceDns {
+    /// Network service name (e.g. "Wi-Fi").
+    pub name: String,
+    /// Manually configured DNS servers.
+    pub servers: Vec<String>,
+    /// Manually configured search domains.
+    pub search: Vec<String>,
+}
+
+/// Persisted record of an applied override.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PersistedState {
+    /// Profile the override belongs to.
+    pub profile: String,
+    /// Servers that should be answering while connected.
+    pub servers: Vec<String>,
+    /// Resolver change to undo, if any.
+    pub restore: Option<DnsRestore>,
+    /// Whether the port 53 block is installed.
+    pub block_outside: bool,
+}
+
+/// Load the DNS override stored for a profile, if any.
+#[must_use]
+pub fn for_profile(config_path: &Path) -> Option<DnsOverride> {
+    let key = config_path.to_string_lossy().to_string();
+    utils::load_profile_metadata()
+        .ok()?
+        .remove(&key)?
+        .dns
+        .filter(|d| !d.is_empty())
+}
+
+/// Apply `dns` for `profile`, whose tunnel runs on `interface`.
+///
+/// Any override left behind by a previous session is restored first.
+///
+/// # Errors
+///
+/// Returns an error if the resolver or the port 53 block could not be
+/// configured. Whatever was applied before the failure is still recorded
+/// so the next [`restore`] undoes it.
+pub fn apply(profile: &str, interface: &str, dns: &DnsOverride) -> Result<(), String> {
+    restore();
+    logger::log(
+        LogLevel::Info,
+        "DNS",
+        format!("Applying DNS override on {interface}: {}", dns.summary()),
+    );
+
+    let mut state = PersistedState {
+        profile: profile.to_string(),
+        servers: dns.servers.clone(),
+        restore: None,
+        block_outside: false,
+    };
+
+    if !dns.servers.is_empty() || !dns.search.is_empty() {
+        state.restore = Some(PlatformDns::apply_dns(
+            interface,
+            &dns.servers,
+            &dns.search,
+        )?);
+        save_state(&state);
+    }
+
+    if dns.block_outside {
+        // Record first so a partially installed rule set is still torn down
+        state.block_outside = true;
+        save_state(&state);
+        PlatformFirewall::enable_dns_block(interface)
+            .map_err(|e| format!("Failed to block outside DNS: {e}"))?;
+    }
+    Ok(())
+}
+
+/// Undo the override recorded in `dns.state`, if any.
+pub fn restore() {
+    let Some(state) = load_state() else {
+        return;
+    };
+    logger::log(
+        LogLevel::Info,
+        "DNS",
+        format!("Restoring DNS settings changed for '{}'", state.profile),
+    );
+    if let Some(restore) = &state.restore {
+        if let Err(e) = PlatformDns::restore_dns(restore) {
+            logger::log(
+                LogLevel::Error,
+                "DNS",
+                format!("Failed to restore DNS: {e}"),
+            );
+        }
+    }
+    if state.block_outside {
+        if let Err(e) = PlatformFirewall::disable_dns_block() {
+            logger::log(
+                LogLevel::Error,
+                "DNS",
+                format!("Failed to remove DNS block: {e}"),
+            );
+        }
+    }
+    clear_state();
+}
+
+/// Servers the active override expects to answer queries (empty if none).
+#[must_use]
+pub fn applied_servers() -> Vec<String> {
+    load_state().map(|s| s.servers).unwrap_or_default()
+}
+
+fn get_state_path() -> Option<PathBuf> {
+    utils::get_app_config_dir()
+        .ok()
+        .map(|dir| dir.join(constants::DNS_STATE_FILE))
+}
+
+fn load_state() -> Option<PersistedState> {
+    let content = fs::read_to_string(get_state_path()?).ok()?;
+    serde_json::from_str(&content)
+        .map_err(|e| {
+            logger::log(
+                LogLevel::Warning,
+                "DNS",
+                format!("Failed to parse DNS state: {e}"),
+            );
+        })
+        .ok()
+}
+
+fn save_state(state: &PersistedState) {
+    let Some(path) = get_state_path() else {
+        return;
+    };
+    if let Ok(content) = serde_json::to_string_pretty(state) {
+        if let Err(e) = utils::write_user_file(&path, content) {
+            logger::log(
+                LogLevel::Error,
+                "DNS",
+                format!("Failed to save DNS state: {e}"),
+            );
+        }
+    }
+}
+
+fn clear_state() {
+    if let Some(path) = get_state_path() {
+        let _ = fs::remove_file(path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_fields_validates_entries() {
+        let dns =
+            DnsOverride::from_fields("10.8.0.1, 2606:4700::1111", "corp.example.", true).unwrap();
+        assert_eq!(dns.servers, vec!["10.8.0.1", "2606:4700::1111"]);
+        assert_eq!(dns.search, vec!["corp.example"]);
+        assert!(dns.block_outside);
+
+        assert!(DnsOverride::from_fields("dns.example.com", "", false).is_err());
+        assert!(DnsOverride::from_fields("", "bad_domain", false).is_err());
+        assert!(DnsOverride::from_fields("", "", false).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_persisted_state_round_trip() {
+        let state = PersistedState {
+            profile: "work".to_string(),
+            servers: vec!["10.8.0.1".to_string()],
+            restore: Some(DnsRestore::ResolvConf {
+                original: Some("nameserver 192.168.1.1\n".to_string()),
+                link: None,
+            }),
+            block_outside: true,
+        };
+        let json = serde_json::to_string(&state).unwrap();
+        assert!(json.contains(r#""method":"resolv_conf""#));
+        let back: PersistedState = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.restore, state.restore);
+        assert!(back.block_outside);
+    }
+}
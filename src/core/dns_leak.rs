@@ -0,0 +1,269 @@
+//! Active DNS leak test.
+//!
+//! Reading the configured resolver says nothing about where queries actually
+//! leave the machine. This test resolves unique, never-cached names under a
+//! probe domain whose authoritative server records which resolvers asked,
+//! then compares those resolvers with the VPN exit seen by the same server.
+//!
+//! The endpoint follows the `bash.ws` protocol:
+//! 1. `GET {api}/id` returns a fresh test id
+//! 2. `<random>.<id>.<api host>` is resolved a few times
+//! 3. `GET {api}/dnsleak/test/<id>?json` lists the exit (`"type": "ip"`) and
+//!    every resolver that queried the authoritative server (`"type": "dns"`)
+
+use crate::constants;
+use serde::Deserialize;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::net::ToSocketAddrs;
+
+/// A resolver (or the exit) as seen by the leak test server.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ObservedResolver {
+    /// Address the query arrived from.
+    pub ip: String,
+    /// Autonomous system, e.g. `"AS9009 M247 Europe SRL"`.
+    pub asn: String,
+    /// Country name reported by the server.
+    pub country: String,
+    /// Whether this resolver is outside the VPN provider's network.
+    pub leaking: bool,
+}
+
+/// Outcome of one leak test run.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DnsLeakReport {
+    /// Public exit the test server saw for our HTTP requests.
+    pub exit: Option<ObservedResolver>,
+    /// Resolvers that answered the probe names.
+    pub resolvers: Vec<ObservedResolver>,
+}
+
+impl DnsLeakReport {
+    /// Whether any observed resolver sits outside the VPN provider's network.
+    #[must_use]
+    pub fn is_leaking(&self) -> bool {
+        self.resolvers.iter().any(|r| r.leaking)
+    }
+}
+
+#[derive(Deserialize)]
+struct Entry {
+    #[serde(default)]
+    ip: String,
+    #[serde(default)]
+    asn: String,
+    #[serde(default)]
+    country_name: String,
+    #[serde(rename = "type", default)]
+    kind: String,
+}
+
+/// Run the leak test against `api`, resolving probe names with `resolve`.
+///
+/// # Errors
+///
+/// Returns an error if the endpoint is unreachable or its answer cannot be
+/// parsed.
+pub fn run(api: &str, timeout_secs: u64, resolve: impl Fn(&str)) -> Result<DnsLeakReport, String> {
+    let api = api.trim_end_matches('/');
+    let domain = probe_domain(api).ok_or_else(|| format!("Invalid DNS leak endpoint: {api}"))?;
+
+    let id = http_get(&format!("{api}/id"), timeout_secs)?;
+    let id = id.trim();
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(format!("Unexpected DNS leak test id: '{id}'"));
+    }
+
+    for name in probe_names(id, &domain, constants::DNS_LEAK_PROBES) {
+        resolve(&name);
+    }
+
+    let json = http_get(&format!("{api}/dnsleak/test/{id}?json"), timeout_secs)?;
+    parse_report(&json)
+}
+
+/// Resolve a probe name through the system resolver. The answer (usually
+/// NXDOMAIN) is irrelevant; only the query reaching the probe domain counts.
+pub fn resolve_probe(name: &str) {
+    let _ = (name, 0).to_socket_addrs();
+}
+
+/// `count` unique names under `<id>.<domain>`, each with a random label so
+/// no resolver can answer from cache.
+fn probe_names(id: &str, domain: &str, count: usize) -> Vec<String> {
+    (0..count)
+        .map(|i| format!("{:016x}{i}.{id}.{domain}", random_u64()))
+        .collect()
+}
+
+fn random_u64() -> u64 {
+    let mut hasher = RandomState::new().build_hasher();
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos());
+    hasher.write_u128(nanos);
+    hasher.finish()
+}
+
+/// Host part of the endpoint URL.
+fn probe_domain(api: &str) -> Option<String> {
+    let rest = api.split_once("://").map_or(api, |(_, r)| r);
+    let host = rest.split('/').next()?;
+    let host = host.rsplit_once(':').map_or(host, |(h, _)| h);
+    (!host.is_empty()).then(|| host.to_string())
+}
+
+fn http_get(url: &str, timeout_secs: u64) -> Result<String, String> {
+    let output = std::process::Command::new("curl")
+        .args(["-s", "-f", "--max-time", &timeout_secs.to_string(), url])
+        .output()
+        .map_err(|e| format!("curl failed: {e}"))?;
+    if !output.status.success() {
+        return Err(format!("{url}: request failed ({})", output.status));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Parse the results list and flag resolvers outside the exit's network.
+///
+/// A resolver is in-tunnel when it is the exit address itself or shares
+/// its autonomous system (the VPN provider's own resolvers). Without an
+/// exit entry nothing can be compared, so nothing is flagged.
+fn parse_report(json: &str) -> Result<DnsLeakReport, String> {
+    let entries: Vec<Entry> =
+        serde_json::from_str(json).map_err(|e| format!("Invalid DNS leak test result: {e}"))?;
+
+    let observed = |e: &Entry| ObservedResolver {
+        ip: e.ip.clone(),
+        asn: e.asn.clone(),
+        country: e.country_name.clone(),
+        leaking: false,
+    };
+    let exit = entries.iter().find(|e| e.kind == "ip").map(observed);
+
+    let mut resolvers: Vec<ObservedResolver> = Vec::new();
+    for entry in entries.iter().filter(|e| e.kind == "dns") {
+        if resolvers.iter().any(|r| r.ip == entry.ip) {
+            continue;
+        }
+        let mut resolver = observed(entry);
+        if let Some(exit) = &exit {
+            let same_asn = match (asn_number(&resolver.asn), asn_number(&exit.asn)) {
+                (Some(a), Some(b)) => a == b,
+                _ => false,
+            };
+            resolver.leaking = resolver.ip != exit.ip && !same_asn;
+        }
+        resolvers.push(resolver);
+    }
+
+    Ok(DnsLeakReport { exit, resolvers })
+}
+
+/// `"AS13335 Cloudflare, Inc."` → `13335`.
+fn asn_number(asn: &str) -> Option<u32> {
+    let first = asn.split_whitespace().next()?;
+    first
+        .strip_prefix("AS")
+        .or_else(|| first.strip_prefix("as"))
+        .unwrap_or(first)
+        .parse()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+
+    const RESULT: &str = r#"[
+        {"ip":"185.1.2.3","country_name":"Netherlands","asn":"AS9009 M247 Europe SRL","type":"ip"},
+        {"ip":"185.1.2.53","country_name":"Netherlands","asn":"AS9009 M247 Europe SRL","type":"dns"},
+        {"ip":"84.116.46.23","country_name":"Germany","asn":"AS6830 Liberty Global B.V.","type":"dns"},
+        {"ip":"84.116.46.23","country_name":"Germany","asn":"AS6830 Liberty Global B.V.","type":"dns"},
+        {"ip":"0","country_name":"","asn":"","type":"conclusion"}
+    ]"#;
+
+    /// Minimal HTTP stand-in for the leak test endpoint.
+    fn serve(responses: Vec<(&'static str, &'static str)>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().take(responses.len()) {
+                let mut stream = stream.unwrap();
+                let mut request = String::new();
+                BufReader::new(&stream).read_line(&mut request).unwrap();
+                let path = request.split_whitespace().nth(1).unwrap_or("");
+                let body = responses
+                    .iter()
+                    .find(|(prefix, _)| path.starts_with(prefix))
+                    .map_or("", |(_, body)| body);
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+            }
+        });
+        format!("http://{addr}")
+    }
+
+    #[test]
+    fn test_parse_report_flags_foreign_resolvers() {
+        let report = parse_report(RESULT).unwrap();
+        assert_eq!(report.exit.as_ref().unwrap().ip, "185.1.2.3");
+        assert_eq!(report.resolvers.len(), 2);
+        assert!(!report.resolvers[0].leaking);
+        assert!(report.resolvers[1].leaking);
+        assert!(report.is_leaking());
+    }
+
+    #[test]
+    fn test_parse_report_without_exit_flags_nothing() {
+        let report =
+            parse_report(r#"[{"ip":"84.116.46.23","asn":"AS6830 Liberty Global","type":"dns"}]"#)
+                .unwrap();
+        assert!(report.exit.is_none());
+        assert!(!report.is_leaking());
+        assert!(parse_report("not json").is_err());
+    }
+
+    #[test]
+    fn test_probe_names_are_unique() {
+        let names = probe_names("42", "bash.ws", 5);
+        assert_eq!(names.len(), 5);
+        assert!(names.iter().all(|n| n.ends_with(".42.bash.ws")));
+        let mut unique = names.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), names.len());
+    }
+
+    #[test]
+    fn test_probe_domain() {
+        assert_eq!(probe_domain("https://bash.ws").as_deref(), Some("bash.ws"));
+        assert_eq!(
+            probe_domain("http://127.0.0.1:8053/leak").as_deref(),
+            Some("127.0.0.1")
+        );
+        assert_eq!(probe_domain("https://"), None);
+    }
+
+    #[test]
+    fn test_run_against_local_endpoint() {
+        let api = serve(vec![("/id", "4711\n"), ("/dnsleak/test/4711", RESULT)]);
+        let probed = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&probed);
+
+        let report = run(&api, 5, |name| sink.lock().unwrap().push(name.to_string())).unwrap();
+
+        let probed = probed.lock().unwrap();
+        assert_eq!(probed.len(), constants::DNS_LEAK_PROBES);
+        assert!(probed.iter().all(|n| n.ends_with(".4711.127.0.0.1")));
+        assert_eq!(report.resolvers.len(), 2);
+        assert!(report.is_leaking());
+    }
+}
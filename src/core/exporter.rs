@@ -0,0 +1,460 @@
+//! `OpenMetrics` exporter for tunnel health.
+//!
+//! The owner of the connection state (the TUI or the daemon) publishes a
+//! [`Snapshot`] after every update; a small HTTP server thread renders the
+//! latest one as `OpenMetrics` text on `GET /metrics`. The server never
+//! touches application state directly, it only reads the published copy.
+
+use std::fmt::Write as _;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::daemon::protocol::SessionState;
+use crate::state::{KillSwitchMode, KillSwitchState, Protocol};
+
+/// Content type of an `OpenMetrics` 1.0 exposition.
+const CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+/// How long a scraper may take to send its whole request.
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Most of a request that is read; a scrape needs a fraction of it.
+const MAX_REQUEST_BYTES: u64 = 8192;
+
+/// A profile and whether its tunnel is up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileStatus {
+    pub name: String,
+    pub protocol: Protocol,
+    pub up: bool,
+}
+
+/// Everything the exporter reports. `None` means "not measured yet", which
+/// leaves the metric out rather than reporting a misleading zero.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Snapshot {
+    pub state: SessionState,
+    pub profiles: Vec<ProfileStatus>,
+    /// Bytes per second received on the tunnel. The TUI reports all
+    /// interfaces while disconnected, like its chart.
+    pub rx_rate: u64,
+    /// Bytes per second sent.
+    pub tx_rate: u64,
+    /// Seconds since the last `WireGuard` handshake.
+    pub handshake_age_secs: Option<u64>,
+    pub latency_ms: Option<u64>,
+    pub jitter_ms: Option<u64>,
+    /// Packet loss in percent.
+    pub loss_pct: Option<f32>,
+    pub killswitch_mode: KillSwitchMode,
+    pub killswitch_state: KillSwitchState,
+    pub dns_leak: Option<bool>,
+    pub ipv6_leak: Option<bool>,
+}
+
+/// The latest snapshot, shared between its publisher and the server thread.
+pub type SharedSnapshot = Arc<Mutex<Snapshot>>;
+
+/// Bind `addr` and serve `snapshot` on a background thread.
+///
+/// Returns the bound address (useful with port 0).
+///
+/// # Errors
+///
+/// Returns an error if the address is invalid or cannot be bound.
+pub fn serve(addr: &str, snapshot: SharedSnapshot) -> Result<SocketAddr, String> {
+    let addr: SocketAddr = addr
+        .parse()
+        .map_err(|_| format!("Invalid metrics address '{addr}'"))?;
+    let listener =
+        TcpListener::bind(addr).map_err(|e| format!("Failed to bind metrics on {addr}: {e}"))?;
+    let local = listener
+        .local_addr()
+        .map_err(|e| format!("Failed to bind metrics on {addr}: {e}"))?;
+
+    std::thread::spawn(move || {
+        // Scrapes are rare and cheap; one at a time is plenty
+        for stream in listener.incoming().flatten() {
+            let _ = handle_connection(stream, &snapshot, READ_TIMEOUT);
+        }
+    });
+    Ok(local)
+}
+
+fn handle_connection(
+    stream: TcpStream,
+    snapshot: &SharedSnapshot,
+    timeout: Duration,
+) -> std::io::Result<()> {
+    let deadline = Deadline {
+        stream: stream.try_clone()?,
+        at: Instant::now() + timeout,
+    };
+    // A client trickling bytes can neither hold the server past the
+    // deadline nor grow the buffer without bound
+    let mut reader = BufReader::new(deadline.take(MAX_REQUEST_BYTES));
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Skip the headers; nothing in them changes the answer
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let current = match snapshot.lock() {
+        Ok(s) => s.clone(),
+        Err(poisoned) => poisoned.into_inner().clone(),
+    };
+    let mut writer = stream;
+    writer.write_all(respond(&request_line, &current).as_bytes())?;
+    writer.flush()
+}
+
+/// Reads from a socket until a fixed point in time, however slowly the
+/// peer sends.
+struct Deadline {
+    stream: TcpStream,
+    at: Instant,
+}
+
+impl Read for Deadline {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let left = self.at.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return Err(std::io::ErrorKind::TimedOut.into());
+        }
+        self.stream.set_read_timeout(Some(left))?;
+        self.stream.read(buf)
+    }
+}
+
+/// Build the full HTTP response for a request line.
+fn respond(request_line: &str, snapshot: &Snapshot) -> String {
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let path = parts.next().unwrap_or_default();
+    let path = path.split('?').next().unwrap_or_default();
+
+    let (status, content_type, body) = match (method, path) {
+        ("GET" | "HEAD", "/metrics") => ("200 OK", CONTENT_TYPE, render(snapshot)),
+        ("GET" | "HEAD", _) => (
+            "404 Not Found",
+            "text/plain; charset=utf-8",
+            "Metrics are served on /metrics\n".to_string(),
+        ),
+        _ => (
+            "405 Method Not Allowed",
+            "text/plain; charset=utf-8",
+            "Only GET is supported\n".to_string(),
+        ),
+    };
+    let mut response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    );
+    if method != "HEAD" {
+        response.push_str(&body);
+    }
+    response
+}
+
+/// Render `snapshot` in the `OpenMetrics` text format.
+#[must_use]
+#[allow(clippy::too_many_lines)]
+pub fn render(s: &Snapshot) -> String {
+    let mut out = String::new();
+
+    stateset(
+        &mut out,
+        "vortix_connection_state",
+        "Lifecycle phase of the VPN connection.",
+        &[
+            ("disconnected", s.state == SessionState::Disconnected),
+            ("connecting", s.state == SessionState::Connecting),
+            ("connected", s.state == SessionState::Connected),
+            ("disconnecting", s.state == SessionState::Disconnecting),
+        ],
+    );
+
+    let profiles: Vec<(String, f64)> = s
+        .profiles
+        .iter()
+        .map(|p| {
+            let labels = format!(
+                "profile=\"{}\",protocol=\"{}\"",
+                escape(&p.name),
+                p.protocol
+            );
+            (labels, flag(p.up))
+        })
+        .collect();
+    gauge(
+        &mut out,
+        "vortix_profile_up",
+        None,
+        "Whether the profile's tunnel is up.",
+        &profiles,
+    );
+
+    #[allow(clippy::cast_precision_loss)]
+    let rates = [
+        ("direction=\"rx\"".to_string(), s.rx_rate as f64),
+        ("direction=\"tx\"".to_string(), s.tx_rate as f64),
+    ];
+    gauge(
+        &mut out,
+        "vortix_throughput_bytes_per_second",
+        None,
+        "Current transfer rate of the tunnel.",
+        &rates,
+    );
+
+    #[allow(clippy::cast_precision_loss)]
+    let optional = [
+        (
+            "vortix_handshake_age_seconds",
+            Some("seconds"),
+            "Time since the last WireGuard handshake.",
+            s.handshake_age_secs.map(|secs| secs as f64),
+        ),
+        (
+            "vortix_latency_seconds",
+            Some("seconds"),
+            "Round-trip latency of the last probe.",
+            s.latency_ms.map(|ms| ms as f64 / 1000.0),
+        ),
+        (
+            "vortix_jitter_seconds",
+            Some("seconds"),
+            "Latency jitter of the last probe.",
+            s.jitter_ms.map(|ms| ms as f64 / 1000.0),
+        ),
+        (
+            "vortix_packet_loss_ratio",
+            Some("ratio"),
+            "Share of probe packets lost (0-1).",
+            s.loss_pct.map(|pct| f64::from(pct) / 100.0),
+        ),
+    ];
+    for (name, unit, help, value) in optional {
+        if let Some(value) = value {
+            gauge(&mut out, name, unit, help, &[(String::new(), value)]);
+        }
+    }
+
+    stateset(
+        &mut out,
+        "vortix_killswitch_mode",
+        "Configured kill switch mode.",
+        &[
+            ("off", s.killswitch_mode == KillSwitchMode::Off),
+            ("auto", s.killswitch_mode == KillSwitchMode::Auto),
+            ("always_on", s.killswitch_mode == KillSwitchMode::AlwaysOn),
+        ],
+    );
+    stateset(
+        &mut out,
+        "vortix_killswitch_state",
+        "What the kill switch is doing right now.",
+        &[
+            ("disabled", s.killswitch_state == KillSwitchState::Disabled),
+            ("armed", s.killswitch_state == KillSwitchState::Armed),
+            ("blocking", s.killswitch_state == KillSwitchState::Blocking),
+        ],
+    );
+
+    let leaks = [
+        (
+            "vortix_dns_leak",
+            "Whether DNS queries leak outside the tunnel.",
+            s.dns_leak,
+        ),
+        (
+            "vortix_ipv6_leak",
+            "Whether IPv6 traffic leaks outside the tunnel.",
+            s.ipv6_leak,
+        ),
+    ];
+    for (name, help, leak) in leaks {
+        if let Some(leak) = leak {
+            gauge(&mut out, name, None, help, &[(String::new(), flag(leak))]);
+        }
+    }
+
+    out.push_str("# EOF\n");
+    out
+}
+
+/// Write the metadata lines of a metric family.
+fn family(out: &mut String, name: &str, kind: &str, unit: Option<&str>, help: &str) {
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+    if let Some(unit) = unit {
+        let _ = writeln!(out, "# UNIT {name} {unit}");
+    }
+    let _ = writeln!(out, "# HELP {name} {help}");
+}
+
+/// Write a gauge family; each sample is its label set (may be empty) and value.
+fn gauge(out: &mut String, name: &str, unit: Option<&str>, help: &str, samples: &[(String, f64)]) {
+    family(out, name, "gauge", unit, help);
+    for (labels, value) in samples {
+        if labels.is_empty() {
+            let _ = writeln!(out, "{name} {value}");
+        } else {
+            let _ = writeln!(out, "{name}{{{labels}}} {value}");
+        }
+    }
+}
+
+/// Write a stateset family: one sample per state, 1 for the active one.
+fn stateset(out: &mut String, name: &str, help: &str, states: &[(&str, bool)]) {
+    family(out, name, "stateset", None, help);
+    for (state, active) in states {
+        let _ = writeln!(out, "{name}{{{name}=\"{state}\"}} {}", u8::from(*active));
+    }
+}
+
+fn flag(value: bool) -> f64 {
+    f64::from(u8::from(value))
+}
+
+/// Escape a label value (backslash, double quote and newline).
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn connected() -> Snapshot {
+        Snapshot {
+            state: SessionState::Connected,
+            profiles: vec![
+                ProfileStatus {
+                    name: "work".to_string(),
+                    protocol: Protocol::WireGuard,
+                    up: true,
+                },
+                ProfileStatus {
+                    name: "home \"nl\"".to_string(),
+                    protocol: Protocol::OpenVPN,
+                    up: false,
+                },
+            ],
+            rx_rate: 2048,
+            tx_rate: 512,
+            handshake_age_secs: Some(65),
+            latency_ms: Some(42),
+            jitter_ms: Some(3),
+            loss_pct: Some(2.5),
+            killswitch_mode: KillSwitchMode::Auto,
+            killswitch_state: KillSwitchState::Armed,
+            dns_leak: Some(false),
+            ipv6_leak: None,
+        }
+    }
+
+    #[test]
+    fn test_render_openmetrics() {
+        let text = render(&connected());
+        for line in [
+            "vortix_connection_state{vortix_connection_state=\"connected\"} 1",
+            "vortix_connection_state{vortix_connection_state=\"connecting\"} 0",
+            "vortix_profile_up{profile=\"work\",protocol=\"WireGuard\"} 1",
+            "vortix_profile_up{profile=\"home \\\"nl\\\"\",protocol=\"OpenVPN\"} 0",
+            "vortix_throughput_bytes_per_second{direction=\"rx\"} 2048",
+            "vortix_handshake_age_seconds 65",
+            "vortix_latency_seconds 0.042",
+            "vortix_jitter_seconds 0.003",
+            "vortix_packet_loss_ratio 0.025",
+            "vortix_killswitch_mode{vortix_killswitch_mode=\"auto\"} 1",
+            "vortix_killswitch_state{vortix_killswitch_state=\"armed\"} 1",
+            "vortix_dns_leak 0",
+        ] {
+            assert!(text.lines().any(|l| l == line), "missing {line}\n{text}");
+        }
+        // Unknown values are left out
+        assert!(!text.contains("vortix_ipv6_leak"));
+        assert!(text.ends_with("# EOF\n"));
+    }
+
+    #[test]
+    fn test_respond_routes() {
+        let snapshot = Snapshot::default();
+        let ok = respond("GET /metrics HTTP/1.1\r\n", &snapshot);
+        assert!(ok.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(ok.contains(CONTENT_TYPE));
+        assert!(ok.ends_with("# EOF\n"));
+
+        let head = respond("HEAD /metrics HTTP/1.1\r\n", &snapshot);
+        assert!(head.ends_with("\r\n\r\n"));
+        assert!(respond("GET / HTTP/1.1\r\n", &snapshot).starts_with("HTTP/1.1 404"));
+        assert!(respond("POST /metrics HTTP/1.1\r\n", &snapshot).starts_with("HTTP/1.1 405"));
+    }
+
+    #[test]
+    fn test_serve_scrape() {
+        let shared = Arc::new(Mutex::new(Snapshot::default()));
+        let addr = serve("127.0.0.1:0", Arc::clone(&shared)).unwrap();
+        *shared.lock().unwrap() = connected();
+
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream
+            .write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains("vortix_latency_seconds 0.042"));
+
+        assert!(serve("not-an-address", shared).is_err());
+    }
+
+    /// A connected pair: the client end and the server's accepted end.
+    fn socket_pair() -> (TcpStream, TcpStream) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (server, _) = listener.accept().unwrap();
+        (client, server)
+    }
+
+    #[test]
+    fn test_slow_client_hits_deadline() {
+        let shared = Arc::new(Mutex::new(Snapshot::default()));
+        let (mut client, server) = socket_pair();
+        let trickle = std::thread::spawn(move || {
+            for _ in 0..40 {
+                if client.write_all(b"G").is_err() {
+                    break;
+                }
+                std::thread::sleep(Duration::from_millis(50));
+            }
+        });
+
+        let started = Instant::now();
+        let result = handle_connection(server, &shared, Duration::from_millis(300));
+        assert!(result.is_err());
+        assert!(started.elapsed() < Duration::from_secs(1));
+        let _ = trickle.join();
+    }
+
+    #[test]
+    fn test_oversized_request_is_cut_off() {
+        let shared = Arc::new(Mutex::new(Snapshot::default()));
+        let (mut client, server) = socket_pair();
+        // No line end, ever: only MAX_REQUEST_BYTES of it are read
+        client.write_all(&[b'A'; 16 * 1024]).unwrap();
+
+        // Answered at once instead of waiting for the rest
+        let started = Instant::now();
+        handle_connection(server, &shared, Duration::from_secs(5)).unwrap();
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+}
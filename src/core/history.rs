@@ -0,0 +1,427 @@
+//! Append-only session history.
+//!
+//! Every session, including connects that never came up, ends as one JSON
+//! line in `history.jsonl` under the config dir. Lines are only ever
+//! appended, so a crash loses at most the session in progress, and a line
+//! that fails to parse (e.g. half-written) is skipped on load.
+//!
+//! A [`SessionTracker`] collects the figures while the session runs; the TUI
+//! and the daemon feed it from telemetry and the tunnel's interface counters,
+//! and [`append`] stores the finished [`SessionRecord`].
+
+use crate::app::Protocol;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Why a session ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EndReason {
+    /// Disconnected on request.
+    User,
+    /// The tunnel went away on its own.
+    Drop,
+    /// The connect did not complete in time.
+    Timeout,
+    /// The connect failed.
+    Error,
+}
+
+impl EndReason {
+    /// Lowercase name, as stored and accepted by `vortix history --reason`.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::User => "user",
+            Self::Drop => "drop",
+            Self::Timeout => "timeout",
+            Self::Error => "error",
+        }
+    }
+}
+
+impl std::fmt::Display for EndReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+impl std::str::FromStr for EndReason {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "user" => Ok(Self::User),
+            "drop" => Ok(Self::Drop),
+            "timeout" => Ok(Self::Timeout),
+            "error" => Ok(Self::Error),
+            _ => Err(format!(
+                "unknown end reason '{s}' (expected user, drop, timeout or error)"
+            )),
+        }
+    }
+}
+
+/// One finished session.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionRecord {
+    /// Profile name.
+    pub profile: String,
+    /// `WireGuard` or `OpenVPN`.
+    pub protocol: String,
+    /// Unix seconds the session started (the connect attempt, for failures).
+    pub started_at: u64,
+    /// Unix seconds the session ended.
+    pub ended_at: u64,
+    /// Why it ended.
+    pub end_reason: EndReason,
+    /// Error message for failed connects.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub error: String,
+    /// Bytes received through the tunnel.
+    pub rx_bytes: u64,
+    /// Bytes sent through the tunnel.
+    pub tx_bytes: u64,
+    /// Mean latency over the session (`None` without measurements).
+    pub avg_latency_ms: Option<u64>,
+    /// Mean packet loss in percent (`None` without measurements).
+    pub avg_loss_pct: Option<f32>,
+    /// Public IP seen through the tunnel.
+    #[serde(default)]
+    pub exit_ip: String,
+    /// Location of the exit IP.
+    #[serde(default)]
+    pub location: String,
+}
+
+impl SessionRecord {
+    /// Session length in seconds.
+    #[must_use]
+    pub fn duration_secs(&self) -> u64 {
+        self.ended_at.saturating_sub(self.started_at)
+    }
+
+    /// Case-insensitive match of `query` against the profile, end reason,
+    /// exit IP and location. An empty query matches everything.
+    #[must_use]
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        query.is_empty()
+            || [
+                self.profile.as_str(),
+                self.end_reason.as_str(),
+                self.exit_ip.as_str(),
+                self.location.as_str(),
+            ]
+            .iter()
+            .any(|field| field.to_lowercase().contains(&query))
+    }
+}
+
+/// Figures of the session in progress.
+#[derive(Debug, Clone)]
+pub struct SessionTracker {
+    profile: String,
+    protocol: Protocol,
+    started_at: SystemTime,
+    rx_bytes: u64,
+    tx_bytes: u64,
+    latency_total: u64,
+    latency_samples: u64,
+    loss_total: f64,
+    loss_samples: u32,
+    exit_ip: String,
+    location: String,
+}
+
+impl SessionTracker {
+    /// Start tracking a session of `profile` that began at `started_at`.
+    #[must_use]
+    pub fn new(profile: &str, protocol: Protocol, started_at: SystemTime) -> Self {
+        Self {
+            profile: profile.to_string(),
+            protocol,
+            started_at,
+            rx_bytes: 0,
+            tx_bytes: 0,
+            latency_total: 0,
+            latency_samples: 0,
+            loss_total: 0.0,
+            loss_samples: 0,
+            exit_ip: String::new(),
+            location: String::new(),
+        }
+    }
+
+    /// Profile this session belongs to.
+    #[must_use]
+    pub fn profile(&self) -> &str {
+        &self.profile
+    }
+
+    /// The tunnel came up: the session counts from `at`, not from the
+    /// connect attempt.
+    pub fn connected_at(&mut self, at: SystemTime) {
+        self.started_at = at;
+    }
+
+    /// Latest counters of the tunnel interface. The interface is created
+    /// for the session, so its counters are the session's traffic.
+    pub fn record_bytes(&mut self, rx: u64, tx: u64) {
+        self.rx_bytes = self.rx_bytes.max(rx);
+        self.tx_bytes = self.tx_bytes.max(tx);
+    }
+
+    /// A latency measurement taken through the tunnel.
+    pub fn record_latency(&mut self, ms: u64) {
+        self.latency_total += ms;
+        self.latency_samples += 1;
+    }
+
+    /// A packet loss measurement taken through the tunnel.
+    pub fn record_loss(&mut self, pct: f32) {
+        self.loss_total += f64::from(pct);
+        self.loss_samples += 1;
+    }
+
+    /// Public IP seen through the tunnel.
+    pub fn record_exit_ip(&mut self, ip: &str) {
+        self.exit_ip = ip.to_string();
+    }
+
+    /// Location of the exit IP.
+    pub fn record_location(&mut self, location: &str) {
+        self.location = location.to_string();
+    }
+
+    /// Close the session now.
+    #[must_use]
+    pub fn finish(self, reason: EndReason, error: &str) -> SessionRecord {
+        #[allow(clippy::cast_possible_truncation)]
+        let avg_loss_pct = (self.loss_samples > 0)
+            .then(|| (self.loss_total / f64::from(self.loss_samples)) as f32);
+        SessionRecord {
+            profile: self.profile,
+            protocol: self.protocol.to_string(),
+            started_at: unix_secs(self.started_at),
+            ended_at: unix_secs(SystemTime::now()),
+            end_reason: reason,
+            error: error.to_string(),
+            rx_bytes: self.rx_bytes,
+            tx_bytes: self.tx_bytes,
+            avg_latency_ms: (self.latency_samples > 0)
+                .then(|| self.latency_total / self.latency_samples),
+            avg_loss_pct,
+            exit_ip: self.exit_ip,
+            location: self.location,
+        }
+    }
+}
+
+fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+/// Path of the history file in `config_dir`.
+#[must_use]
+pub fn history_path(config_dir: &Path) -> PathBuf {
+    config_dir.join(crate::constants::HISTORY_FILE_NAME)
+}
+
+/// Append `record` to the history file in `config_dir`.
+///
+/// # Errors
+///
+/// Returns a human-readable error if the file cannot be written.
+pub fn append(config_dir: &Path, record: &SessionRecord) -> Result<(), String> {
+    append_to(&history_path(config_dir), record)
+        .map_err(|e| format!("Failed to write session history: {e}"))
+}
+
+fn append_to(path: &Path, record: &SessionRecord) -> std::io::Result<()> {
+    let existed = path.exists();
+    let line = serde_json::to_string(record)?;
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    // One write per record keeps concurrent appends from interleaving
+    file.write_all(format!("{line}\n").as_bytes())?;
+    if !existed {
+        crate::config::fix_ownership(path);
+    }
+    Ok(())
+}
+
+/// All records in `config_dir`, oldest first. A missing file is an empty
+/// history.
+///
+/// # Errors
+///
+/// Returns a human-readable error if the file exists but cannot be read.
+pub fn load(config_dir: &Path) -> Result<Vec<SessionRecord>, String> {
+    load_from(&history_path(config_dir)).map_err(|e| format!("Failed to read session history: {e}"))
+}
+
+fn load_from(path: &Path) -> std::io::Result<Vec<SessionRecord>> {
+    let file = match std::fs::File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut records = Vec::new();
+    for line in std::io::BufReader::new(file).lines() {
+        let line = line?;
+        if let Ok(record) = serde_json::from_str(&line) {
+            records.push(record);
+        }
+    }
+    Ok(records)
+}
+
+/// Records as CSV with a header row. Times are Unix seconds.
+#[must_use]
+pub fn to_csv(records: &[SessionRecord]) -> String {
+    let mut out = String::from(
+        "profile,protocol,started_at,ended_at,duration_secs,end_reason,error,\
+         rx_bytes,tx_bytes,avg_latency_ms,avg_loss_pct,exit_ip,location\n",
+    );
+    for r in records {
+        let fields = [
+            csv_field(&r.profile),
+            csv_field(&r.protocol),
+            r.started_at.to_string(),
+            r.ended_at.to_string(),
+            r.duration_secs().to_string(),
+            r.end_reason.to_string(),
+            csv_field(&r.error),
+            r.rx_bytes.to_string(),
+            r.tx_bytes.to_string(),
+            r.avg_latency_ms.map(|v| v.to_string()).unwrap_or_default(),
+            r.avg_loss_pct
+                .map(|v| format!("{v:.1}"))
+                .unwrap_or_default(),
+            csv_field(&r.exit_ip),
+            csv_field(&r.location),
+        ];
+        out.push_str(&fields.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// Quote a CSV field if it contains a separator, quote or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn record(profile: &str, reason: EndReason) -> SessionRecord {
+        SessionRecord {
+            profile: profile.to_string(),
+            protocol: "WireGuard".to_string(),
+            started_at: 1_700_000_000,
+            ended_at: 1_700_000_600,
+            end_reason: reason,
+            error: String::new(),
+            rx_bytes: 2048,
+            tx_bytes: 512,
+            avg_latency_ms: Some(42),
+            avg_loss_pct: Some(0.5),
+            exit_ip: "203.0.113.9".to_string(),
+            location: "Zurich, CH".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_append_and_load_skip_broken_lines() {
+        let dir = std::env::temp_dir().join(format!("vortix-history-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = history_path(&dir);
+        let _ = std::fs::remove_file(&path);
+
+        assert!(load(&dir).unwrap().is_empty());
+        append(&dir, &record("office", EndReason::User)).unwrap();
+        // A crash mid-write leaves a partial line behind
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"{\"profile\":\"cut\n")
+            .unwrap();
+        append(&dir, &record("home", EndReason::Drop)).unwrap();
+
+        let records = load(&dir).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0], record("office", EndReason::User));
+        assert_eq!(records[1].end_reason, EndReason::Drop);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_tracker_averages() {
+        let start = SystemTime::now() - Duration::from_secs(90);
+        let mut tracker = SessionTracker::new("office", Protocol::OpenVPN, start);
+        tracker.record_latency(40);
+        tracker.record_latency(60);
+        tracker.record_loss(1.0);
+        tracker.record_loss(2.0);
+        tracker.record_bytes(4096, 1024);
+        // A reading taken after the interface vanished
+        tracker.record_bytes(0, 0);
+
+        let r = tracker.finish(EndReason::Drop, "");
+        assert_eq!(r.protocol, "OpenVPN");
+        assert_eq!(r.avg_latency_ms, Some(50));
+        assert_eq!(r.avg_loss_pct, Some(1.5));
+        assert_eq!((r.rx_bytes, r.tx_bytes), (4096, 1024));
+        assert!(r.duration_secs() >= 90);
+
+        let failed = SessionTracker::new("home", Protocol::WireGuard, SystemTime::now())
+            .finish(EndReason::Error, "AUTH_FAILED");
+        assert_eq!(failed.avg_latency_ms, None);
+        assert_eq!(failed.avg_loss_pct, None);
+    }
+
+    #[test]
+    fn test_matches_and_reason_parsing() {
+        let r = record("Office-VPN", EndReason::Drop);
+        assert!(r.matches(""));
+        assert!(r.matches("office"));
+        assert!(r.matches("DROP"));
+        assert!(r.matches("zurich"));
+        assert!(!r.matches("timeout"));
+
+        assert_eq!("Timeout".parse::<EndReason>(), Ok(EndReason::Timeout));
+        assert!("crash".parse::<EndReason>().is_err());
+    }
+
+    #[test]
+    fn test_csv_quotes_fields() {
+        let mut r = record("office", EndReason::Error);
+        r.error = "OpenVPN: TLS Error, \"handshake\" failed".to_string();
+        r.avg_latency_ms = None;
+
+        let csv = to_csv(&[r]);
+        let mut lines = csv.lines();
+        assert!(lines
+            .next()
+            .unwrap()
+            .starts_with("profile,protocol,started_at"));
+        assert_eq!(
+            lines.next().unwrap(),
+            "office,WireGuard,1700000000,1700000600,600,error,\
+             \"OpenVPN: TLS Error, \"\"handshake\"\" failed\",2048,512,,0.5,203.0.113.9,\"Zurich, CH\""
+        );
+    }
+}
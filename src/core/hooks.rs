@@ -0,0 +1,397 @@
+//! Lifecycle hook commands.
+//!
+//! Users can attach shell commands to tunnel transitions in the `[hooks]`
+//! table of `config.toml`, globally and per profile. A hook runs through
+//! `sh -c` with `VORTIX_*` environment variables describing the tunnel, under
+//! the configured timeout. Its output is returned as activity log lines so the
+//! caller can route it wherever it logs (TUI event log, daemon log, stderr).
+//!
+//! Hooks run with the privileges of vortix itself, which is usually root.
+
+use std::io::Read;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::config::{HooksConfig, ProfileHooks};
+use crate::constants;
+use crate::core::scanner::ActiveSession;
+
+/// Tunnel transition a hook is attached to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookEvent {
+    /// Before the tunnel is brought up.
+    PreUp,
+    /// After the tunnel is up.
+    PostUp,
+    /// Before the tunnel is brought down on request.
+    PreDown,
+    /// After the tunnel was brought down on request.
+    PostDown,
+    /// The tunnel went away on its own.
+    OnDrop,
+}
+
+impl HookEvent {
+    /// Name of the config key, also passed to hooks as `VORTIX_HOOK`.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::PreUp => "pre_up",
+            Self::PostUp => "post_up",
+            Self::PreDown => "pre_down",
+            Self::PostDown => "post_down",
+            Self::OnDrop => "on_drop",
+        }
+    }
+}
+
+/// What a hook is told about the tunnel. Fields that aren't known at the
+/// time of the transition (e.g. the interface before `pre_up`) stay empty.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HookContext {
+    pub profile: String,
+    pub interface: String,
+    pub internal_ip: String,
+    pub endpoint: String,
+    /// Why the transition happens: `connect`, `reconnect`, `user`, `quit` or `drop`.
+    pub reason: String,
+}
+
+impl HookContext {
+    /// Context with only the profile and reason known.
+    #[must_use]
+    pub fn new(profile: &str, reason: &str) -> Self {
+        Self {
+            profile: profile.to_string(),
+            reason: reason.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Context for a tunnel the scanner found.
+    #[must_use]
+    pub fn from_session(session: &ActiveSession, reason: &str) -> Self {
+        Self {
+            interface: session.interface.clone(),
+            internal_ip: session.internal_ip.clone(),
+            endpoint: session.endpoint.clone(),
+            ..Self::new(&session.name, reason)
+        }
+    }
+
+    fn env(&self, event: HookEvent) -> [(&'static str, &str); 6] {
+        [
+            ("VORTIX_HOOK", event.as_str()),
+            ("VORTIX_PROFILE", &self.profile),
+            ("VORTIX_INTERFACE", &self.interface),
+            ("VORTIX_INTERNAL_IP", &self.internal_ip),
+            ("VORTIX_ENDPOINT", &self.endpoint),
+            ("VORTIX_REASON", &self.reason),
+        ]
+    }
+}
+
+fn command_for(hooks: &ProfileHooks, event: HookEvent) -> &str {
+    match event {
+        HookEvent::PreUp => &hooks.pre_up,
+        HookEvent::PostUp => &hooks.post_up,
+        HookEvent::PreDown => &hooks.pre_down,
+        HookEvent::PostDown => &hooks.post_down,
+        HookEvent::OnDrop => &hooks.on_drop,
+    }
+}
+
+/// Commands configured for `event` on `profile`: the global one first, then
+/// the profile's own. Blank entries are left out.
+#[must_use]
+pub fn commands(config: &HooksConfig, event: HookEvent, profile: &str) -> Vec<String> {
+    let global = match event {
+        HookEvent::PreUp => &config.pre_up,
+        HookEvent::PostUp => &config.post_up,
+        HookEvent::PreDown => &config.pre_down,
+        HookEvent::PostDown => &config.post_down,
+        HookEvent::OnDrop => &config.on_drop,
+    };
+    std::iter::once(global.as_str())
+        .chain(config.profile.get(profile).map(|p| command_for(p, event)))
+        .map(str::trim)
+        .filter(|c| !c.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Whether any command is configured for `event` on `profile`.
+#[must_use]
+pub fn has_hooks(config: &HooksConfig, event: HookEvent, profile: &str) -> bool {
+    !commands(config, event, profile).is_empty()
+}
+
+/// Run the commands for `event` one after another, each under the configured
+/// timeout, and return log lines (`HOOK:`/`WARN:` prefixed) describing what
+/// happened. Blocks until every command has finished or timed out.
+#[must_use]
+pub fn run(config: &HooksConfig, event: HookEvent, ctx: &HookContext) -> Vec<String> {
+    let timeout = Duration::from_secs(config.timeout.max(1));
+    let mut lines = Vec::new();
+    for command in commands(config, event, &ctx.profile) {
+        run_one(&command, event, ctx, timeout, &mut lines);
+    }
+    lines
+}
+
+fn run_one(
+    command: &str,
+    event: HookEvent,
+    ctx: &HookContext,
+    timeout: Duration,
+    lines: &mut Vec<String>,
+) {
+    let name = event.as_str();
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c")
+        .arg(command)
+        .envs(ctx.env(event))
+        .stdin(Stdio::null());
+
+    let Some(output) = run_hook(&mut cmd, timeout) else {
+        lines.push(format!(
+            "WARN: {name} hook timed out after {}s or could not start: {command}",
+            timeout.as_secs()
+        ));
+        return;
+    };
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    lines.extend(
+        stdout
+            .lines()
+            .chain(stderr.lines())
+            .map(str::trim_end)
+            .filter(|l| !l.trim().is_empty())
+            .take(constants::HOOK_OUTPUT_MAX_LINES)
+            .map(|l| format!("HOOK: [{name}] {l}")),
+    );
+
+    if output.status.success() {
+        lines.push(format!("HOOK: {name} hook finished: {command}"));
+    } else {
+        let code = output
+            .status
+            .code()
+            .map_or_else(|| "a signal".to_string(), |c| format!("status {c}"));
+        lines.push(format!("WARN: {name} hook exited with {code}: {command}"));
+    }
+}
+
+/// What a finished hook left behind.
+struct HookOutput {
+    status: ExitStatus,
+    stdout: Vec<u8>,
+    stderr: Vec<u8>,
+}
+
+/// Run a hook in its own process group, killing the group if the shell is
+/// still running after `timeout`. Output is drained while it runs, so a
+/// chatty hook never blocks on a full pipe, and collected only briefly
+/// after the shell exits: whatever it left running in the background may
+/// keep the pipes open. `None` if it could not start or timed out.
+fn run_hook(cmd: &mut Command, timeout: Duration) -> Option<HookOutput> {
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(cmd, 0);
+    let mut child = cmd
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .ok()?;
+
+    let (done_tx, done_rx) = mpsc::channel();
+    let stdout = drain(child.stdout.take(), done_tx.clone());
+    let stderr = drain(child.stderr.take(), done_tx);
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() >= deadline => {
+                kill_group(&mut child);
+                return None;
+            }
+            Ok(None) => std::thread::sleep(Duration::from_millis(50)),
+            Err(_) => {
+                kill_group(&mut child);
+                return None;
+            }
+        }
+    };
+
+    let grace = Instant::now() + Duration::from_millis(constants::HOOK_OUTPUT_GRACE_MS);
+    for _ in 0..2 {
+        let left = grace.saturating_duration_since(Instant::now());
+        if done_rx.recv_timeout(left).is_err() {
+            break;
+        }
+    }
+    let take = |buf: &Arc<Mutex<Vec<u8>>>| {
+        std::mem::take(
+            &mut *buf
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner),
+        )
+    };
+    Some(HookOutput {
+        status,
+        stdout: take(&stdout),
+        stderr: take(&stderr),
+    })
+}
+
+/// Read `pipe` to its end on a thread, keeping the first
+/// [`constants::HOOK_OUTPUT_MAX_BYTES`]; signals `done` at the end.
+fn drain(pipe: Option<impl Read + Send + 'static>, done: mpsc::Sender<()>) -> Arc<Mutex<Vec<u8>>> {
+    let buf = Arc::new(Mutex::new(Vec::new()));
+    let Some(mut pipe) = pipe else {
+        let _ = done.send(());
+        return buf;
+    };
+    let shared = Arc::clone(&buf);
+    std::thread::spawn(move || {
+        let mut chunk = [0u8; 4096];
+        while let Ok(n) = pipe.read(&mut chunk) {
+            if n == 0 {
+                break;
+            }
+            let mut out = shared
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            let room = constants::HOOK_OUTPUT_MAX_BYTES.saturating_sub(out.len());
+            out.extend_from_slice(&chunk[..n.min(room)]);
+        }
+        let _ = done.send(());
+    });
+    buf
+}
+
+/// Kill the hook's shell and everything it started.
+fn kill_group(child: &mut Child) {
+    #[cfg(unix)]
+    if let Ok(pgid) = libc::pid_t::try_from(child.id()) {
+        // SAFETY: plain syscall; the group was created for this hook
+        #[allow(unsafe_code)]
+        unsafe {
+            libc::kill(-pgid, libc::SIGKILL);
+        }
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(toml: &str) -> HooksConfig {
+        toml::from_str::<crate::config::AppConfig>(toml)
+            .unwrap()
+            .hooks
+    }
+
+    #[test]
+    fn test_commands_global_then_profile() {
+        let hooks = config(
+            r#"
+            [hooks]
+            post_up = "echo global"
+            on_drop = "  "
+
+            [hooks.profile."Work VPN"]
+            post_up = "echo work"
+            "#,
+        );
+        assert_eq!(
+            commands(&hooks, HookEvent::PostUp, "Work VPN"),
+            vec!["echo global", "echo work"]
+        );
+        assert_eq!(
+            commands(&hooks, HookEvent::PostUp, "Home"),
+            vec!["echo global"]
+        );
+        assert!(!has_hooks(&hooks, HookEvent::OnDrop, "Work VPN"));
+        assert_eq!(hooks.timeout, constants::DEFAULT_HOOK_TIMEOUT);
+    }
+
+    #[test]
+    fn test_run_passes_context_and_captures_output() {
+        let hooks = HooksConfig {
+            post_up: "echo \"$VORTIX_HOOK $VORTIX_PROFILE $VORTIX_INTERFACE $VORTIX_REASON\"; \
+                      echo oops >&2; exit 3"
+                .to_string(),
+            ..HooksConfig::default()
+        };
+        let ctx = HookContext {
+            interface: "wg0".to_string(),
+            ..HookContext::new("office", "connect")
+        };
+        let lines = run(&hooks, HookEvent::PostUp, &ctx);
+        assert_eq!(lines[0], "HOOK: [post_up] post_up office wg0 connect");
+        assert_eq!(lines[1], "HOOK: [post_up] oops");
+        assert!(lines[2].starts_with("WARN: post_up hook exited with status 3"));
+    }
+
+    #[test]
+    fn test_run_kills_slow_hook() {
+        let hooks = HooksConfig {
+            pre_down: "sleep 5".to_string(),
+            timeout: 1,
+            ..HooksConfig::default()
+        };
+        let started = std::time::Instant::now();
+        let lines = run(&hooks, HookEvent::PreDown, &HookContext::new("p", "user"));
+        assert!(started.elapsed() < Duration::from_secs(4));
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].contains("timed out after 1s"));
+    }
+
+    #[test]
+    fn test_run_returns_while_background_job_runs() {
+        let hooks = HooksConfig {
+            post_up: "sleep 60 & echo started".to_string(),
+            timeout: 5,
+            ..HooksConfig::default()
+        };
+        let started = std::time::Instant::now();
+        let lines = run(&hooks, HookEvent::PostUp, &HookContext::new("p", "connect"));
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert_eq!(lines[0], "HOOK: [post_up] started");
+        assert!(lines[1].starts_with("HOOK: post_up hook finished"));
+    }
+
+    #[test]
+    fn test_run_kills_background_job_on_timeout() {
+        let hooks = HooksConfig {
+            pre_up: "sleep 60 & wait".to_string(),
+            timeout: 1,
+            ..HooksConfig::default()
+        };
+        let started = std::time::Instant::now();
+        let lines = run(&hooks, HookEvent::PreUp, &HookContext::new("p", "connect"));
+        assert!(started.elapsed() < Duration::from_secs(3));
+        assert!(lines[0].contains("timed out after 1s"));
+    }
+
+    #[test]
+    fn test_run_survives_large_output() {
+        let hooks = HooksConfig {
+            pre_up: "head -c 200000 /dev/zero | tr '\\0' 'x'; echo".to_string(),
+            timeout: 5,
+            ..HooksConfig::default()
+        };
+        let lines = run(&hooks, HookEvent::PreUp, &HookContext::new("p", "connect"));
+        assert!(lines
+            .last()
+            .unwrap()
+            .starts_with("HOOK: pre_up hook finished"));
+    }
+}
@@ -3,7 +3,8 @@
 //! Controls the system firewall to block non-VPN traffic when kill switch is active.
 //! Uses platform-specific implementations:
 //! - macOS: pf (Packet Filter) via pfctl
-//! - Linux: iptables with custom `VORTIX_KILLSWITCH` chain
+//! - Linux: iptables/ip6tables with custom `VORTIX_KILLSWITCH` chains, or an
+//!   `inet` nftables table covering both families
 //!
 //! # Safety
 //!
@@ -11,9 +12,11 @@
 //! Firewall rules are designed to:
 //! - Always allow loopback traffic
 //! - Always allow local network (RFC1918) traffic
+//! - Allow IPv6 link-local and neighbor discovery (unless IPv6 is blocked entirely)
 //! - Allow VPN server IP for reconnection
 //! - Allow all traffic on VPN interface
 
+use crate::config::KillSwitchConfig;
 use crate::constants;
 use crate::logger::{self, LogLevel};
 #[cfg(any(target_os = "macos", target_os = "linux"))]
@@ -36,6 +39,8 @@ pub enum KillSwitchError {
     Io(io::Error),
     /// Not running as root
     NotRoot,
+    /// Invalid `[killswitch]` configuration
+    InvalidConfig(String),
 }
 
 impl std::fmt::Display for KillSwitchError {
@@ -44,6 +49,7 @@ impl std::fmt::Display for KillSwitchError {
             Self::CommandFailed(msg) => write!(f, "firewall command failed: {msg}"),
             Self::Io(e) => write!(f, "I/O error: {e}"),
             Self::NotRoot => write!(f, "kill switch requires root privileges"),
+            Self::InvalidConfig(msg) => write!(f, "invalid kill switch config: {msg}"),
         }
     }
 }
@@ -56,6 +62,339 @@ impl From<io::Error> for KillSwitchError {
     }
 }
 
+/// Transport protocol of an outbound kill switch exception.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PortProtocol {
+    Tcp,
+    Udp,
+    /// ICMP (`ICMPv6` for IPv6 rules).
+    Icmp,
+}
+
+impl PortProtocol {
+    /// Lowercase name as used by iptables, nft and pf.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Tcp => "tcp",
+            Self::Udp => "udp",
+            Self::Icmp => "icmp",
+        }
+    }
+}
+
+/// An outbound exception from `[killswitch] allow_ports`: a protocol and an
+/// optional destination port range.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AllowedPort {
+    pub protocol: PortProtocol,
+    /// Inclusive destination port range (`None` = any port).
+    pub ports: Option<(u16, u16)>,
+}
+
+impl AllowedPort {
+    /// Parse `udp/5353`, `tcp/8000-8100`, `tcp` or `icmp`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a message naming the entry if it cannot be parsed.
+    pub fn parse(entry: &str) -> std::result::Result<Self, String> {
+        let invalid = || format!("invalid allow_ports entry '{entry}'");
+        let (proto, ports) = match entry.trim().split_once('/') {
+            Some((proto, ports)) => (proto, Some(ports)),
+            None => (entry.trim(), None),
+        };
+        let protocol = match proto.to_ascii_lowercase().as_str() {
+            "tcp" => PortProtocol::Tcp,
+            "udp" => PortProtocol::Udp,
+            "icmp" => PortProtocol::Icmp,
+            _ => return Err(invalid()),
+        };
+        let ports = match ports {
+            None => None,
+            Some(_) if protocol == PortProtocol::Icmp => return Err(invalid()),
+            Some(ports) => {
+                let (lo, hi) = ports.split_once('-').unwrap_or((ports, ports));
+                let lo: u16 = lo.trim().parse().map_err(|_| invalid())?;
+                let hi: u16 = hi.trim().parse().map_err(|_| invalid())?;
+                if lo == 0 || lo > hi {
+                    return Err(invalid());
+                }
+                Some((lo, hi))
+            }
+        };
+        Ok(Self { protocol, ports })
+    }
+}
+
+/// One step of a kill switch [`RulePlan`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PlanStep {
+    /// Explanatory comment; not executed.
+    Note(String),
+    /// Run `program args…`. `may_fail` marks cleanup that is allowed to fail
+    /// (e.g. deleting a rule that is not there).
+    Run {
+        program: &'static str,
+        args: Vec<String>,
+        may_fail: bool,
+    },
+    /// Run `program args…` with `input` on stdin (atomic ruleset loads).
+    Load {
+        program: &'static str,
+        args: Vec<String>,
+        input: String,
+    },
+    /// Write `content` to a root-only (0600) file.
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))] // Only pf loads from a file
+    WriteFile { path: PathBuf, content: String },
+}
+
+/// Exactly what enabling the kill switch will do, in order.
+///
+/// Built by pure functions in the platform backends so it can be printed
+/// (`vortix killswitch plan`) and tested without root, then executed.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RulePlan {
+    /// Firewall backend the plan targets (`"iptables"`, `"nftables"`, `"pf"`).
+    pub backend: &'static str,
+    pub steps: Vec<PlanStep>,
+}
+
+impl RulePlan {
+    /// Empty plan for `backend`.
+    #[must_use]
+    pub fn new(backend: &'static str) -> Self {
+        Self {
+            backend,
+            steps: Vec::new(),
+        }
+    }
+
+    /// Append a comment.
+    pub fn note(&mut self, text: impl Into<String>) {
+        self.steps.push(PlanStep::Note(text.into()));
+    }
+
+    /// Append a command that must succeed.
+    pub fn run(&mut self, program: &'static str, args: &[&str]) {
+        self.push_run(program, args, false);
+    }
+
+    /// Append a cleanup command that may fail.
+    pub fn try_run(&mut self, program: &'static str, args: &[&str]) {
+        self.push_run(program, args, true);
+    }
+
+    fn push_run(&mut self, program: &'static str, args: &[&str], may_fail: bool) {
+        self.steps.push(PlanStep::Run {
+            program,
+            args: args.iter().map(|a| (*a).to_string()).collect(),
+            may_fail,
+        });
+    }
+
+    /// Append a command fed `input` on stdin.
+    pub fn load(&mut self, program: &'static str, args: &[&str], input: String) {
+        self.steps.push(PlanStep::Load {
+            program,
+            args: args.iter().map(|a| (*a).to_string()).collect(),
+            input,
+        });
+    }
+
+    /// Append a root-only file write.
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    pub fn write_file(&mut self, path: impl Into<PathBuf>, content: String) {
+        self.steps.push(PlanStep::WriteFile {
+            path: path.into(),
+            content,
+        });
+    }
+
+    /// Commands the plan runs, one per line (notes and file contents omitted).
+    #[cfg(test)]
+    #[must_use]
+    pub fn commands(&self) -> Vec<String> {
+        self.steps
+            .iter()
+            .filter_map(|step| match step {
+                PlanStep::Run { program, args, .. } | PlanStep::Load { program, args, .. } => {
+                    Some(shell_command(program, args))
+                }
+                PlanStep::Note(_) | PlanStep::WriteFile { .. } => None,
+            })
+            .collect()
+    }
+
+    /// Execute every step in order, stopping at the first required failure.
+    ///
+    /// # Errors
+    ///
+    /// Returns the failing command and its stderr, or the I/O error of a
+    /// file write.
+    pub fn execute(&self) -> Result<()> {
+        use std::io::Write;
+        use std::process::{Command, Stdio};
+
+        for step in &self.steps {
+            match step {
+                PlanStep::Note(_) => {}
+                PlanStep::Run {
+                    program,
+                    args,
+                    may_fail,
+                } => {
+                    let output = Command::new(program).args(args).output();
+                    let failure = match output {
+                        Ok(out) if out.status.success() => None,
+                        Ok(out) => Some(String::from_utf8_lossy(&out.stderr).trim().to_string()),
+                        Err(e) => Some(e.to_string()),
+                    };
+                    if let (Some(err), false) = (failure, *may_fail) {
+                        return Err(KillSwitchError::CommandFailed(format!(
+                            "{}: {err}",
+                            shell_command(program, args)
+                        )));
+                    }
+                }
+                PlanStep::Load {
+                    program,
+                    args,
+                    input,
+                } => {
+                    let mut child = Command::new(program)
+                        .args(args)
+                        .stdin(Stdio::piped())
+                        .stderr(Stdio::piped())
+                        .spawn()?;
+                    if let Some(mut stdin) = child.stdin.take() {
+                        stdin.write_all(input.as_bytes())?;
+                    }
+                    let output = child.wait_with_output()?;
+                    if !output.status.success() {
+                        return Err(KillSwitchError::CommandFailed(format!(
+                            "{}: {}",
+                            shell_command(program, args),
+                            String::from_utf8_lossy(&output.stderr).trim()
+                        )));
+                    }
+                }
+                PlanStep::WriteFile { path, content } => {
+                    use std::os::unix::fs::OpenOptionsExt;
+                    let mut file = fs::OpenOptions::new()
+                        .write(true)
+                        .create(true)
+                        .truncate(true)
+                        .mode(0o600) // Root-only read/write — prevents symlink attacks
+                        .open(path)?;
+                    file.write_all(content.as_bytes())?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Renders the plan as a shell script that performs the same steps.
+impl std::fmt::Display for RulePlan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for step in &self.steps {
+            match step {
+                PlanStep::Note(text) => writeln!(f, "# {text}")?,
+                PlanStep::Run {
+                    program,
+                    args,
+                    may_fail,
+                } => {
+                    let cmd = shell_command(program, args);
+                    if *may_fail {
+                        writeln!(f, "{cmd} 2>/dev/null || true")?;
+                    } else {
+                        writeln!(f, "{cmd}")?;
+                    }
+                }
+                PlanStep::Load {
+                    program,
+                    args,
+                    input,
+                } => {
+                    writeln!(f, "{} <<'EOF'", shell_command(program, args))?;
+                    write!(f, "{input}")?;
+                    writeln!(f, "EOF")?;
+                }
+                PlanStep::WriteFile { path, content } => {
+                    writeln!(
+                        f,
+                        "(umask 077 && cat > {}) <<'EOF'",
+                        shell_quote(&path.to_string_lossy())
+                    )?;
+                    write!(f, "{content}")?;
+                    writeln!(f, "EOF")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn shell_command(program: &str, args: &[String]) -> String {
+    std::iter::once(program.to_string())
+        .chain(args.iter().map(|a| shell_quote(a)))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Single-quote `arg` if the shell would otherwise split or expand it.
+fn shell_quote(arg: &str) -> String {
+    let safe = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:=,@%+".contains(c));
+    if safe {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+/// Build the rule plan [`enable_blocking`] would execute, without touching
+/// the firewall. Needs no root.
+///
+/// # Errors
+///
+/// Returns an error if the options are invalid or no firewall backend is
+/// available.
+pub fn plan_blocking(
+    vpn_interface: &str,
+    vpn_server_ip: Option<&str>,
+    options: &KillSwitchConfig,
+) -> Result<RulePlan> {
+    options.validate().map_err(KillSwitchError::InvalidConfig)?;
+
+    #[cfg(target_os = "macos")]
+    {
+        crate::platform::macos::firewall::PfFirewall::plan_blocking(
+            vpn_interface,
+            vpn_server_ip,
+            options,
+        )
+    }
+    #[cfg(target_os = "linux")]
+    {
+        crate::platform::linux::firewall::IptablesFirewall::plan_blocking(
+            vpn_interface,
+            vpn_server_ip,
+            options,
+        )
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+    {
+        let _ = (vpn_interface, vpn_server_ip, options);
+        compile_error!("kill switch is only supported on macOS and Linux")
+    }
+}
+
 /// Enable kill switch by loading restrictive firewall rules.
 ///
 /// Delegates to the platform-specific firewall implementation.
@@ -64,29 +403,97 @@ impl From<io::Error> for KillSwitchError {
 ///
 /// * `vpn_interface` - The VPN tunnel interface (e.g., "utun3" on macOS, "wg0" on Linux)
 /// * `vpn_server_ip` - Optional VPN server IP to allow for reconnection
+/// * `options` - Rule options from the `[killswitch]` config table
 ///
 /// # Errors
 ///
 /// Returns error if not running as root or firewall commands fail.
-pub fn enable_blocking(vpn_interface: &str, vpn_server_ip: Option<&str>) -> Result<()> {
+pub fn enable_blocking(
+    vpn_interface: &str,
+    vpn_server_ip: Option<&str>,
+    options: &KillSwitchConfig,
+) -> Result<()> {
+    options.validate().map_err(KillSwitchError::InvalidConfig)?;
+
     #[cfg(target_os = "macos")]
     {
-        crate::platform::macos::firewall::PfFirewall::enable_blocking(vpn_interface, vpn_server_ip)
+        crate::platform::macos::firewall::PfFirewall::enable_blocking(
+            vpn_interface,
+            vpn_server_ip,
+            options,
+        )
     }
     #[cfg(target_os = "linux")]
     {
         crate::platform::linux::firewall::IptablesFirewall::enable_blocking(
             vpn_interface,
             vpn_server_ip,
+            options,
+        )
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+    {
+        let _ = (vpn_interface, vpn_server_ip, options);
+        compile_error!("kill switch is only supported on macOS and Linux")
+    }
+}
+
+/// Compare the live firewall rules with the plan for these arguments.
+///
+/// Returns a description of every difference found; empty means the kill
+/// switch is intact.
+///
+/// # Errors
+///
+/// Returns error if not running as root or the rules cannot be read back.
+pub fn verify_blocking(
+    vpn_interface: &str,
+    vpn_server_ip: Option<&str>,
+    options: &KillSwitchConfig,
+) -> Result<Vec<String>> {
+    #[cfg(target_os = "macos")]
+    {
+        crate::platform::macos::firewall::PfFirewall::verify_blocking(
+            vpn_interface,
+            vpn_server_ip,
+            options,
+        )
+    }
+    #[cfg(target_os = "linux")]
+    {
+        crate::platform::linux::firewall::IptablesFirewall::verify_blocking(
+            vpn_interface,
+            vpn_server_ip,
+            options,
         )
     }
     #[cfg(not(any(target_os = "macos", target_os = "linux")))]
     {
-        let _ = (vpn_interface, vpn_server_ip);
+        let _ = (vpn_interface, vpn_server_ip, options);
         compile_error!("kill switch is only supported on macOS and Linux")
     }
 }
 
+/// Verify the installed rules and re-apply them if anything drifted (e.g.
+/// another tool flushed the firewall).
+///
+/// Returns the drift that was repaired; empty means nothing was wrong.
+///
+/// # Errors
+///
+/// Returns error if verification or re-applying the rules fails.
+pub fn repair_blocking(
+    vpn_interface: &str,
+    vpn_server_ip: Option<&str>,
+    options: &KillSwitchConfig,
+) -> Result<Vec<String>> {
+    let drift = verify_blocking(vpn_interface, vpn_server_ip, options)?;
+    if !drift.is_empty() {
+        enable_blocking(vpn_interface, vpn_server_ip, options)?;
+    }
+    Ok(drift)
+}
+
 /// Disable kill switch by flushing firewall rules.
 ///
 /// # Errors
@@ -190,6 +597,62 @@ mod tests {
 
     // pf rules tests are now in platform/macos/firewall.rs
 
+    #[test]
+    fn test_rule_plan_renders_shell_script() {
+        let mut plan = RulePlan::new("test");
+        plan.note("cleanup");
+        plan.try_run("nft", &["delete", "table", "inet", "t"]);
+        plan.run(
+            "iptables",
+            &["-A", "C", "-m", "comment", "--comment", "it's"],
+        );
+        plan.load("nft", &["-f", "-"], "table inet t {}\n".to_string());
+        plan.write_file("/tmp/rules file.conf", "block all\n".to_string());
+
+        assert_eq!(
+            plan.to_string(),
+            "# cleanup\n\
+             nft delete table inet t 2>/dev/null || true\n\
+             iptables -A C -m comment --comment 'it'\\''s'\n\
+             nft -f - <<'EOF'\ntable inet t {}\nEOF\n\
+             (umask 077 && cat > '/tmp/rules file.conf') <<'EOF'\nblock all\nEOF\n"
+        );
+    }
+
+    #[test]
+    fn test_rule_plan_execute_stops_at_required_failure() {
+        let mut plan = RulePlan::new("test");
+        plan.try_run("false", &[]);
+        plan.run("true", &[]);
+        assert!(plan.execute().is_ok());
+
+        plan.run("false", &["--flag"]);
+        match plan.execute() {
+            Err(KillSwitchError::CommandFailed(msg)) => assert!(msg.starts_with("false --flag")),
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_allowed_port_parse() {
+        assert_eq!(
+            AllowedPort::parse("udp/5353").unwrap(),
+            AllowedPort {
+                protocol: PortProtocol::Udp,
+                ports: Some((5353, 5353)),
+            }
+        );
+        assert_eq!(
+            AllowedPort::parse("TCP/8000-8100").unwrap().ports,
+            Some((8000, 8100))
+        );
+        assert_eq!(AllowedPort::parse("icmp").unwrap().ports, None);
+        assert!(AllowedPort::parse("icmp/8").is_err());
+        assert!(AllowedPort::parse("sctp/9").is_err());
+        assert!(AllowedPort::parse("udp/0").is_err());
+        assert!(AllowedPort::parse("tcp/9000-8000").is_err());
+    }
+
     #[test]
     fn test_persisted_state_serialization() {
         let state = PersistedState {
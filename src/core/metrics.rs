@@ -0,0 +1,363 @@
+//! Ring-buffered telemetry time series.
+//!
+//! Every tick the TUI records one [`Sample`]. The store folds it into three
+//! rings of fixed-size buckets, one per [`ChartWindow`]: 1 s buckets for the
+//! last minute, 1 min buckets for the last hour and 15 min buckets for the
+//! last day. A bucket keeps the sum and count of each metric, so reading it
+//! back gives the mean over the bucket. Memory stays constant no matter how
+//! long the app runs.
+//!
+//! Buckets are keyed by Unix time, which keeps a persisted store meaningful
+//! after a restart: the gap while the app was closed simply has no buckets.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::Path;
+
+/// Number of metrics a bucket aggregates.
+const METRIC_COUNT: usize = 7;
+
+/// One measured quantity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    /// Bytes per second received on the charted interface.
+    Down,
+    /// Bytes per second sent on the charted interface.
+    Up,
+    /// Bytes per second received on the uplink (while tunneled).
+    UplinkDown,
+    /// Bytes per second sent on the uplink (while tunneled).
+    UplinkUp,
+    /// Round-trip latency in milliseconds.
+    Latency,
+    /// Latency jitter in milliseconds.
+    Jitter,
+    /// Packet loss in percent.
+    Loss,
+}
+
+/// Values measured at one point in time. `None` means "not measured", which
+/// keeps it out of the bucket's mean.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Sample {
+    pub down: u64,
+    pub up: u64,
+    pub uplink_down: u64,
+    pub uplink_up: u64,
+    pub latency_ms: Option<u64>,
+    pub jitter_ms: Option<u64>,
+    pub loss_pct: Option<f32>,
+}
+
+impl Sample {
+    #[allow(clippy::cast_precision_loss)]
+    fn values(&self) -> [Option<f64>; METRIC_COUNT] {
+        [
+            Some(self.down as f64),
+            Some(self.up as f64),
+            Some(self.uplink_down as f64),
+            Some(self.uplink_up as f64),
+            self.latency_ms.map(|v| v as f64),
+            self.jitter_ms.map(|v| v as f64),
+            self.loss_pct.map(f64::from),
+        ]
+    }
+}
+
+/// Time span shown by the chart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChartWindow {
+    /// Last minute at 1 s resolution.
+    #[default]
+    Minute,
+    /// Last hour at 1 min resolution.
+    Hour,
+    /// Last 24 hours at 15 min resolution.
+    Day,
+}
+
+impl ChartWindow {
+    /// The window after this one, wrapping around.
+    #[must_use]
+    pub fn next(self) -> Self {
+        match self {
+            Self::Minute => Self::Hour,
+            Self::Hour => Self::Day,
+            Self::Day => Self::Minute,
+        }
+    }
+
+    /// Short label for the chart title.
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Minute => "1m",
+            Self::Hour => "1h",
+            Self::Day => "24h",
+        }
+    }
+
+    /// Seconds covered by one bucket.
+    #[must_use]
+    pub fn resolution_secs(self) -> u64 {
+        match self {
+            Self::Minute => 1,
+            Self::Hour => 60,
+            Self::Day => 15 * 60,
+        }
+    }
+
+    /// Number of buckets the window spans.
+    #[must_use]
+    pub fn points(self) -> usize {
+        match self {
+            Self::Minute | Self::Hour => 60,
+            Self::Day => 96,
+        }
+    }
+
+    fn index(self) -> usize {
+        match self {
+            Self::Minute => 0,
+            Self::Hour => 1,
+            Self::Day => 2,
+        }
+    }
+}
+
+/// Sums and counts of every metric over `resolution` seconds from `start`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Bucket {
+    start: u64,
+    sums: [f64; METRIC_COUNT],
+    counts: [u32; METRIC_COUNT],
+}
+
+impl Bucket {
+    fn new(start: u64) -> Self {
+        Self {
+            start,
+            sums: [0.0; METRIC_COUNT],
+            counts: [0; METRIC_COUNT],
+        }
+    }
+
+    fn add(&mut self, values: &[Option<f64>; METRIC_COUNT]) {
+        for (i, value) in values.iter().enumerate() {
+            if let Some(v) = value {
+                self.sums[i] += v;
+                self.counts[i] += 1;
+            }
+        }
+    }
+
+    fn mean(&self, metric: Metric) -> Option<f64> {
+        let i = metric as usize;
+        (self.counts[i] > 0).then(|| self.sums[i] / f64::from(self.counts[i]))
+    }
+}
+
+/// Fixed-capacity buckets of one resolution, oldest first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Ring {
+    resolution: u64,
+    capacity: usize,
+    buckets: VecDeque<Bucket>,
+}
+
+impl Ring {
+    fn new(window: ChartWindow) -> Self {
+        Self {
+            resolution: window.resolution_secs(),
+            capacity: window.points(),
+            buckets: VecDeque::with_capacity(window.points()),
+        }
+    }
+
+    fn add(&mut self, at: u64, values: &[Option<f64>; METRIC_COUNT]) {
+        let start = at - at % self.resolution;
+        match self.buckets.back_mut() {
+            Some(last) if last.start == start => last.add(values),
+            // A clock step backwards: fold into the newest bucket
+            Some(last) if last.start > start => last.add(values),
+            _ => {
+                if self.buckets.len() == self.capacity {
+                    self.buckets.pop_front();
+                }
+                let mut bucket = Bucket::new(start);
+                bucket.add(values);
+                self.buckets.push_back(bucket);
+            }
+        }
+    }
+
+    fn get(&self, start: u64) -> Option<&Bucket> {
+        self.buckets
+            .binary_search_by_key(&start, |b| b.start)
+            .ok()
+            .map(|i| &self.buckets[i])
+    }
+}
+
+/// Throughput and link quality over the three chart windows.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricsStore {
+    rings: [Ring; 3],
+}
+
+impl Default for MetricsStore {
+    fn default() -> Self {
+        Self {
+            rings: [
+                Ring::new(ChartWindow::Minute),
+                Ring::new(ChartWindow::Hour),
+                Ring::new(ChartWindow::Day),
+            ],
+        }
+    }
+}
+
+impl MetricsStore {
+    /// Fold a sample taken at Unix time `at` into every ring.
+    pub fn record(&mut self, at: u64, sample: &Sample) {
+        let values = sample.values();
+        for ring in &mut self.rings {
+            ring.add(at, &values);
+        }
+    }
+
+    /// Bucket means of `metric` across `window` ending at Unix time `now`,
+    /// oldest first. Buckets without a measurement are `None`.
+    #[must_use]
+    pub fn series(&self, window: ChartWindow, metric: Metric, now: u64) -> Vec<Option<f64>> {
+        let ring = &self.rings[window.index()];
+        let newest = now - now % ring.resolution;
+        (0..ring.capacity as u64)
+            .rev()
+            .map(|age| {
+                newest
+                    .checked_sub(age * ring.resolution)
+                    .and_then(|start| ring.get(start))
+                    .and_then(|b| b.mean(metric))
+            })
+            .collect()
+    }
+
+    /// Load a store saved by [`Self::save`]. A missing or unreadable file
+    /// (e.g. from an older layout) gives an empty store.
+    #[must_use]
+    pub fn load(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|json| serde_json::from_str::<Self>(&json).ok())
+            .filter(Self::is_compatible)
+            .unwrap_or_default()
+    }
+
+    /// Write the store to `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        let json = serde_json::to_string(self)?;
+        crate::utils::write_user_file(path, json)
+    }
+
+    /// Whether the rings match the current windows (resolution and size).
+    fn is_compatible(&self) -> bool {
+        let defaults = Self::default();
+        self.rings.iter().zip(&defaults.rings).all(|(a, b)| {
+            a.resolution == b.resolution
+                && a.capacity == b.capacity
+                && a.buckets.len() <= b.capacity
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn throughput(down: u64) -> Sample {
+        Sample {
+            down,
+            ..Sample::default()
+        }
+    }
+
+    #[test]
+    fn test_buckets_average_and_downsample() {
+        let mut store = MetricsStore::default();
+        // Two minutes of samples, 100 B/s in the first minute, 300 B/s in the second
+        let t0 = 1_699_999_200; // aligned to 15 minutes
+        for s in 0..120 {
+            store.record(t0 + s, &throughput(if s < 60 { 100 } else { 300 }));
+        }
+
+        let now = t0 + 119;
+        let minute = store.series(ChartWindow::Minute, Metric::Down, now);
+        assert_eq!(minute.len(), 60);
+        assert!(minute.iter().all(|v| *v == Some(300.0)));
+
+        let hour = store.series(ChartWindow::Hour, Metric::Down, now);
+        assert_eq!(hour.len(), 60);
+        assert_eq!(&hour[58..], &[Some(100.0), Some(300.0)]);
+        assert!(hour[..58].iter().all(Option::is_none));
+
+        let day = store.series(ChartWindow::Day, Metric::Down, now);
+        assert_eq!(day.len(), 96);
+        assert_eq!(day[95], Some(200.0));
+    }
+
+    #[test]
+    fn test_ring_drops_oldest_and_skips_unmeasured() {
+        let mut store = MetricsStore::default();
+        let t0 = 1_700_000_000;
+        for s in 0..90 {
+            let mut sample = throughput(s);
+            // Latency measured only every tenth second
+            sample.latency_ms = (s % 10 == 0).then_some(40);
+            store.record(t0 + s, &sample);
+        }
+        assert_eq!(store.rings[0].buckets.len(), 60);
+        assert_eq!(store.rings[0].buckets[0].start, t0 + 30);
+
+        let now = t0 + 89;
+        let latency = store.series(ChartWindow::Minute, Metric::Latency, now);
+        assert_eq!(latency.iter().flatten().count(), 6);
+        assert_eq!(latency[0], Some(40.0)); // t0 + 30
+        assert_eq!(latency[1], None);
+
+        // A gap while the app was closed leaves empty slots, not stale ones
+        let later = store.series(ChartWindow::Minute, Metric::Down, now + 30);
+        assert!(later[30..].iter().all(Option::is_none));
+        assert_eq!(later[29], Some(89.0));
+    }
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let path = std::env::temp_dir().join(format!("vortix-metrics-{}.json", std::process::id()));
+        let mut store = MetricsStore::default();
+        store.record(1_700_000_000, &throughput(512));
+        store.save(&path).unwrap();
+
+        let loaded = MetricsStore::load(&path);
+        assert_eq!(
+            loaded.series(ChartWindow::Minute, Metric::Down, 1_700_000_000)[59],
+            Some(512.0)
+        );
+
+        std::fs::write(&path, "not json").unwrap();
+        assert!(MetricsStore::load(&path).rings[0].buckets.is_empty());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_window_cycle() {
+        let w = ChartWindow::default();
+        assert_eq!(w.label(), "1m");
+        assert_eq!(w.next().next().label(), "24h");
+        assert_eq!(w.next().next().next(), w);
+    }
+}
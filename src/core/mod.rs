@@ -1,17 +1,43 @@
 //! Core modules for VPN detection and telemetry.
 //!
 //! This module contains production-ready background workers:
+//! - `benchmark`: Probes profile endpoints to find the fastest server
+//! - `connector`: Brings tunnels up and down without a terminal attached
+//! - `dns`: Per-profile DNS override applied while connected
+//! - `dns_leak`: Active DNS leak test using unique probe names
+//! - `exporter`: `OpenMetrics` endpoint for tunnel health
+//! - `hooks`: User commands run on tunnel transitions
+//! - `history`: Append-only record of finished sessions
+//! - `metrics`: Downsampled throughput and link quality time series
+//! - `notify`: Desktop (D-Bus) and terminal notifications
 //! - `scanner`: Detects active VPN connections on the system
+//! - `secrets`: Saved credentials in the Secret Service keyring
+//! - `totp`: Authenticator codes for `OpenVPN` two-factor logins
+//! - `vault`: Passphrase-encrypted storage for profiles and credentials
 //! - `telemetry`: Collects network telemetry (IP, latency, ISP, etc.)
 //! - `killswitch`: macOS pf firewall control for traffic blocking
+//! - `openvpn_mgmt`: `OpenVPN` management socket client (phases, counters, auth)
 
 #![allow(unused_imports)]
 
+pub mod benchmark;
+pub mod connector;
+pub mod dns;
+pub mod dns_leak;
 pub mod downloader;
+pub mod exporter;
+pub mod history;
+pub mod hooks;
 pub mod importer;
 pub mod killswitch;
+pub mod metrics;
+pub mod notify;
+pub mod openvpn_mgmt;
 pub mod scanner;
+pub mod secrets;
 pub mod telemetry;
+pub mod totp;
+pub mod vault;
 
 // Re-export commonly used items
 pub use scanner::{get_active_profiles, ActiveSession};
@@ -0,0 +1,210 @@
+//! Desktop notifications for events worth seeing outside the TUI.
+//!
+//! Toasts only show while the TUI is on screen. With `[notifications]`
+//! enabled, connection changes, drops, kill switch changes and leaks are also
+//! announced as freedesktop notifications over the session D-Bus (Linux, via
+//! `gdbus`). Where no session bus is reachable, an OSC 9 escape is written to
+//! the terminal instead: terminals that support it (iTerm2, kitty, `WezTerm`,
+//! foot) show a notification, and its trailing BEL rings the bell, which tmux
+//! and screen flag on background windows.
+
+use std::io::Write;
+
+use crate::config::{NotificationsConfig, NotifyMethod};
+use crate::constants;
+
+/// Something the user should hear about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotifyEvent {
+    Connected,
+    Disconnected,
+    /// The tunnel went away without being asked to.
+    Dropped,
+    KillSwitchEngaged,
+    KillSwitchReleased,
+    /// A DNS or IPv6 leak was detected.
+    Leak,
+}
+
+impl NotifyEvent {
+    /// Whether this event is switched on in `config`.
+    #[must_use]
+    pub fn enabled(self, config: &NotificationsConfig) -> bool {
+        config.enabled
+            && match self {
+                Self::Connected => config.connect,
+                Self::Disconnected => config.disconnect,
+                Self::Dropped => config.drop,
+                Self::KillSwitchEngaged => config.killswitch_engage,
+                Self::KillSwitchReleased => config.killswitch_release,
+                Self::Leak => config.leak,
+            }
+    }
+
+    /// Notification title.
+    #[must_use]
+    pub fn summary(self) -> &'static str {
+        match self {
+            Self::Connected => "VPN connected",
+            Self::Disconnected => "VPN disconnected",
+            Self::Dropped => "VPN connection dropped",
+            Self::KillSwitchEngaged => "Kill switch engaged",
+            Self::KillSwitchReleased => "Kill switch released",
+            Self::Leak => "Leak detected",
+        }
+    }
+
+    /// Freedesktop urgency: 0 low, 1 normal, 2 critical.
+    fn urgency(self) -> u8 {
+        match self {
+            Self::Connected | Self::Disconnected | Self::KillSwitchReleased => 1,
+            Self::Dropped | Self::KillSwitchEngaged | Self::Leak => 2,
+        }
+    }
+}
+
+/// How notifications are delivered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    /// Freedesktop notifications over the session D-Bus.
+    Desktop,
+    /// OSC 9 escape plus bell on the controlling terminal.
+    Terminal,
+}
+
+/// Pick the backend for the configured `method`: `"desktop"`, `"terminal"`,
+/// or `"auto"` (desktop when a session bus can be found).
+#[must_use]
+pub fn backend(config: &NotificationsConfig) -> Backend {
+    match config.method {
+        NotifyMethod::Desktop => Backend::Desktop,
+        NotifyMethod::Auto if crate::utils::session_bus().is_some() => Backend::Desktop,
+        NotifyMethod::Auto | NotifyMethod::Terminal => Backend::Terminal,
+    }
+}
+
+/// Show `event` as a desktop notification. Blocks for up to a few seconds.
+///
+/// # Errors
+///
+/// Returns an error if no session bus is reachable or the notification
+/// daemon rejected the call.
+#[cfg(target_os = "linux")]
+pub fn send_desktop(event: NotifyEvent, body: &str) -> Result<(), String> {
+    use std::os::unix::process::CommandExt;
+
+    let (address, uid, gid) = crate::utils::session_bus().ok_or("no session D-Bus found")?;
+    let mut cmd = std::process::Command::new("gdbus");
+    cmd.args([
+        "call",
+        "--session",
+        "--dest",
+        "org.freedesktop.Notifications",
+        "--object-path",
+        "/org/freedesktop/Notifications",
+        "--method",
+        "org.freedesktop.Notifications.Notify",
+        &gvariant_string(constants::APP_NAME),
+        "0",
+        &gvariant_string("network-vpn"),
+        &gvariant_string(event.summary()),
+        &gvariant_string(body),
+        "[]",
+        &format!("{{'urgency': <byte {}>}}", event.urgency()),
+        "-1",
+    ])
+    .env("DBUS_SESSION_BUS_ADDRESS", address)
+    .stdin(std::process::Stdio::null());
+    if crate::utils::is_root() {
+        cmd.uid(uid).gid(gid);
+    }
+
+    let timeout = std::time::Duration::from_secs(constants::NOTIFY_TIMEOUT_SECS);
+    let output = crate::utils::run_with_timeout(&mut cmd, timeout)
+        .ok_or("gdbus timed out or is not installed")?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn send_desktop(_event: NotifyEvent, _body: &str) -> Result<(), String> {
+    Err("desktop notifications need D-Bus (Linux)".to_string())
+}
+
+/// Write `event` to the terminal as an OSC 9 notification (which ends in a
+/// bell). Must be called between frames, from the thread that draws.
+pub fn ring_terminal(event: NotifyEvent, body: &str) {
+    let mut out = std::io::stdout();
+    let _ = out.write_all(terminal_sequence(event, body).as_bytes());
+    let _ = out.flush();
+}
+
+/// OSC 9 escape carrying the summary and body. Control characters are
+/// dropped so the text can't end the sequence early.
+fn terminal_sequence(event: NotifyEvent, body: &str) -> String {
+    let text: String = format!("{}: {body}", event.summary())
+        .chars()
+        .filter(|c| !c.is_control())
+        .collect();
+    format!("\x1b]9;{text}\x07")
+}
+
+/// Quote `s` as a `GVariant` text-format string, so gdbus never parses it
+/// as some other type.
+fn gvariant_string(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' | '\\' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            '\n' => quoted.push_str("\\n"),
+            c if c.is_control() => {}
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_flags_need_master_switch() {
+        let mut config = NotificationsConfig::default();
+        assert!(!NotifyEvent::Dropped.enabled(&config));
+        config.enabled = true;
+        assert!(NotifyEvent::Dropped.enabled(&config));
+        config.drop = false;
+        assert!(!NotifyEvent::Dropped.enabled(&config));
+        assert!(NotifyEvent::Leak.enabled(&config));
+    }
+
+    #[test]
+    fn test_terminal_sequence_strips_control_chars() {
+        let seq = terminal_sequence(NotifyEvent::Leak, "IPv6\x07 leak\x1b]");
+        assert_eq!(seq, "\x1b]9;Leak detected: IPv6 leak]\x07");
+    }
+
+    #[test]
+    fn test_gvariant_string_escapes() {
+        assert_eq!(gvariant_string("it's \"ok\""), r#""it's \"ok\"""#);
+        assert_eq!(gvariant_string("a\\b\nc"), r#""a\\b\nc""#);
+    }
+
+    #[test]
+    fn test_forced_backend() {
+        let config = NotificationsConfig {
+            method: NotifyMethod::Terminal,
+            ..NotificationsConfig::default()
+        };
+        assert_eq!(backend(&config), Backend::Terminal);
+    }
+}
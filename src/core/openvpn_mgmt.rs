@@ -0,0 +1,1062 @@
+//! `OpenVPN` management interface client.
+//!
+//! `OpenVPN` is started with `--management <socket> unix --management-hold
+//! --management-query-passwords`, so it waits for us before connecting and
+//! asks for credentials over the socket instead of a terminal. The client
+//! turns the socket's real-time messages into [`Notification`]s:
+//!
+//! - `>STATE:` connection phases (`WAIT`, `AUTH`, `GET_CONFIG`, `CONNECTED`, ...)
+//! - `>BYTECOUNT:` tunnel byte counters, pushed every few seconds
+//! - `>PASSWORD:` credential requests and authentication failures
+//!
+//! Two-factor logins arrive the same way: a `static-challenge` profile asks
+//! for the password with `SC:<flags>,<text>` and gets `SCRV1:` back, and a
+//! server that wants a code after the password rejects the first attempt
+//! with a `CRV1:` challenge that the next attempt answers (see [`Challenge`]).
+//!
+//! After the connect, [`spawn_monitor`] keeps the connection open in the
+//! background: it logs later phases, records the counters for the scanner and
+//! is the way [`signal`] reaches the daemon (the interface serves one client
+//! at a time).
+
+use crate::constants;
+use crate::core::totp::Totp;
+use crate::logger::{self, LogLevel};
+use std::collections::VecDeque;
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// A real-time message from the management interface.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Notification {
+    /// `>STATE:` connection phase change.
+    State(StateChange),
+    /// `>BYTECOUNT:` bytes received / sent over the tunnel.
+    ByteCount { rx: u64, tx: u64 },
+    /// `>PASSWORD:Need ...` credentials are required.
+    NeedPassword(AuthRequest),
+    /// `>PASSWORD:Verification Failed: '<kind>'` the server rejected them.
+    AuthFailed(String),
+    /// `>PASSWORD:Verification Failed: 'Auth' ['CRV1:...']` the server wants
+    /// an answer to a dynamic challenge, asked for next.
+    Challenge(Challenge),
+    /// `>HOLD:` waiting for `hold release`.
+    Hold,
+    /// `>FATAL:` the daemon is about to exit.
+    Fatal(String),
+    /// Anything else (`>INFO:`, `>LOG:`, ...), without the leading `>`.
+    Other(String),
+}
+
+/// One `>STATE:` line.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StateChange {
+    /// Phase name, e.g. `"CONNECTED"` or `"RECONNECTING"`.
+    pub state: String,
+    /// Reason or result, e.g. `"SUCCESS"` or `"ping-restart"`.
+    pub detail: String,
+    /// Tunnel address assigned to us (empty before `ASSIGN_IP`).
+    pub local_ip: String,
+    /// Server address.
+    pub remote_ip: String,
+}
+
+impl StateChange {
+    /// `"CONNECTED (SUCCESS)"`, or just the phase when there is no detail.
+    #[must_use]
+    pub fn summary(&self) -> String {
+        if self.detail.is_empty() {
+            self.state.clone()
+        } else {
+            format!("{} ({})", self.state, self.detail)
+        }
+    }
+}
+
+/// A `>PASSWORD:Need '<kind>' ...` request.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AuthRequest {
+    /// Credential being asked for: `"Auth"` or e.g. `"Private Key"`.
+    pub kind: String,
+    /// Whether a username is wanted as well as the password.
+    pub username: bool,
+    /// Second factor to ask for along with the credentials.
+    pub challenge: Option<Challenge>,
+}
+
+/// A second factor (usually an OTP code) the server wants with the password.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Challenge {
+    /// Prompt from the profile or server, e.g. `"Enter Authenticator Code"`.
+    pub text: String,
+    /// Whether the answer may be shown while it is typed.
+    pub echo: bool,
+    /// How the answer is sent back.
+    pub kind: ChallengeKind,
+}
+
+/// How a [`Challenge`] answer is sent back.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChallengeKind {
+    /// `static-challenge`: password `SCRV1:<base64 password>:<base64 answer>`.
+    Static,
+    /// `static-challenge` with the concat flag: the answer follows the password.
+    Concat,
+    /// `CRV1` dynamic challenge: password `CRV1::<state_id>::<answer>`, with
+    /// the username the server handed back.
+    Dynamic { state_id: String, username: String },
+}
+
+impl Challenge {
+    /// Parse the `<flags>,<text>` of a `SC:` request. Bit 0 of the flags
+    /// turns on echo, bit 1 the concat format.
+    fn parse_static(sc: &str) -> Option<Self> {
+        let (flags, text) = sc.split_once(',')?;
+        let flags: u32 = flags.trim().parse().ok()?;
+        Some(Self {
+            text: text.to_string(),
+            echo: flags & 1 != 0,
+            kind: if flags & 2 == 0 {
+                ChallengeKind::Static
+            } else {
+                ChallengeKind::Concat
+            },
+        })
+    }
+
+    /// Parse `CRV1:<flags>:<state_id>:<base64 username>:<text>`.
+    fn parse_dynamic(crv1: &str) -> Option<Self> {
+        let mut fields = crv1.strip_prefix("CRV1:")?.splitn(4, ':');
+        let flags = fields.next()?;
+        let state_id = fields.next()?.to_string();
+        let username = data_encoding::BASE64
+            .decode(fields.next()?.as_bytes())
+            .ok()?;
+        Some(Self {
+            text: fields.next()?.to_string(),
+            echo: flags.split(',').any(|f| f == "E"),
+            kind: ChallengeKind::Dynamic {
+                state_id,
+                username: String::from_utf8(username).ok()?,
+            },
+        })
+    }
+
+    /// Whether this is a `CRV1` challenge, which needs only the answer.
+    #[must_use]
+    pub fn is_dynamic(&self) -> bool {
+        matches!(self.kind, ChallengeKind::Dynamic { .. })
+    }
+}
+
+/// An answer to an [`AuthRequest`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Credentials {
+    pub username: String,
+    pub password: String,
+    /// Answer to the request's [`Challenge`], if it has one.
+    pub response: String,
+}
+
+impl Credentials {
+    #[must_use]
+    pub fn new(username: impl Into<String>, password: impl Into<String>) -> Self {
+        Self {
+            username: username.into(),
+            password: password.into(),
+            response: String::new(),
+        }
+    }
+}
+
+/// Parse one line of management output. Returns `None` for command replies
+/// (anything that does not start with `>`).
+#[must_use]
+pub fn parse_notification(line: &str) -> Option<Notification> {
+    let body = line.strip_prefix('>')?;
+    let (kind, rest) = body.split_once(':').unwrap_or((body, ""));
+    let notification = match kind {
+        "STATE" => {
+            // time,state,detail,local_ip,remote_ip,...
+            let fields: Vec<&str> = rest.split(',').collect();
+            let field = |i: usize| fields.get(i).copied().unwrap_or("").to_string();
+            Notification::State(StateChange {
+                state: field(1),
+                detail: field(2),
+                local_ip: field(3),
+                remote_ip: field(4),
+            })
+        }
+        "BYTECOUNT" => {
+            let (rx, tx) = rest.split_once(',')?;
+            Notification::ByteCount {
+                rx: rx.trim().parse().ok()?,
+                tx: tx.trim().parse().ok()?,
+            }
+        }
+        "PASSWORD" => {
+            if let Some(failed) = rest.strip_prefix("Verification Failed: ") {
+                // 'Auth' ['CRV1:...']
+                let crv1 = failed
+                    .split_once("['")
+                    .and_then(|(_, c)| c.strip_suffix("']"))
+                    .and_then(Challenge::parse_dynamic);
+                match crv1 {
+                    Some(challenge) => Notification::Challenge(challenge),
+                    None => Notification::AuthFailed(quoted(failed).unwrap_or(failed).to_string()),
+                }
+            } else if let Some(need) = rest.strip_prefix("Need ") {
+                let kind = quoted(need)?.to_string();
+                // 'Auth' SC:<flags>,<text> (username and password as well)
+                let challenge = need
+                    .split_once(" SC:")
+                    .and_then(|(_, sc)| Challenge::parse_static(sc));
+                Notification::NeedPassword(AuthRequest {
+                    kind,
+                    username: need.contains("username/password") || challenge.is_some(),
+                    challenge,
+                })
+            } else {
+                Notification::Other(body.to_string())
+            }
+        }
+        "HOLD" => Notification::Hold,
+        "FATAL" => Notification::Fatal(rest.to_string()),
+        _ => Notification::Other(body.to_string()),
+    };
+    Some(notification)
+}
+
+/// Text between the first pair of single quotes.
+fn quoted(s: &str) -> Option<&str> {
+    let start = s.find('\'')? + 1;
+    let len = s[start..].find('\'')?;
+    Some(&s[start..start + len])
+}
+
+/// Quote a command argument: `"..."` with `\` and `"` escaped.
+///
+/// Control characters are refused: the protocol is line based, so a newline
+/// would end the command early and run the rest as a second one.
+fn quote_arg(arg: &str) -> io::Result<String> {
+    if arg.chars().any(char::is_control) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "credentials must not contain control characters",
+        ));
+    }
+    let mut out = String::with_capacity(arg.len() + 2);
+    out.push('"');
+    for c in arg.chars() {
+        if c == '\\' || c == '"' {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out.push('"');
+    Ok(out)
+}
+
+/// A connection to the management socket.
+pub struct Client {
+    reader: BufReader<UnixStream>,
+    writer: UnixStream,
+    /// Notifications that arrived while waiting for a command reply.
+    pending: VecDeque<Notification>,
+    /// Bytes of a line whose end has not arrived yet.
+    partial: Vec<u8>,
+}
+
+impl Client {
+    /// Connect to `path`, retrying until the daemon has created the socket
+    /// or `timeout` passes.
+    ///
+    /// # Errors
+    ///
+    /// Returns the last connection error once `timeout` has passed.
+    pub fn connect(path: &Path, timeout: Duration) -> io::Result<Self> {
+        let start = Instant::now();
+        let stream = loop {
+            match UnixStream::connect(path) {
+                Ok(stream) => break stream,
+                Err(e) if start.elapsed() >= timeout => return Err(e),
+                Err(_) => std::thread::sleep(Duration::from_millis(constants::OVPN_LOG_POLL_MS)),
+            }
+        };
+        Ok(Self {
+            reader: BufReader::new(stream.try_clone()?),
+            writer: stream,
+            pending: VecDeque::new(),
+            partial: Vec::new(),
+        })
+    }
+
+    /// Run a single-line command and return the text after `SUCCESS:`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the `ERROR:` text as [`io::ErrorKind::Other`], or a socket
+    /// error (including a timeout when the daemon does not answer).
+    pub fn command(&mut self, cmd: &str) -> io::Result<String> {
+        self.writer.write_all(format!("{cmd}\n").as_bytes())?;
+        let deadline =
+            Instant::now() + Duration::from_millis(constants::OVPN_MGMT_REPLY_TIMEOUT_MS);
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let Some(line) = self.read_line(remaining)? else {
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    format!("no reply to '{}'", cmd.split(' ').next().unwrap_or(cmd)),
+                ));
+            };
+            if let Some(notification) = parse_notification(&line) {
+                self.pending.push_back(notification);
+            } else if let Some(ok) = line.strip_prefix("SUCCESS:") {
+                return Ok(ok.trim().to_string());
+            } else if let Some(err) = line.strip_prefix("ERROR:") {
+                return Err(io::Error::other(err.trim().to_string()));
+            }
+        }
+    }
+
+    /// Wait up to `timeout` for the next notification (`Ok(None)` on timeout).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`io::ErrorKind::UnexpectedEof`] once the daemon closes the
+    /// socket, i.e. has exited.
+    pub fn next_notification(&mut self, timeout: Duration) -> io::Result<Option<Notification>> {
+        if let Some(notification) = self.pending.pop_front() {
+            return Ok(Some(notification));
+        }
+        let deadline = Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let Some(line) = self.read_line(remaining)? else {
+                return Ok(None);
+            };
+            // Stray command replies (e.g. to a `signal` sent by another thread)
+            if let Some(notification) = parse_notification(&line) {
+                return Ok(Some(notification));
+            }
+        }
+    }
+
+    /// Answer a [`Notification::NeedPassword`], encoding the challenge
+    /// answer the way the request's [`Challenge`] wants it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`io::ErrorKind::InvalidInput`] if a credential contains a
+    /// control character, or an error if the daemon rejects the command.
+    pub fn send_credentials(
+        &mut self,
+        request: &AuthRequest,
+        credentials: &Credentials,
+    ) -> io::Result<()> {
+        let (username, password) = reply_for(request, credentials);
+        let kind = quote_arg(&request.kind)?;
+        let username = quote_arg(&username)?;
+        let password = quote_arg(&password)?;
+        if request.username {
+            self.command(&format!("username {kind} {username}"))?;
+        }
+        self.command(&format!("password {kind} {password}"))?;
+        Ok(())
+    }
+
+    /// Read one line within `timeout`; `Ok(None)` on timeout.
+    fn read_line(&mut self, timeout: Duration) -> io::Result<Option<String>> {
+        // A zero timeout would mean "block forever"
+        self.reader
+            .get_ref()
+            .set_read_timeout(Some(timeout.max(Duration::from_millis(1))))?;
+        match self.reader.read_until(b'\n', &mut self.partial) {
+            Ok(0) => Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "management interface closed",
+            )),
+            Ok(_) if self.partial.ends_with(b"\n") => {
+                let line = String::from_utf8_lossy(&self.partial)
+                    .trim_end()
+                    .to_string();
+                self.partial.clear();
+                Ok(Some(line))
+            }
+            // EOF in the middle of a line
+            Ok(_) => Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "management interface closed",
+            )),
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                ) =>
+            {
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// The `username` and `password` to send for `request`.
+fn reply_for(request: &AuthRequest, credentials: &Credentials) -> (String, String) {
+    let Credentials {
+        username,
+        password,
+        response,
+    } = credentials;
+    let base64 = |s: &str| data_encoding::BASE64.encode(s.as_bytes());
+    match request.challenge.as_ref().map(|c| &c.kind) {
+        None => (username.clone(), password.clone()),
+        Some(ChallengeKind::Static) => (
+            username.clone(),
+            format!("SCRV1:{}:{}", base64(password), base64(response)),
+        ),
+        Some(ChallengeKind::Concat) => (username.clone(), format!("{password}{response}")),
+        Some(ChallengeKind::Dynamic { state_id, username }) => {
+            (username.clone(), format!("CRV1::{state_id}::{response}"))
+        }
+    }
+}
+
+/// Answer `request` without asking anyone: saved credentials, plus a code
+/// from the TOTP seed when the server wants a second factor.
+fn unattended(
+    request: &AuthRequest,
+    saved: Option<&(String, String)>,
+    totp: Option<&Totp>,
+) -> Option<Credentials> {
+    if request.kind != "Auth" {
+        return None;
+    }
+    match &request.challenge {
+        None => saved.map(|(username, password)| Credentials::new(username, password)),
+        Some(challenge) if challenge.is_dynamic() => totp.map(|totp| Credentials {
+            response: totp.now(),
+            ..Credentials::default()
+        }),
+        Some(_) => {
+            let (username, password) = saved?;
+            Some(Credentials {
+                response: totp?.now(),
+                ..Credentials::new(username, password)
+            })
+        }
+    }
+}
+
+/// Release the hold and drive the connect until `CONNECTED`.
+///
+/// Credential requests are answered with `saved` first (with a code from
+/// `totp` when the server wants a second factor), then by asking `ask`
+/// (which returns `None` when the user gives up). Returns `Ok(true)`
+/// once connected and `Ok(false)` if `timeout` passes first; time spent in
+/// `ask` does not count.
+///
+/// # Errors
+///
+/// Returns a human-readable error when the daemon exits, reports a fatal
+/// error or authentication fails without new credentials. Authentication
+/// errors contain `AUTH_FAILED`.
+pub fn await_connected(
+    client: &mut Client,
+    profile: &str,
+    timeout: Duration,
+    saved: Option<(String, String)>,
+    totp: Option<&Totp>,
+    ask: &dyn Fn(&AuthRequest) -> Option<Credentials>,
+) -> Result<bool, String> {
+    let setup = |client: &mut Client| -> io::Result<()> {
+        client.command("state on")?;
+        client.command(&format!(
+            "bytecount {}",
+            constants::OVPN_BYTECOUNT_INTERVAL_SECS
+        ))?;
+        client.command("hold release")?;
+        // The hold we just released was announced before we connected
+        client.pending.retain(|n| *n != Notification::Hold);
+        Ok(())
+    };
+    setup(client).map_err(|e| format!("OpenVPN management: {e}"))?;
+
+    let mut saved = saved;
+    let mut totp = totp;
+    let mut challenge = None;
+    let mut failed = false;
+    let mut start = Instant::now();
+    loop {
+        let remaining = timeout.saturating_sub(start.elapsed());
+        if remaining.is_zero() {
+            return Ok(false);
+        }
+        let notification = match client.next_notification(remaining) {
+            Ok(Some(n)) => n,
+            Ok(None) => return Ok(false),
+            Err(e) => return Err(format!("OpenVPN daemon exited: {e}")),
+        };
+        match notification {
+            Notification::State(change) => {
+                logger::log(
+                    LogLevel::Info,
+                    "OPENVPN",
+                    format!("'{profile}': {}", change.summary()),
+                );
+                match change.state.as_str() {
+                    "CONNECTED" => return Ok(true),
+                    "EXITING" => return Err(format!("OpenVPN exited: {}", change.detail)),
+                    _ => {}
+                }
+            }
+            Notification::NeedPassword(mut request) => {
+                if request.kind == "Auth" && challenge.is_some() {
+                    request.challenge = challenge.take();
+                }
+                let credentials = if let Some(creds) = unattended(&request, saved.as_ref(), totp) {
+                    // Each is tried once; after a rejection the user is asked.
+                    // A dynamic challenge follows the saved credentials.
+                    match &request.challenge {
+                        None => saved = None,
+                        Some(c) if c.is_dynamic() => totp = None,
+                        Some(_) => (saved, totp) = (None, None),
+                    }
+                    Some(creds)
+                } else {
+                    let answer = ask(&request);
+                    start = Instant::now();
+                    answer
+                };
+                let Some(credentials) = credentials else {
+                    let _ = client.command("signal SIGTERM");
+                    return Err(if failed {
+                        format!("AUTH_FAILED: '{profile}' rejected the credentials")
+                    } else {
+                        format!(
+                            "AUTH_FAILED: '{profile}' needs {} credentials",
+                            request.kind
+                        )
+                    });
+                };
+                client
+                    .send_credentials(&request, &credentials)
+                    .map_err(|e| format!("OpenVPN management: {e}"))?;
+            }
+            Notification::Challenge(next) => {
+                // `--auth-retry interact` asks for the answer right after this
+                logger::log(
+                    LogLevel::Info,
+                    "OPENVPN",
+                    format!("'{profile}': server asks: {}", next.text),
+                );
+                challenge = Some(next);
+            }
+            Notification::AuthFailed(kind) => {
+                // `--auth-retry interact` asks again right after this
+                failed = true;
+                logger::log(
+                    LogLevel::Warning,
+                    "OPENVPN",
+                    format!("'{profile}': {kind} verification failed"),
+                );
+            }
+            Notification::Hold => {
+                let _ = client.command("hold release");
+            }
+            Notification::Fatal(message) => return Err(format!("OpenVPN: {message}")),
+            Notification::ByteCount { .. } | Notification::Other(_) => {}
+        }
+    }
+}
+
+// === Background monitor ===
+
+/// Latest counters of a tunnel whose management socket this process holds.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LiveStatus {
+    /// Bytes received over the tunnel.
+    pub rx_bytes: u64,
+    /// Bytes sent over the tunnel.
+    pub tx_bytes: u64,
+}
+
+struct Monitored {
+    profile: String,
+    status: LiveStatus,
+    /// Second handle on the socket, for [`signal`].
+    writer: UnixStream,
+}
+
+static MONITORED: Mutex<Vec<Monitored>> = Mutex::new(Vec::new());
+
+fn monitored() -> std::sync::MutexGuard<'static, Vec<Monitored>> {
+    MONITORED
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+/// Keep `client` open on a background thread, logging phase changes and
+/// recording byte counters for [`live_status`] until the daemon exits.
+pub fn spawn_monitor(profile: &str, mut client: Client) {
+    let Ok(writer) = client.writer.try_clone() else {
+        return;
+    };
+    {
+        let mut list = monitored();
+        list.retain(|m| m.profile != profile);
+        list.push(Monitored {
+            profile: profile.to_string(),
+            status: LiveStatus::default(),
+            writer,
+        });
+    }
+
+    let profile = profile.to_string();
+    std::thread::spawn(move || {
+        let mut challenge = None;
+        let poll = Duration::from_secs(constants::OVPN_BYTECOUNT_INTERVAL_SECS * 5);
+        loop {
+            let notification = match client.next_notification(poll) {
+                Ok(Some(n)) => n,
+                Ok(None) => continue,
+                Err(_) => break,
+            };
+            match notification {
+                Notification::State(change) => {
+                    logger::log(
+                        LogLevel::Info,
+                        "OPENVPN",
+                        format!("'{profile}': {}", change.summary()),
+                    );
+                }
+                Notification::ByteCount { rx, tx } => update(&profile, |s| {
+                    s.rx_bytes = rx;
+                    s.tx_bytes = tx;
+                }),
+                Notification::Hold => {
+                    let _ = client.command("hold release");
+                }
+                Notification::NeedPassword(mut request) => {
+                    if request.kind == "Auth" && challenge.is_some() {
+                        request.challenge = challenge.take();
+                    }
+                    // Nobody to ask out here; use saved credentials or give up
+                    let saved = crate::utils::read_openvpn_saved_auth(&profile);
+                    let totp = crate::core::totp::load(&profile);
+                    let answered = unattended(&request, saved.as_ref(), totp.as_ref())
+                        .is_some_and(|creds| client.send_credentials(&request, &creds).is_ok());
+                    if !answered {
+                        logger::log(
+                            LogLevel::Error,
+                            "OPENVPN",
+                            format!("'{profile}' asked for {} credentials again", request.kind),
+                        );
+                        let _ = client.command("signal SIGTERM");
+                    }
+                }
+                Notification::Fatal(message) => {
+                    logger::log(
+                        LogLevel::Error,
+                        "OPENVPN",
+                        format!("'{profile}': {message}"),
+                    );
+                }
+                Notification::Challenge(next) => challenge = Some(next),
+                Notification::AuthFailed(_) | Notification::Other(_) => {}
+            }
+        }
+        monitored().retain(|m| m.profile != profile);
+    });
+}
+
+fn update(profile: &str, f: impl FnOnce(&mut LiveStatus)) {
+    if let Some(m) = monitored().iter_mut().find(|m| m.profile == profile) {
+        f(&mut m.status);
+    }
+}
+
+/// Latest byte counters of `profile`, if this process monitors it.
+#[must_use]
+pub fn live_status(profile: &str) -> Option<LiveStatus> {
+    monitored()
+        .iter()
+        .find(|m| m.profile == profile)
+        .map(|m| m.status.clone())
+}
+
+/// Send `signal <name>` (`SIGTERM`, `SIGUSR1`, ...) to the daemon of
+/// `profile`: through the monitor when this process holds the socket,
+/// otherwise over a fresh connection.
+///
+/// # Errors
+///
+/// Returns an error if the management interface is unreachable or busy,
+/// so the caller can fall back to `kill`.
+pub fn signal(profile: &str, name: &str) -> io::Result<()> {
+    {
+        let mut list = monitored();
+        if let Some(m) = list.iter_mut().find(|m| m.profile == profile) {
+            return m.writer.write_all(format!("signal {name}\n").as_bytes());
+        }
+    }
+    let path = crate::utils::get_openvpn_mgmt_path(profile)?;
+    let mut client = Client::connect(&path, Duration::ZERO)?;
+    client.command(&format!("signal {name}")).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::net::UnixListener;
+
+    /// Scripted stand-in for `openvpn --management`: answers each expected
+    /// command with its reply lines and records what it was sent.
+    fn fake_daemon(
+        name: &str,
+        script: Vec<(&'static str, Vec<&'static str>)>,
+    ) -> (std::path::PathBuf, std::thread::JoinHandle<Vec<String>>) {
+        let dir = std::env::temp_dir().join(format!("vortix-mgmt-{}-{name}", std::process::id()));
+        let _ = std::fs::create_dir_all(&dir);
+        let path = dir.join("mgmt.sock");
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path).unwrap();
+
+        let handle = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut writer = stream.try_clone().unwrap();
+            let mut reader = BufReader::new(stream);
+            writeln!(writer, ">INFO:OpenVPN Management Interface Version 5").unwrap();
+            writeln!(writer, ">HOLD:Waiting for hold release:0").unwrap();
+            let mut received = Vec::new();
+            for (expected, replies) in script {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap() == 0 {
+                    break;
+                }
+                let line = line.trim_end().to_string();
+                assert!(
+                    line.starts_with(expected),
+                    "expected '{expected}', got '{line}'"
+                );
+                received.push(line);
+                for reply in replies {
+                    writeln!(writer, "{reply}").unwrap();
+                }
+            }
+            received
+        });
+        (path, handle)
+    }
+
+    #[test]
+    fn test_parse_notifications() {
+        assert_eq!(
+            parse_notification(">STATE:1700000000,CONNECTED,SUCCESS,10.8.0.6,198.51.100.7,1194,,"),
+            Some(Notification::State(StateChange {
+                state: "CONNECTED".to_string(),
+                detail: "SUCCESS".to_string(),
+                local_ip: "10.8.0.6".to_string(),
+                remote_ip: "198.51.100.7".to_string(),
+            }))
+        );
+        assert_eq!(
+            parse_notification(">BYTECOUNT:1536,512"),
+            Some(Notification::ByteCount { rx: 1536, tx: 512 })
+        );
+        assert_eq!(
+            parse_notification(">PASSWORD:Need 'Auth' username/password"),
+            Some(Notification::NeedPassword(AuthRequest {
+                kind: "Auth".to_string(),
+                username: true,
+                challenge: None,
+            }))
+        );
+        assert_eq!(
+            parse_notification(">PASSWORD:Need 'Private Key' password"),
+            Some(Notification::NeedPassword(AuthRequest {
+                kind: "Private Key".to_string(),
+                username: false,
+                challenge: None,
+            }))
+        );
+        assert_eq!(
+            parse_notification(">PASSWORD:Verification Failed: 'Auth'"),
+            Some(Notification::AuthFailed("Auth".to_string()))
+        );
+        assert_eq!(
+            parse_notification(">HOLD:Waiting for hold release:0"),
+            Some(Notification::Hold)
+        );
+        assert_eq!(parse_notification("SUCCESS: state on"), None);
+    }
+
+    #[test]
+    fn test_parse_challenges() {
+        let Some(Notification::NeedPassword(request)) =
+            parse_notification(">PASSWORD:Need 'Auth' SC:1,Enter Authenticator Code")
+        else {
+            panic!("expected a credential request");
+        };
+        assert!(request.username);
+        assert_eq!(
+            request.challenge,
+            Some(Challenge {
+                text: "Enter Authenticator Code".to_string(),
+                echo: true,
+                kind: ChallengeKind::Static,
+            })
+        );
+
+        assert_eq!(
+            parse_notification(
+                ">PASSWORD:Verification Failed: 'Auth' ['CRV1:R,E:Om01u7Fh4LrGBS7uh0SWmzwabUiGiW6l:Y3Ix:Please enter token PIN']"
+            ),
+            Some(Notification::Challenge(Challenge {
+                text: "Please enter token PIN".to_string(),
+                echo: true,
+                kind: ChallengeKind::Dynamic {
+                    state_id: "Om01u7Fh4LrGBS7uh0SWmzwabUiGiW6l".to_string(),
+                    username: "cr1".to_string(),
+                },
+            }))
+        );
+    }
+
+    #[test]
+    fn test_challenge_replies() {
+        let request = |sc: &str| match parse_notification(&format!(
+            ">PASSWORD:Need 'Auth' username/password SC:{sc},Code"
+        )) {
+            Some(Notification::NeedPassword(request)) => request,
+            other => panic!("unexpected {other:?}"),
+        };
+        let creds = Credentials {
+            response: "123456".to_string(),
+            ..Credentials::new("alice", "pass")
+        };
+
+        assert_eq!(
+            reply_for(&request("0"), &creds),
+            ("alice".to_string(), "SCRV1:cGFzcw==:MTIzNDU2".to_string())
+        );
+        assert_eq!(reply_for(&request("3"), &creds).1, "pass123456".to_string());
+
+        let dynamic = AuthRequest {
+            kind: "Auth".to_string(),
+            username: true,
+            challenge: Some(Challenge {
+                text: "PIN".to_string(),
+                echo: false,
+                kind: ChallengeKind::Dynamic {
+                    state_id: "abc".to_string(),
+                    username: "cr1".to_string(),
+                },
+            }),
+        };
+        assert_eq!(
+            reply_for(&dynamic, &creds),
+            ("cr1".to_string(), "CRV1::abc::123456".to_string())
+        );
+    }
+
+    #[test]
+    fn test_quote_arg_escapes() {
+        assert_eq!(quote_arg(r#"pa"ss\word"#).unwrap(), r#""pa\"ss\\word""#);
+        assert_eq!(
+            quote_arg("pass\nsignal SIGTERM").unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+        assert!(quote_arg("pa\rss").is_err());
+    }
+
+    #[test]
+    fn test_command_queues_notifications() {
+        let (path, server) = fake_daemon(
+            "queue",
+            vec![(
+                "state on",
+                vec![
+                    ">BYTECOUNT:10,20",
+                    "SUCCESS: real-time state notification set to ON",
+                ],
+            )],
+        );
+        let mut client = Client::connect(&path, Duration::from_secs(2)).unwrap();
+
+        let reply = client.command("state on").unwrap();
+        assert_eq!(reply, "real-time state notification set to ON");
+
+        // Banner, hold and the counter that arrived before the reply, in order
+        let timeout = Duration::from_millis(200);
+        assert!(matches!(
+            client.next_notification(timeout),
+            Ok(Some(Notification::Other(_)))
+        ));
+        assert_eq!(
+            client.next_notification(timeout).unwrap(),
+            Some(Notification::Hold)
+        );
+        assert_eq!(
+            client.next_notification(timeout).unwrap(),
+            Some(Notification::ByteCount { rx: 10, tx: 20 })
+        );
+
+        server.join().unwrap();
+        assert!(client.next_notification(timeout).is_err());
+    }
+
+    #[test]
+    fn test_await_connected_prompts_after_failure() {
+        let (path, server) = fake_daemon(
+            "auth",
+            vec![
+                ("state on", vec!["SUCCESS: state on"]),
+                ("bytecount", vec!["SUCCESS: bytecount interval changed"]),
+                (
+                    "hold release",
+                    vec![
+                        "SUCCESS: hold release succeeded",
+                        ">STATE:1,WAIT,,,,,,",
+                        ">PASSWORD:Need 'Auth' username/password",
+                    ],
+                ),
+                (
+                    "username \"Auth\" \"saved\"",
+                    vec!["SUCCESS: 'Auth' username entered"],
+                ),
+                (
+                    "password \"Auth\" \"stale\"",
+                    vec![
+                        "SUCCESS: 'Auth' password entered",
+                        ">PASSWORD:Verification Failed: 'Auth'",
+                        ">PASSWORD:Need 'Auth' username/password",
+                    ],
+                ),
+                (
+                    "username \"Auth\" \"alice\"",
+                    vec!["SUCCESS: 'Auth' username entered"],
+                ),
+                (
+                    "password \"Auth\" \"s3cr\\\"et\"",
+                    vec![
+                        "SUCCESS: 'Auth' password entered",
+                        ">STATE:2,CONNECTED,SUCCESS,10.8.0.6,198.51.100.7,1194,,",
+                    ],
+                ),
+            ],
+        );
+        let mut client = Client::connect(&path, Duration::from_secs(2)).unwrap();
+        let asked = std::cell::Cell::new(0);
+
+        let result = await_connected(
+            &mut client,
+            "office",
+            Duration::from_secs(5),
+            Some(("saved".to_string(), "stale".to_string())),
+            None,
+            &|request| {
+                assert_eq!(request.kind, "Auth");
+                asked.set(asked.get() + 1);
+                Some(Credentials::new("alice", "s3cr\"et"))
+            },
+        );
+
+        assert_eq!(result, Ok(true));
+        assert_eq!(asked.get(), 1);
+        assert_eq!(server.join().unwrap().len(), 7);
+    }
+
+    #[test]
+    fn test_await_connected_gives_up_without_credentials() {
+        let (path, server) = fake_daemon(
+            "cancel",
+            vec![
+                ("state on", vec!["SUCCESS: state on"]),
+                ("bytecount", vec!["SUCCESS: bytecount interval changed"]),
+                (
+                    "hold release",
+                    vec![
+                        "SUCCESS: hold release succeeded",
+                        ">PASSWORD:Need 'Auth' username/password",
+                    ],
+                ),
+                ("signal SIGTERM", vec!["SUCCESS: signal SIGTERM thrown"]),
+            ],
+        );
+        let mut client = Client::connect(&path, Duration::from_secs(2)).unwrap();
+
+        let err = await_connected(
+            &mut client,
+            "office",
+            Duration::from_secs(5),
+            None,
+            None,
+            &|_| None,
+        )
+        .unwrap_err();
+
+        assert!(err.contains("AUTH_FAILED"), "{err}");
+        assert_eq!(server.join().unwrap().last().unwrap(), "signal SIGTERM");
+    }
+
+    #[test]
+    fn test_await_connected_answers_dynamic_challenge_with_totp() {
+        let (path, server) = fake_daemon(
+            "crv1",
+            vec![
+                ("state on", vec!["SUCCESS: state on"]),
+                ("bytecount", vec!["SUCCESS: bytecount interval changed"]),
+                (
+                    "hold release",
+                    vec![
+                        "SUCCESS: hold release succeeded",
+                        ">PASSWORD:Need 'Auth' username/password",
+                    ],
+                ),
+                (
+                    "username \"Auth\" \"alice\"",
+                    vec!["SUCCESS: 'Auth' username entered"],
+                ),
+                (
+                    "password \"Auth\" \"pass\"",
+                    vec![
+                        "SUCCESS: 'Auth' password entered",
+                        ">PASSWORD:Verification Failed: 'Auth' ['CRV1:R:st4te:Y3Ix:Enter code']",
+                        ">PASSWORD:Need 'Auth' username/password",
+                    ],
+                ),
+                (
+                    "username \"Auth\" \"cr1\"",
+                    vec!["SUCCESS: 'Auth' username entered"],
+                ),
+                (
+                    "password \"Auth\" \"CRV1::st4te::",
+                    vec![
+                        "SUCCESS: 'Auth' password entered",
+                        ">STATE:2,CONNECTED,SUCCESS,10.8.0.6,198.51.100.7,1194,,",
+                    ],
+                ),
+            ],
+        );
+        let mut client = Client::connect(&path, Duration::from_secs(2)).unwrap();
+        let totp = Totp::from_base32("GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ").unwrap();
+
+        let result = await_connected(
+            &mut client,
+            "office",
+            Duration::from_secs(5),
+            Some(("alice".to_string(), "pass".to_string())),
+            Some(&totp),
+            &|_| panic!("should not ask"),
+        );
+
+        assert_eq!(result, Ok(true));
+        let received = server.join().unwrap();
+        let code = received.last().unwrap().trim_end_matches('"');
+        assert_eq!(code.rsplit("::").next().unwrap().len(), 6);
+    }
+}
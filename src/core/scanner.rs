@@ -70,16 +70,29 @@ pub fn get_active_profiles(profiles: &[VpnProfile]) -> Vec<ActiveSession> {
             Protocol::WireGuard => check_wireguard_by_name(&profile.name),
             Protocol::OpenVPN => {
                 let path_str = profile.config_path.to_str().unwrap_or("");
+                // An encrypted profile runs from its decrypted copy
+                let copy = crate::core::vault::runtime_copy_path(&profile.config_path)
+                    .filter(|_| crate::core::vault::is_sealed(&profile.config_path));
+                let copy_str = copy.as_deref().and_then(Path::to_str);
                 // Check if any PID matches this path
                 openvpn_pids
                     .iter()
-                    .find(|(path, _)| path.contains(path_str) || path_str.contains(*path))
+                    .find(|(path, _)| {
+                        path.contains(path_str)
+                            || path_str.contains(*path)
+                            || copy_str.is_some_and(|c| path.contains(c))
+                    })
                     .and_then(|(_, &pid)| check_openvpn_by_pid(pid, &profile.config_path))
             }
         };
 
         if let Some(mut session) = session_info {
             session.name.clone_from(&profile.name);
+            // Tunnel byte counters pushed over the management socket
+            if let Some(live) = crate::core::openvpn_mgmt::live_status(&profile.name) {
+                session.transfer_rx = crate::utils::format_bytes(live.rx_bytes);
+                session.transfer_tx = crate::utils::format_bytes(live.tx_bytes);
+            }
             active.push(session);
         }
     }
@@ -115,7 +128,7 @@ fn get_all_openvpn_pids() -> std::collections::HashMap<String, u32> {
 ///
 /// Uses platform-specific interface detection:
 /// - macOS: /var/run/wireguard/*.name + ifconfig
-/// - Linux: ip addr + wg show
+/// - Linux: netlink (or ip addr + wg show)
 fn check_wireguard_by_name(name: &str) -> Option<ActiveSession> {
     use crate::platform::InterfaceDetector;
 
@@ -175,8 +188,17 @@ fn check_wireguard_by_name(name: &str) -> Option<ActiveSession> {
         );
     }
 
-    // 3. Parse `wg show {interface_name}` (works the same on both platforms)
-    if let Some(output) = cmd_output(Command::new("wg").args(["show", &interface_name])) {
+    // 3. Peer details: straight from the kernel on Linux, else `wg show {interface_name}`
+    #[cfg(target_os = "linux")]
+    let from_kernel = read_wireguard_device(&interface_name, &mut session);
+    #[cfg(not(target_os = "linux"))]
+    let from_kernel = false;
+    let wg_show = if from_kernel {
+        None
+    } else {
+        cmd_output(Command::new("wg").args(["show", &interface_name]))
+    };
+    if let Some(output) = wg_show {
         let out = String::from_utf8_lossy(&output.stdout);
         for line in out.lines() {
             let line = line.trim();
@@ -214,6 +236,35 @@ fn check_wireguard_by_name(name: &str) -> Option<ActiveSession> {
     Some(session)
 }
 
+/// Fill the peer fields of `session` from the kernel, formatted like `wg show`.
+/// Returns `false` if the device could not be read (no module, no root).
+#[cfg(target_os = "linux")]
+fn read_wireguard_device(interface: &str, session: &mut ActiveSession) -> bool {
+    use crate::platform::WireGuardControl;
+    use crate::utils::format_bytes;
+    use crate::vpn::wireguard::format_handshake_age;
+
+    let Ok(device) = crate::platform::linux::wireguard::NetlinkWireGuard::device(interface) else {
+        return false;
+    };
+    if let Some(key) = device.public_key {
+        session.public_key = key.to_base64();
+    }
+    if device.listen_port != 0 {
+        session.listen_port = device.listen_port.to_string();
+    }
+    if let Some(endpoint) = device.peers.iter().find_map(|p| p.endpoint) {
+        session.endpoint = endpoint.to_string();
+    }
+    if let Some(at) = device.last_handshake() {
+        let age = SystemTime::now().duration_since(at).unwrap_or_default();
+        session.latest_handshake = format_handshake_age(age);
+    }
+    session.transfer_rx = format_bytes(device.rx_bytes());
+    session.transfer_tx = format_bytes(device.tx_bytes());
+    true
+}
+
 /// Checks if an `OpenVPN` process is running AND has an active tunnel.
 ///
 /// Returns `None` if the process is running but no tun/tap interface is
@@ -413,7 +464,7 @@ fn check_openvpn_by_pid(pid: u32, config_path: &Path) -> Option<ActiveSession> {
     session.public_key = "OpenVPN".to_string();
 
     // Read config file once for both endpoint and cipher extraction
-    if let Ok(config_content) = std::fs::read_to_string(config_path) {
+    if let Ok(config_content) = crate::core::vault::read_to_string(config_path) {
         // If no endpoint from args, try parsing the config file
         if session.endpoint.is_empty() {
             for line in config_content.lines() {
@@ -0,0 +1,317 @@
+//! Saved `OpenVPN` credentials in the system keyring.
+//!
+//! With `credential_store = "keyring"` credentials go to the freedesktop
+//! Secret Service (GNOME Keyring, `KWallet`, `KeePassXC`) instead of files
+//! under `auth/`. The service is driven through `secret-tool`, which holds
+//! the D-Bus session the secret is transferred in; like notifications, it
+//! runs as the invoking user under sudo so it reaches their session bus.
+//! `load_config` refuses the keyring store when `secret-tool` is missing.
+//!
+//! Items carry the attributes `service = vortix` and `profile = <name>`;
+//! the secret is `username\npassword`, the same layout as an auth file.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use crate::constants;
+
+/// Where saved credentials live.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CredentialStore {
+    /// One mode-600 file per profile under `auth/` (encrypted by the vault).
+    File,
+    /// The freedesktop Secret Service.
+    Keyring,
+}
+
+impl CredentialStore {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::File => "file",
+            Self::Keyring => "keyring",
+        }
+    }
+}
+
+impl std::fmt::Display for CredentialStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+impl std::str::FromStr for CredentialStore {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "file" => Ok(Self::File),
+            "keyring" => Ok(Self::Keyring),
+            _ => Err(format!(
+                "unknown credential store '{s}' (expected file or keyring)"
+            )),
+        }
+    }
+}
+
+/// Process-wide credential store, set once from `config.toml` at startup.
+static STORE: OnceLock<CredentialStore> = OnceLock::new();
+
+/// Use `store` for saved credentials for the lifetime of the process.
+/// Later calls are ignored (first write wins).
+pub fn set_store(store: CredentialStore) {
+    let _ = STORE.set(store);
+}
+
+/// The configured credential store (files until [`set_store`] is called).
+#[must_use]
+pub fn store() -> CredentialStore {
+    STORE.get().copied().unwrap_or(CredentialStore::File)
+}
+
+/// Check that `store` can be used here: the keyring needs `secret-tool`.
+///
+/// # Errors
+///
+/// Returns an error naming the missing tool and its package.
+pub fn check_store(store: CredentialStore) -> Result<(), String> {
+    check_store_with(constants::SECRET_TOOL_BIN, store)
+}
+
+fn check_store_with(program: &str, store: CredentialStore) -> Result<(), String> {
+    if store == CredentialStore::Keyring && Command::new(program).output().is_err() {
+        return Err(format!(
+            "credential_store = \"keyring\" needs {program} \
+             (package libsecret-tools or libsecret)"
+        ));
+    }
+    Ok(())
+}
+
+/// Save `username`/`password` for `profile`, replacing an existing item.
+///
+/// # Errors
+///
+/// Returns an error if `secret-tool` is missing or the service refused.
+pub fn save(profile: &str, username: &str, password: &str) -> Result<(), String> {
+    save_with(constants::SECRET_TOOL_BIN, profile, username, password)
+}
+
+/// Saved credentials of `profile`; `Ok(None)` when the keyring has none.
+///
+/// # Errors
+///
+/// Returns an error if `secret-tool` is missing or the service is unreachable.
+pub fn load(profile: &str) -> Result<Option<(String, String)>, String> {
+    load_with(constants::SECRET_TOOL_BIN, profile)
+}
+
+/// Remove the saved credentials of `profile`, if any.
+///
+/// # Errors
+///
+/// Returns an error if `secret-tool` is missing or the service refused.
+pub fn delete(profile: &str) -> Result<(), String> {
+    delete_with(constants::SECRET_TOOL_BIN, profile)
+}
+
+fn save_with(program: &str, profile: &str, username: &str, password: &str) -> Result<(), String> {
+    let label = format!("--label={} VPN credentials: {profile}", constants::APP_NAME);
+    let mut args = vec!["store".to_string(), label];
+    args.extend(attributes(profile));
+    let secret = zeroize::Zeroizing::new(format!("{username}\n{password}"));
+    run(program, &args, Some(secret.as_bytes()))?
+        .map(|_| ())
+        .ok_or_else(|| format!("{program} store failed"))
+}
+
+fn load_with(program: &str, profile: &str) -> Result<Option<(String, String)>, String> {
+    let mut args = vec!["lookup".to_string()];
+    args.extend(attributes(profile));
+    let Some(secret) = run(program, &args, None)? else {
+        return Ok(None);
+    };
+    let secret = zeroize::Zeroizing::new(secret);
+    let mut lines = secret.lines();
+    let username = lines.next().unwrap_or_default().to_string();
+    let password = lines.next().unwrap_or_default().to_string();
+    Ok((!username.is_empty() && !password.is_empty()).then_some((username, password)))
+}
+
+fn delete_with(program: &str, profile: &str) -> Result<(), String> {
+    let mut args = vec!["clear".to_string()];
+    args.extend(attributes(profile));
+    run(program, &args, None)?
+        .map(|_| ())
+        .ok_or_else(|| format!("{program} clear failed"))
+}
+
+/// Lookup attributes of a profile's item.
+fn attributes(profile: &str) -> [String; 4] {
+    [
+        "service".to_string(),
+        constants::SECRET_SERVICE_ATTRIBUTE.to_string(),
+        "profile".to_string(),
+        profile.to_string(),
+    ]
+}
+
+/// Run `secret-tool` with `stdin` piped in. Returns its output, or `None`
+/// when it failed without a message: how `lookup` reports "no such item",
+/// but a failure for every other command.
+fn run(program: &str, args: &[String], stdin: Option<&[u8]>) -> Result<Option<String>, String> {
+    let mut cmd = Command::new(program);
+    cmd.args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    as_session_user(&mut cmd);
+
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("{program} is not available: {e}"))?;
+    if let Some(mut pipe) = child.stdin.take() {
+        pipe.write_all(stdin.unwrap_or_default())
+            .map_err(|e| format!("{program}: {e}"))?;
+    }
+    // An unlock prompt nobody answers must not hang a connect
+    let timeout = Duration::from_secs(constants::SECRET_TOOL_TIMEOUT_SECS);
+    let output = crate::utils::wait_with_timeout(child, timeout)
+        .ok_or_else(|| format!("{program} timed out (is the keyring locked?)"))?;
+
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    if output.status.success() {
+        Ok(Some(String::from_utf8_lossy(&output.stdout).to_string()))
+    } else if stderr.is_empty() {
+        Ok(None)
+    } else {
+        Err(format!("Secret Service: {stderr}"))
+    }
+}
+
+/// Point the command at the invoking user's session bus, and under sudo run
+/// it as that user: the bus only accepts its owner.
+#[cfg(unix)]
+fn as_session_user(cmd: &mut Command) {
+    use std::os::unix::process::CommandExt;
+
+    if let Some((address, uid, gid)) = crate::utils::session_bus() {
+        cmd.env("DBUS_SESSION_BUS_ADDRESS", address);
+        if crate::utils::is_root() {
+            cmd.uid(uid).gid(gid);
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn as_session_user(_cmd: &mut Command) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A stand-in for `secret-tool` keeping items as files named after their
+    /// attributes, with the real tool's exit conventions.
+    fn mock_secret_tool(name: &str) -> (std::path::PathBuf, String) {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir =
+            std::env::temp_dir().join(format!("vortix-secrets-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("items")).unwrap();
+        let script = dir.join("secret-tool");
+        std::fs::write(
+            &script,
+            format!(
+                "#!/bin/sh\n\
+                 items='{}'\n\
+                 cmd=$1; shift\n\
+                 [ \"$cmd\" = store ] && shift\n\
+                 key=$(echo \"$*\" | tr ' /' '__')\n\
+                 case $cmd in\n\
+                 store) cat > \"$items/$key\" ;;\n\
+                 lookup) [ -f \"$items/$key\" ] || exit 1; cat \"$items/$key\" ;;\n\
+                 clear) rm -f \"$items/$key\" ;;\n\
+                 *) echo \"unknown command $cmd\" >&2; exit 2 ;;\n\
+                 esac\n",
+                dir.join("items").display()
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let program = script.to_string_lossy().to_string();
+        (dir, program)
+    }
+
+    #[test]
+    fn test_save_load_delete_roundtrip() {
+        let (dir, tool) = mock_secret_tool("roundtrip");
+        assert_eq!(load_with(&tool, "office").unwrap(), None);
+
+        save_with(&tool, "office", "alice", "s3cret pass").unwrap();
+        assert_eq!(
+            load_with(&tool, "office").unwrap(),
+            Some(("alice".to_string(), "s3cret pass".to_string()))
+        );
+        // Items are per profile
+        assert_eq!(load_with(&tool, "home").unwrap(), None);
+
+        save_with(&tool, "office", "bob", "other").unwrap();
+        assert_eq!(
+            load_with(&tool, "office").unwrap(),
+            Some(("bob".to_string(), "other".to_string()))
+        );
+
+        delete_with(&tool, "office").unwrap();
+        assert_eq!(load_with(&tool, "office").unwrap(), None);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_tool_errors_are_reported() {
+        let (dir, tool) = mock_secret_tool("errors");
+        let err = run(&tool, &["bogus".to_string()], None).unwrap_err();
+        assert!(err.contains("unknown command bogus"), "{err}");
+        assert!(load_with("/nonexistent/secret-tool", "x").is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_silent_failure_is_only_a_miss_for_lookup() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir =
+            std::env::temp_dir().join(format!("vortix-secrets-silent-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let script = dir.join("secret-tool");
+        std::fs::write(&script, "#!/bin/sh\ncat > /dev/null\nexit 1\n").unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let tool = script.to_string_lossy().to_string();
+
+        assert_eq!(load_with(&tool, "office").unwrap(), None);
+        assert!(save_with(&tool, "office", "alice", "pw").is_err());
+        assert!(delete_with(&tool, "office").is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_keyring_needs_secret_tool() {
+        let (dir, tool) = mock_secret_tool("check");
+        assert!(check_store_with(&tool, CredentialStore::Keyring).is_ok());
+        let err =
+            check_store_with("/nonexistent/secret-tool", CredentialStore::Keyring).unwrap_err();
+        assert!(err.contains("libsecret"), "{err}");
+        assert!(check_store_with("/nonexistent/secret-tool", CredentialStore::File).is_ok());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_parse_store() {
+        assert_eq!("keyring".parse(), Ok(CredentialStore::Keyring));
+        assert_eq!("File".parse(), Ok(CredentialStore::File));
+        assert!("vault".parse::<CredentialStore>().is_err());
+        assert_eq!(CredentialStore::Keyring.to_string(), "keyring");
+    }
+}
@@ -9,7 +9,7 @@
 
 use std::sync::mpsc::{self, Receiver, Sender};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use crate::constants;
 use crate::logger::LogLevel;
@@ -32,6 +32,8 @@ pub struct TelemetryConfig {
     pub ip_api_primary: String,
     /// Fallback API endpoints for IP lookup.
     pub ip_api_fallbacks: Vec<String>,
+    /// DNS leak test endpoint (empty = disabled).
+    pub dns_leak_api: String,
 }
 
 impl From<&crate::config::AppConfig> for TelemetryConfig {
@@ -44,6 +46,7 @@ impl From<&crate::config::AppConfig> for TelemetryConfig {
             ipv6_check_apis: config.ipv6_check_apis.clone(),
             ip_api_primary: config.ip_api_primary.clone(),
             ip_api_fallbacks: config.ip_api_fallbacks.clone(),
+            dns_leak_api: config.dns_leak_api.clone(),
         }
     }
 }
@@ -67,6 +70,8 @@ pub enum TelemetryUpdate {
     Location(String),
     /// IPv6 leak detection result (true = leak detected).
     Ipv6Leak(bool),
+    /// Resolvers observed by the active DNS leak test.
+    DnsLeakTest(crate::core::dns_leak::DnsLeakReport),
     /// Log message with level for production logging (uses centralized logger)
     Log(LogLevel, String),
 }
@@ -87,15 +92,27 @@ pub fn spawn_telemetry_worker(config: TelemetryConfig) -> (Receiver<TelemetryUpd
     let (nudge_tx, nudge_rx) = mpsc::channel::<()>();
     let config = std::sync::Arc::new(config);
 
-    thread::spawn(move || loop {
-        fetch_ip_and_isp(&tx, &config);
-        fetch_latency(&tx, &config);
-        fetch_security_info(&tx, &config);
+    thread::spawn(move || {
+        let leak_interval = Duration::from_secs(constants::DNS_LEAK_TEST_INTERVAL_SECS);
+        let mut last_leak_test: Option<Instant> = None;
+        let mut nudged = false;
+        loop {
+            fetch_ip_and_isp(&tx, &config);
+            fetch_latency(&tx, &config);
+            fetch_security_info(&tx, &config);
+
+            // The leak test is heavier and talks to a third party, so it runs
+            // on its own (longer) interval, or right away after a nudge.
+            if nudged || last_leak_test.map_or(true, |t| t.elapsed() >= leak_interval) {
+                run_dns_leak_test(&tx, &config);
+                last_leak_test = Some(Instant::now());
+            }
 
-        // Wait for the poll interval, but wake up immediately if nudged.
-        // Drain any extra nudges that accumulated while we were fetching.
-        let _ = nudge_rx.recv_timeout(config.poll_rate);
-        while nudge_rx.try_recv().is_ok() {}
+            // Wait for the poll interval, but wake up immediately if nudged.
+            // Drain any extra nudges that accumulated while we were fetching.
+            nudged = nudge_rx.recv_timeout(config.poll_rate).is_ok();
+            while nudge_rx.try_recv().is_ok() {}
+        }
     });
 
     (rx, nudge_tx)
@@ -561,8 +578,17 @@ pub fn parse_ping_output(output: &str) -> PingStats {
 /// Format: `iface: rx_bytes rx_packets rx_errs ... tx_bytes tx_packets tx_errs ...`
 /// Returns (`total_bytes_in`, `total_bytes_out`) excluding loopback.
 pub fn parse_proc_net_dev(content: &str) -> (u64, u64) {
-    let mut total_in: u64 = 0;
-    let mut total_out: u64 = 0;
+    parse_proc_net_dev_interfaces(content)
+        .iter()
+        .filter(|(iface, _)| !crate::platform::is_loopback(iface))
+        .fold((0, 0), |(total_in, total_out), (_, (rx, tx))| {
+            (total_in + rx, total_out + tx)
+        })
+}
+
+/// Parse `/proc/net/dev` output (Linux) into (`bytes_in`, `bytes_out`) per interface.
+pub fn parse_proc_net_dev_interfaces(content: &str) -> crate::platform::InterfaceBytes {
+    let mut counters = crate::platform::InterfaceBytes::new();
 
     for line in content.lines().skip(2) {
         // Skip 2 header lines
@@ -572,30 +598,20 @@ pub fn parse_proc_net_dev(content: &str) -> (u64, u64) {
         }
 
         // Split on ':' to get interface name and stats
-        let parts: Vec<&str> = line.splitn(2, ':').collect();
-        if parts.len() != 2 {
-            continue;
-        }
-
-        let iface = parts[0].trim();
-        // Skip loopback
-        if iface == "lo" {
+        let Some((iface, stats)) = line.split_once(':') else {
             continue;
-        }
+        };
 
-        let stats: Vec<&str> = parts[1].split_whitespace().collect();
+        let stats: Vec<&str> = stats.split_whitespace().collect();
         // rx_bytes is index 0, tx_bytes is index 8
         if stats.len() >= 10 {
-            if let Ok(rx) = stats[0].parse::<u64>() {
-                total_in += rx;
-            }
-            if let Ok(tx) = stats[8].parse::<u64>() {
-                total_out += tx;
-            }
+            let rx = stats[0].parse::<u64>().unwrap_or(0);
+            let tx = stats[8].parse::<u64>().unwrap_or(0);
+            counters.insert(iface.trim().to_string(), (rx, tx));
         }
     }
 
-    (total_in, total_out)
+    counters
 }
 
 /// Parse `ip addr show {iface}` output (Linux) to extract IP and MTU.
@@ -702,7 +718,7 @@ fn fetch_security_info(tx: &Sender<TelemetryUpdate>, cfg: &std::sync::Arc<Teleme
             let output6 = std::process::Command::new("curl")
                 .args(["-6", "-s", "--max-time", &ipv6_timeout, endpoint])
                 .output();
-            if output6.map(|o| o.status.success()).unwrap_or(false) {
+            if output6.is_ok_and(|o| o.status.success()) {
                 is_leaking = true;
                 break;
             }
@@ -711,6 +727,32 @@ fn fetch_security_info(tx: &Sender<TelemetryUpdate>, cfg: &std::sync::Arc<Teleme
     });
 }
 
+/// Runs the active DNS leak test (see [`crate::core::dns_leak`]).
+fn run_dns_leak_test(tx: &Sender<TelemetryUpdate>, cfg: &std::sync::Arc<TelemetryConfig>) {
+    if cfg.dns_leak_api.is_empty() {
+        return;
+    }
+    let tx_clone = tx.clone();
+    let cfg = std::sync::Arc::clone(cfg);
+    thread::spawn(move || {
+        match crate::core::dns_leak::run(
+            &cfg.dns_leak_api,
+            cfg.api_timeout,
+            crate::core::dns_leak::resolve_probe,
+        ) {
+            Ok(report) => {
+                let _ = tx_clone.send(TelemetryUpdate::DnsLeakTest(report));
+            }
+            Err(e) => {
+                let _ = tx_clone.send(TelemetryUpdate::Log(
+                    LogLevel::Warning,
+                    format!("DNS leak test failed: {e}"),
+                ));
+            }
+        }
+    });
+}
+
 // Network stats delta calculation is now handled directly in App::poll_network_stats()
 // using last_bytes_in / last_bytes_out fields on the App struct.
 
@@ -851,6 +893,22 @@ Inter-|   Receive                                                |  Transmit
         assert_eq!(bytes_out, 0);
     }
 
+    #[test]
+    fn test_parse_proc_net_dev_interfaces() {
+        let content = "\
+Inter-|   Receive                                                |  Transmit
+ face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed
+    lo: 1000       10    0    0    0     0          0         0     1000       10    0    0    0     0       0          0
+  eth0: 5000       50    0    0    0     0          0         0     3000       30    0    0    0     0       0          0
+wg-office:2000     20    0    0    0     0          0         0     1500       15    0    0    0     0       0          0";
+
+        let counters = parse_proc_net_dev_interfaces(content);
+        assert_eq!(counters.len(), 3);
+        assert_eq!(counters["eth0"], (5000, 3000));
+        // Large counters can run into the colon
+        assert_eq!(counters["wg-office"], (2000, 1500));
+    }
+
     #[test]
     fn test_parse_proc_net_dev_empty() {
         let (bytes_in, bytes_out) = parse_proc_net_dev("");
@@ -948,6 +1006,7 @@ Inter-|   Receive                                                |  Transmit
             log_retention_days: 7,
             disconnect_timeout: 30,
             openvpn_verbosity: "3".to_string(),
+            ..crate::config::AppConfig::default()
         };
 
         let tel_cfg = TelemetryConfig::from(&app_cfg);
@@ -0,0 +1,644 @@
+//! Encrypted-at-rest vault for profiles and saved credentials.
+//!
+//! Once enabled, files under `profiles/` and `auth/` hold ciphertext:
+//! `VORTIXV1 | 24-byte nonce | XChaCha20-Poly1305(content)`, authenticated
+//! together with the file's place under the config dir (`auth/work.auth`),
+//! so a sealed file copied over another one fails to decrypt. The key comes
+//! from a passphrase through Argon2id; `vault.json` keeps the salt, the
+//! derivation cost and a sealed check value that tells a wrong passphrase
+//! apart from a damaged file.
+//!
+//! Unlocking keeps the key in memory for the rest of the process. Readers
+//! that parse a profile decrypt it in memory; VPN tools that need a path get
+//! a decrypted copy in a private runtime directory (tmpfs on Linux, mode
+//! 600), removed when the tunnel goes down.
+
+use crate::constants;
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use zeroize::Zeroizing;
+
+const NONCE_LEN: usize = 24;
+const SALT_LEN: usize = 16;
+/// Plaintext of the check value in `vault.json`.
+const CHECK: &[u8] = b"vortix-vault";
+
+/// Key of an unlocked vault, wiped from memory when dropped.
+pub struct Key(Zeroizing<[u8; 32]>);
+
+impl Key {
+    /// Encrypt `plain` into the on-disk format, bound to `label` (see
+    /// [`file_label`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no random nonce can be drawn.
+    pub fn seal(&self, plain: &[u8], label: &str) -> Result<Vec<u8>, String> {
+        let mut nonce = [0u8; NONCE_LEN];
+        getrandom::getrandom(&mut nonce).map_err(|e| format!("No randomness: {e}"))?;
+        let sealed = self
+            .cipher()
+            .encrypt(
+                XNonce::from_slice(&nonce),
+                Payload {
+                    msg: plain,
+                    aad: &aad(label),
+                },
+            )
+            .map_err(|_| "Encryption failed".to_string())?;
+        let mut out = Vec::with_capacity(constants::VAULT_MAGIC.len() + NONCE_LEN + sealed.len());
+        out.extend_from_slice(constants::VAULT_MAGIC);
+        out.extend_from_slice(&nonce);
+        out.extend_from_slice(&sealed);
+        Ok(out)
+    }
+
+    /// Decrypt data produced by [`Key::seal`] with the same `label`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `data` isn't sealed, was sealed with another key
+    /// or for another file, or has been altered.
+    pub fn open(&self, data: &[u8], label: &str) -> Result<Zeroizing<Vec<u8>>, String> {
+        let body = data
+            .strip_prefix(constants::VAULT_MAGIC)
+            .filter(|b| b.len() >= NONCE_LEN)
+            .ok_or("Not a vault file")?;
+        let (nonce, sealed) = body.split_at(NONCE_LEN);
+        self.cipher()
+            .decrypt(
+                XNonce::from_slice(nonce),
+                Payload {
+                    msg: sealed,
+                    aad: &aad(label),
+                },
+            )
+            .map(Zeroizing::new)
+            .map_err(|_| "Decryption failed: wrong key or damaged file".to_string())
+    }
+
+    fn cipher(&self) -> XChaCha20Poly1305 {
+        XChaCha20Poly1305::new(self.0.as_ref().into())
+    }
+}
+
+/// Associated data of a sealed file: the magic header and its label.
+fn aad(label: &str) -> Vec<u8> {
+    [constants::VAULT_MAGIC, label.as_bytes()].concat()
+}
+
+/// What a vault file is bound to: its directory and name under the config
+/// dir, e.g. `auth/work.auth`. Moving the whole config dir keeps it.
+#[must_use]
+pub fn file_label(path: &Path) -> String {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    match path.parent().and_then(Path::file_name) {
+        Some(dir) => format!("{}/{name}", dir.to_string_lossy()),
+        None => name.into_owned(),
+    }
+}
+
+/// Contents of `vault.json`.
+#[derive(Serialize, Deserialize)]
+struct VaultFile {
+    version: u32,
+    /// Argon2id salt (hex).
+    salt: String,
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+    /// [`CHECK`] sealed with the key (hex).
+    check: String,
+}
+
+/// Key of the vault unlocked in this process.
+static UNLOCKED: Mutex<Option<Key>> = Mutex::new(None);
+
+fn unlocked() -> std::sync::MutexGuard<'static, Option<Key>> {
+    UNLOCKED
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+/// Whether a vault has been set up in `config_dir`.
+#[must_use]
+pub fn is_enabled(config_dir: &Path) -> bool {
+    config_dir.join(constants::VAULT_FILE_NAME).exists()
+}
+
+/// Whether this process holds the vault key.
+#[must_use]
+pub fn is_unlocked() -> bool {
+    unlocked().is_some()
+}
+
+/// Set up a vault in `config_dir` protected by `passphrase`. Existing files
+/// are not touched; see [`seal_all`].
+///
+/// # Errors
+///
+/// Returns an error if a vault already exists or `vault.json` can't be written.
+pub fn create(config_dir: &Path, passphrase: &str) -> Result<Key, String> {
+    create_with(
+        config_dir,
+        passphrase,
+        constants::VAULT_KDF_MEMORY_KIB,
+        constants::VAULT_KDF_ITERATIONS,
+        constants::VAULT_KDF_PARALLELISM,
+    )
+}
+
+fn create_with(
+    config_dir: &Path,
+    passphrase: &str,
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+) -> Result<Key, String> {
+    if is_enabled(config_dir) {
+        return Err("A vault already exists".to_string());
+    }
+    let mut salt = [0u8; SALT_LEN];
+    getrandom::getrandom(&mut salt).map_err(|e| format!("No randomness: {e}"))?;
+    let mut file = VaultFile {
+        version: 1,
+        salt: to_hex(&salt),
+        memory_kib,
+        iterations,
+        parallelism,
+        check: String::new(),
+    };
+    let key = derive(passphrase, &file)?;
+    file.check = to_hex(&key.seal(CHECK, constants::VAULT_FILE_NAME)?);
+    let json = serde_json::to_string_pretty(&file).map_err(|e| e.to_string())?;
+    let path = config_dir.join(constants::VAULT_FILE_NAME);
+    crate::utils::write_private(&path, json.as_bytes())
+        .map_err(|e| format!("Failed to write vault: {e}"))?;
+    crate::config::fix_ownership(&path);
+    Ok(key)
+}
+
+/// Derive the vault key of `config_dir` from `passphrase`.
+///
+/// # Errors
+///
+/// Returns an error if there is no vault or the passphrase is wrong.
+pub fn open(config_dir: &Path, passphrase: &str) -> Result<Key, String> {
+    let path = config_dir.join(constants::VAULT_FILE_NAME);
+    let json = std::fs::read_to_string(&path).map_err(|e| format!("No vault: {e}"))?;
+    let file: VaultFile =
+        serde_json::from_str(&json).map_err(|e| format!("Invalid {}: {e}", path.display()))?;
+    let key = derive(passphrase, &file)?;
+    let check = from_hex(&file.check).ok_or("Invalid vault check value")?;
+    match key.open(&check, constants::VAULT_FILE_NAME) {
+        Ok(plain) if plain.as_slice() == CHECK => Ok(key),
+        _ => Err("Wrong passphrase".to_string()),
+    }
+}
+
+/// Open the vault of `config_dir` and keep its key for this process.
+///
+/// # Errors
+///
+/// Returns an error if there is no vault or the passphrase is wrong.
+pub fn unlock(config_dir: &Path, passphrase: &str) -> Result<(), String> {
+    let key = open(config_dir, passphrase)?;
+    *unlocked() = Some(key);
+    Ok(())
+}
+
+fn derive(passphrase: &str, file: &VaultFile) -> Result<Key, String> {
+    let salt = from_hex(&file.salt).ok_or("Invalid vault salt")?;
+    let params = Params::new(file.memory_kib, file.iterations, file.parallelism, Some(32))
+        .map_err(|e| format!("Invalid vault parameters: {e}"))?;
+    let mut key = Zeroizing::new([0u8; 32]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), &salt, key.as_mut())
+        .map_err(|e| format!("Key derivation failed: {e}"))?;
+    Ok(Key(key))
+}
+
+/// Whether `data` is in the vault's on-disk format.
+#[must_use]
+pub fn is_sealed_data(data: &[u8]) -> bool {
+    data.starts_with(constants::VAULT_MAGIC)
+}
+
+/// Whether the file at `path` is encrypted.
+#[must_use]
+pub fn is_sealed(path: &Path) -> bool {
+    use std::io::Read;
+    let mut header = [0u8; 8];
+    std::fs::File::open(path)
+        .and_then(|mut f| f.read_exact(&mut header))
+        .is_ok_and(|()| is_sealed_data(&header))
+}
+
+/// Read a profile or credential file, decrypting it with the unlocked key
+/// when it is encrypted.
+///
+/// # Errors
+///
+/// Returns an error if the file can't be read, the vault is locked or the
+/// file doesn't decrypt.
+pub fn read(path: &Path) -> std::io::Result<Zeroizing<Vec<u8>>> {
+    let data = std::fs::read(path)?;
+    if !is_sealed_data(&data) {
+        return Ok(Zeroizing::new(data));
+    }
+    let guard = unlocked();
+    let key = guard
+        .as_ref()
+        .ok_or_else(|| std::io::Error::other("Vault is locked"))?;
+    key.open(&data, &file_label(path))
+        .map_err(std::io::Error::other)
+}
+
+/// [`read`] as UTF-8 text, converted in place so no unzeroized copy of a
+/// decrypted file is left behind.
+///
+/// # Errors
+///
+/// Returns an error if the file can't be read or decrypted, or isn't UTF-8.
+pub fn read_to_string(path: &Path) -> std::io::Result<Zeroizing<String>> {
+    let mut bytes = read(path)?;
+    match String::from_utf8(std::mem::take(&mut *bytes)) {
+        Ok(text) => Ok(Zeroizing::new(text)),
+        Err(e) => {
+            drop(Zeroizing::new(e.into_bytes()));
+            Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "Not UTF-8 text",
+            ))
+        }
+    }
+}
+
+/// What to write for `plain` to `path` under the config dir: ciphertext
+/// bound to `path` when the vault is enabled, `plain` otherwise.
+///
+/// # Errors
+///
+/// Returns an error if the vault is enabled but locked.
+pub fn seal_for_disk(plain: &[u8], path: &Path) -> std::io::Result<Vec<u8>> {
+    let enabled = crate::utils::get_app_config_dir().is_ok_and(|dir| is_enabled(&dir));
+    if !enabled {
+        return Ok(plain.to_vec());
+    }
+    let guard = unlocked();
+    let key = guard
+        .as_ref()
+        .ok_or_else(|| std::io::Error::other("Vault is locked"))?;
+    key.seal(plain, &file_label(path))
+        .map_err(std::io::Error::other)
+}
+
+/// Private directory for decrypted copies: `$XDG_RUNTIME_DIR/vortix`,
+/// `/run/vortix` for root or `/run/user/<uid>/vortix`, else a per-user
+/// directory under `/dev/shm` (all tmpfs). Other platforms use the per-user
+/// temp dir. Either way it is only used once [`write_runtime_file`] has
+/// checked that this user owns it.
+#[must_use]
+pub fn runtime_dir() -> PathBuf {
+    let uid = crate::utils::effective_uid();
+    #[cfg(target_os = "linux")]
+    {
+        if let Some(dir) = std::env::var_os("XDG_RUNTIME_DIR")
+            .map(PathBuf::from)
+            .filter(|d| d.is_dir())
+        {
+            return dir.join(constants::VAULT_RUNTIME_DIR);
+        }
+        if uid == 0 {
+            return Path::new("/run").join(constants::VAULT_RUNTIME_DIR);
+        }
+        let user_run = PathBuf::from(format!("/run/user/{uid}"));
+        if user_run.is_dir() {
+            return user_run.join(constants::VAULT_RUNTIME_DIR);
+        }
+        Path::new("/dev/shm").join(format!("{}-{uid}", constants::VAULT_RUNTIME_DIR))
+    }
+    #[cfg(not(target_os = "linux"))]
+    std::env::temp_dir().join(format!("{}-{uid}", constants::VAULT_RUNTIME_DIR))
+}
+
+/// Where the decrypted copy of `config_path` goes. It keeps the file name,
+/// so `wg-quick` derives the same interface name.
+#[must_use]
+pub fn runtime_copy_path(config_path: &Path) -> Option<PathBuf> {
+    Some(runtime_dir().join(config_path.file_name()?))
+}
+
+/// A path VPN tools can read the profile from: the profile itself, or for
+/// an encrypted one a decrypted copy in [`runtime_dir`].
+///
+/// # Errors
+///
+/// Returns an error if the vault is locked or the copy can't be written.
+pub fn plaintext_path(config_path: &Path) -> Result<PathBuf, String> {
+    if !is_sealed(config_path) {
+        return Ok(config_path.to_path_buf());
+    }
+    let plain = read(config_path).map_err(|e| format!("{}: {e}", config_path.display()))?;
+    let copy = runtime_copy_path(config_path).ok_or("Invalid profile path")?;
+    write_runtime_file(&copy, &plain)?;
+    Ok(copy)
+}
+
+/// Write `contents` into [`runtime_dir`] as `path`, readable by the owner only.
+///
+/// # Errors
+///
+/// Returns an error if the directory or file can't be created, or the
+/// directory exists but is not a directory owned by this user.
+pub fn write_runtime_file(path: &Path, contents: &[u8]) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        create_private_dir(dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+    }
+    crate::utils::write_private(path, contents)
+        .map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+/// Remove the decrypted copy of `config_path`, if any.
+pub fn remove_runtime_copy(config_path: &Path) {
+    if let Some(copy) = runtime_copy_path(config_path) {
+        let _ = std::fs::remove_file(copy);
+    }
+}
+
+/// Encrypt every plaintext profile and credential file in `config_dir`.
+/// Returns how many files were encrypted.
+///
+/// # Errors
+///
+/// Returns an error naming the first file that couldn't be rewritten.
+pub fn seal_all(config_dir: &Path, key: &Key) -> Result<usize, String> {
+    rewrite_all(config_dir, |path, data| {
+        (!is_sealed_data(data))
+            .then(|| key.seal(data, &file_label(path)).map(Zeroizing::new))
+            .transpose()
+    })
+}
+
+/// Decrypt every encrypted profile and credential file in `config_dir`.
+/// Returns how many files were decrypted.
+///
+/// # Errors
+///
+/// Returns an error naming the first file that couldn't be decrypted or
+/// rewritten.
+pub fn open_all(config_dir: &Path, key: &Key) -> Result<usize, String> {
+    rewrite_all(config_dir, |path, data| {
+        is_sealed_data(data)
+            .then(|| key.open(data, &file_label(path)))
+            .transpose()
+    })
+}
+
+/// Remove `vault.json` (after [`open_all`]).
+///
+/// # Errors
+///
+/// Returns an error if the file can't be removed.
+pub fn remove(config_dir: &Path) -> Result<(), String> {
+    std::fs::remove_file(config_dir.join(constants::VAULT_FILE_NAME))
+        .map_err(|e| format!("Failed to remove vault: {e}"))
+}
+
+/// Files the vault covers: profiles, saved `OpenVPN` credentials and TOTP
+/// seeds.
+#[must_use]
+pub fn vault_files(config_dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for (dir, exts) in [
+        (constants::PROFILES_DIR_NAME, &["conf", "ovpn"][..]),
+        (
+            constants::OPENVPN_AUTH_DIR,
+            &["auth", constants::TOTP_SEED_EXTENSION][..],
+        ),
+    ] {
+        let Ok(entries) = std::fs::read_dir(config_dir.join(dir)) else {
+            continue;
+        };
+        files.extend(entries.flatten().map(|e| e.path()).filter(|p| {
+            p.is_file()
+                && p.extension()
+                    .and_then(|e| e.to_str())
+                    .is_some_and(|e| exts.contains(&e))
+        }));
+    }
+    files.sort();
+    files
+}
+
+/// Replace each vault file with `convert(contents)` where it returns `Some`.
+fn rewrite_all(
+    config_dir: &Path,
+    convert: impl Fn(&Path, &[u8]) -> Result<Option<Zeroizing<Vec<u8>>>, String>,
+) -> Result<usize, String> {
+    let mut count = 0;
+    for path in vault_files(config_dir) {
+        let data = std::fs::read(&path).map_err(|e| format!("{}: {e}", path.display()))?;
+        let Some(converted) =
+            convert(&path, &data).map_err(|e| format!("{}: {e}", path.display()))?
+        else {
+            continue;
+        };
+        // Write next to the file and rename, so a crash never leaves it half-written
+        let tmp = path.with_extension("vault-tmp");
+        crate::utils::write_private(&tmp, &converted)
+            .and_then(|()| std::fs::rename(&tmp, &path))
+            .map_err(|e| format!("{}: {e}", path.display()))?;
+        crate::config::fix_ownership(&path);
+        count += 1;
+    }
+    Ok(count)
+}
+
+#[cfg(unix)]
+fn create_private_dir(dir: &Path) -> std::io::Result<()> {
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+    std::fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(dir)?;
+    // The fallback sits in a world-writable directory: never use one that
+    // another user created first, or a symlink planted in its place
+    let meta = std::fs::symlink_metadata(dir)?;
+    if !meta.is_dir() || meta.uid() != crate::utils::effective_uid() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::PermissionDenied,
+            "not a directory owned by this user",
+        ));
+    }
+    std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))
+}
+
+#[cfg(not(unix))]
+fn create_private_dir(dir: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    use std::fmt::Write as _;
+    bytes.iter().fold(String::new(), |mut s, b| {
+        let _ = write!(s, "{b:02x}");
+        s
+    })
+}
+
+fn from_hex(s: &str) -> Option<Vec<u8>> {
+    if s.len() % 2 != 0 {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("vortix-vault-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// A vault with the cheapest derivation Argon2 accepts.
+    fn quick_vault(dir: &Path, passphrase: &str) -> Key {
+        create_with(dir, passphrase, 8, 1, 1).unwrap()
+    }
+
+    #[test]
+    fn test_seal_open_roundtrip_and_tamper() {
+        let dir = temp_dir("roundtrip");
+        let key = quick_vault(&dir, "correct horse");
+        let sealed = key.seal(b"PrivateKey = abc", "profiles/wg0.conf").unwrap();
+        assert!(is_sealed_data(&sealed));
+        assert!(!sealed.windows(3).any(|w| w == b"abc"));
+        assert_eq!(
+            key.open(&sealed, "profiles/wg0.conf").unwrap().as_slice(),
+            b"PrivateKey = abc"
+        );
+        // Bound to its file: a copy over another profile doesn't open
+        assert!(key.open(&sealed, "profiles/wg1.conf").is_err());
+
+        let mut tampered = sealed;
+        let last = tampered.len() - 1;
+        tampered[last] ^= 1;
+        assert!(key.open(&tampered, "profiles/wg0.conf").is_err());
+        assert!(key.open(b"plain text", "profiles/wg0.conf").is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_open_checks_passphrase() {
+        let dir = temp_dir("passphrase");
+        let key = quick_vault(&dir, "s3cret");
+        assert!(create_with(&dir, "again", 8, 1, 1).is_err());
+
+        let sealed = key.seal(b"x", "auth/x.auth").unwrap();
+        let reopened = open(&dir, "s3cret").unwrap();
+        assert_eq!(
+            reopened.open(&sealed, "auth/x.auth").unwrap().as_slice(),
+            b"x"
+        );
+        assert_eq!(
+            open(&dir, "wrong").err().as_deref(),
+            Some("Wrong passphrase")
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_seal_all_and_open_all() {
+        let dir = temp_dir("all");
+        let profiles = dir.join(constants::PROFILES_DIR_NAME);
+        let auth = dir.join(constants::OPENVPN_AUTH_DIR);
+        std::fs::create_dir_all(&profiles).unwrap();
+        std::fs::create_dir_all(&auth).unwrap();
+        std::fs::write(profiles.join("wg0.conf"), "[Interface]\n").unwrap();
+        std::fs::write(profiles.join("notes.txt"), "untouched").unwrap();
+        std::fs::write(auth.join("work.auth"), "user\npass\n").unwrap();
+
+        let key = quick_vault(&dir, "pw");
+        assert_eq!(seal_all(&dir, &key).unwrap(), 2);
+        assert!(is_sealed(&profiles.join("wg0.conf")));
+        assert!(is_sealed(&auth.join("work.auth")));
+        assert!(!is_sealed(&profiles.join("notes.txt")));
+        // Already sealed files are skipped
+        assert_eq!(seal_all(&dir, &key).unwrap(), 0);
+
+        // One profile's sealed credentials copied over another's
+        std::fs::write(auth.join("home.auth"), "other\nsecret\n").unwrap();
+        assert_eq!(seal_all(&dir, &key).unwrap(), 1);
+        std::fs::copy(auth.join("work.auth"), auth.join("home.auth")).unwrap();
+        assert!(open_all(&dir, &key).unwrap_err().contains("home.auth"));
+        std::fs::remove_file(auth.join("home.auth")).unwrap();
+
+        assert_eq!(open_all(&dir, &key).unwrap(), 2);
+        assert_eq!(
+            std::fs::read_to_string(auth.join("work.auth")).unwrap(),
+            "user\npass\n"
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_private_dir_refuses_planted_symlink() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = temp_dir("runtime");
+        let own = dir.join("own");
+        create_private_dir(&own).unwrap();
+        let mode = std::fs::metadata(&own).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o700);
+        // Already there and ours: reused
+        create_private_dir(&own).unwrap();
+
+        let planted = dir.join("planted");
+        std::os::unix::fs::symlink(&own, &planted).unwrap();
+        assert!(create_private_dir(&planted).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_file_label() {
+        assert_eq!(
+            file_label(Path::new("/home/u/.config/vortix/auth/work.auth")),
+            "auth/work.auth"
+        );
+        assert_eq!(file_label(Path::new("wg0.conf")), "wg0.conf");
+    }
+
+    #[test]
+    fn test_hex_roundtrip() {
+        assert_eq!(to_hex(&[0, 15, 255]), "000fff");
+        assert_eq!(from_hex("000fff"), Some(vec![0, 15, 255]));
+        assert_eq!(from_hex("0g"), None);
+        assert_eq!(from_hex("abc"), None);
+    }
+
+    #[test]
+    fn test_read_to_string_plain_and_invalid() {
+        let dir = temp_dir("text");
+        let text = dir.join("plain.conf");
+        std::fs::write(&text, "[Interface]\n").unwrap();
+        assert_eq!(read_to_string(&text).unwrap().as_str(), "[Interface]\n");
+
+        let binary = dir.join("binary.conf");
+        std::fs::write(&binary, [0xff, 0xfe]).unwrap();
+        let err = read_to_string(&binary).unwrap_err();
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,114 @@
+//! Blocking client for the daemon control socket.
+//!
+//! Used by the TUI when a daemon is running. Every call blocks on the
+//! socket, so callers on the UI thread must only issue quick requests
+//! (`status`, `kill_switch`) and push `connect`/`disconnect` to a thread.
+
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+use std::time::Duration;
+
+use super::protocol::{self, Event, Request, Response, Status};
+
+/// A connection to a running daemon.
+pub struct DaemonClient {
+    writer: UnixStream,
+    reader: BufReader<UnixStream>,
+}
+
+impl DaemonClient {
+    /// Open a connection to the daemon listening on `socket_path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if nothing is listening on the socket.
+    pub fn connect(socket_path: &Path) -> Result<Self, String> {
+        let writer = UnixStream::connect(socket_path)
+            .map_err(|e| format!("Cannot reach daemon at {}: {e}", socket_path.display()))?;
+        let reader = writer
+            .try_clone()
+            .map_err(|e| format!("Failed to clone daemon socket: {e}"))?;
+        Ok(Self {
+            writer,
+            reader: BufReader::new(reader),
+        })
+    }
+
+    /// Limit how long a single response may take (`None` waits forever).
+    pub fn set_timeout(&self, timeout: Option<Duration>) {
+        let _ = self.writer.set_read_timeout(timeout);
+    }
+
+    /// Send one request and wait for its response.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the socket fails or the daemon hangs up.
+    pub fn request(&mut self, request: &Request) -> Result<Response, String> {
+        let line = protocol::encode(request)?;
+        self.writer
+            .write_all(line.as_bytes())
+            .map_err(|e| format!("Failed to send request to daemon: {e}"))?;
+        self.read_line()
+    }
+
+    /// Subscribe to events. Returns the current status; call
+    /// [`next_event`](Self::next_event) afterwards to receive updates.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the socket fails or the daemon rejects the request.
+    pub fn subscribe(&mut self) -> Result<Status, String> {
+        match self.request(&Request::Subscribe)? {
+            Response::Ok { status } => Ok(status),
+            Response::Error { message, .. } => Err(message),
+        }
+    }
+
+    /// Block until the daemon pushes the next event.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error once the daemon hangs up.
+    pub fn next_event(&mut self) -> Result<Event, String> {
+        self.read_line()
+    }
+
+    fn read_line<T: serde::de::DeserializeOwned>(&mut self) -> Result<T, String> {
+        let mut line = String::new();
+        match self.reader.read_line(&mut line) {
+            Ok(0) => Err("Daemon closed the connection".to_string()),
+            Ok(_) => protocol::decode(&line),
+            Err(e) => Err(format!("Failed to read from daemon: {e}")),
+        }
+    }
+}
+
+/// Ask the daemon for its status, giving up after `timeout`.
+///
+/// Returns `None` when no daemon is listening or it does not answer in time.
+pub fn probe(socket_path: &Path, timeout: Duration) -> Option<Status> {
+    call(socket_path, &Request::Status, Some(timeout)).ok()
+}
+
+/// Open a connection, send one request and return the resulting status.
+///
+/// `timeout` bounds the wait for the response; `None` waits until the daemon
+/// answers (connect/disconnect only answer once the tunnel settles).
+///
+/// # Errors
+///
+/// Returns the daemon's error message, or a transport error if it cannot be reached.
+pub fn call(
+    socket_path: &Path,
+    request: &Request,
+    timeout: Option<Duration>,
+) -> Result<Status, String> {
+    let mut client = DaemonClient::connect(socket_path)?;
+    client.set_timeout(timeout);
+    match client.request(request)? {
+        Response::Ok { status } => Ok(status),
+        Response::Error { message, .. } => Err(message),
+    }
+}
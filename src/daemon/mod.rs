@@ -0,0 +1,13 @@
+//! Daemon mode: a long-running root process that owns the VPN connection.
+//!
+//! `vortix daemon` keeps the tunnel and kill switch alive independently of
+//! any terminal. The TUI detects a running daemon on startup and becomes a
+//! thin client of it, sending requests and rendering the pushed state.
+//!
+//! - [`protocol`]: Newline-delimited JSON messages on the control socket.
+//! - [`server`]: The daemon process and its state machine.
+//! - [`client`]: Blocking client used by the TUI.
+
+pub mod client;
+pub mod protocol;
+pub mod server;
@@ -0,0 +1,179 @@
+//! Wire format for the daemon control socket.
+//!
+//! Newline-delimited JSON: every request, response and event is a single
+//! JSON object followed by `\n`. A client sends one request per line and
+//! reads exactly one response line back, except after `subscribe`, where
+//! the socket keeps streaming [`Event`] lines until either side hangs up.
+//!
+//! ```text
+//! → {"cmd":"connect","profile":"work"}
+//! ← {"result":"ok","status":{"state":"connected","profile":"work",...}}
+//! → {"cmd":"subscribe"}
+//! ← {"result":"ok","status":{...}}
+//! ← {"event":"state","status":{...}}
+//! ← {"event":"log","message":"WARN: Connection dropped from 'work'"}
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use crate::state::{KillSwitchMode, KillSwitchState};
+
+/// A command sent by a client.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "cmd", rename_all = "snake_case")]
+pub enum Request {
+    /// Bring up `profile`. The response is sent once the outcome is known.
+    Connect { profile: String },
+    /// Tear down the active tunnel. The response is sent once it is down.
+    Disconnect,
+    /// Return the current [`Status`] immediately.
+    Status,
+    /// Change the kill switch mode.
+    KillSwitch { mode: KillSwitchMode },
+    /// Acknowledge with the current status, then stream [`Event`]s.
+    Subscribe,
+}
+
+/// The daemon's reply to a [`Request`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "result", rename_all = "snake_case")]
+pub enum Response {
+    /// The request succeeded; carries the status after it was applied.
+    Ok { status: Status },
+    /// The request failed. `code` uses the same values as the CLI exit codes
+    /// (`constants::EXIT_*`) so clients can map it straight through.
+    Error { code: i32, message: String },
+}
+
+/// A notification pushed to subscribed clients.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    /// The connection or kill switch state changed.
+    State { status: Status },
+    /// An activity log line, using the same `PREFIX: message` format as the TUI.
+    Log { message: String },
+}
+
+/// Lifecycle phase of the daemon-owned connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionState {
+    #[default]
+    Disconnected,
+    Connecting,
+    Connected,
+    Disconnecting,
+}
+
+/// Snapshot of everything the daemon owns.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Status {
+    pub state: SessionState,
+    /// Profile the state refers to (`None` while disconnected).
+    pub profile: Option<String>,
+    /// Unix timestamp (seconds) of the last state change.
+    pub since: Option<u64>,
+    pub killswitch_mode: KillSwitchMode,
+    pub killswitch_state: KillSwitchState,
+    /// Current automatic reconnect attempt after a drop (1-based; `None`
+    /// when not reconnecting).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reconnect_attempt: Option<u32>,
+}
+
+/// Serialize a message as one protocol line (including the trailing newline).
+///
+/// # Errors
+///
+/// Returns an error if the value cannot be serialized.
+pub fn encode<T: Serialize>(msg: &T) -> Result<String, String> {
+    serde_json::to_string(msg)
+        .map(|mut line| {
+            line.push('\n');
+            line
+        })
+        .map_err(|e| format!("Failed to encode message: {e}"))
+}
+
+/// Parse one protocol line.
+///
+/// # Errors
+///
+/// Returns an error if the line is not a valid message of type `T`.
+pub fn decode<'a, T: Deserialize<'a>>(line: &'a str) -> Result<T, String> {
+    serde_json::from_str(line.trim_end()).map_err(|e| format!("Malformed message: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_wire_format() {
+        let req = Request::Connect {
+            profile: "work".to_string(),
+        };
+        assert_eq!(
+            encode(&req).unwrap(),
+            "{\"cmd\":\"connect\",\"profile\":\"work\"}\n"
+        );
+        assert_eq!(encode(&Request::Status).unwrap(), "{\"cmd\":\"status\"}\n");
+    }
+
+    #[test]
+    fn test_request_roundtrip() {
+        for req in [
+            Request::Connect {
+                profile: "a b".to_string(),
+            },
+            Request::Disconnect,
+            Request::Status,
+            Request::KillSwitch {
+                mode: KillSwitchMode::AlwaysOn,
+            },
+            Request::Subscribe,
+        ] {
+            let line = encode(&req).unwrap();
+            assert_eq!(decode::<Request>(&line).unwrap(), req);
+        }
+    }
+
+    #[test]
+    fn test_response_error_wire_format() {
+        let resp = Response::Error {
+            code: 3,
+            message: "auth".to_string(),
+        };
+        let line = encode(&resp).unwrap();
+        assert_eq!(
+            line,
+            "{\"result\":\"error\",\"code\":3,\"message\":\"auth\"}\n"
+        );
+        assert_eq!(decode::<Response>(&line).unwrap(), resp);
+    }
+
+    #[test]
+    fn test_event_roundtrip() {
+        let event = Event::State {
+            status: Status {
+                state: SessionState::Connected,
+                profile: Some("work".to_string()),
+                since: Some(1_700_000_000),
+                killswitch_mode: KillSwitchMode::Auto,
+                killswitch_state: KillSwitchState::Armed,
+                reconnect_attempt: None,
+            },
+        };
+        let line = encode(&event).unwrap();
+        assert!(line.contains("\"state\":\"connected\""));
+        assert!(!line.contains("reconnect_attempt"));
+        assert_eq!(decode::<Event>(&line).unwrap(), event);
+    }
+
+    #[test]
+    fn test_decode_rejects_unknown_command() {
+        assert!(decode::<Request>("{\"cmd\":\"reboot\"}").is_err());
+        assert!(decode::<Request>("not json").is_err());
+    }
+}
@@ -0,0 +1,1695 @@
+//! The daemon process: owns the connection and kill switch state machine.
+//!
+//! Same shape as the TUI: a single owner (`Daemon`) processes [`Input`]s
+//! from one mpsc channel. Socket clients, the scanner loop and the
+//! connect/disconnect workers each run on their own thread and only ever
+//! talk to the owner through that channel, so there is no shared state.
+
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use super::protocol::{self, Event, Request, Response, SessionState, Status};
+use crate::app::VpnProfile;
+use crate::config::{AppConfig, NetworkChangeAction};
+use crate::constants;
+use crate::core::connector::{self, LaunchOutcome};
+use crate::core::exporter::{self, ProfileStatus, SharedSnapshot, Snapshot};
+use crate::core::history::{self, EndReason, SessionTracker};
+use crate::core::hooks::{self, HookContext, HookEvent};
+use crate::core::scanner::{self, ActiveSession};
+use crate::core::telemetry::{self, TelemetryUpdate};
+use crate::state::{KillSwitchMode, KillSwitchState, ReconnectState};
+
+/// Everything the owner thread reacts to.
+enum Input {
+    /// A client request and the channel its response goes back on.
+    Request(Request, Sender<Response>),
+    /// A client subscribed; events are pushed on `events`.
+    Subscribe {
+        events: Sender<Event>,
+        reply: Sender<Response>,
+    },
+    /// A `bring_up` worker finished.
+    ConnectDone {
+        profile: String,
+        result: Result<LaunchOutcome, String>,
+    },
+    /// A `bring_down` worker finished.
+    DisconnectDone {
+        profile: String,
+        result: Result<(), String>,
+    },
+    /// Periodic scanner result.
+    Scan(Vec<ActiveSession>),
+    /// Link quality from the telemetry worker (only while exporting metrics).
+    Telemetry(TelemetryUpdate),
+    /// Log lines from a finished hook run.
+    HookOutput(Vec<String>),
+    /// A kill switch rule check finished: `Ok` lists the drift it repaired.
+    KillSwitchChecked(Result<Vec<String>, String>),
+    /// Links whose addresses or routes changed (Linux).
+    NetworkChanged(Vec<String>),
+    /// A log line from a worker.
+    Log(String),
+}
+
+/// Run the daemon until the process is killed.
+///
+/// Binds `socket_path`, optionally starts connecting to `auto_connect`, then
+/// processes requests forever.
+///
+/// # Errors
+///
+/// Returns an error if the socket cannot be bound (e.g. another daemon is running).
+pub fn run(
+    config: AppConfig,
+    socket_path: &Path,
+    auto_connect: Option<String>,
+) -> Result<(), String> {
+    let config_dir =
+        crate::utils::get_app_config_dir().map_err(|e| format!("Failed to get config dir: {e}"))?;
+    let listener = bind(socket_path)?;
+    let (tx, rx) = mpsc::channel::<Input>();
+
+    // Accept loop: one thread per client, from root or the invoking user only
+    {
+        let tx = tx.clone();
+        let allowed = allowed_peers();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                match crate::utils::peer_uid(&stream) {
+                    Ok(uid) if allowed.contains(&uid) => {}
+                    Ok(uid) => {
+                        eprintln!(
+                            "[{}] SEC: Refused daemon connection from uid {uid}",
+                            crate::utils::format_local_time()
+                        );
+                        continue;
+                    }
+                    Err(e) => {
+                        eprintln!(
+                            "[{}] SEC: Refused daemon connection: {e}",
+                            crate::utils::format_local_time()
+                        );
+                        continue;
+                    }
+                }
+                let tx = tx.clone();
+                std::thread::spawn(move || serve_client(stream, &tx));
+            }
+        });
+    }
+
+    // Scanner loop: same detection the TUI uses, on the configured tick
+    {
+        let tx = tx.clone();
+        let tick = Duration::from_millis(config.tick_rate);
+        std::thread::spawn(move || loop {
+            let profiles = crate::vpn::load_profiles();
+            if tx
+                .send(Input::Scan(scanner::get_active_profiles(&profiles)))
+                .is_err()
+            {
+                return;
+            }
+            std::thread::sleep(tick);
+        });
+    }
+
+    // Network changes, as the TUI watches them when it owns the tunnel
+    #[cfg(target_os = "linux")]
+    {
+        use crate::platform::NetworkWatcher;
+
+        let tx = tx.clone();
+        std::thread::spawn(move || {
+            let result =
+                crate::platform::linux::netwatch::RtnetlinkWatcher::watch(&mut |interfaces| {
+                    tx.send(Input::NetworkChanged(interfaces.to_vec())).is_ok()
+                });
+            if let Err(e) = result {
+                let _ = tx.send(Input::Log(format!(
+                    "WARN: Network change detection unavailable: {e}"
+                )));
+            }
+        });
+    }
+
+    // Metrics exporter, fed by its own telemetry worker (nothing else needs it)
+    let exporter = if config.metrics_listen.is_empty() {
+        None
+    } else {
+        let shared = SharedSnapshot::default();
+        let addr = exporter::serve(&config.metrics_listen, shared.clone())?;
+        eprintln!("Serving metrics on http://{addr}/metrics");
+        let (updates, nudge) =
+            telemetry::spawn_telemetry_worker(telemetry::TelemetryConfig::from(&config));
+        let tx = tx.clone();
+        std::thread::spawn(move || {
+            for update in updates {
+                if tx.send(Input::Telemetry(update)).is_err() {
+                    return;
+                }
+            }
+        });
+        Some((shared, nudge))
+    };
+
+    let mut daemon = Daemon::new(config, config_dir, tx, crate::utils::is_root());
+    if let Some((shared, nudge)) = exporter {
+        daemon.exporter = Some(shared);
+        daemon.telemetry_nudge = Some(nudge);
+    }
+    daemon.log(&format!(
+        "INIT: {} daemon v{} listening on {}",
+        constants::APP_NAME,
+        constants::APP_VERSION,
+        socket_path.display()
+    ));
+
+    if let Some(profile) = auto_connect {
+        // Nobody is waiting for this reply; the outcome shows up in the log.
+        let (reply, _) = mpsc::channel();
+        daemon.handle(Input::Request(Request::Connect { profile }, reply));
+    }
+
+    for input in rx {
+        daemon.handle(input);
+    }
+    Ok(())
+}
+
+/// Bind the control socket, replacing a stale one left by a crashed daemon.
+fn bind(socket_path: &Path) -> Result<UnixListener, String> {
+    if socket_path.exists() {
+        if UnixStream::connect(socket_path).is_ok() {
+            return Err(format!(
+                "Another daemon is already listening on {}",
+                socket_path.display()
+            ));
+        }
+        let _ = std::fs::remove_file(socket_path);
+    }
+
+    let listener = bind_private(socket_path)
+        .map_err(|e| format!("Failed to bind {}: {e}", socket_path.display()))?;
+
+    // Only root and the invoking user may talk to the daemon
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(socket_path, std::fs::Permissions::from_mode(0o600))
+            .map_err(|e| format!("Failed to restrict {}: {e}", socket_path.display()))?;
+    }
+    crate::config::fix_ownership(socket_path);
+
+    Ok(listener)
+}
+
+/// Bind with a 077 umask, so the socket is never connectable by others,
+/// not even between `bind` and the `chmod` after it.
+#[allow(unsafe_code)]
+fn bind_private(socket_path: &Path) -> std::io::Result<UnixListener> {
+    // SAFETY: umask only swaps the process file mode mask. `run` binds
+    // before starting any thread, so nothing else creates files meanwhile.
+    let previous = unsafe { libc::umask(0o077) };
+    let result = UnixListener::bind(socket_path);
+    // SAFETY: as above; restores the mask we replaced.
+    unsafe { libc::umask(previous) };
+    result
+}
+
+/// Uids allowed on the control socket: root and the user who started the
+/// daemon (through sudo, `SUDO_UID`). The daemon runs profile and hook
+/// commands as root, so nobody else may drive it.
+fn allowed_peers() -> Vec<u32> {
+    let mut uids = vec![0, crate::utils::effective_uid()];
+    if let Some(uid) = std::env::var("SUDO_UID").ok().and_then(|s| s.parse().ok()) {
+        uids.push(uid);
+    }
+    uids
+}
+
+/// Read requests from one client and write back responses (and events, once subscribed).
+fn serve_client(stream: UnixStream, tx: &Sender<Input>) {
+    let Ok(read_half) = stream.try_clone() else {
+        return;
+    };
+    let mut writer = stream;
+    let reader = BufReader::new(read_half);
+
+    for line in reader.lines() {
+        let Ok(line) = line else { return };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let (reply_tx, reply_rx) = mpsc::channel();
+        let events = match protocol::decode::<Request>(&line) {
+            Ok(Request::Subscribe) => {
+                let (events_tx, events_rx) = mpsc::channel();
+                let input = Input::Subscribe {
+                    events: events_tx,
+                    reply: reply_tx,
+                };
+                if tx.send(input).is_err() {
+                    return;
+                }
+                Some(events_rx)
+            }
+            Ok(request) => {
+                if tx.send(Input::Request(request, reply_tx)).is_err() {
+                    return;
+                }
+                None
+            }
+            Err(message) => {
+                let _ = reply_tx.send(Response::Error {
+                    code: constants::EXIT_FAILURE,
+                    message,
+                });
+                None
+            }
+        };
+
+        let Ok(response) = reply_rx.recv() else {
+            return;
+        };
+        if write_line(&mut writer, &response).is_err() {
+            return;
+        }
+
+        if let Some(events) = events {
+            // Subscribed clients only receive events from here on
+            stream_events(&mut writer, &events);
+            return;
+        }
+    }
+}
+
+fn stream_events(writer: &mut UnixStream, events: &Receiver<Event>) {
+    for event in events {
+        if write_line(writer, &event).is_err() {
+            return;
+        }
+    }
+}
+
+fn write_line<T: serde::Serialize>(writer: &mut UnixStream, msg: &T) -> std::io::Result<()> {
+    let line = protocol::encode(msg).map_err(std::io::Error::other)?;
+    writer.write_all(line.as_bytes())
+}
+
+fn unix_secs(time: SystemTime) -> Option<u64> {
+    time.duration_since(UNIX_EPOCH).ok().map(|d| d.as_secs())
+}
+
+/// The state machine. Only ever touched from the owner thread.
+#[allow(clippy::struct_excessive_bools)]
+struct Daemon {
+    config: AppConfig,
+    profiles: Vec<VpnProfile>,
+    state: SessionState,
+    profile: Option<String>,
+    since: Option<SystemTime>,
+    /// Latest scanner details for the active tunnel (interface, endpoint, pid).
+    session: Option<ActiveSession>,
+    /// Where finished sessions are recorded.
+    config_dir: PathBuf,
+    /// Figures of the session in progress, recorded when it ends.
+    tracker: Option<SessionTracker>,
+    killswitch_mode: KillSwitchMode,
+    killswitch_state: KillSwitchState,
+    /// Last time the installed kill switch rules were verified.
+    killswitch_verified_at: Option<Instant>,
+    /// A rule check is running on a worker.
+    killswitch_checking: bool,
+    is_root: bool,
+    /// Snapshot read by the metrics exporter (`None` = not serving).
+    exporter: Option<SharedSnapshot>,
+    /// Throughput and link quality reported to the exporter; the connection
+    /// and kill switch fields are filled in when publishing.
+    exported: Snapshot,
+    /// Last tunnel counter reading, for the throughput rate.
+    last_bytes: Option<(u64, u64, Instant)>,
+    /// Wakes the exporter's telemetry worker after the route changed.
+    telemetry_nudge: Option<Sender<()>>,
+    /// `post_up` waits for the scanner to report the tunnel's interface.
+    post_up_pending: bool,
+    /// Tunnel details captured before a requested disconnect, for `post_down`.
+    down_hook: Option<HookContext>,
+    /// A requested disconnect reported failure; if the tunnel vanishes before
+    /// a scan sees it up again, that was the user's disconnect, not a drop.
+    manual_down: bool,
+    /// Automatic reconnect after a drop (`None` = not reconnecting).
+    reconnect: Option<ReconnectState>,
+    subscribers: Vec<Sender<Event>>,
+    /// Clients waiting for the in-flight connect/disconnect to finish.
+    waiters: Vec<Sender<Response>>,
+    /// Handed to worker threads so they can report back.
+    tx: Sender<Input>,
+}
+
+impl Daemon {
+    fn new(config: AppConfig, config_dir: PathBuf, tx: Sender<Input>, is_root: bool) -> Self {
+        let mut daemon = Self {
+            config,
+            profiles: crate::vpn::load_profiles(),
+            state: SessionState::Disconnected,
+            profile: None,
+            since: None,
+            session: None,
+            config_dir,
+            tracker: None,
+            killswitch_mode: KillSwitchMode::default(),
+            killswitch_state: KillSwitchState::default(),
+            killswitch_verified_at: None,
+            killswitch_checking: false,
+            is_root,
+            exporter: None,
+            exported: Snapshot::default(),
+            last_bytes: None,
+            telemetry_nudge: None,
+            post_up_pending: false,
+            down_hook: None,
+            manual_down: false,
+            reconnect: None,
+            subscribers: Vec::new(),
+            waiters: Vec::new(),
+            tx,
+        };
+
+        // Keep the mode from the previous run; the first scan decides the state.
+        if let Some(persisted) = crate::core::killswitch::load_state() {
+            daemon.killswitch_mode = persisted.mode;
+            daemon.killswitch_state = persisted.state;
+        }
+
+        daemon
+    }
+
+    fn status(&self) -> Status {
+        Status {
+            state: self.state,
+            profile: self.profile.clone(),
+            since: self.since.and_then(unix_secs),
+            killswitch_mode: self.killswitch_mode,
+            killswitch_state: self.killswitch_state,
+            reconnect_attempt: self.reconnect.as_ref().map(|rc| rc.attempt),
+        }
+    }
+
+    /// Log to stderr (journald picks it up) and to subscribers.
+    fn log(&mut self, message: &str) {
+        eprintln!("[{}] {message}", crate::utils::format_local_time());
+        self.broadcast(&Event::Log {
+            message: message.to_string(),
+        });
+    }
+
+    fn broadcast(&mut self, event: &Event) {
+        // Drop subscribers whose client went away
+        self.subscribers.retain(|s| s.send(event.clone()).is_ok());
+    }
+
+    fn set_state(&mut self, state: SessionState, profile: Option<String>) {
+        self.state = state;
+        self.profile = profile;
+        self.since = Some(SystemTime::now());
+        if matches!(state, SessionState::Connecting | SessionState::Disconnected) {
+            self.manual_down = false;
+        }
+        if state != SessionState::Connected {
+            self.session = None;
+            self.last_bytes = None;
+            self.exported.rx_rate = 0;
+            self.exported.tx_rate = 0;
+        }
+        if matches!(state, SessionState::Connected | SessionState::Disconnected) {
+            // The previous measurements described a different path
+            self.exported.dns_leak = None;
+            if let Some(nudge) = &self.telemetry_nudge {
+                let _ = nudge.send(());
+            }
+        }
+        self.track_session();
+        self.sync_killswitch();
+        let status = self.status();
+        self.broadcast(&Event::State { status });
+    }
+
+    fn reply_waiters(&mut self, response: &Response) {
+        for waiter in self.waiters.drain(..) {
+            let _ = waiter.send(response.clone());
+        }
+    }
+
+    /// Start or advance the session tracker after a state change. Ending a
+    /// session is explicit ([`Self::end_session`]) since only the caller
+    /// knows why it ended.
+    fn track_session(&mut self) {
+        let Some(name) = self.profile.clone() else {
+            return;
+        };
+        let tracked = self.tracker.as_ref().is_some_and(|t| t.profile() == name);
+        match self.state {
+            SessionState::Connecting => {
+                let protocol = self
+                    .profiles
+                    .iter()
+                    .find(|p| p.name == name)
+                    .map(|p| p.protocol)
+                    .unwrap_or_default();
+                self.tracker = Some(SessionTracker::new(&name, protocol, SystemTime::now()));
+            }
+            SessionState::Connected => {
+                let started_at = self
+                    .session
+                    .as_ref()
+                    .and_then(|s| s.started_at)
+                    .unwrap_or_else(SystemTime::now);
+                if let Some(tracker) = self.tracker.as_mut().filter(|_| tracked) {
+                    tracker.connected_at(started_at);
+                } else {
+                    // Adopted: the tunnel came up without us
+                    let protocol = self
+                        .profiles
+                        .iter()
+                        .find(|p| p.name == name)
+                        .map(|p| p.protocol)
+                        .unwrap_or_default();
+                    self.tracker = Some(SessionTracker::new(&name, protocol, started_at));
+                }
+            }
+            SessionState::Disconnecting | SessionState::Disconnected => {}
+        }
+    }
+
+    /// Update the exported throughput from a tunnel counter reading.
+    fn record_rate(&mut self, rx: u64, tx: u64) {
+        let now = Instant::now();
+        if let Some((last_rx, last_tx, at)) = self.last_bytes {
+            let secs = now.duration_since(at).as_secs_f64();
+            if secs > 0.0 {
+                #[allow(
+                    clippy::cast_possible_truncation,
+                    clippy::cast_sign_loss,
+                    clippy::cast_precision_loss
+                )]
+                let rate = |delta: u64| (delta as f64 / secs) as u64;
+                self.exported.rx_rate = rate(rx.saturating_sub(last_rx));
+                self.exported.tx_rate = rate(tx.saturating_sub(last_tx));
+            }
+        }
+        self.last_bytes = Some((rx, tx, now));
+    }
+
+    /// Record the tracked session as ended for `reason`.
+    fn end_session(&mut self, reason: EndReason, error: &str) {
+        let Some(tracker) = self.tracker.take() else {
+            return;
+        };
+        if let Err(e) = history::append(&self.config_dir, &tracker.finish(reason, error)) {
+            self.log(&format!("WARN: {e}"));
+        }
+    }
+
+    fn handle(&mut self, input: Input) {
+        match input {
+            Input::Request(request, reply) => self.handle_request(request, reply),
+            Input::Subscribe { events, reply } => {
+                let _ = reply.send(Response::Ok {
+                    status: self.status(),
+                });
+                self.subscribers.push(events);
+            }
+            Input::ConnectDone { profile, result } => self.on_connect_done(&profile, result),
+            Input::DisconnectDone { profile, result } => self.on_disconnect_done(&profile, result),
+            Input::Scan(active) => self.on_scan(&active),
+            Input::Telemetry(update) => self.on_telemetry(update),
+            Input::HookOutput(lines) => {
+                for line in lines {
+                    self.log(&line);
+                }
+            }
+            Input::KillSwitchChecked(result) => self.on_killswitch_checked(result),
+            Input::NetworkChanged(interfaces) => self.on_network_change(&interfaces),
+            Input::Log(line) => self.log(&line),
+        }
+        self.publish_snapshot();
+    }
+
+    /// Keep the exported link quality current and, while connected, feed
+    /// the session record the same figures the TUI would.
+    fn on_telemetry(&mut self, update: TelemetryUpdate) {
+        let exported = &mut self.exported;
+        let tracker = self
+            .tracker
+            .as_mut()
+            .filter(|_| self.state == SessionState::Connected);
+        match update {
+            TelemetryUpdate::Latency(ms) => {
+                exported.latency_ms = (ms > 0).then_some(ms);
+                if let (Some(tracker), true) = (tracker, ms > 0) {
+                    tracker.record_latency(ms);
+                }
+            }
+            TelemetryUpdate::PacketLoss(pct) => {
+                exported.loss_pct = Some(pct);
+                if let Some(tracker) = tracker {
+                    tracker.record_loss(pct);
+                }
+            }
+            TelemetryUpdate::PublicIp(ip) => {
+                if let Some(tracker) = tracker {
+                    tracker.record_exit_ip(&ip);
+                }
+            }
+            TelemetryUpdate::Location(location) => {
+                if let Some(tracker) = tracker {
+                    tracker.record_location(&location);
+                }
+            }
+            TelemetryUpdate::Jitter(ms) => exported.jitter_ms = Some(ms),
+            TelemetryUpdate::Ipv6Leak(leak) => exported.ipv6_leak = Some(leak),
+            TelemetryUpdate::DnsLeakTest(report) => exported.dns_leak = Some(report.is_leaking()),
+            _ => {}
+        }
+    }
+
+    /// Hand the exporter the current state.
+    fn publish_snapshot(&self) {
+        let Some(shared) = &self.exporter else {
+            return;
+        };
+        let connected = self.state == SessionState::Connected;
+        let snapshot = Snapshot {
+            state: self.state,
+            profiles: self
+                .profiles
+                .iter()
+                .map(|p| ProfileStatus {
+                    name: p.name.clone(),
+                    protocol: p.protocol,
+                    up: connected && self.profile.as_deref() == Some(p.name.as_str()),
+                })
+                .collect(),
+            handshake_age_secs: self
+                .session
+                .as_ref()
+                .and_then(|s| crate::vpn::wireguard::parse_handshake_age(&s.latest_handshake)),
+            killswitch_mode: self.killswitch_mode,
+            killswitch_state: self.killswitch_state,
+            ..self.exported.clone()
+        };
+        if let Ok(mut current) = shared.lock() {
+            *current = snapshot;
+        }
+    }
+
+    fn handle_request(&mut self, request: Request, reply: Sender<Response>) {
+        match request {
+            Request::Status | Request::Subscribe => {
+                let _ = reply.send(Response::Ok {
+                    status: self.status(),
+                });
+            }
+            Request::Connect { profile } => {
+                self.cancel_reconnect();
+                self.request_connect(&profile, reply);
+            }
+            Request::Disconnect => {
+                self.cancel_reconnect();
+                self.request_disconnect(reply);
+            }
+            Request::KillSwitch { mode } => {
+                if mode != self.killswitch_mode {
+                    self.killswitch_mode = mode;
+                    self.log(&format!("SEC: Kill switch mode set to {mode:?}"));
+                }
+                self.sync_killswitch();
+                let status = self.status();
+                self.broadcast(&Event::State {
+                    status: status.clone(),
+                });
+                let _ = reply.send(Response::Ok { status });
+            }
+        }
+    }
+
+    fn request_connect(&mut self, name: &str, reply: Sender<Response>) {
+        let fail = |code: i32, message: String| {
+            let _ = reply.send(Response::Error { code, message });
+        };
+
+        match self.state {
+            SessionState::Connected if self.profile.as_deref() == Some(name) => {
+                let _ = reply.send(Response::Ok {
+                    status: self.status(),
+                });
+                return;
+            }
+            SessionState::Disconnected => {}
+            _ => {
+                let current = self.profile.clone().unwrap_or_default();
+                return fail(
+                    constants::EXIT_FAILURE,
+                    format!("Busy with '{current}'; disconnect first"),
+                );
+            }
+        }
+
+        // Pick up profiles imported since the daemon started
+        self.profiles = crate::vpn::load_profiles();
+        let Some(profile) = self.profiles.iter().find(|p| p.name == name).cloned() else {
+            return fail(
+                constants::EXIT_FAILURE,
+                format!("{}{name}", constants::CLI_MSG_PROFILE_NOT_FOUND),
+            );
+        };
+
+        let missing = connector::check_dependencies(profile.protocol);
+        if !missing.is_empty() {
+            return fail(
+                constants::EXIT_MISSING_DEPENDENCY,
+                format!("Missing system tools: {}", missing.join(", ")),
+            );
+        }
+        if !self.is_root {
+            return fail(
+                constants::EXIT_PERMISSION_DENIED,
+                "The daemon is not running as root".to_string(),
+            );
+        }
+        if connector::needs_auth_prompt(&profile.name, profile.protocol, &profile.config_path) {
+            return fail(
+                constants::EXIT_AUTH_REQUIRED,
+                format!("'{name}' needs saved credentials"),
+            );
+        }
+
+        self.log(&format!(
+            "ACTION: Connecting to '{}' [{}]...",
+            profile.name, profile.protocol
+        ));
+        self.waiters.push(reply);
+        self.set_state(SessionState::Connecting, Some(profile.name.clone()));
+
+        let tx = self.tx.clone();
+        let timeout = self.config.connect_timeout;
+        let verbosity = self.config.openvpn_verbosity.clone();
+        let hooks = self.config.hooks.clone();
+        std::thread::spawn(move || {
+            let ctx = HookContext::new(&profile.name, "connect");
+            let lines = hooks::run(&hooks, HookEvent::PreUp, &ctx);
+            if !lines.is_empty() {
+                let _ = tx.send(Input::HookOutput(lines));
+            }
+            let result = connector::bring_up(
+                &profile.name,
+                profile.protocol,
+                &profile.config_path,
+                timeout,
+                &verbosity,
+                // Nobody to ask; saved credentials are checked above
+                &|_| None,
+            );
+            let _ = tx.send(Input::ConnectDone {
+                profile: profile.name,
+                result,
+            });
+        });
+    }
+
+    fn request_disconnect(&mut self, reply: Sender<Response>) {
+        let target = match self.state {
+            SessionState::Disconnected => {
+                let _ = reply.send(Response::Ok {
+                    status: self.status(),
+                });
+                return;
+            }
+            SessionState::Disconnecting => {
+                // Already on its way down; answer when it gets there
+                self.waiters.push(reply);
+                return;
+            }
+            SessionState::Connecting | SessionState::Connected => self
+                .profile
+                .as_ref()
+                .and_then(|name| self.profiles.iter().find(|p| p.name == *name))
+                .cloned(),
+        };
+
+        let Some(profile) = target else {
+            let _ = reply.send(Response::Error {
+                code: constants::EXIT_FAILURE,
+                message: "Active profile is no longer on disk".to_string(),
+            });
+            return;
+        };
+
+        let pid = self.session.as_ref().and_then(|s| s.pid);
+        let ctx = self
+            .hook_context("user")
+            .unwrap_or_else(|| HookContext::new(&profile.name, "user"));
+        self.down_hook = Some(ctx.clone());
+        self.post_up_pending = false;
+        self.log(&format!("ACTION: Disconnecting from '{}'...", profile.name));
+        // A manual disconnect must not trip the Auto kill switch, even if
+        // the tool reports failure and the tunnel only disappears later
+        self.manual_down = true;
+        self.waiters.push(reply);
+        self.set_state(SessionState::Disconnecting, Some(profile.name.clone()));
+
+        let tx = self.tx.clone();
+        let hooks = self.config.hooks.clone();
+        std::thread::spawn(move || {
+            let lines = hooks::run(&hooks, HookEvent::PreDown, &ctx);
+            if !lines.is_empty() {
+                let _ = tx.send(Input::HookOutput(lines));
+            }
+            let result =
+                connector::bring_down(&profile.name, profile.protocol, &profile.config_path, pid);
+            let _ = tx.send(Input::DisconnectDone {
+                profile: profile.name,
+                result,
+            });
+        });
+    }
+
+    /// What the hooks are told about the current tunnel (`None` when idle).
+    fn hook_context(&self, reason: &str) -> Option<HookContext> {
+        let profile = self.profile.as_deref()?;
+        Some(match &self.session {
+            Some(session) => HookContext::from_session(session, reason),
+            None => HookContext::new(profile, reason),
+        })
+    }
+
+    /// Run the `event` hooks on a worker; their output comes back as `HookOutput`.
+    fn spawn_hooks(&self, event: HookEvent, ctx: HookContext) {
+        if !hooks::has_hooks(&self.config.hooks, event, &ctx.profile) {
+            return;
+        }
+        let config = self.config.hooks.clone();
+        let tx = self.tx.clone();
+        std::thread::spawn(move || {
+            let _ = tx.send(Input::HookOutput(hooks::run(&config, event, &ctx)));
+        });
+    }
+
+    fn run_post_down(&mut self, name: &str) {
+        let ctx = self
+            .down_hook
+            .take()
+            .unwrap_or_else(|| HookContext::new(name, "user"));
+        self.spawn_hooks(HookEvent::PostDown, ctx);
+    }
+
+    fn on_connect_done(&mut self, name: &str, result: Result<LaunchOutcome, String>) {
+        // Ignore stale results (e.g. a disconnect raced the connect worker)
+        if self.state != SessionState::Connecting || self.profile.as_deref() != Some(name) {
+            return;
+        }
+
+        match result {
+            Ok(outcome) => {
+                if outcome == LaunchOutcome::Unconfirmed {
+                    self.log(&format!(
+                        "WARN: OpenVPN log confirmation timed out for '{name}' \
+                         — scanner will confirm tunnel status"
+                    ));
+                }
+                self.log(&format!("STATUS: Connected to '{name}'"));
+                self.set_state(SessionState::Connected, Some(name.to_string()));
+                // The next scan fills in the interface for post_up
+                self.post_up_pending = true;
+                let status = self.status();
+                self.reply_waiters(&Response::Ok { status });
+            }
+            Err(e) => {
+                if let Some(p) = self.profiles.iter().find(|p| p.name == name) {
+                    connector::cleanup(&p.name, p.protocol, &p.config_path);
+                }
+                self.log(&format!("ERR: Failed to connect '{name}': {e}"));
+                self.end_session(EndReason::Error, &e);
+                self.set_state(SessionState::Disconnected, None);
+                self.reply_waiters(&Response::Error {
+                    code: connector::exit_code_for_error(&e),
+                    message: e,
+                });
+            }
+        }
+    }
+
+    fn on_disconnect_done(&mut self, name: &str, result: Result<(), String>) {
+        if self.state != SessionState::Disconnecting || self.profile.as_deref() != Some(name) {
+            return;
+        }
+
+        match result {
+            Ok(()) => {
+                self.log(&format!("STATUS: Disconnected from '{name}'"));
+                self.end_session(EndReason::User, "");
+                self.run_post_down(name);
+                self.set_state(SessionState::Disconnected, None);
+                let status = self.status();
+                self.reply_waiters(&Response::Ok { status });
+            }
+            Err(e) => {
+                // Assume it is still up; the next scan corrects us if not
+                self.log(&format!("ERR: Failed to disconnect '{name}': {}", e.trim()));
+                self.set_state(SessionState::Connected, Some(name.to_string()));
+                self.reply_waiters(&Response::Error {
+                    code: constants::EXIT_FAILURE,
+                    message: e,
+                });
+            }
+        }
+    }
+
+    fn on_scan(&mut self, active: &[ActiveSession]) {
+        let current = self
+            .profile
+            .as_ref()
+            .and_then(|name| active.iter().find(|s| s.name == *name))
+            .cloned();
+
+        match (self.state, current) {
+            (SessionState::Connecting, Some(session)) => {
+                // The scanner saw the tunnel before the worker reported back
+                let name = session.name.clone();
+                self.session = Some(session);
+                self.log(&format!("STATUS: Connection established to '{name}'"));
+                self.set_state(SessionState::Connected, Some(name));
+                if let Some(ctx) = self.hook_context("connect") {
+                    self.spawn_hooks(HookEvent::PostUp, ctx);
+                }
+                let status = self.status();
+                self.reply_waiters(&Response::Ok { status });
+            }
+            (SessionState::Connected, Some(session)) => {
+                if let Some(&(rx, tx)) = crate::platform::interface_bytes().get(&session.interface)
+                {
+                    if let Some(tracker) = &mut self.tracker {
+                        tracker.record_bytes(rx, tx);
+                    }
+                    self.record_rate(rx, tx);
+                }
+                self.session = Some(session);
+                // Still up: the failed disconnect really failed
+                self.manual_down = false;
+                if std::mem::take(&mut self.post_up_pending) {
+                    if let Some(ctx) = self.hook_context("connect") {
+                        self.spawn_hooks(HookEvent::PostUp, ctx);
+                    }
+                }
+            }
+            (SessionState::Connected, None) if self.manual_down => {
+                // The disconnect that reported failure went through after all
+                let name = self.profile.clone().unwrap_or_default();
+                self.log(&format!("STATUS: Disconnected from '{name}'"));
+                self.end_session(EndReason::User, "");
+                self.run_post_down(&name);
+                self.set_state(SessionState::Disconnected, None);
+            }
+            (SessionState::Connected, None) => {
+                let name = self.profile.clone().unwrap_or_default();
+                self.log(&format!("WARN: Connection dropped from '{name}'"));
+                if self.killswitch_mode != KillSwitchMode::Off
+                    && self.killswitch_state == KillSwitchState::Armed
+                {
+                    self.killswitch_state = KillSwitchState::Blocking;
+                    self.log("SEC: Kill switch ACTIVATED - blocking traffic");
+                }
+                crate::utils::cleanup_openvpn_run_files(&name);
+                self.post_up_pending = false;
+                if let Some(ctx) = self.hook_context("drop") {
+                    self.spawn_hooks(HookEvent::OnDrop, ctx);
+                }
+                self.end_session(EndReason::Drop, "");
+                // Before the state change, so the kill switch keeps the server reachable
+                if self.config.auto_reconnect && self.config.reconnect_max_attempts > 0 {
+                    self.schedule_reconnect(&name);
+                }
+                self.set_state(SessionState::Disconnected, None);
+            }
+            (SessionState::Disconnecting, None) => {
+                let name = self.profile.clone().unwrap_or_default();
+                self.log(&format!("STATUS: Disconnected from '{name}'"));
+                self.end_session(EndReason::User, "");
+                self.run_post_down(&name);
+                self.set_state(SessionState::Disconnected, None);
+                let status = self.status();
+                self.reply_waiters(&Response::Ok { status });
+            }
+            (SessionState::Disconnected, None) => {
+                // A tunnel brought up before the daemon started (or by `vortix connect`)
+                if let Some(session) = active.first().cloned() {
+                    let name = session.name.clone();
+                    self.session = Some(session);
+                    self.log(&format!("STATUS: Adopted running session '{name}'"));
+                    self.set_state(SessionState::Connected, Some(name));
+                }
+            }
+            // Connecting without a tunnel yet, or Disconnecting with the
+            // tunnel still up: wait for the worker to report.
+            _ => {}
+        }
+        self.supervise_reconnect();
+        self.verify_killswitch();
+    }
+
+    /// Mirror of `App::on_network_change`: refresh telemetry, hurry a
+    /// pending reconnect and apply `network_change_action` to the tunnel.
+    fn on_network_change(&mut self, interfaces: &[String]) {
+        let settle = Duration::from_secs(constants::NETWORK_CHANGE_SETTLE_SECS);
+        let tunnel = match self.state {
+            // Our own tunnel coming up or going down is not a network change
+            SessionState::Connecting | SessionState::Disconnecting => return,
+            // The VPN tool is still installing its own routes and rules
+            SessionState::Connected
+                if self
+                    .since
+                    .and_then(|t| t.elapsed().ok())
+                    .is_some_and(|age| age < settle) =>
+            {
+                return;
+            }
+            SessionState::Connected => self.session.as_ref().map(|s| s.interface.clone()),
+            SessionState::Disconnected => None,
+        };
+        let changed: Vec<&str> = interfaces
+            .iter()
+            .map(String::as_str)
+            .filter(|i| Some(*i) != tunnel.as_deref())
+            .collect();
+        // Empty means the watcher could not name the link (e.g. it was removed)
+        if changed.is_empty() && !interfaces.is_empty() {
+            return;
+        }
+
+        let what = if changed.is_empty() {
+            "link removed".to_string()
+        } else {
+            changed.join(", ")
+        };
+        self.log(&format!("NET: Network change detected ({what})"));
+        if let Some(nudge) = &self.telemetry_nudge {
+            let _ = nudge.send(());
+        }
+
+        // The network may be back: don't sit out the rest of the backoff
+        if let Some(rc) = &mut self.reconnect {
+            if rc.next_at.is_some() {
+                rc.next_at = Some(Instant::now());
+            }
+            self.supervise_reconnect();
+        }
+
+        if self.state != SessionState::Connected {
+            return;
+        }
+        let Some(profile) = self
+            .profile
+            .as_ref()
+            .and_then(|name| self.profiles.iter().find(|p| p.name == *name))
+            .cloned()
+        else {
+            return;
+        };
+        match self.config.network_change_action {
+            NetworkChangeAction::None => {}
+            NetworkChangeAction::Rehandshake => {
+                let interface = tunnel.unwrap_or_default();
+                let pid = self.session.as_ref().and_then(|s| s.pid);
+                let tx = self.tx.clone();
+                self.log(&format!(
+                    "ACTION: Re-handshaking '{}' on the new network",
+                    profile.name
+                ));
+                std::thread::spawn(move || {
+                    let name = profile.name;
+                    let msg = match connector::rehandshake(&name, profile.protocol, &interface, pid)
+                    {
+                        Ok(()) => format!("NET: Re-handshake sent for '{name}'"),
+                        Err(e) => format!("WARN: Re-handshake failed for '{name}': {e}"),
+                    };
+                    let _ = tx.send(Input::Log(msg));
+                });
+            }
+            NetworkChangeAction::Reconnect => {
+                self.log(&format!(
+                    "ACTION: Reconnecting '{}' on the new network",
+                    profile.name
+                ));
+                // Brought back up by the reconnect supervisor once it is down,
+                // with its retries if the new network is not ready yet
+                let (interface, server_ip) = self.killswitch_target();
+                self.reconnect = Some(ReconnectState {
+                    profile: profile.name,
+                    attempt: 1,
+                    next_at: Some(Instant::now()),
+                    interface,
+                    server_ip: server_ip.filter(|ip| !ip.is_empty()),
+                });
+                let (reply, _) = mpsc::channel();
+                self.request_disconnect(reply);
+            }
+        }
+    }
+
+    /// Start reconnecting to `name`, which just dropped.
+    fn schedule_reconnect(&mut self, name: &str) {
+        let (interface, server_ip) = self.killswitch_target();
+        let delay = self.reconnect_delay(1);
+        self.reconnect = Some(ReconnectState {
+            profile: name.to_string(),
+            attempt: 1,
+            next_at: Some(Instant::now() + delay),
+            interface,
+            server_ip: server_ip.filter(|ip| !ip.is_empty()),
+        });
+        self.log(&format!(
+            "ACTION: Reconnecting to '{name}' in {}s (attempt 1/{})",
+            delay.as_secs(),
+            self.config.reconnect_max_attempts
+        ));
+    }
+
+    /// Mirror of `App::reconnect_delay`.
+    fn reconnect_delay(&self, attempt: u32) -> Duration {
+        let entropy = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| u64::from(d.subsec_nanos()));
+        crate::state::backoff_delay(
+            attempt,
+            Duration::from_secs(self.config.reconnect_base_delay),
+            Duration::from_secs(self.config.reconnect_max_delay),
+            self.config.reconnect_jitter,
+            entropy,
+        )
+    }
+
+    /// Mirror of `App::supervise_reconnect` (driven by the scanner tick):
+    /// fire due attempts, and judge the one in flight by the state it left.
+    fn supervise_reconnect(&mut self) {
+        let Some(rc) = &self.reconnect else {
+            return;
+        };
+        match self.state {
+            SessionState::Connected => {
+                let (name, attempt) = (rc.profile.clone(), rc.attempt);
+                self.reconnect = None;
+                self.log(&format!(
+                    "STATUS: Reconnected to '{name}' (attempt {attempt})"
+                ));
+                self.publish_status();
+            }
+            SessionState::Disconnected => match rc.next_at {
+                None => self.reconnect_failed(),
+                Some(at) if Instant::now() >= at => self.reconnect_attempt(),
+                Some(_) => {}
+            },
+            SessionState::Connecting | SessionState::Disconnecting => {}
+        }
+    }
+
+    /// Fire the current reconnect attempt through the regular connect path.
+    fn reconnect_attempt(&mut self) {
+        let Some(rc) = &mut self.reconnect else {
+            return;
+        };
+        rc.next_at = None;
+        let (name, attempt) = (rc.profile.clone(), rc.attempt);
+        self.log(&format!(
+            "ACTION: Reconnect attempt {attempt}/{} for '{name}'",
+            self.config.reconnect_max_attempts
+        ));
+
+        let (reply, answer) = mpsc::channel();
+        self.request_connect(&name, reply);
+        // Refused up front (profile gone, credentials needed, ...): retrying won't help
+        if let Ok(Response::Error { message, .. }) = answer.try_recv() {
+            self.reconnect = None;
+            self.log(&format!("WARN: Reconnect stopped: {message}"));
+            self.sync_killswitch();
+            self.publish_status();
+        }
+    }
+
+    /// The attempt in flight failed: schedule the next one or give up.
+    fn reconnect_failed(&mut self) {
+        let Some(rc) = &self.reconnect else {
+            return;
+        };
+        let (name, attempt) = (rc.profile.clone(), rc.attempt);
+        let max = self.config.reconnect_max_attempts;
+
+        if attempt >= max {
+            self.reconnect = None;
+            self.log(&format!(
+                "ERR: Giving up reconnecting to '{name}' after {attempt} attempts"
+            ));
+            self.sync_killswitch();
+            self.publish_status();
+            return;
+        }
+
+        let delay = self.reconnect_delay(attempt + 1);
+        if let Some(rc) = &mut self.reconnect {
+            rc.attempt += 1;
+            rc.next_at = Some(Instant::now() + delay);
+        }
+        self.log(&format!(
+            "ACTION: Retrying '{name}' in {}s (attempt {}/{max})",
+            delay.as_secs(),
+            attempt + 1
+        ));
+        self.publish_status();
+    }
+
+    /// A client took over: drop the pending reconnect.
+    fn cancel_reconnect(&mut self) {
+        let Some(rc) = self.reconnect.take() else {
+            return;
+        };
+        self.log(&format!("ACTION: Reconnect to '{}' cancelled", rc.profile));
+        // The rules no longer need to let the old server through
+        self.sync_killswitch();
+        self.publish_status();
+    }
+
+    /// Tell subscribers about a change that did not go through `set_state`.
+    fn publish_status(&mut self) {
+        let status = self.status();
+        self.broadcast(&Event::State { status });
+    }
+
+    /// VPN interface and server IP the kill switch rules are built for.
+    fn killswitch_target(&self) -> (String, Option<String>) {
+        match (&self.session, &self.reconnect) {
+            (Some(s), _) => (
+                s.interface.clone(),
+                Some(crate::utils::endpoint_host(&s.endpoint).to_string()),
+            ),
+            // Keep the dropped server reachable so reconnect attempts get through
+            (None, Some(rc)) => (rc.interface.clone(), rc.server_ip.clone()),
+            (None, None) => (crate::platform::DEFAULT_VPN_INTERFACE.to_string(), None),
+        }
+    }
+
+    /// Mirror of `App::verify_killswitch`: re-apply the rules if something
+    /// removed or changed them. The check runs on a worker so a slow
+    /// `iptables`/`nft` does not hold up requests; it reports back as
+    /// `Input::KillSwitchChecked`.
+    fn verify_killswitch(&mut self) {
+        if self.killswitch_checking || !self.is_root || !self.killswitch_state.is_blocking() {
+            return;
+        }
+        let interval = Duration::from_secs(constants::KILLSWITCH_VERIFY_INTERVAL_SECS);
+        if self
+            .killswitch_verified_at
+            .is_some_and(|at| at.elapsed() < interval)
+        {
+            return;
+        }
+
+        let (interface, server_ip) = self.killswitch_target();
+        let config = self.config.killswitch.clone();
+        let tx = self.tx.clone();
+        self.killswitch_checking = true;
+        std::thread::spawn(move || {
+            let result =
+                crate::core::killswitch::repair_blocking(&interface, server_ip.as_deref(), &config)
+                    .map_err(|e| e.to_string());
+            let _ = tx.send(Input::KillSwitchChecked(result));
+        });
+    }
+
+    /// Mirror of `App::on_killswitch_checked`.
+    fn on_killswitch_checked(&mut self, result: Result<Vec<String>, String>) {
+        self.killswitch_checking = false;
+        if !self.killswitch_state.is_blocking() {
+            // Released while the check ran; undo anything it re-applied
+            if matches!(&result, Ok(drift) if !drift.is_empty()) {
+                if let Err(e) = crate::core::killswitch::disable_blocking() {
+                    self.log(&format!("WARN: Failed to release kill switch: {e}"));
+                }
+            }
+            return;
+        }
+        match result {
+            Ok(drift) => {
+                self.killswitch_verified_at = Some(Instant::now());
+                if !drift.is_empty() {
+                    self.log(&format!(
+                        "ERR: Kill switch rules were modified externally ({}); re-applied",
+                        drift.join("; ")
+                    ));
+                }
+            }
+            Err(e) => self.log(&format!(
+                "WARN: Kill switch rules could not be verified: {e}"
+            )),
+        }
+    }
+
+    /// Mirror of `App::sync_killswitch`: compute the target state and apply it.
+    fn sync_killswitch(&mut self) {
+        let old_state = self.killswitch_state;
+        self.killswitch_state = KillSwitchState::target(
+            self.killswitch_mode,
+            self.state == SessionState::Connected,
+            old_state,
+        );
+
+        if self.killswitch_state.is_blocking() {
+            let (interface, server_ip) = self.killswitch_target();
+            if self.is_root {
+                if let Err(e) = crate::core::killswitch::enable_blocking(
+                    &interface,
+                    server_ip.as_deref(),
+                    &self.config.killswitch,
+                ) {
+                    self.log(&format!("WARN: Failed to enable kill switch: {e}"));
+                }
+                self.killswitch_verified_at = Some(Instant::now());
+            }
+        } else if old_state.is_blocking() && self.is_root {
+            if let Err(e) = crate::core::killswitch::disable_blocking() {
+                self.log(&format!("WARN: Failed to release kill switch: {e}"));
+            }
+        }
+
+        let _ = crate::core::killswitch::save_state(
+            self.killswitch_mode,
+            self.killswitch_state,
+            None,
+            None,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_daemon() -> (Daemon, Receiver<Input>) {
+        let (tx, rx) = mpsc::channel();
+        let daemon = Daemon {
+            config: AppConfig::default(),
+            profiles: Vec::new(),
+            state: SessionState::Disconnected,
+            profile: None,
+            since: None,
+            session: None,
+            config_dir: std::env::temp_dir().join("vortix_daemon_test"),
+            tracker: None,
+            killswitch_mode: KillSwitchMode::Off,
+            killswitch_state: KillSwitchState::Disabled,
+            killswitch_verified_at: None,
+            killswitch_checking: false,
+            is_root: false,
+            exporter: None,
+            exported: Snapshot::default(),
+            last_bytes: None,
+            telemetry_nudge: None,
+            post_up_pending: false,
+            down_hook: None,
+            manual_down: false,
+            reconnect: None,
+            subscribers: Vec::new(),
+            waiters: Vec::new(),
+            tx,
+        };
+        (daemon, rx)
+    }
+
+    fn session(name: &str) -> ActiveSession {
+        ActiveSession {
+            name: name.to_string(),
+            interface: "wg0".to_string(),
+            endpoint: "203.0.113.1:51820".to_string(),
+            ..ActiveSession::default()
+        }
+    }
+
+    /// Helper: a connected daemon past the network change settle window.
+    fn settled(daemon: &mut Daemon, name: &str) {
+        daemon.profiles = vec![VpnProfile {
+            name: name.to_string(),
+            protocol: crate::state::Protocol::WireGuard,
+            location: String::new(),
+            config_path: PathBuf::from(format!("/nonexistent/vortix-test/{name}.conf")),
+            last_used: None,
+            labels: crate::state::ProfileLabels::default(),
+        }];
+        daemon.on_scan(&[session(name)]);
+        daemon.since = Some(
+            SystemTime::now() - Duration::from_secs(constants::NETWORK_CHANGE_SETTLE_SECS + 1),
+        );
+    }
+
+    #[test]
+    fn test_network_change_reconnect_action() {
+        let (mut daemon, _rx) = test_daemon();
+        daemon.config.network_change_action = NetworkChangeAction::Reconnect;
+        settled(&mut daemon, "work");
+
+        // Our own tunnel is not a network change
+        daemon.handle(Input::NetworkChanged(vec!["wg0".to_string()]));
+        assert_eq!(daemon.state, SessionState::Connected);
+
+        daemon.handle(Input::NetworkChanged(vec!["wlan0".to_string()]));
+        assert_eq!(daemon.state, SessionState::Disconnecting);
+        let rc = daemon.reconnect.as_ref().unwrap();
+        assert_eq!(rc.profile, "work");
+        assert!(rc.next_at.is_some());
+    }
+
+    #[test]
+    fn test_network_change_ignored_right_after_connect() {
+        let (mut daemon, _rx) = test_daemon();
+        daemon.config.network_change_action = NetworkChangeAction::Reconnect;
+        settled(&mut daemon, "work");
+        daemon.since = Some(SystemTime::now());
+
+        daemon.handle(Input::NetworkChanged(vec!["eth0".to_string()]));
+        assert_eq!(daemon.state, SessionState::Connected);
+        assert!(daemon.reconnect.is_none());
+    }
+
+    #[test]
+    fn test_network_change_hurries_pending_reconnect() {
+        let (mut daemon, _rx) = test_daemon();
+        daemon.config.reconnect_base_delay = 60;
+        daemon.on_scan(&[session("work")]);
+        daemon.on_scan(&[]);
+        let next_at = daemon.reconnect.as_ref().unwrap().next_at.unwrap();
+        assert!(next_at > Instant::now() + Duration::from_secs(1));
+
+        daemon.handle(Input::NetworkChanged(vec!["wlan0".to_string()]));
+
+        // Attempted at once; refused here (no such profile), so it stops
+        assert!(daemon.reconnect.is_none());
+    }
+
+    #[test]
+    fn test_scan_adopts_running_session() {
+        let (mut daemon, _rx) = test_daemon();
+        daemon.on_scan(&[session("work")]);
+        assert_eq!(daemon.state, SessionState::Connected);
+        assert_eq!(daemon.profile.as_deref(), Some("work"));
+        assert_eq!(daemon.session.as_ref().unwrap().interface, "wg0");
+    }
+
+    #[test]
+    fn test_exporter_snapshot_follows_state() {
+        let (mut daemon, _rx) = test_daemon();
+        let shared = SharedSnapshot::default();
+        daemon.exporter = Some(shared.clone());
+
+        let mut running = session("work");
+        running.latest_handshake = "1 minute, 5 seconds ago".to_string();
+        daemon.handle(Input::Scan(vec![running]));
+        daemon.handle(Input::Telemetry(TelemetryUpdate::Latency(42)));
+        daemon.handle(Input::Telemetry(TelemetryUpdate::PacketLoss(0.0)));
+        {
+            let snapshot = shared.lock().unwrap();
+            assert_eq!(snapshot.state, SessionState::Connected);
+            assert_eq!(snapshot.handshake_age_secs, Some(65));
+            assert_eq!(snapshot.latency_ms, Some(42));
+            assert_eq!(snapshot.loss_pct, Some(0.0));
+        }
+
+        daemon.handle(Input::Scan(vec![]));
+        let snapshot = shared.lock().unwrap();
+        assert_eq!(snapshot.state, SessionState::Disconnected);
+        assert_eq!(snapshot.handshake_age_secs, None);
+    }
+
+    #[test]
+    fn test_scan_drop_trips_auto_killswitch() {
+        let (mut daemon, _rx) = test_daemon();
+        daemon.killswitch_mode = KillSwitchMode::Auto;
+        daemon.on_scan(&[session("work")]);
+        assert_eq!(daemon.killswitch_state, KillSwitchState::Armed);
+
+        let (events_tx, events_rx) = mpsc::channel();
+        daemon.subscribers.push(events_tx);
+        daemon.on_scan(&[]);
+
+        assert_eq!(daemon.state, SessionState::Disconnected);
+        assert_eq!(daemon.profile, None);
+        assert_eq!(daemon.killswitch_state, KillSwitchState::Blocking);
+        let events: Vec<Event> = events_rx.try_iter().collect();
+        assert!(events.iter().any(|e| matches!(
+            e,
+            Event::State { status } if status.killswitch_state == KillSwitchState::Blocking
+        )));
+    }
+
+    #[test]
+    fn test_killswitch_check_reports_through_owner_channel() {
+        let (mut daemon, rx) = test_daemon();
+        daemon.is_root = true;
+        daemon.killswitch_state = KillSwitchState::Blocking;
+        daemon.killswitch_checking = true;
+
+        // A check is already running: no second worker
+        daemon.verify_killswitch();
+        assert!(rx.try_recv().is_err());
+
+        daemon.handle(Input::KillSwitchChecked(Ok(vec![
+            "OUTPUT policy changed".to_string()
+        ])));
+        assert!(!daemon.killswitch_checking);
+        assert!(daemon.killswitch_verified_at.is_some());
+    }
+
+    #[test]
+    fn test_failed_disconnect_does_not_trip_auto_killswitch() {
+        let (mut daemon, _rx) = test_daemon();
+        daemon.killswitch_mode = KillSwitchMode::Auto;
+        daemon.on_scan(&[session("work")]);
+
+        // What request_disconnect leaves behind before its worker reports
+        daemon.manual_down = true;
+        daemon.set_state(SessionState::Disconnecting, Some("work".to_string()));
+        daemon.on_disconnect_done("work", Err("timed out".to_string()));
+        assert_eq!(daemon.state, SessionState::Connected);
+
+        daemon.on_scan(&[]);
+        assert_eq!(daemon.state, SessionState::Disconnected);
+        assert_eq!(daemon.killswitch_state, KillSwitchState::Armed);
+    }
+
+    #[test]
+    fn test_drop_after_failed_disconnect_still_trips() {
+        let (mut daemon, _rx) = test_daemon();
+        daemon.killswitch_mode = KillSwitchMode::Auto;
+        daemon.on_scan(&[session("work")]);
+        daemon.manual_down = true;
+        daemon.set_state(SessionState::Disconnecting, Some("work".to_string()));
+        daemon.on_disconnect_done("work", Err("timed out".to_string()));
+
+        // The tunnel stayed up, so a later loss is a real drop
+        daemon.on_scan(&[session("work")]);
+        daemon.on_scan(&[]);
+        assert_eq!(daemon.killswitch_state, KillSwitchState::Blocking);
+    }
+
+    #[test]
+    fn test_scan_drop_schedules_reconnect() {
+        let (mut daemon, _rx) = test_daemon();
+        daemon.killswitch_mode = KillSwitchMode::Auto;
+        daemon.on_scan(&[session("work")]);
+        daemon.on_scan(&[]);
+
+        let rc = daemon.reconnect.as_ref().unwrap();
+        assert_eq!(rc.profile, "work");
+        assert_eq!(rc.attempt, 1);
+        assert!(rc.next_at.is_some());
+        assert_eq!(daemon.status().reconnect_attempt, Some(1));
+        // The kill switch keeps the dropped server reachable
+        assert_eq!(
+            daemon.killswitch_target(),
+            ("wg0".to_string(), Some("203.0.113.1".to_string()))
+        );
+
+        // The tunnel comes back: done
+        daemon.on_scan(&[session("work")]);
+        assert_eq!(daemon.state, SessionState::Connected);
+        assert!(daemon.reconnect.is_none());
+        assert_eq!(daemon.status().reconnect_attempt, None);
+    }
+
+    #[test]
+    fn test_scan_drop_without_auto_reconnect() {
+        let (mut daemon, _rx) = test_daemon();
+        daemon.config.auto_reconnect = false;
+        daemon.on_scan(&[session("work")]);
+        daemon.on_scan(&[]);
+        assert!(daemon.reconnect.is_none());
+    }
+
+    #[test]
+    fn test_reconnect_retries_then_gives_up() {
+        let (mut daemon, _rx) = test_daemon();
+        daemon.config.reconnect_max_attempts = 2;
+        daemon.on_scan(&[session("work")]);
+        daemon.on_scan(&[]);
+
+        // The attempt in flight failed
+        daemon.reconnect.as_mut().unwrap().next_at = None;
+        daemon.on_scan(&[]);
+        let rc = daemon.reconnect.as_ref().unwrap();
+        assert_eq!(rc.attempt, 2);
+        assert!(rc.next_at.is_some());
+
+        daemon.reconnect.as_mut().unwrap().next_at = None;
+        daemon.on_scan(&[]);
+        assert!(daemon.reconnect.is_none());
+    }
+
+    #[test]
+    fn test_reconnect_stops_when_connect_is_refused() {
+        let (mut daemon, _rx) = test_daemon();
+        let name = "vortix-test-no-such-profile";
+        daemon.on_scan(&[session(name)]);
+        daemon.on_scan(&[]);
+
+        daemon.reconnect.as_mut().unwrap().next_at = Some(Instant::now());
+        daemon.on_scan(&[]);
+        assert_eq!(daemon.state, SessionState::Disconnected);
+        assert!(daemon.reconnect.is_none());
+    }
+
+    #[test]
+    fn test_client_disconnect_cancels_reconnect() {
+        let (mut daemon, _rx) = test_daemon();
+        daemon.on_scan(&[session("work")]);
+        daemon.on_scan(&[]);
+        assert!(daemon.reconnect.is_some());
+
+        let (reply_tx, reply_rx) = mpsc::channel();
+        daemon.handle(Input::Request(Request::Disconnect, reply_tx));
+        assert!(daemon.reconnect.is_none());
+        assert!(matches!(
+            reply_rx.try_recv().unwrap(),
+            Response::Ok { status } if status.reconnect_attempt.is_none()
+        ));
+    }
+
+    #[test]
+    fn test_scan_drop_is_recorded_in_history() {
+        let (mut daemon, _rx) = test_daemon();
+        daemon.config_dir =
+            std::env::temp_dir().join(format!("vortix-daemon-history-{}", std::process::id()));
+        std::fs::create_dir_all(&daemon.config_dir).unwrap();
+
+        daemon.on_scan(&[session("work")]);
+        daemon.on_scan(&[]);
+
+        let records = history::load(&daemon.config_dir).unwrap();
+        let _ = std::fs::remove_dir_all(&daemon.config_dir);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].profile, "work");
+        assert_eq!(records[0].end_reason, EndReason::Drop);
+        assert!(daemon.tracker.is_none());
+    }
+
+    #[test]
+    fn test_connect_done_replies_to_waiters() {
+        let (mut daemon, _rx) = test_daemon();
+        let (reply_tx, reply_rx) = mpsc::channel();
+        daemon.waiters.push(reply_tx);
+        daemon.set_state(SessionState::Connecting, Some("work".to_string()));
+
+        daemon.on_connect_done("work", Ok(LaunchOutcome::Confirmed));
+
+        assert_eq!(daemon.state, SessionState::Connected);
+        match reply_rx.try_recv().unwrap() {
+            Response::Ok { status } => assert_eq!(status.profile.as_deref(), Some("work")),
+            Response::Error { message, .. } => panic!("unexpected error: {message}"),
+        }
+    }
+
+    #[test]
+    fn test_stale_connect_done_is_ignored() {
+        let (mut daemon, _rx) = test_daemon();
+        daemon.on_connect_done("work", Err("AUTH_FAILED".to_string()));
+        assert_eq!(daemon.state, SessionState::Disconnected);
+    }
+
+    #[test]
+    fn test_request_while_busy_is_rejected() {
+        let (mut daemon, _rx) = test_daemon();
+        daemon.set_state(SessionState::Connecting, Some("work".to_string()));
+
+        let (reply_tx, reply_rx) = mpsc::channel();
+        daemon.request_connect("home", reply_tx);
+
+        assert!(matches!(
+            reply_rx.try_recv().unwrap(),
+            Response::Error { code, .. } if code == constants::EXIT_FAILURE
+        ));
+        assert_eq!(daemon.profile.as_deref(), Some("work"));
+    }
+
+    #[test]
+    fn test_peer_uid_and_allowed_peers() {
+        let (client, _server) = UnixStream::pair().unwrap();
+        let uid = crate::utils::peer_uid(&client).unwrap();
+        assert_eq!(uid, crate::utils::effective_uid());
+        let allowed = allowed_peers();
+        assert!(allowed.contains(&0));
+        assert!(allowed.contains(&uid));
+    }
+
+    #[test]
+    fn test_bind_creates_private_socket() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("vortix-daemon-bind-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("daemon.sock");
+        let listener = bind(&path).unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        drop(listener);
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn test_serve_client_roundtrip() {
+        let (tx, rx) = mpsc::channel();
+        let (mut client, server) = UnixStream::pair().unwrap();
+        std::thread::spawn(move || serve_client(server, &tx));
+
+        client.write_all(b"{\"cmd\":\"status\"}\n").unwrap();
+        match rx.recv().unwrap() {
+            Input::Request(Request::Status, reply) => {
+                reply
+                    .send(Response::Ok {
+                        status: Status::default(),
+                    })
+                    .unwrap();
+            }
+            _ => panic!("expected a status request"),
+        }
+
+        let mut line = String::new();
+        BufReader::new(client).read_line(&mut line).unwrap();
+        assert_eq!(
+            protocol::decode::<Response>(&line).unwrap(),
+            Response::Ok {
+                status: Status::default()
+            }
+        );
+    }
+}
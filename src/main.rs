@@ -8,6 +8,7 @@
 //! - [`cli`]: Command-line argument parsing.
 //! - [`config`]: Configuration management.
 //! - [`core`]: Scanner and telemetry background workers.
+//! - [`daemon`]: Background service and its control socket.
 //! - [`event`]: Event loop handling.
 //! - [`ui`]: TUI rendering and widget definitions.
 //! - [`vpn`]: Profile parsing and configuration management.
@@ -17,6 +18,7 @@ mod cli;
 mod config;
 mod constants;
 mod core;
+mod daemon;
 mod event;
 mod logger;
 mod message;
@@ -80,7 +82,7 @@ fn main() -> Result<()> {
     config::set_config_dir(config_dir.clone());
 
     // Load config.toml (or use defaults)
-    let app_config = match config::load_config(&config_dir) {
+    let mut app_config = match config::load_config(&config_dir) {
         Ok(cfg) => cfg,
         Err(e) => {
             eprintln!("Error: {e}");
@@ -92,9 +94,18 @@ fn main() -> Result<()> {
         }
     };
 
+    // Validated by load_config
+    if let Ok(store) = app_config.credential_store.parse() {
+        core::secrets::set_store(store);
+    }
+
+    if let Some(addr) = args.metrics_listen {
+        app_config.metrics_listen = addr.to_string();
+    }
+
     // Handle CLI commands (import, update, info, etc.)
     if let Some(command) = &args.command {
-        if cli::commands::handle_command(command, &config_dir, config_dir_source)? {
+        if cli::commands::handle_command(command, &app_config, &config_dir, config_dir_source)? {
             return Ok(());
         }
     }
@@ -147,8 +158,7 @@ fn prompt_migration(old_dir: &std::path::Path, new_dir: &std::path::Path) -> std
                 // Verify profiles were actually migrated
                 let profiles_exist = new_dir.join("profiles").is_dir()
                     && std::fs::read_dir(new_dir.join("profiles"))
-                        .map(|mut d| d.next().is_some())
-                        .unwrap_or(false);
+                        .is_ok_and(|mut d| d.next().is_some());
                 if profiles_exist {
                     eprintln!("  Done! Data moved to {}\n", new_dir.display());
                 } else {
@@ -48,6 +48,8 @@ pub enum Message {
     FocusPanel(FocusedPanel),
     /// Toggle zoom on current panel
     ToggleZoom,
+    /// Show the next chart time window (1m → 1h → 24h)
+    CycleChartWindow,
 
     // === Profile Management ===
     /// Move selection in profile list
@@ -60,8 +62,8 @@ pub enum Message {
     Disconnect,
     /// Reconnect to last profile
     Reconnect,
-    /// Connect to quick slot (0-8)
-    QuickConnect(usize),
+    /// Connect the profile bound to quick slot 1-9
+    QuickConnect(u8),
 
     // === UI Overlays ===
     /// Close current overlay (Action menu, Help, Config, etc.)
@@ -74,6 +76,18 @@ pub enum Message {
     OpenDelete(Option<usize>),
     /// Confirm deletion
     ConfirmDelete,
+    /// Browse the recorded sessions
+    OpenHistory,
+    /// Filter the profile sidebar by typing
+    OpenSearch,
+    /// Find the fastest server, optionally among one tag or location
+    OpenBenchmark,
+    /// Probe the servers of the profiles in scope (tag or location, empty = all)
+    RunBenchmark(String),
+    /// Ranked results from the background benchmark
+    BenchmarkResult(Vec<crate::core::benchmark::ProbeResult>),
+    /// Unlock the profile vault with this passphrase
+    UnlockVault(String),
 
     // === Action Menu ===
     /// Open the action menu (Single actions)
@@ -124,6 +138,19 @@ pub enum Message {
         /// Error message if the command failed
         error: Option<String>,
     },
+    /// Links, addresses or routes changed on these interfaces
+    NetworkChanged(Vec<String>),
+    /// A desktop notification could not be shown; ring the terminal instead
+    NotifyFailed {
+        /// Event that was being announced
+        event: crate::core::notify::NotifyEvent,
+        /// Notification body
+        body: String,
+        /// Why the desktop notification failed
+        error: String,
+    },
+    /// Event pushed by the daemon this TUI is attached to
+    Daemon(crate::daemon::protocol::Event),
     /// Terminal resize event
     Resize(u16, u16),
     /// Import profile from path
@@ -138,19 +165,90 @@ pub enum Message {
         username: String,
         /// Password entered by the user
         password: String,
+        /// Challenge answer (OTP code); never saved
+        response: String,
         /// Whether to persist credentials for future sessions
         save: bool,
         /// Whether to auto-connect after saving (false = save-only from manage flow)
         connect_after: bool,
     },
+    /// `OpenVPN` asked for credentials while connecting
+    OpenVpnAuth {
+        /// Profile being connected
+        profile: String,
+        /// What the daemon asked for
+        request: crate::core::openvpn_mgmt::AuthRequest,
+        /// Receives the credentials, or `None` to cancel the connect
+        reply: std::sync::mpsc::Sender<Option<crate::core::openvpn_mgmt::Credentials>>,
+    },
     /// Open the auth credentials manager for the selected profile (edit/view/clear)
     ManageAuth,
     /// Clear saved credentials for the selected profile
     ClearAuth,
 
+    // === Split Tunnel ===
+    /// Open the split tunnel editor for the selected profile
+    ManageSplitTunnel,
+    /// Save the split tunnel policy from the editor overlay
+    SplitTunnelSubmit {
+        /// Profile index the policy belongs to
+        idx: usize,
+        /// Comma-separated CIDRs/hostnames routed through the tunnel
+        include: String,
+        /// Comma-separated CIDRs/hostnames that bypass the tunnel
+        exclude: String,
+        /// Keep LAN traffic off the tunnel
+        bypass_lan: bool,
+    },
+
+    // === DNS ===
+    /// Open the DNS override editor for the selected profile
+    ManageDns,
+    /// Save the DNS override from the editor overlay
+    DnsSubmit {
+        /// Profile index the override belongs to
+        idx: usize,
+        /// Comma-separated DNS server addresses
+        servers: String,
+        /// Comma-separated search domains
+        search: String,
+        /// Drop port 53 traffic that does not go through the tunnel
+        block_outside: bool,
+    },
+
+    // === Labels ===
+    /// Open the group/tags editor for the selected profile
+    ManageTags,
+    /// Save the labels from the editor overlay
+    TagsSubmit {
+        /// Profile index the labels belong to
+        idx: usize,
+        /// Sidebar group (empty = ungrouped)
+        group: String,
+        /// Comma-separated tags
+        tags: String,
+        /// List under Favorites
+        favorite: bool,
+    },
+    /// Add or remove the selected profile from Favorites
+    ToggleFavorite,
+    /// Fold or unfold the selected profile's sidebar section
+    ToggleGroup,
+    /// Open the quick slot picker for the selected profile
+    OpenQuickSlot,
+    /// Bind a profile to a quick slot (None = unbind)
+    AssignQuickSlot {
+        /// Profile index to bind
+        idx: usize,
+        /// Slot number 1-9
+        slot: Option<u8>,
+    },
+
     // === Kill Switch ===
     /// Toggle kill switch mode (Off → Auto → `AlwaysOn` → Off)
     ToggleKillSwitch,
+    /// Result of a background kill switch rule check (drift that was repaired)
+    KillSwitchChecked(Result<Vec<String>, String>),
 }
 
 /// An item in the action menu, mapping a key to a message.
@@ -165,6 +263,7 @@ pub struct ActionMenuItem {
 }
 
 /// Get specific actions for the focused item/panel (triggered by 'x')
+#[allow(clippy::too_many_lines)]
 pub fn get_single_actions(focused_panel: &FocusedPanel) -> Vec<ActionMenuItem> {
     let mut actions = Vec::new();
 
@@ -176,6 +275,11 @@ pub fn get_single_actions(focused_panel: &FocusedPanel) -> Vec<ActionMenuItem> {
                 label: "Import Profiles",
                 message: Message::OpenImport,
             });
+            actions.push(ActionMenuItem {
+                key: "/",
+                label: "Search Profiles",
+                message: Message::OpenSearch,
+            });
             actions.push(ActionMenuItem {
                 key: "c",
                 label: "Connect / Disconnect",
@@ -201,6 +305,36 @@ pub fn get_single_actions(focused_panel: &FocusedPanel) -> Vec<ActionMenuItem> {
                 label: "Clear Auth Credentials",
                 message: Message::ClearAuth,
             });
+            actions.push(ActionMenuItem {
+                key: "s",
+                label: "Edit Split Tunnel",
+                message: Message::ManageSplitTunnel,
+            });
+            actions.push(ActionMenuItem {
+                key: "n",
+                label: "Edit DNS Override",
+                message: Message::ManageDns,
+            });
+            actions.push(ActionMenuItem {
+                key: "t",
+                label: "Edit Group / Tags",
+                message: Message::ManageTags,
+            });
+            actions.push(ActionMenuItem {
+                key: "f",
+                label: "Toggle Favorite",
+                message: Message::ToggleFavorite,
+            });
+            actions.push(ActionMenuItem {
+                key: "o",
+                label: "Fold / Unfold Group",
+                message: Message::ToggleGroup,
+            });
+            actions.push(ActionMenuItem {
+                key: "#",
+                label: "Assign Quick Slot (1-9)",
+                message: Message::OpenQuickSlot,
+            });
             actions.push(ActionMenuItem {
                 key: "DEL",
                 label: "Delete Profile",
@@ -221,8 +355,15 @@ pub fn get_single_actions(focused_panel: &FocusedPanel) -> Vec<ActionMenuItem> {
                 message: Message::CopyIp,
             });
         }
-        FocusedPanel::Security | FocusedPanel::Chart => {
-            // No specific panel actions yet for Security Guard or Chart
+        FocusedPanel::Chart => {
+            actions.push(ActionMenuItem {
+                key: "w",
+                label: "Cycle Chart Window",
+                message: Message::CycleChartWindow,
+            });
+        }
+        FocusedPanel::Security => {
+            // No specific panel actions yet for Security Guard
         }
     }
 
@@ -259,6 +400,16 @@ pub fn get_bulk_actions() -> Vec<ActionMenuItem> {
             label: "Copy Public IP",
             message: Message::CopyIp,
         },
+        ActionMenuItem {
+            key: "H",
+            label: "Session History",
+            message: Message::OpenHistory,
+        },
+        ActionMenuItem {
+            key: "B",
+            label: "Connect to Fastest Server",
+            message: Message::OpenBenchmark,
+        },
         ActionMenuItem {
             key: "l",
             label: "Next Panel",
@@ -289,6 +440,12 @@ mod tests {
         assert!(actions.iter().any(|a| a.key == "v"));
         assert!(actions.iter().any(|a| a.key == "a")); // edit auth credentials
         assert!(actions.iter().any(|a| a.key == "A")); // clear auth credentials
+        assert!(actions.iter().any(|a| a.key == "s")); // split tunnel editor
+        assert!(actions.iter().any(|a| a.key == "n")); // DNS override editor
+        assert!(actions.iter().any(|a| a.key == "t")); // group/tags editor
+        assert!(actions.iter().any(|a| a.key == "f")); // favorite
+        assert!(actions.iter().any(|a| a.key == "#")); // quick slot
+        assert!(actions.iter().any(|a| a.key == "/")); // search
         assert!(actions.iter().any(|a| a.key == "DEL"));
         assert!(actions.iter().any(|a| a.key == "z")); // universal zoom
     }
@@ -307,10 +464,11 @@ mod tests {
     }
 
     #[test]
-    fn test_chart_actions_only_zoom() {
+    fn test_chart_actions_window_and_zoom() {
         let actions = get_single_actions(&FocusedPanel::Chart);
-        assert_eq!(actions.len(), 1);
-        assert_eq!(actions[0].key, "z");
+        assert_eq!(actions.len(), 2);
+        assert_eq!(actions[0].key, "w");
+        assert_eq!(actions[1].key, "z");
     }
 
     #[test]
@@ -327,12 +485,14 @@ mod tests {
         assert!(actions.iter().any(|a| a.key == "D")); // disconnect all
         assert!(actions.iter().any(|a| a.key == "q")); // quit
         assert!(actions.iter().any(|a| a.key == "y")); // copy IP
+        assert!(actions.iter().any(|a| a.key == "H")); // session history
+        assert!(actions.iter().any(|a| a.key == "B")); // fastest server
     }
 
     #[test]
     fn test_bulk_actions_count() {
         let actions = get_bulk_actions();
-        assert_eq!(actions.len(), 7);
+        assert_eq!(actions.len(), 9);
     }
 
     #[test]
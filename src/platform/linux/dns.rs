@@ -1,7 +1,9 @@
 //! Linux DNS resolver using resolvectl, nmcli, and /etc/resolv.conf.
 
 use crate::constants;
-use crate::platform::DnsResolver;
+use crate::core::dns::DnsRestore;
+use crate::platform::{DnsConfigurator, DnsResolver};
+use std::path::Path;
 use std::process::Command;
 
 /// Linux DNS resolution with fallback chain:
@@ -18,6 +20,149 @@ impl DnsResolver for LinuxDns {
     }
 }
 
+/// Overrides follow the same order as detection. `nmcli` is skipped: it
+/// cannot configure the tunnel interfaces `wg-quick`/`openvpn` create, which
+/// `NetworkManager` does not manage, so those systems use `/etc/resolv.conf`.
+impl DnsConfigurator for LinuxDns {
+    fn apply_dns(
+        vpn_interface: &str,
+        servers: &[String],
+        search: &[String],
+    ) -> Result<DnsRestore, String> {
+        if resolvectl_available() {
+            apply_resolvectl(vpn_interface, servers, search)?;
+            return Ok(DnsRestore::Resolvectl {
+                interface: vpn_interface.to_string(),
+            });
+        }
+        apply_resolv_conf(servers, search)
+    }
+
+    fn restore_dns(restore: &DnsRestore) -> Result<(), String> {
+        match restore {
+            DnsRestore::Resolvectl { interface } => {
+                // Fails harmlessly when the interface is already gone
+                let _ = Command::new("resolvectl")
+                    .args(["revert", interface])
+                    .output();
+                Ok(())
+            }
+            DnsRestore::ResolvConf { original, link } => {
+                let path = Path::new(constants::RESOLV_CONF_PATH);
+                let _ = std::fs::remove_file(path);
+                if let Some(target) = link {
+                    std::os::unix::fs::symlink(target, path)
+                        .map_err(|e| format!("Failed to restore resolv.conf link: {e}"))
+                } else if let Some(content) = original {
+                    std::fs::write(path, content)
+                        .map_err(|e| format!("Failed to restore resolv.conf: {e}"))
+                } else {
+                    Ok(())
+                }
+            }
+            DnsRestore::Networksetup { .. } => Err("networksetup is macOS only".to_string()),
+        }
+    }
+}
+
+fn resolvectl_available() -> bool {
+    Command::new("resolvectl")
+        .arg("status")
+        .output()
+        .is_ok_and(|o| o.status.success())
+}
+
+fn resolvectl(args: &[&str]) -> Result<(), String> {
+    let output = Command::new("resolvectl")
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run resolvectl: {e}"))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "resolvectl {}: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+/// Per-link settings on the tunnel. With servers set, the `~.` routing domain
+/// sends every query to this link so other links cannot answer.
+fn apply_resolvectl(iface: &str, servers: &[String], search: &[String]) -> Result<(), String> {
+    if !servers.is_empty() {
+        let mut args = vec!["dns", iface];
+        args.extend(servers.iter().map(String::as_str));
+        resolvectl(&args)?;
+    }
+    let mut args = vec!["domain", iface];
+    if !servers.is_empty() {
+        args.push("~.");
+    }
+    args.extend(search.iter().map(String::as_str));
+    if args.len() > 2 {
+        resolvectl(&args)?;
+    }
+    if !servers.is_empty() {
+        // Not supported by older systemd; the routing domain already covers it
+        let _ = resolvectl(&["default-route", iface, "yes"]);
+    }
+    Ok(())
+}
+
+fn apply_resolv_conf(servers: &[String], search: &[String]) -> Result<DnsRestore, String> {
+    let path = Path::new(constants::RESOLV_CONF_PATH);
+    let link = std::fs::read_link(path).ok();
+    let current = std::fs::read_to_string(path).unwrap_or_default();
+    let restore = DnsRestore::ResolvConf {
+        original: if link.is_some() || !path.exists() {
+            None
+        } else {
+            Some(current.clone())
+        },
+        link,
+    };
+
+    // Replace a symlink with a regular file instead of writing through it
+    let _ = std::fs::remove_file(path);
+    std::fs::write(path, render_resolv_conf(&current, servers, search))
+        .map_err(|e| format!("Failed to write resolv.conf: {e}"))?;
+    Ok(restore)
+}
+
+/// `resolv.conf` content for an override. Settings the override leaves
+/// empty (servers or search domains) and `options` lines are carried over.
+fn render_resolv_conf(current: &str, servers: &[String], search: &[String]) -> String {
+    let mut out = String::from("# Generated by vortix for the active VPN profile\n");
+    let keep = |out: &mut String, prefix: &str| {
+        for line in current.lines().map(str::trim) {
+            if line.starts_with(prefix) {
+                out.push_str(line);
+                out.push('\n');
+            }
+        }
+    };
+    if servers.is_empty() {
+        keep(&mut out, "nameserver");
+    } else {
+        for server in servers {
+            out.push_str("nameserver ");
+            out.push_str(server);
+            out.push('\n');
+        }
+    }
+    if search.is_empty() {
+        keep(&mut out, "search");
+    } else {
+        out.push_str("search ");
+        out.push_str(&search.join(" "));
+        out.push('\n');
+    }
+    keep(&mut out, "options");
+    out
+}
+
 /// Try to get DNS from resolvectl (systemd-resolved, most modern distros).
 fn try_get_dns_resolvectl() -> Option<String> {
     let output = Command::new("resolvectl").args(["status"]).output().ok()?;
@@ -85,6 +230,24 @@ fn try_get_dns_resolv_conf() -> Option<String> {
 
 #[cfg(test)]
 mod tests {
+    use super::render_resolv_conf;
+
+    #[test]
+    fn test_render_resolv_conf_override() {
+        let current = "# Generated by NetworkManager\nsearch home.lan\nnameserver 192.168.1.1\noptions edns0\n";
+        let servers = vec!["10.8.0.1".to_string(), "10.8.0.2".to_string()];
+        let out = render_resolv_conf(current, &servers, &[]);
+        assert!(out.contains("nameserver 10.8.0.1\nnameserver 10.8.0.2\n"));
+        assert!(!out.contains("192.168.1.1"));
+        assert!(out.contains("search home.lan\n"));
+        assert!(out.contains("options edns0\n"));
+
+        let out = render_resolv_conf(current, &[], &["corp.example".to_string()]);
+        assert!(out.contains("nameserver 192.168.1.1\n"));
+        assert!(out.contains("search corp.example\n"));
+        assert!(!out.contains("home.lan"));
+    }
+
     #[test]
     fn test_parse_resolv_conf() {
         // Simulate the parsing logic
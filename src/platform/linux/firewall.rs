@@ -1,16 +1,98 @@
 //! Linux iptables/nftables firewall implementation for kill switch.
 //!
 //! Prefers iptables when available, falls back to nftables (nft).
+//! IPv6 is covered by a matching ip6tables chain, or by the `inet` family
+//! of the nft table.
 
+use crate::config::KillSwitchConfig;
 use crate::constants;
-use crate::core::killswitch::{KillSwitchError, Result};
+use crate::core::killswitch::{KillSwitchError, PortProtocol, Result, RulePlan};
 use crate::logger::{self, LogLevel};
 use crate::platform::Firewall;
+use std::net::IpAddr;
 use std::process::Command;
 
 /// Alias for readability within this module.
 const CHAIN_NAME: &str = constants::IPTABLES_CHAIN_NAME;
 const NFT_TABLE: &str = constants::NFT_TABLE_NAME;
+const DNS_CHAIN_NAME: &str = constants::IPTABLES_DNS_CHAIN_NAME;
+const NFT_DNS_TABLE: &str = constants::NFT_DNS_TABLE_NAME;
+
+/// Private IPv4 ranges exempted unless `block_lan` is set (RFC1918).
+const LAN_V4: [&str; 3] = ["192.168.0.0/16", "10.0.0.0/8", "172.16.0.0/12"];
+/// Link-local IPv6 unicast and multicast, exempted unless `block_lan` is set.
+const LAN_V6: [&str; 2] = ["fe80::/10", "ff02::/16"];
+
+/// Outgoing `ICMPv6` types needed for router and neighbor discovery.
+const ICMPV6_ND_TYPES: [&str; 4] = [
+    "router-solicitation",
+    "router-advertisement",
+    "neighbour-solicitation",
+    "neighbour-advertisement",
+];
+
+/// Parse the server address. Hostnames and unparsable endpoints are ignored
+/// rather than handed to the firewall as a bogus address.
+fn parse_server(vpn_server_ip: Option<&str>) -> Option<IpAddr> {
+    vpn_server_ip.and_then(|ip| ip.parse::<IpAddr>().ok())
+}
+
+/// Owned argument list for one firewall rule.
+fn args(list: &[&str]) -> Vec<String> {
+    list.iter().map(|a| (*a).to_string()).collect()
+}
+
+/// A rule as `iptables -S` saves it: match modules (`-m udp`) dropped,
+/// single-host prefixes (`/32`, `/128`) removed and `ICMPv6` type names
+/// replaced by their numbers, so planned and live rules compare equal.
+fn normalize_rule(rule: &str) -> String {
+    let mut out = Vec::new();
+    let mut tokens = rule.split_whitespace();
+    while let Some(token) = tokens.next() {
+        if token == "-m" {
+            tokens.next();
+            continue;
+        }
+        let token = token
+            .strip_suffix("/32")
+            .or_else(|| token.strip_suffix("/128"))
+            .unwrap_or(token);
+        // ICMPV6_ND_TYPES are types 133 to 136, in order
+        match ICMPV6_ND_TYPES.iter().position(|t| *t == token) {
+            Some(i) => out.push((133 + i).to_string()),
+            None => out.push(token.to_string()),
+        }
+    }
+    out.join(" ")
+}
+
+/// Rule statements of an nft ruleset, whitespace-normalized, without
+/// comments and table/chain scaffolding. Single-host prefixes are removed,
+/// as `nft list` prints them.
+fn nft_statements(ruleset: &str) -> Vec<String> {
+    ruleset
+        .lines()
+        .map(str::trim)
+        .filter(|l| {
+            !l.is_empty()
+                && !l.starts_with('#')
+                && !l.starts_with("table ")
+                && !l.starts_with("chain ")
+                && !l.starts_with("type ")
+                && *l != "}"
+        })
+        .map(|l| {
+            l.split_whitespace()
+                .map(|t| {
+                    t.strip_suffix("/32")
+                        .or_else(|| t.strip_suffix("/128"))
+                        .unwrap_or(t)
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect()
+}
 
 /// Detected firewall backend on this system.
 enum FirewallBackend {
@@ -53,10 +135,19 @@ impl IptablesFirewall {
 
     /// Run an iptables command and return success.
     fn iptables(args: &[&str]) -> std::result::Result<(), String> {
-        let output = Command::new("iptables")
+        Self::xtables("iptables", args)
+    }
+
+    /// Run an ip6tables command and return success.
+    fn ip6tables(args: &[&str]) -> std::result::Result<(), String> {
+        Self::xtables("ip6tables", args)
+    }
+
+    fn xtables(bin: &str, args: &[&str]) -> std::result::Result<(), String> {
+        let output = Command::new(bin)
             .args(args)
             .output()
-            .map_err(|e| format!("Failed to run iptables: {e}"))?;
+            .map_err(|e| format!("Failed to run {bin}: {e}"))?;
 
         if output.status.success() {
             Ok(())
@@ -65,68 +156,250 @@ impl IptablesFirewall {
         }
     }
 
-    /// Set up the kill switch chain with iptables.
-    fn setup_iptables(vpn_interface: &str, vpn_server_ip: Option<&str>) -> Result<()> {
-        // Create custom chain (ignore error if already exists)
-        let _ = Self::iptables(&["-N", CHAIN_NAME]);
+    /// Run a command and return its stdout, or `None` if it failed.
+    fn capture(bin: &str, args: &[&str]) -> Option<String> {
+        Command::new(bin)
+            .args(args)
+            .output()
+            .ok()
+            .filter(|o| o.status.success())
+            .map(|o| String::from_utf8_lossy(&o.stdout).to_string())
+    }
+
+    /// Plan for the kill switch chains in iptables and, when `with_ipv6`,
+    /// ip6tables.
+    fn iptables_plan(
+        vpn_interface: &str,
+        vpn_server_ip: Option<IpAddr>,
+        options: &KillSwitchConfig,
+        with_ipv6: bool,
+    ) -> RulePlan {
+        let mut plan = RulePlan::new("iptables");
+        let rules = Self::iptables_rules(vpn_interface, vpn_server_ip, options);
+        Self::chain_plan(&mut plan, "iptables", &rules);
+
+        // The kill switch ACCEPTs LAN traffic, so the DNS block must stay in
+        // front. Both steps fail harmlessly when the DNS chain is not installed.
+        plan.note("Keep the outside-DNS block (if any) in front");
+        plan.try_run("iptables", &["-D", "OUTPUT", "-j", DNS_CHAIN_NAME]);
+        plan.try_run("iptables", &["-I", "OUTPUT", "1", "-j", DNS_CHAIN_NAME]);
+
+        // Skipped on systems without ip6tables, where IPv6 is usually absent.
+        if with_ipv6 {
+            let rules = Self::ip6tables_rules(vpn_interface, vpn_server_ip, options);
+            Self::chain_plan(&mut plan, "ip6tables", &rules);
+            plan.try_run("ip6tables", &["-D", "OUTPUT", "-j", DNS_CHAIN_NAME]);
+            plan.try_run("ip6tables", &["-I", "OUTPUT", "1", "-j", DNS_CHAIN_NAME]);
+        } else {
+            plan.note("ip6tables not found - IPv6 traffic is not covered");
+        }
+        plan
+    }
 
+    /// (Re)create the kill switch chain from `rules` and jump to it from the
+    /// top of OUTPUT.
+    fn chain_plan(plan: &mut RulePlan, program: &'static str, rules: &[Vec<String>]) {
+        plan.note(format!("{program}: kill switch chain"));
+        // Create custom chain (fails if it already exists)
+        plan.try_run(program, &["-N", CHAIN_NAME]);
         // Flush existing rules in our chain
-        Self::iptables(&["-F", CHAIN_NAME])
-            .map_err(|e| KillSwitchError::CommandFailed(format!("flush chain: {e}")))?;
+        plan.run(program, &["-F", CHAIN_NAME]);
 
-        // Add rules to our chain
+        for rule in rules {
+            let mut args = vec!["-A", CHAIN_NAME];
+            args.extend(rule.iter().map(String::as_str));
+            plan.run(program, &args);
+        }
 
-        // Allow loopback
-        Self::iptables(&["-A", CHAIN_NAME, "-o", "lo", "-j", "ACCEPT"])
-            .map_err(|e| KillSwitchError::CommandFailed(format!("allow lo: {e}")))?;
+        // Insert jump to our chain at the top of OUTPUT, replacing any old one
+        plan.try_run(program, &["-D", "OUTPUT", "-j", CHAIN_NAME]);
+        plan.run(program, &["-I", "OUTPUT", "1", "-j", CHAIN_NAME]);
+    }
 
-        // Allow VPN interface
-        Self::iptables(&["-A", CHAIN_NAME, "-o", vpn_interface, "-j", "ACCEPT"])
-            .map_err(|e| KillSwitchError::CommandFailed(format!("allow VPN iface: {e}")))?;
+    /// iptables rules for the IPv4 kill switch chain, in order (arguments
+    /// after `-A <chain>`).
+    fn iptables_rules(
+        vpn_interface: &str,
+        vpn_server_ip: Option<IpAddr>,
+        options: &KillSwitchConfig,
+    ) -> Vec<Vec<String>> {
+        let mut rules = vec![
+            // Allow loopback
+            args(&["-o", "lo", "-j", "ACCEPT"]),
+            // Allow VPN interface
+            args(&["-o", vpn_interface, "-j", "ACCEPT"]),
+        ];
 
         // Allow local network (RFC1918)
-        for net in &["192.168.0.0/16", "10.0.0.0/8", "172.16.0.0/12"] {
-            Self::iptables(&["-A", CHAIN_NAME, "-d", net, "-j", "ACCEPT"])
-                .map_err(|e| KillSwitchError::CommandFailed(format!("allow {net}: {e}")))?;
+        if !options.block_lan {
+            for net in LAN_V4 {
+                rules.push(args(&["-d", net, "-j", "ACCEPT"]));
+            }
         }
 
         // Allow DHCP
-        Self::iptables(&[
-            "-A", CHAIN_NAME, "-p", "udp", "--sport", "68", "--dport", "67", "-j", "ACCEPT",
-        ])
-        .map_err(|e| KillSwitchError::CommandFailed(format!("allow DHCP: {e}")))?;
+        rules.push(args(&[
+            "-p", "udp", "--sport", "68", "--dport", "67", "-j", "ACCEPT",
+        ]));
+
+        rules.extend(Self::exception_rules(options, false));
 
         // Allow VPN server IP if known (for reconnection)
-        if let Some(ip) = vpn_server_ip {
-            Self::iptables(&["-A", CHAIN_NAME, "-d", ip, "-p", "udp", "-j", "ACCEPT"]).map_err(
-                |e| KillSwitchError::CommandFailed(format!("allow VPN server udp: {e}")),
-            )?;
-            Self::iptables(&["-A", CHAIN_NAME, "-d", ip, "-p", "tcp", "-j", "ACCEPT"]).map_err(
-                |e| KillSwitchError::CommandFailed(format!("allow VPN server tcp: {e}")),
-            )?;
+        if let Some(ip) = vpn_server_ip.filter(IpAddr::is_ipv4) {
+            let ip = ip.to_string();
+            for proto in ["udp", "tcp"] {
+                rules.push(args(&["-d", &ip, "-p", proto, "-j", "ACCEPT"]));
+            }
         }
 
         // Default: drop everything else
-        Self::iptables(&["-A", CHAIN_NAME, "-j", "DROP"])
-            .map_err(|e| KillSwitchError::CommandFailed(format!("default drop: {e}")))?;
+        rules.push(args(&["-j", "DROP"]));
+        rules
+    }
 
-        // Insert jump to our chain at the top of OUTPUT
-        // First remove any existing jump (ignore error)
-        let _ = Self::iptables(&["-D", "OUTPUT", "-j", CHAIN_NAME]);
-        Self::iptables(&["-I", "OUTPUT", "1", "-j", CHAIN_NAME])
-            .map_err(|e| KillSwitchError::CommandFailed(format!("insert jump: {e}")))?;
+    /// ip6tables rules for the kill switch chain, in order (arguments after
+    /// `-A <chain>`).
+    fn ip6tables_rules(
+        vpn_interface: &str,
+        vpn_server_ip: Option<IpAddr>,
+        options: &KillSwitchConfig,
+    ) -> Vec<Vec<String>> {
+        let mut rules = vec![args(&["-o", "lo", "-j", "ACCEPT"])];
 
-        Ok(())
+        if !options.block_ipv6 {
+            rules.push(args(&["-o", vpn_interface, "-j", "ACCEPT"]));
+
+            // Link-local unicast and multicast
+            if !options.block_lan {
+                for net in LAN_V6 {
+                    rules.push(args(&["-d", net, "-j", "ACCEPT"]));
+                }
+            }
+
+            // Neighbor discovery and DHCPv6 keep the host's own address working
+            for icmp_type in ICMPV6_ND_TYPES {
+                rules.push(args(&[
+                    "-p",
+                    "ipv6-icmp",
+                    "--icmpv6-type",
+                    icmp_type,
+                    "-j",
+                    "ACCEPT",
+                ]));
+            }
+            rules.push(args(&[
+                "-p", "udp", "--sport", "546", "--dport", "547", "-j", "ACCEPT",
+            ]));
+
+            rules.extend(Self::exception_rules(options, true));
+
+            if let Some(ip) = vpn_server_ip.filter(IpAddr::is_ipv6) {
+                let ip = ip.to_string();
+                for proto in ["udp", "tcp"] {
+                    rules.push(args(&["-d", &ip, "-p", proto, "-j", "ACCEPT"]));
+                }
+            }
+        }
+
+        rules.push(args(&["-j", "DROP"]));
+        rules
+    }
+
+    /// `allow_cidrs` and `allow_ports` exceptions for one address family.
+    fn exception_rules(options: &KillSwitchConfig, v6: bool) -> Vec<Vec<String>> {
+        let mut rules: Vec<Vec<String>> = options
+            .allowed_cidrs()
+            .into_iter()
+            .filter(|net| net.is_ipv6() == v6)
+            .map(|net| args(&["-d", &net.to_string(), "-j", "ACCEPT"]))
+            .collect();
+
+        for port in options.allowed_ports() {
+            let proto = match port.protocol {
+                PortProtocol::Icmp if v6 => "ipv6-icmp",
+                protocol => protocol.as_str(),
+            };
+            let mut rule = args(&["-p", proto]);
+            if let Some((lo, hi)) = port.ports {
+                let range = if lo == hi {
+                    lo.to_string()
+                } else {
+                    format!("{lo}:{hi}")
+                };
+                rule.extend(args(&["--dport", &range]));
+            }
+            rule.extend(args(&["-j", "ACCEPT"]));
+            rules.push(rule);
+        }
+        rules
     }
 
-    /// Remove the kill switch chain from iptables.
+    /// Differences between the live chain (`iptables -S <chain>`, `None` if
+    /// it does not exist), the live OUTPUT chain and the planned `expected`
+    /// rules.
+    fn chain_drift(
+        program: &str,
+        expected: &[Vec<String>],
+        chain: Option<&str>,
+        output: &str,
+    ) -> Vec<String> {
+        let Some(chain) = chain else {
+            return vec![format!("{program}: chain {CHAIN_NAME} is missing")];
+        };
+
+        let mut drift = Vec::new();
+        let prefix = format!("-A {CHAIN_NAME} ");
+        let live: Vec<String> = chain
+            .lines()
+            .filter_map(|l| l.strip_prefix(&prefix))
+            .map(normalize_rule)
+            .collect();
+        let want: Vec<String> = expected
+            .iter()
+            .map(|r| normalize_rule(&r.join(" ")))
+            .collect();
+        if live != want {
+            for rule in want.iter().filter(|r| !live.contains(r)) {
+                drift.push(format!("{program}: missing rule '{rule}'"));
+            }
+            for rule in live.iter().filter(|r| !want.contains(r)) {
+                drift.push(format!("{program}: unexpected rule '{rule}'"));
+            }
+            if drift.is_empty() {
+                drift.push(format!(
+                    "{program}: rules in {CHAIN_NAME} are reordered or duplicated"
+                ));
+            }
+        }
+
+        // Our jump must come first in OUTPUT, after at most the DNS block
+        let ours = format!("-j {CHAIN_NAME}");
+        let dns = format!("-j {DNS_CHAIN_NAME}");
+        let jumps: Vec<&str> = output
+            .lines()
+            .filter_map(|l| l.strip_prefix("-A OUTPUT "))
+            .map(str::trim)
+            .collect();
+        if !jumps.contains(&ours.as_str()) {
+            drift.push(format!("{program}: OUTPUT no longer jumps to {CHAIN_NAME}"));
+        } else if jumps.iter().find(|j| **j != dns) != Some(&ours.as_str()) {
+            drift.push(format!(
+                "{program}: other rules precede the {CHAIN_NAME} jump in OUTPUT"
+            ));
+        }
+        drift
+    }
+
+    /// Remove the kill switch chains from iptables and ip6tables.
     fn teardown_iptables() {
-        // Remove jump from OUTPUT chain (ignore error if not present)
-        let _ = Self::iptables(&["-D", "OUTPUT", "-j", CHAIN_NAME]);
+        for run in [Self::iptables, Self::ip6tables] {
+            // Remove jump from OUTPUT chain (ignore error if not present)
+            let _ = run(&["-D", "OUTPUT", "-j", CHAIN_NAME]);
 
-        // Flush and delete our custom chain
-        let _ = Self::iptables(&["-F", CHAIN_NAME]);
-        let _ = Self::iptables(&["-X", CHAIN_NAME]);
+            // Flush and delete our custom chain
+            let _ = run(&["-F", CHAIN_NAME]);
+            let _ = run(&["-X", CHAIN_NAME]);
+        }
     }
 
     // ─── nftables backend ───────────────────────────────────────────────
@@ -145,8 +418,30 @@ impl IptablesFirewall {
         }
     }
 
-    /// Set up the kill switch with nftables using an atomic ruleset load.
-    fn setup_nftables(vpn_interface: &str, vpn_server_ip: Option<&str>) -> Result<()> {
+    /// Plan for the kill switch as one atomic nftables ruleset load.
+    fn nft_plan(
+        vpn_interface: &str,
+        vpn_server_ip: Option<IpAddr>,
+        options: &KillSwitchConfig,
+    ) -> RulePlan {
+        let mut plan = RulePlan::new("nftables");
+        // Delete existing table first (fails if not present)
+        plan.try_run("nft", &["delete", "table", "inet", NFT_TABLE]);
+        plan.load(
+            "nft",
+            &["-f", "-"],
+            Self::nft_ruleset(vpn_interface, vpn_server_ip, options),
+        );
+        plan
+    }
+
+    /// Build the kill switch ruleset. The `inet` table filters IPv4 and IPv6
+    /// alike, so anything not accepted here is dropped for both families.
+    fn nft_ruleset(
+        vpn_interface: &str,
+        vpn_server_ip: Option<IpAddr>,
+        options: &KillSwitchConfig,
+    ) -> String {
         use std::fmt::Write;
 
         // Build an atomic nft ruleset — applied in one shot so there's no
@@ -158,33 +453,116 @@ impl IptablesFirewall {
 
     # Allow loopback
     oifname "lo" accept
+"#,
+        );
 
-    # Allow VPN interface
-    oifname "{vpn_interface}" accept
+        if options.block_ipv6 {
+            ruleset.push_str("\n    # Block IPv6 entirely\n    meta nfproto ipv6 drop\n");
+        }
 
-    # Allow local networks (RFC1918)
-    ip daddr 192.168.0.0/16 accept
-    ip daddr 10.0.0.0/8 accept
-    ip daddr 172.16.0.0/12 accept
+        let _ = write!(
+            ruleset,
+            "\n    # Allow VPN interface\n    oifname \"{vpn_interface}\" accept\n"
+        );
+
+        if !options.block_lan {
+            ruleset.push_str("\n    # Allow local networks (RFC1918, IPv6 link-local)\n");
+            for net in LAN_V4 {
+                let _ = writeln!(ruleset, "    ip daddr {net} accept");
+            }
+            for net in LAN_V6 {
+                let _ = writeln!(ruleset, "    ip6 daddr {net} accept");
+            }
+        }
 
-    # Allow DHCP
+        ruleset.push_str(
+            r"
+    # Allow DHCP, DHCPv6 and IPv6 neighbor discovery
     udp sport 68 udp dport 67 accept
-"#,
+    udp sport 546 udp dport 547 accept
+    icmpv6 type { nd-router-solicit, nd-router-advert, nd-neighbor-solicit, nd-neighbor-advert } accept
+",
         );
 
+        let cidrs = options.allowed_cidrs();
+        let ports = options.allowed_ports();
+        if !cidrs.is_empty() || !ports.is_empty() {
+            ruleset.push_str("\n    # Configured exceptions\n");
+        }
+        for net in cidrs {
+            let family = if net.is_ipv6() { "ip6" } else { "ip" };
+            let _ = writeln!(ruleset, "    {family} daddr {net} accept");
+        }
+        for port in ports {
+            let _ = match (port.protocol, port.ports) {
+                (PortProtocol::Icmp, _) => {
+                    writeln!(ruleset, "    meta l4proto {{ icmp, ipv6-icmp }} accept")
+                }
+                (protocol, None) => {
+                    writeln!(ruleset, "    meta l4proto {} accept", protocol.as_str())
+                }
+                (protocol, Some((lo, hi))) if lo == hi => {
+                    writeln!(ruleset, "    {} dport {lo} accept", protocol.as_str())
+                }
+                (protocol, Some((lo, hi))) => {
+                    writeln!(ruleset, "    {} dport {lo}-{hi} accept", protocol.as_str())
+                }
+            };
+        }
+
         if let Some(ip) = vpn_server_ip {
+            let family = if ip.is_ipv4() { "ip" } else { "ip6" };
             let _ = write!(
                 ruleset,
-                "\n    # Allow VPN server for reconnection\n    ip daddr {ip} accept\n"
+                "\n    # Allow VPN server for reconnection\n    {family} daddr {ip} accept\n"
             );
         }
 
         ruleset.push_str("  }\n}\n");
+        ruleset
+    }
+
+    /// Differences between the live table (`nft list table`, `None` if it
+    /// does not exist) and the `expected` ruleset.
+    fn nft_drift(expected: &str, live: Option<&str>) -> Vec<String> {
+        let Some(live) = live else {
+            return vec![format!("nft: table inet {NFT_TABLE} is missing")];
+        };
 
-        // Delete existing table first (ignore error if not present)
+        let mut drift = Vec::new();
+        if !live
+            .lines()
+            .any(|l| l.trim().starts_with("type ") && l.contains("policy drop"))
+        {
+            drift.push("nft: output chain no longer drops by default".to_string());
+        }
+
+        let live = nft_statements(live);
+        let want = nft_statements(expected);
+        if live != want {
+            for rule in want.iter().filter(|r| !live.contains(r)) {
+                drift.push(format!("nft: missing rule '{rule}'"));
+            }
+            for rule in live.iter().filter(|r| !want.contains(r)) {
+                drift.push(format!("nft: unexpected rule '{rule}'"));
+            }
+            if drift.is_empty() {
+                drift.push(format!(
+                    "nft: rules in {NFT_TABLE} are reordered or duplicated"
+                ));
+            }
+        }
+        drift
+    }
+
+    /// Remove the kill switch nftables table.
+    fn teardown_nftables() {
+        // Deleting the table removes all chains and rules inside it
         let _ = Self::nft(&["delete", "table", "inet", NFT_TABLE]);
+    }
 
-        // Apply the full ruleset atomically via stdin
+    /// Load an nft ruleset atomically from stdin.
+    fn nft_load(ruleset: &str) -> Result<()> {
         let mut child = Command::new("nft")
             .arg("-f")
             .arg("-")
@@ -203,24 +581,171 @@ impl IptablesFirewall {
             .wait()
             .map_err(|e| KillSwitchError::CommandFailed(format!("nft wait: {e}")))?;
 
-        if !status.success() {
-            return Err(KillSwitchError::CommandFailed(
+        if status.success() {
+            Ok(())
+        } else {
+            Err(KillSwitchError::CommandFailed(
                 "nft failed to load ruleset".to_string(),
-            ));
+            ))
         }
+    }
+
+    // ─── outside-DNS block ──────────────────────────────────────────────
+
+    /// Rules of the DNS block chain, in order (arguments after `-A <chain>`).
+    /// The same rules serve iptables and ip6tables.
+    fn dns_rules(vpn_interface: &str) -> Vec<Vec<String>> {
+        vec![
+            args(&["-o", "lo", "-j", "RETURN"]),
+            args(&["-o", vpn_interface, "-j", "RETURN"]),
+            args(&["-p", "udp", "--dport", "53", "-j", "DROP"]),
+            args(&["-p", "tcp", "--dport", "53", "-j", "DROP"]),
+        ]
+    }
 
+    /// Set up the DNS block chain with iptables and, when available,
+    /// ip6tables.
+    fn setup_dns_iptables(vpn_interface: &str) -> Result<()> {
+        let rules = Self::dns_rules(vpn_interface);
+        Self::setup_dns_chain(Self::iptables, &rules)?;
+        if Self::ip6tables(&["--version"]).is_ok() {
+            Self::setup_dns_chain(Self::ip6tables, &rules)?;
+        } else {
+            logger::log(
+                LogLevel::Warning,
+                "FIREWALL",
+                "ip6tables not found - DNS over IPv6 is not blocked",
+            );
+        }
         Ok(())
     }
 
-    /// Remove the kill switch nftables table.
-    fn teardown_nftables() {
-        // Deleting the table removes all chains and rules inside it
-        let _ = Self::nft(&["delete", "table", "inet", NFT_TABLE]);
+    /// (Re)create the DNS block chain with `run` and jump to it from the top
+    /// of OUTPUT.
+    fn setup_dns_chain(
+        run: fn(&[&str]) -> std::result::Result<(), String>,
+        rules: &[Vec<String>],
+    ) -> Result<()> {
+        let _ = run(&["-N", DNS_CHAIN_NAME]);
+        run(&["-F", DNS_CHAIN_NAME])
+            .map_err(|e| KillSwitchError::CommandFailed(format!("flush DNS chain: {e}")))?;
+
+        for rule in rules {
+            let mut args = vec!["-A", DNS_CHAIN_NAME];
+            args.extend(rule.iter().map(String::as_str));
+            run(&args).map_err(|e| KillSwitchError::CommandFailed(format!("DNS rule: {e}")))?;
+        }
+
+        let _ = run(&["-D", "OUTPUT", "-j", DNS_CHAIN_NAME]);
+        run(&["-I", "OUTPUT", "1", "-j", DNS_CHAIN_NAME])
+            .map_err(|e| KillSwitchError::CommandFailed(format!("insert DNS jump: {e}")))
+    }
+
+    /// Build the DNS block ruleset. Like the kill switch table it is an
+    /// `inet` table, so the drops apply to IPv4 and IPv6 alike.
+    fn nft_dns_ruleset(vpn_interface: &str) -> String {
+        format!(
+            r#"table inet {NFT_DNS_TABLE} {{
+  chain output {{
+    type filter hook output priority -1; policy accept;
+
+    oifname "lo" accept
+    oifname "{vpn_interface}" accept
+    udp dport 53 drop
+    tcp dport 53 drop
+  }}
+}}
+"#
+        )
+    }
+
+    /// Set up the DNS block with nftables. A drop in any base chain is final,
+    /// so no ordering against the kill switch table is needed.
+    fn setup_dns_nftables(vpn_interface: &str) -> Result<()> {
+        let _ = Self::nft(&["delete", "table", "inet", NFT_DNS_TABLE]);
+        Self::nft_load(&Self::nft_dns_ruleset(vpn_interface))
     }
 }
 
 impl Firewall for IptablesFirewall {
-    fn enable_blocking(vpn_interface: &str, vpn_server_ip: Option<&str>) -> Result<()> {
+    fn plan_blocking(
+        vpn_interface: &str,
+        vpn_server_ip: Option<&str>,
+        options: &KillSwitchConfig,
+    ) -> Result<RulePlan> {
+        let server_ip = parse_server(vpn_server_ip);
+
+        match Self::detect_backend() {
+            Some(FirewallBackend::Iptables) => {
+                logger::log(LogLevel::Debug, "FIREWALL", "Using iptables backend");
+                let with_ipv6 = Self::ip6tables(&["--version"]).is_ok();
+                if !with_ipv6 {
+                    logger::log(
+                        LogLevel::Warning,
+                        "FIREWALL",
+                        "ip6tables not found - IPv6 traffic is not covered by the kill switch",
+                    );
+                }
+                Ok(Self::iptables_plan(
+                    vpn_interface,
+                    server_ip,
+                    options,
+                    with_ipv6,
+                ))
+            }
+            Some(FirewallBackend::Nftables) => {
+                logger::log(LogLevel::Debug, "FIREWALL", "Using nftables backend");
+                Ok(Self::nft_plan(vpn_interface, server_ip, options))
+            }
+            None => Err(KillSwitchError::CommandFailed(
+                "Neither iptables nor nft found on this system".to_string(),
+            )),
+        }
+    }
+
+    fn verify_blocking(
+        vpn_interface: &str,
+        vpn_server_ip: Option<&str>,
+        options: &KillSwitchConfig,
+    ) -> Result<Vec<String>> {
+        if !crate::utils::is_root() {
+            return Err(KillSwitchError::NotRoot);
+        }
+
+        let server_ip = parse_server(vpn_server_ip);
+        match Self::detect_backend() {
+            Some(FirewallBackend::Iptables) => {
+                let mut drift = Self::chain_drift(
+                    "iptables",
+                    &Self::iptables_rules(vpn_interface, server_ip, options),
+                    Self::capture("iptables", &["-S", CHAIN_NAME]).as_deref(),
+                    &Self::capture("iptables", &["-S", "OUTPUT"]).unwrap_or_default(),
+                );
+                if Self::ip6tables(&["--version"]).is_ok() {
+                    drift.extend(Self::chain_drift(
+                        "ip6tables",
+                        &Self::ip6tables_rules(vpn_interface, server_ip, options),
+                        Self::capture("ip6tables", &["-S", CHAIN_NAME]).as_deref(),
+                        &Self::capture("ip6tables", &["-S", "OUTPUT"]).unwrap_or_default(),
+                    ));
+                }
+                Ok(drift)
+            }
+            Some(FirewallBackend::Nftables) => Ok(Self::nft_drift(
+                &Self::nft_ruleset(vpn_interface, server_ip, options),
+                Self::capture("nft", &["list", "table", "inet", NFT_TABLE]).as_deref(),
+            )),
+            None => Err(KillSwitchError::CommandFailed(
+                "Neither iptables nor nft found on this system".to_string(),
+            )),
+        }
+    }
+
+    fn enable_blocking(
+        vpn_interface: &str,
+        vpn_server_ip: Option<&str>,
+        options: &KillSwitchConfig,
+    ) -> Result<()> {
         logger::log(
             LogLevel::Info,
             "FIREWALL",
@@ -242,20 +767,13 @@ impl Firewall for IptablesFirewall {
             return Err(KillSwitchError::NotRoot);
         }
 
-        match Self::detect_backend() {
-            Some(FirewallBackend::Iptables) => {
-                logger::log(LogLevel::Debug, "FIREWALL", "Using iptables backend");
-                Self::setup_iptables(vpn_interface, vpn_server_ip)?;
-            }
-            Some(FirewallBackend::Nftables) => {
-                logger::log(LogLevel::Debug, "FIREWALL", "Using nftables backend");
-                Self::setup_nftables(vpn_interface, vpn_server_ip)?;
-            }
-            None => {
-                return Err(KillSwitchError::CommandFailed(
-                    "Neither iptables nor nft found on this system".to_string(),
-                ));
-            }
+        if let Err(e) = Self::plan_blocking(vpn_interface, vpn_server_ip, options)?.execute() {
+            logger::log(
+                LogLevel::Error,
+                "FIREWALL",
+                format!("Failed to load kill switch rules: {e}"),
+            );
+            return Err(e);
         }
 
         logger::log(
@@ -289,4 +807,377 @@ impl Firewall for IptablesFirewall {
         );
         Ok(())
     }
+
+    fn enable_dns_block(vpn_interface: &str) -> Result<()> {
+        if !crate::utils::is_root() {
+            return Err(KillSwitchError::NotRoot);
+        }
+
+        match Self::detect_backend() {
+            Some(FirewallBackend::Iptables) => Self::setup_dns_iptables(vpn_interface)?,
+            Some(FirewallBackend::Nftables) => Self::setup_dns_nftables(vpn_interface)?,
+            None => {
+                return Err(KillSwitchError::CommandFailed(
+                    "Neither iptables nor nft found on this system".to_string(),
+                ));
+            }
+        }
+
+        logger::log(
+            LogLevel::Info,
+            "FIREWALL",
+            format!("Blocking DNS outside '{vpn_interface}'"),
+        );
+        Ok(())
+    }
+
+    fn disable_dns_block() -> Result<()> {
+        if !crate::utils::is_root() {
+            return Err(KillSwitchError::NotRoot);
+        }
+
+        for run in [Self::iptables, Self::ip6tables] {
+            let _ = run(&["-D", "OUTPUT", "-j", DNS_CHAIN_NAME]);
+            let _ = run(&["-F", DNS_CHAIN_NAME]);
+            let _ = run(&["-X", DNS_CHAIN_NAME]);
+        }
+        let _ = Self::nft(&["delete", "table", "inet", NFT_DNS_TABLE]);
+
+        logger::log(LogLevel::Info, "FIREWALL", "DNS block removed");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block_ipv6() -> KillSwitchConfig {
+        KillSwitchConfig {
+            block_ipv6: true,
+            ..KillSwitchConfig::default()
+        }
+    }
+
+    fn office() -> KillSwitchConfig {
+        KillSwitchConfig {
+            block_lan: true,
+            allow_cidrs: vec!["100.64.0.0/10".to_string(), "fd7a::/48".to_string()],
+            allow_ports: vec![
+                "udp/5353".to_string(),
+                "tcp/8000-8100".to_string(),
+                "icmp".to_string(),
+            ],
+            ..KillSwitchConfig::default()
+        }
+    }
+
+    fn joined(rules: &[Vec<String>]) -> Vec<String> {
+        rules.iter().map(|r| r.join(" ")).collect()
+    }
+
+    #[test]
+    fn test_iptables_rules_default() {
+        let server: IpAddr = "1.2.3.4".parse().unwrap();
+        let rules =
+            IptablesFirewall::iptables_rules("wg0", Some(server), &KillSwitchConfig::default());
+        assert_eq!(
+            joined(&rules),
+            vec![
+                "-o lo -j ACCEPT",
+                "-o wg0 -j ACCEPT",
+                "-d 192.168.0.0/16 -j ACCEPT",
+                "-d 10.0.0.0/8 -j ACCEPT",
+                "-d 172.16.0.0/12 -j ACCEPT",
+                "-p udp --sport 68 --dport 67 -j ACCEPT",
+                "-d 1.2.3.4 -p udp -j ACCEPT",
+                "-d 1.2.3.4 -p tcp -j ACCEPT",
+                "-j DROP",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_iptables_rules_block_lan_with_exceptions() {
+        let rules = IptablesFirewall::iptables_rules("wg0", None, &office());
+        assert_eq!(
+            joined(&rules),
+            vec![
+                "-o lo -j ACCEPT",
+                "-o wg0 -j ACCEPT",
+                "-p udp --sport 68 --dport 67 -j ACCEPT",
+                "-d 100.64.0.0/10 -j ACCEPT",
+                "-p udp --dport 5353 -j ACCEPT",
+                "-p tcp --dport 8000:8100 -j ACCEPT",
+                "-p icmp -j ACCEPT",
+                "-j DROP",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ip6tables_rules_block_lan_with_exceptions() {
+        let rules = joined(&IptablesFirewall::ip6tables_rules("wg0", None, &office()));
+        assert!(!rules.contains(&"-d fe80::/10 -j ACCEPT".to_string()));
+        assert!(rules.contains(&"-d fd7a::/48 -j ACCEPT".to_string()));
+        assert!(!rules.contains(&"-d 100.64.0.0/10 -j ACCEPT".to_string()));
+        assert!(rules.contains(&"-p ipv6-icmp -j ACCEPT".to_string()));
+        assert!(rules.contains(&"-p udp --sport 546 --dport 547 -j ACCEPT".to_string()));
+    }
+
+    #[test]
+    fn test_ip6tables_rules_allow_link_local_and_nd() {
+        let server: IpAddr = "2001:db8::1".parse().unwrap();
+        let rules =
+            IptablesFirewall::ip6tables_rules("wg0", Some(server), &KillSwitchConfig::default());
+        let joined = joined(&rules);
+
+        assert_eq!(joined[0], "-o lo -j ACCEPT");
+        assert!(joined.contains(&"-o wg0 -j ACCEPT".to_string()));
+        assert!(joined.contains(&"-d fe80::/10 -j ACCEPT".to_string()));
+        assert!(joined
+            .contains(&"-p ipv6-icmp --icmpv6-type neighbour-solicitation -j ACCEPT".to_string()));
+        assert!(joined.contains(&"-d 2001:db8::1 -p udp -j ACCEPT".to_string()));
+        assert_eq!(joined.last().unwrap(), "-j DROP");
+    }
+
+    #[test]
+    fn test_ip6tables_rules_block_ipv6_entirely() {
+        let rules = IptablesFirewall::ip6tables_rules("wg0", None, &block_ipv6());
+        assert_eq!(joined(&rules), vec!["-o lo -j ACCEPT", "-j DROP"]);
+    }
+
+    #[test]
+    fn test_iptables_plan_installs_both_chains() {
+        let plan = IptablesFirewall::iptables_plan("wg0", None, &block_ipv6(), true);
+        let commands = plan.commands();
+        assert_eq!(plan.backend, "iptables");
+        assert_eq!(commands[0], "iptables -N VORTIX_KILLSWITCH");
+        assert_eq!(commands[1], "iptables -F VORTIX_KILLSWITCH");
+        assert_eq!(commands[2], "iptables -A VORTIX_KILLSWITCH -o lo -j ACCEPT");
+        assert!(commands.contains(&"iptables -I OUTPUT 1 -j VORTIX_KILLSWITCH".to_string()));
+        assert!(commands.contains(&"ip6tables -A VORTIX_KILLSWITCH -j DROP".to_string()));
+        assert!(commands.contains(&"ip6tables -I OUTPUT 1 -j VORTIX_KILLSWITCH".to_string()));
+        assert_eq!(
+            commands.last().unwrap(),
+            "ip6tables -I OUTPUT 1 -j VORTIX_DNS"
+        );
+
+        let script = plan.to_string();
+        assert!(script.contains("iptables -N VORTIX_KILLSWITCH 2>/dev/null || true\n"));
+        assert!(script.contains("\niptables -F VORTIX_KILLSWITCH\n"));
+    }
+
+    #[test]
+    fn test_iptables_plan_without_ip6tables() {
+        let plan =
+            IptablesFirewall::iptables_plan("wg0", None, &KillSwitchConfig::default(), false);
+        assert!(plan.commands().iter().all(|c| c.starts_with("iptables ")));
+        assert!(plan.to_string().contains("# ip6tables not found"));
+    }
+
+    #[test]
+    fn test_nft_plan_loads_ruleset_atomically() {
+        let plan = IptablesFirewall::nft_plan("wg0", None, &KillSwitchConfig::default());
+        assert_eq!(
+            plan.commands(),
+            vec!["nft delete table inet vortix_killswitch", "nft -f -"]
+        );
+        let script = plan.to_string();
+        assert!(script.contains("nft -f - <<'EOF'\ntable inet vortix_killswitch {"));
+        assert!(script.ends_with("}\nEOF\n"));
+    }
+
+    #[test]
+    fn test_chain_drift_accepts_saved_rules() {
+        let server: IpAddr = "1.2.3.4".parse().unwrap();
+        let rules =
+            IptablesFirewall::iptables_rules("wg0", Some(server), &KillSwitchConfig::default());
+        let saved = "-N VORTIX_KILLSWITCH
+-A VORTIX_KILLSWITCH -o lo -j ACCEPT
+-A VORTIX_KILLSWITCH -o wg0 -j ACCEPT
+-A VORTIX_KILLSWITCH -d 192.168.0.0/16 -j ACCEPT
+-A VORTIX_KILLSWITCH -d 10.0.0.0/8 -j ACCEPT
+-A VORTIX_KILLSWITCH -d 172.16.0.0/12 -j ACCEPT
+-A VORTIX_KILLSWITCH -p udp -m udp --sport 68 --dport 67 -j ACCEPT
+-A VORTIX_KILLSWITCH -d 1.2.3.4/32 -p udp -j ACCEPT
+-A VORTIX_KILLSWITCH -d 1.2.3.4/32 -p tcp -j ACCEPT
+-A VORTIX_KILLSWITCH -j DROP
+";
+        let output = "-P OUTPUT ACCEPT
+-A OUTPUT -j VORTIX_DNS
+-A OUTPUT -j VORTIX_KILLSWITCH
+-A OUTPUT -j DOCKER-USER
+";
+        assert!(IptablesFirewall::chain_drift("iptables", &rules, Some(saved), output).is_empty());
+    }
+
+    #[test]
+    fn test_chain_drift_detects_flushed_and_bypassed_chain() {
+        let rules = IptablesFirewall::iptables_rules("wg0", None, &KillSwitchConfig::default());
+        let output = "-P OUTPUT ACCEPT\n-A OUTPUT -j VORTIX_KILLSWITCH\n";
+        assert_eq!(
+            IptablesFirewall::chain_drift("iptables", &rules, None, output),
+            vec!["iptables: chain VORTIX_KILLSWITCH is missing"]
+        );
+
+        let flushed = "-N VORTIX_KILLSWITCH\n";
+        let drift = IptablesFirewall::chain_drift("iptables", &rules, Some(flushed), "");
+        assert!(drift.contains(&"iptables: missing rule '-j DROP'".to_string()));
+        assert_eq!(
+            drift.last().unwrap(),
+            "iptables: OUTPUT no longer jumps to VORTIX_KILLSWITCH"
+        );
+
+        let saved: String = std::iter::once("-N VORTIX_KILLSWITCH".to_string())
+            .chain(
+                rules
+                    .iter()
+                    .map(|r| format!("-A VORTIX_KILLSWITCH {}", r.join(" "))),
+            )
+            .chain(std::iter::once(
+                "-A VORTIX_KILLSWITCH -j ACCEPT".to_string(),
+            ))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let output = "-A OUTPUT -j ACCEPT\n-A OUTPUT -j VORTIX_KILLSWITCH\n";
+        assert_eq!(
+            IptablesFirewall::chain_drift("iptables", &rules, Some(&saved), output),
+            vec![
+                "iptables: unexpected rule '-j ACCEPT'",
+                "iptables: other rules precede the VORTIX_KILLSWITCH jump in OUTPUT",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_normalize_rule_maps_icmpv6_types() {
+        assert_eq!(
+            normalize_rule("-p ipv6-icmp --icmpv6-type neighbour-solicitation -j ACCEPT"),
+            normalize_rule("-p ipv6-icmp -m icmp6 --icmpv6-type 135 -j ACCEPT")
+        );
+        assert_eq!(
+            normalize_rule("-d 2001:db8::1/128 -p udp -j ACCEPT"),
+            "-d 2001:db8::1 -p udp -j ACCEPT"
+        );
+    }
+
+    #[test]
+    fn test_nft_drift() {
+        let expected = IptablesFirewall::nft_ruleset("wg0", None, &KillSwitchConfig::default());
+        // `nft list` output: tabs, symbolic priority, no comments
+        let listed: String = expected
+            .lines()
+            .filter(|l| !l.trim_start().starts_with('#'))
+            .map(|l| {
+                l.replace("priority 0", "priority filter")
+                    .replace("  ", "\t")
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert!(IptablesFirewall::nft_drift(&expected, Some(&listed)).is_empty());
+
+        assert_eq!(
+            IptablesFirewall::nft_drift(&expected, None),
+            vec!["nft: table inet vortix_killswitch is missing"]
+        );
+
+        let tampered = listed
+            .replace("policy drop", "policy accept")
+            .replace("\t\toifname \"wg0\" accept\n", "");
+        assert_eq!(
+            IptablesFirewall::nft_drift(&expected, Some(&tampered)),
+            vec![
+                "nft: output chain no longer drops by default",
+                "nft: missing rule 'oifname \"wg0\" accept'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_nft_drift_host_exceptions() {
+        let options = KillSwitchConfig {
+            allow_cidrs: vec!["192.0.2.10/32".to_string(), "2001:db8::10".to_string()],
+            ..KillSwitchConfig::default()
+        };
+        let expected = IptablesFirewall::nft_ruleset("wg0", None, &options);
+        // `nft list` prints single hosts without a prefix length
+        let listed = expected
+            .replace("192.0.2.10/32", "192.0.2.10")
+            .replace("2001:db8::10/128", "2001:db8::10");
+        assert!(listed.contains("ip daddr 192.0.2.10 accept"));
+        assert!(listed.contains("ip6 daddr 2001:db8::10 accept"));
+        assert!(IptablesFirewall::nft_drift(&expected, Some(&listed)).is_empty());
+    }
+
+    #[test]
+    fn test_nft_ruleset_covers_ipv6() {
+        let server: IpAddr = "2001:db8::1".parse().unwrap();
+        let rules =
+            IptablesFirewall::nft_ruleset("wg0", Some(server), &KillSwitchConfig::default());
+        assert!(rules.contains("table inet vortix_killswitch"));
+        assert!(rules.contains("policy drop"));
+        assert!(rules.contains("ip6 daddr fe80::/10 accept"));
+        assert!(rules.contains("nd-neighbor-solicit"));
+        assert!(rules.contains("ip6 daddr 2001:db8::1 accept"));
+        assert!(!rules.contains("meta nfproto ipv6 drop"));
+    }
+
+    #[test]
+    fn test_nft_ruleset_block_ipv6_precedes_tunnel() {
+        let rules = IptablesFirewall::nft_ruleset("wg0", None, &block_ipv6());
+        let drop = rules.find("meta nfproto ipv6 drop").unwrap();
+        let tunnel = rules.find(r#"oifname "wg0" accept"#).unwrap();
+        assert!(drop < tunnel);
+    }
+
+    #[test]
+    fn test_nft_ruleset_block_lan_with_exceptions() {
+        let rules = IptablesFirewall::nft_ruleset("wg0", None, &office());
+        assert!(!rules.contains("192.168.0.0/16"));
+        assert!(!rules.contains("fe80::/10"));
+        assert!(rules.contains("udp sport 68 udp dport 67 accept"));
+        assert!(rules.contains("ip daddr 100.64.0.0/10 accept"));
+        assert!(rules.contains("ip6 daddr fd7a::/48 accept"));
+        assert!(rules.contains("udp dport 5353 accept"));
+        assert!(rules.contains("tcp dport 8000-8100 accept"));
+        assert!(rules.contains("meta l4proto { icmp, ipv6-icmp } accept"));
+    }
+
+    #[test]
+    fn test_dns_rules_drop_outside_tunnel() {
+        assert_eq!(
+            joined(&IptablesFirewall::dns_rules("wg0")),
+            vec![
+                "-o lo -j RETURN",
+                "-o wg0 -j RETURN",
+                "-p udp --dport 53 -j DROP",
+                "-p tcp --dport 53 -j DROP",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_iptables_plan_keeps_dns_block_first_for_both_families() {
+        let plan = IptablesFirewall::iptables_plan("wg0", None, &KillSwitchConfig::default(), true);
+        let commands = plan.commands();
+        for program in ["iptables", "ip6tables"] {
+            let ours = format!("{program} -I OUTPUT 1 -j VORTIX_KILLSWITCH");
+            let dns = format!("{program} -I OUTPUT 1 -j VORTIX_DNS");
+            let ours = commands.iter().position(|c| *c == ours).unwrap();
+            let dns = commands.iter().position(|c| *c == dns).unwrap();
+            // Inserted last, so it ends up in front
+            assert!(dns > ours);
+        }
+    }
+
+    #[test]
+    fn test_nft_dns_ruleset_covers_ipv6() {
+        let rules = IptablesFirewall::nft_dns_ruleset("wg0");
+        assert!(rules.contains("table inet vortix_dns"));
+        assert!(rules.contains("policy accept"));
+        assert!(rules.contains(r#"oifname "wg0" accept"#));
+        assert!(rules.contains("udp dport 53 drop"));
+        assert!(rules.contains("tcp dport 53 drop"));
+    }
 }
@@ -1,4 +1,4 @@
-//! Linux VPN interface detection via `ip addr` and `wg show`.
+//! Linux VPN interface detection via netlink, `ip addr` and `wg show`.
 
 use crate::core::telemetry::parse_ip_addr_output;
 use crate::platform::InterfaceDetector;
@@ -84,6 +84,12 @@ impl InterfaceDetector for LinuxInterface {
 }
 
 fn check_wg_interface_exists(name: &str) -> bool {
+    use super::wireguard::NetlinkWireGuard;
+    use crate::platform::WireGuardControl;
+
+    if NetlinkWireGuard::device(name).is_ok() {
+        return true;
+    }
     cmd_output(Command::new("wg").args(["show", name, "public-key"]))
         .is_some_and(|o| o.status.success())
 }
@@ -1,8 +1,12 @@
 //! Linux platform implementations.
 //!
-//! Uses iptables/nftables, /proc/net/dev, ip addr, and resolvectl.
+//! Uses iptables/nftables, /proc/net/dev, ip addr, resolvectl, rtnetlink, and
+//! generic netlink for `WireGuard`.
 
 pub mod dns;
 pub mod firewall;
 pub mod interface;
+mod netlink;
+pub mod netwatch;
 pub mod network;
+pub mod wireguard;
@@ -0,0 +1,366 @@
+//! Minimal netlink plumbing shared by the rtnetlink watcher and the native
+//! `WireGuard` backend: message building, attribute parsing and a raw
+//! `AF_NETLINK` socket.
+
+use std::io;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+
+// Fixed header sizes from <linux/netlink.h>.
+pub const NLMSG_HDR_LEN: usize = 16;
+pub const NLA_HDR_LEN: usize = 4;
+
+// <linux/netlink.h> message types and flags, typed to match the header fields.
+pub const NLMSG_ERROR: u16 = 2;
+pub const NLMSG_DONE: u16 = 3;
+pub const NLM_F_REQUEST: u16 = 0x1;
+pub const NLM_F_ACK: u16 = 0x4;
+pub const NLM_F_DUMP: u16 = 0x300;
+pub const NLM_F_EXCL: u16 = 0x200;
+pub const NLM_F_CREATE: u16 = 0x400;
+pub const NLA_F_NESTED: u16 = 0x8000;
+/// Mask that strips `NLA_F_NESTED` / `NLA_F_NET_BYTEORDER` from an attribute type.
+pub const NLA_TYPE_MASK: u16 = 0x3fff;
+
+pub const fn align4(len: usize) -> usize {
+    (len + 3) & !3
+}
+
+pub fn read_u16(buf: &[u8], at: usize) -> Option<u16> {
+    buf.get(at..at + 2)
+        .map(|b| u16::from_ne_bytes([b[0], b[1]]))
+}
+
+pub fn read_u32(buf: &[u8], at: usize) -> Option<u32> {
+    buf.get(at..at + 4)
+        .map(|b| u32::from_ne_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+pub fn read_u64(buf: &[u8], at: usize) -> Option<u64> {
+    let b: [u8; 8] = buf.get(at..at + 8)?.try_into().ok()?;
+    Some(u64::from_ne_bytes(b))
+}
+
+/// Iterate `(type, payload)` over the attributes in `buf`. The type has
+/// the nested/byte-order flags masked off.
+pub fn attributes(buf: &[u8]) -> impl Iterator<Item = (u16, &[u8])> {
+    let mut offset = 0;
+    std::iter::from_fn(move || {
+        let len = usize::from(read_u16(buf, offset)?);
+        let kind = read_u16(buf, offset + 2)? & NLA_TYPE_MASK;
+        if len < NLA_HDR_LEN || offset + len > buf.len() {
+            return None;
+        }
+        let payload = &buf[offset + NLA_HDR_LEN..offset + len];
+        offset += align4(len);
+        Some((kind, payload))
+    })
+}
+
+/// One message out of a datagram.
+pub struct Message<'a> {
+    pub kind: u16,
+    pub seq: u32,
+    /// Everything after the netlink header.
+    pub body: &'a [u8],
+}
+
+/// Iterate the messages in one datagram, stopping at the first truncated one.
+pub fn messages(buf: &[u8]) -> impl Iterator<Item = Message<'_>> {
+    let mut offset = 0;
+    std::iter::from_fn(move || {
+        let len = read_u32(buf, offset)? as usize;
+        let kind = read_u16(buf, offset + 4)?;
+        let seq = read_u32(buf, offset + 8)?;
+        if len < NLMSG_HDR_LEN || offset + len > buf.len() {
+            return None;
+        }
+        let body = &buf[offset + NLMSG_HDR_LEN..offset + len];
+        offset += align4(len);
+        Some(Message { kind, seq, body })
+    })
+}
+
+/// Builds one netlink request: header, fixed family header, attributes.
+pub struct MessageBuilder {
+    buf: Vec<u8>,
+    nests: Vec<usize>,
+}
+
+impl MessageBuilder {
+    /// Start a request of `kind`; `NLM_F_REQUEST` is always set.
+    pub fn new(kind: u16, flags: u16) -> Self {
+        let mut buf = Vec::with_capacity(256);
+        buf.extend_from_slice(&0u32.to_ne_bytes()); // length, set in finish()
+        buf.extend_from_slice(&kind.to_ne_bytes());
+        buf.extend_from_slice(&(flags | NLM_F_REQUEST).to_ne_bytes());
+        buf.extend_from_slice(&0u32.to_ne_bytes()); // seq, set in finish()
+        buf.extend_from_slice(&0u32.to_ne_bytes()); // port id: the kernel fills it
+        Self {
+            buf,
+            nests: Vec::new(),
+        }
+    }
+
+    /// Append a fixed-size family header (`ifinfomsg`, `genlmsghdr`, ...).
+    pub fn header(&mut self, bytes: &[u8]) -> &mut Self {
+        self.buf.extend_from_slice(bytes);
+        self.pad();
+        self
+    }
+
+    /// Append an attribute.
+    pub fn attr(&mut self, kind: u16, value: &[u8]) -> &mut Self {
+        let len = u16::try_from(NLA_HDR_LEN + value.len()).unwrap_or(u16::MAX);
+        self.buf.extend_from_slice(&len.to_ne_bytes());
+        self.buf.extend_from_slice(&kind.to_ne_bytes());
+        self.buf.extend_from_slice(value);
+        self.pad();
+        self
+    }
+
+    pub fn attr_u8(&mut self, kind: u16, value: u8) -> &mut Self {
+        self.attr(kind, &[value])
+    }
+
+    pub fn attr_u16(&mut self, kind: u16, value: u16) -> &mut Self {
+        self.attr(kind, &value.to_ne_bytes())
+    }
+
+    pub fn attr_u32(&mut self, kind: u16, value: u32) -> &mut Self {
+        self.attr(kind, &value.to_ne_bytes())
+    }
+
+    /// Append a NUL-terminated string attribute.
+    pub fn attr_str(&mut self, kind: u16, value: &str) -> &mut Self {
+        let mut bytes = value.as_bytes().to_vec();
+        bytes.push(0);
+        self.attr(kind, &bytes)
+    }
+
+    /// Open a nested attribute; close it with [`Self::end_nested`].
+    pub fn begin_nested(&mut self, kind: u16) -> &mut Self {
+        self.nests.push(self.buf.len());
+        self.buf.extend_from_slice(&0u16.to_ne_bytes());
+        self.buf
+            .extend_from_slice(&(kind | NLA_F_NESTED).to_ne_bytes());
+        self
+    }
+
+    pub fn end_nested(&mut self) -> &mut Self {
+        if let Some(start) = self.nests.pop() {
+            let len = u16::try_from(self.buf.len() - start).unwrap_or(u16::MAX);
+            self.buf[start..start + 2].copy_from_slice(&len.to_ne_bytes());
+        }
+        self
+    }
+
+    /// Finish the message with sequence number `seq`.
+    pub fn finish(&mut self, seq: u32) -> Vec<u8> {
+        let len = u32::try_from(self.buf.len()).unwrap_or(u32::MAX);
+        self.buf[0..4].copy_from_slice(&len.to_ne_bytes());
+        self.buf[8..12].copy_from_slice(&seq.to_ne_bytes());
+        std::mem::take(&mut self.buf)
+    }
+
+    fn pad(&mut self) {
+        self.buf.resize(align4(self.buf.len()), 0);
+    }
+}
+
+/// Next request sequence number (process-wide, so replies are never mixed up).
+fn next_seq() -> u32 {
+    static SEQ: AtomicU32 = AtomicU32::new(1);
+    SEQ.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Owned `AF_NETLINK` socket.
+pub struct NetlinkSocket(std::os::fd::OwnedFd);
+
+impl NetlinkSocket {
+    /// Open a socket for `protocol` (`NETLINK_ROUTE`, `NETLINK_GENERIC`),
+    /// subscribed to the multicast `groups` (0 for request/response use).
+    // libc constants and struct sizes always fit their C types
+    #[allow(unsafe_code, clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn open(protocol: libc::c_int, groups: u32) -> io::Result<Self> {
+        use std::os::fd::FromRawFd;
+
+        // SAFETY: plain socket(2) call; the fd is checked before use.
+        let fd = unsafe {
+            libc::socket(
+                libc::AF_NETLINK,
+                libc::SOCK_RAW | libc::SOCK_CLOEXEC,
+                protocol,
+            )
+        };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: `fd` is a freshly created, valid descriptor we own.
+        let socket = Self(unsafe { std::os::fd::OwnedFd::from_raw_fd(fd) });
+
+        // SAFETY: sockaddr_nl is plain old data; all-zero is a valid value.
+        let mut addr: libc::sockaddr_nl = unsafe { std::mem::zeroed() };
+        addr.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+        addr.nl_groups = groups;
+
+        // SAFETY: `addr` is a valid sockaddr_nl and the length matches it.
+        let rc = unsafe {
+            libc::bind(
+                socket.raw(),
+                std::ptr::addr_of!(addr).cast(),
+                std::mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+            )
+        };
+        if rc < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(socket)
+    }
+
+    fn raw(&self) -> libc::c_int {
+        use std::os::fd::AsRawFd;
+        self.0.as_raw_fd()
+    }
+
+    #[allow(unsafe_code, clippy::cast_possible_truncation)]
+    pub fn set_read_timeout(&self, timeout: Duration) -> io::Result<()> {
+        let tv = libc::timeval {
+            tv_sec: libc::time_t::try_from(timeout.as_secs()).unwrap_or(libc::time_t::MAX),
+            tv_usec: libc::suseconds_t::from(timeout.subsec_micros()),
+        };
+        // SAFETY: `tv` is a valid timeval and the length matches it.
+        let rc = unsafe {
+            libc::setsockopt(
+                self.raw(),
+                libc::SOL_SOCKET,
+                libc::SO_RCVTIMEO,
+                std::ptr::addr_of!(tv).cast(),
+                std::mem::size_of::<libc::timeval>() as libc::socklen_t,
+            )
+        };
+        if rc < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    #[allow(unsafe_code)]
+    pub fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
+        // SAFETY: `buf` is valid for writes of `buf.len()` bytes.
+        let n = unsafe { libc::recv(self.raw(), buf.as_mut_ptr().cast(), buf.len(), 0) };
+        usize::try_from(n).map_err(|_| io::Error::last_os_error())
+    }
+
+    #[allow(unsafe_code)]
+    fn send(&self, msg: &[u8]) -> io::Result<()> {
+        // SAFETY: `msg` is valid for reads of `msg.len()` bytes.
+        let n = unsafe { libc::send(self.raw(), msg.as_ptr().cast(), msg.len(), 0) };
+        if n < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    /// Send a request and collect the bodies of every reply message until
+    /// the final acknowledgement or end of dump.
+    ///
+    /// Non-dump requests must set `NLM_F_ACK` so the exchange terminates.
+    ///
+    /// # Errors
+    ///
+    /// Returns the kernel's error (e.g. `EEXIST`, `EPERM`) or a socket error.
+    pub fn request(&self, builder: &mut MessageBuilder) -> io::Result<Vec<Vec<u8>>> {
+        let seq = next_seq();
+        self.send(&builder.finish(seq))?;
+
+        let mut buf = vec![0u8; 32 * 1024];
+        let mut replies = Vec::new();
+        loop {
+            let len = self.recv(&mut buf)?;
+            for msg in messages(&buf[..len]).filter(|m| m.seq == seq) {
+                match msg.kind {
+                    NLMSG_DONE => return Ok(replies),
+                    NLMSG_ERROR => {
+                        // struct nlmsgerr: negative errno, 0 for an ACK
+                        let errno = msg
+                            .body
+                            .get(..4)
+                            .map_or(0, |b| i32::from_ne_bytes([b[0], b[1], b[2], b[3]]));
+                        return if errno == 0 {
+                            Ok(replies)
+                        } else {
+                            Err(io::Error::from_raw_os_error(-errno))
+                        };
+                    }
+                    _ => replies.push(msg.body.to_vec()),
+                }
+            }
+        }
+    }
+}
+
+/// Resolve an interface name to its index (`None` if there is no such link).
+#[allow(unsafe_code)]
+pub fn name_to_index(name: &str) -> Option<u32> {
+    let name = std::ffi::CString::new(name).ok()?;
+    // SAFETY: `name` is a valid NUL-terminated string.
+    let index = unsafe { libc::if_nametoindex(name.as_ptr()) };
+    (index != 0).then_some(index)
+}
+
+/// Resolve an interface index to its name (`None` once the link is gone).
+#[allow(unsafe_code)]
+pub fn index_to_name(ifindex: u32) -> Option<String> {
+    if ifindex == 0 {
+        return None;
+    }
+    let mut name = [0 as libc::c_char; libc::IF_NAMESIZE];
+    // SAFETY: `name` is IF_NAMESIZE bytes as required; the result is either
+    // null or a pointer into `name` holding a NUL-terminated string.
+    let ptr = unsafe { libc::if_indextoname(ifindex, name.as_mut_ptr()) };
+    if ptr.is_null() {
+        return None;
+    }
+    // SAFETY: non-null means `name` now holds a NUL-terminated string.
+    let cstr = unsafe { std::ffi::CStr::from_ptr(name.as_ptr()) };
+    Some(cstr.to_string_lossy().into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder_nests_and_pads() {
+        let mut builder = MessageBuilder::new(16, NLM_F_ACK);
+        builder.header(&[1, 2, 3]).begin_nested(8);
+        builder.attr_str(2, "wg0").attr_u16(6, 51820);
+        builder.end_nested();
+        let msg = builder.finish(7);
+
+        let parsed: Vec<Message> = messages(&msg).collect();
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].kind, 16);
+        assert_eq!(parsed[0].seq, 7);
+        assert_eq!(read_u16(&msg, 6), Some(NLM_F_REQUEST | NLM_F_ACK));
+
+        // Fixed header is padded to 4 bytes
+        let body = parsed[0].body;
+        assert_eq!(&body[..4], &[1, 2, 3, 0]);
+        let (kind, nested) = attributes(&body[4..]).next().unwrap();
+        assert_eq!(kind, 8);
+        assert_eq!(read_u16(&body[4..], 2), Some(8 | NLA_F_NESTED));
+
+        let inner: Vec<(u16, &[u8])> = attributes(nested).collect();
+        assert_eq!(inner[0], (2, &b"wg0\0"[..]));
+        assert_eq!(read_u16(inner[1].1, 0), Some(51820));
+    }
+
+    #[test]
+    fn test_messages_stop_at_truncation() {
+        let msg = MessageBuilder::new(16, 0).attr_u32(1, 5).finish(1);
+        assert_eq!(messages(&msg[..msg.len() - 1]).count(), 0);
+        assert_eq!(messages(&[]).count(), 0);
+    }
+}
@@ -0,0 +1,209 @@
+//! Linux network change notifications via rtnetlink.
+//!
+//! Subscribes a `NETLINK_ROUTE` socket to the link, address and route
+//! multicast groups. Notifications arrive in bursts (a Wi-Fi roam produces a
+//! dozen messages), so they are collected until the socket has been quiet for
+//! `NETWORK_CHANGE_DEBOUNCE_MS` and reported as one change.
+
+use super::netlink::{attributes, index_to_name, messages, read_u32, Message, NetlinkSocket};
+use crate::constants;
+use crate::platform::NetworkWatcher;
+use std::io;
+use std::time::Duration;
+
+/// Linux network watcher backed by an rtnetlink multicast socket.
+pub struct RtnetlinkWatcher;
+
+impl NetworkWatcher for RtnetlinkWatcher {
+    fn watch(on_change: &mut dyn FnMut(&[String]) -> bool) -> io::Result<()> {
+        let socket = NetlinkSocket::open(libc::NETLINK_ROUTE, multicast_groups())?;
+        socket.set_read_timeout(Duration::from_millis(constants::NETWORK_CHANGE_DEBOUNCE_MS))?;
+
+        let mut buf = vec![0u8; 16 * 1024];
+        let mut pending: Vec<String> = Vec::new();
+        let mut dirty = false;
+
+        loop {
+            match socket.recv(&mut buf) {
+                Ok(len) => {
+                    for change in parse_messages(&buf[..len]) {
+                        dirty = true;
+                        let name = change.ifname.or_else(|| index_to_name(change.ifindex));
+                        if let Some(name) = name {
+                            if name != "lo" && !pending.contains(&name) {
+                                pending.push(name);
+                            }
+                        }
+                    }
+                }
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                    // Quiet period elapsed: report the burst
+                    if dirty {
+                        dirty = false;
+                        if !on_change(&pending) {
+                            return Ok(());
+                        }
+                        pending.clear();
+                    }
+                }
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+/// Link, address and route multicast groups.
+// The RTMGRP_* constants are small positive bit flags
+#[allow(clippy::cast_sign_loss)]
+fn multicast_groups() -> u32 {
+    (libc::RTMGRP_LINK
+        | libc::RTMGRP_IPV4_IFADDR
+        | libc::RTMGRP_IPV6_IFADDR
+        | libc::RTMGRP_IPV4_ROUTE
+        | libc::RTMGRP_IPV6_ROUTE) as u32
+}
+
+/// A single link, address or route notification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Change {
+    /// Interface index the change refers to (0 if unknown).
+    ifindex: u32,
+    /// Interface name, when the message carries it (link messages only).
+    ifname: Option<String>,
+}
+
+// Fixed header sizes from <linux/rtnetlink.h>.
+const IFINFOMSG_LEN: usize = 16;
+const IFADDRMSG_LEN: usize = 8;
+const RTMSG_LEN: usize = 12;
+
+/// Parse one datagram of rtnetlink messages into the changes we care about.
+///
+/// Routes outside the main table are skipped: `wg-quick` installs its
+/// catch-all routes in a dedicated table, and those come and go with the
+/// tunnel itself rather than with the underlying network.
+fn parse_messages(buf: &[u8]) -> Vec<Change> {
+    let mut changes = Vec::new();
+
+    for Message { kind, body, .. } in messages(buf) {
+        let change = match kind {
+            libc::RTM_NEWLINK | libc::RTM_DELLINK if body.len() >= IFINFOMSG_LEN => {
+                let ifname = attributes(&body[IFINFOMSG_LEN..])
+                    .find(|(t, _)| *t == libc::IFLA_IFNAME)
+                    .map(|(_, v)| {
+                        let v = v.split(|b| *b == 0).next().unwrap_or_default();
+                        String::from_utf8_lossy(v).into_owned()
+                    });
+                Some(Change {
+                    ifindex: read_u32(body, 4).unwrap_or(0),
+                    ifname,
+                })
+            }
+            libc::RTM_NEWADDR | libc::RTM_DELADDR if body.len() >= IFADDRMSG_LEN => Some(Change {
+                ifindex: read_u32(body, 4).unwrap_or(0),
+                ifname: None,
+            }),
+            libc::RTM_NEWROUTE | libc::RTM_DELROUTE if body.len() >= RTMSG_LEN => {
+                let mut table = u32::from(body[4]);
+                let mut oif = 0;
+                for (t, v) in attributes(&body[RTMSG_LEN..]) {
+                    match t {
+                        libc::RTA_TABLE => table = read_u32(v, 0).unwrap_or(table),
+                        libc::RTA_OIF => oif = read_u32(v, 0).unwrap_or(0),
+                        _ => {}
+                    }
+                }
+                (table == u32::from(libc::RT_TABLE_MAIN)).then_some(Change {
+                    ifindex: oif,
+                    ifname: None,
+                })
+            }
+            _ => None,
+        };
+        changes.extend(change);
+    }
+
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::netlink::MessageBuilder;
+    use super::*;
+
+    /// Build one netlink message: header + fixed body + rtattrs.
+    fn message(kind: u16, fixed: &[u8], attrs: &[(u16, &[u8])]) -> Vec<u8> {
+        let mut builder = MessageBuilder::new(kind, 0);
+        builder.header(fixed);
+        for (t, v) in attrs {
+            builder.attr(*t, v);
+        }
+        builder.finish(0)
+    }
+
+    fn ifinfomsg(index: u32) -> Vec<u8> {
+        let mut b = vec![0u8; IFINFOMSG_LEN];
+        b[4..8].copy_from_slice(&index.to_ne_bytes());
+        b
+    }
+
+    fn rtmsg(table: u8) -> Vec<u8> {
+        let mut b = vec![0u8; RTMSG_LEN];
+        b[4] = table;
+        b
+    }
+
+    #[test]
+    fn test_parse_link_with_name() {
+        let buf = message(
+            libc::RTM_NEWLINK,
+            &ifinfomsg(3),
+            &[(libc::IFLA_IFNAME, b"wlan0\0")],
+        );
+        assert_eq!(
+            parse_messages(&buf),
+            vec![Change {
+                ifindex: 3,
+                ifname: Some("wlan0".to_string())
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_addr_and_route_batch() {
+        let mut addr = vec![0u8; IFADDRMSG_LEN];
+        addr[4..8].copy_from_slice(&7u32.to_ne_bytes());
+        let mut buf = message(libc::RTM_NEWADDR, &addr, &[]);
+        buf.extend(message(
+            libc::RTM_DELROUTE,
+            &rtmsg(libc::RT_TABLE_MAIN),
+            &[(libc::RTA_OIF, &9u32.to_ne_bytes())],
+        ));
+
+        let changes = parse_messages(&buf);
+        let indices: Vec<u32> = changes.iter().map(|c| c.ifindex).collect();
+        assert_eq!(indices, vec![7, 9]);
+    }
+
+    #[test]
+    fn test_parse_skips_non_main_route_tables() {
+        // wg-quick style: table 51820 carried in RTA_TABLE
+        let buf = message(
+            libc::RTM_NEWROUTE,
+            &rtmsg(252),
+            &[
+                (libc::RTA_TABLE, &51820u32.to_ne_bytes()),
+                (libc::RTA_OIF, &4u32.to_ne_bytes()),
+            ],
+        );
+        assert!(parse_messages(&buf).is_empty());
+    }
+
+    #[test]
+    fn test_parse_truncated_input() {
+        let buf = message(libc::RTM_NEWLINK, &ifinfomsg(3), &[]);
+        assert!(parse_messages(&buf[..buf.len() - 4]).is_empty());
+        assert!(parse_messages(&[]).is_empty());
+    }
+}
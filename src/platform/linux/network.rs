@@ -1,17 +1,64 @@
 //! Linux network statistics via `/proc/net/dev`.
 
 use crate::constants;
-use crate::core::telemetry::parse_proc_net_dev;
-use crate::platform::NetworkStatsProvider;
+use crate::core::telemetry::parse_proc_net_dev_interfaces;
+use crate::platform::{InterfaceBytes, NetworkStatsProvider};
 
 /// Linux network stats from /proc/net/dev.
 pub struct LinuxNetworkStats;
 
 impl NetworkStatsProvider for LinuxNetworkStats {
-    fn get_total_bytes() -> (u64, u64) {
+    fn get_interface_bytes() -> InterfaceBytes {
         match std::fs::read_to_string(constants::PROC_NET_DEV_PATH) {
-            Ok(content) => parse_proc_net_dev(&content),
-            Err(_) => (0, 0),
+            Ok(content) => parse_proc_net_dev_interfaces(&content),
+            Err(_) => InterfaceBytes::new(),
         }
     }
+
+    fn get_uplink_interface() -> Option<String> {
+        let content = std::fs::read_to_string(constants::PROC_NET_ROUTE_PATH).ok()?;
+        parse_default_route_interface(&content)
+    }
+}
+
+/// Interface of the IPv4 default route in `/proc/net/route`.
+///
+/// Tunnels that take over all traffic (`wg-quick`'s policy table,
+/// `OpenVPN`'s `def1` half routes) leave the main table's default route on
+/// the physical interface, so this is the uplink even while connected.
+fn parse_default_route_interface(content: &str) -> Option<String> {
+    content
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            // Iface Destination Gateway Flags RefCnt Use Metric Mask ...
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let metric = fields.get(6)?.parse::<u32>().ok()?;
+            (fields.get(1) == Some(&"00000000") && fields.get(7) == Some(&"00000000"))
+                .then(|| (metric, fields[0].to_string()))
+        })
+        .min()
+        .map(|(_, iface)| iface)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_default_route_interface() {
+        let content = "\
+Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\tWindow\tIRTT
+tun0\t00000080\t0100080A\t0003\t0\t0\t0\t00000080\t0\t0\t0
+wlan0\t00000000\t0101A8C0\t0003\t0\t0\t600\t00000000\t0\t0\t0
+eth0\t00000000\t0100A8C0\t0003\t0\t0\t100\t00000000\t0\t0\t0
+eth0\t0000A8C0\t00000000\t0001\t0\t0\t100\t00FFFFFF\t0\t0\t0";
+
+        // Lowest metric wins; the OpenVPN half route is not a default route
+        assert_eq!(
+            parse_default_route_interface(content),
+            Some("eth0".to_string())
+        );
+        assert_eq!(parse_default_route_interface("Iface\tDestination\n"), None);
+    }
 }
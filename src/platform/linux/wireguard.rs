@@ -0,0 +1,896 @@
+//! Native `WireGuard` control over netlink.
+//!
+//! The interface is created with rtnetlink (`ip link add type wireguard`),
+//! configured through the `wireguard` generic netlink family (what `wg set`
+//! does) and given its addresses, routes and policy rules with rtnetlink,
+//! following the same steps as `wg-quick up`:
+//!
+//! - every allowed IP gets a route through the interface in the main table;
+//! - `0.0.0.0/0` and `::/0` go into a policy table instead (the profile's
+//!   `FwMark`, else [`constants::WIREGUARD_ROUTE_TABLE`]), selected by a
+//!   `not fwmark` rule for the same value, plus a rule that lets the main
+//!   table win for anything more specific than a default route.
+//!
+//! The rules installed for a tunnel are recorded in its state file so
+//! [`NetlinkWireGuard::down`] can remove them from another process.
+
+use super::netlink::{
+    attributes, name_to_index, read_u16, read_u32, read_u64, MessageBuilder, NetlinkSocket,
+    NLM_F_ACK, NLM_F_CREATE, NLM_F_DUMP, NLM_F_EXCL,
+};
+use crate::constants;
+use crate::platform::WireGuardControl;
+use crate::vpn::split::Cidr;
+use crate::vpn::wireguard::{Key, WgConfig, WgDevice, WgPeerStatus};
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs};
+use std::time::{Duration, SystemTime};
+
+// Generic netlink controller, <linux/genetlink.h>.
+const GENL_ID_CTRL: u16 = 0x10;
+const CTRL_CMD_GETFAMILY: u8 = 3;
+const CTRL_ATTR_FAMILY_ID: u16 = 1;
+const CTRL_ATTR_FAMILY_NAME: u16 = 2;
+const GENL_HDR_LEN: usize = 4;
+
+// <linux/wireguard.h>
+const WG_GENL_NAME: &str = "wireguard";
+const WG_GENL_VERSION: u8 = 1;
+const WG_CMD_GET_DEVICE: u8 = 0;
+const WG_CMD_SET_DEVICE: u8 = 1;
+const WGDEVICE_A_IFNAME: u16 = 2;
+const WGDEVICE_A_PRIVATE_KEY: u16 = 3;
+const WGDEVICE_A_PUBLIC_KEY: u16 = 4;
+const WGDEVICE_A_FLAGS: u16 = 5;
+const WGDEVICE_A_LISTEN_PORT: u16 = 6;
+const WGDEVICE_A_FWMARK: u16 = 7;
+const WGDEVICE_A_PEERS: u16 = 8;
+const WGDEVICE_F_REPLACE_PEERS: u32 = 1;
+const WGPEER_A_PUBLIC_KEY: u16 = 1;
+const WGPEER_A_PRESHARED_KEY: u16 = 2;
+const WGPEER_A_FLAGS: u16 = 3;
+const WGPEER_A_ENDPOINT: u16 = 4;
+const WGPEER_A_PERSISTENT_KEEPALIVE_INTERVAL: u16 = 5;
+const WGPEER_A_LAST_HANDSHAKE_TIME: u16 = 6;
+const WGPEER_A_RX_BYTES: u16 = 7;
+const WGPEER_A_TX_BYTES: u16 = 8;
+const WGPEER_A_ALLOWEDIPS: u16 = 9;
+const WGPEER_F_REPLACE_ALLOWEDIPS: u32 = 2;
+const WGALLOWEDIP_A_FAMILY: u16 = 1;
+const WGALLOWEDIP_A_IPADDR: u16 = 2;
+const WGALLOWEDIP_A_CIDR_MASK: u16 = 3;
+
+// <linux/fib_rules.h>, not exported by libc.
+const FRA_FWMARK: u16 = 10;
+const FRA_SUPPRESS_PREFIXLEN: u16 = 14;
+const FRA_TABLE: u16 = 15;
+const FR_ACT_TO_TBL: u8 = 1;
+const FIB_RULE_INVERT: u32 = 0x2;
+
+// Address families as they appear in netlink headers.
+const AF_INET: u8 = 2;
+const AF_INET6: u8 = 10;
+
+/// `WireGuard` control through the kernel's netlink interfaces.
+pub struct NetlinkWireGuard;
+
+impl WireGuardControl for NetlinkWireGuard {
+    fn is_available() -> bool {
+        if resolve_family().is_ok() || std::path::Path::new("/sys/module/wireguard").exists() {
+            return true;
+        }
+        // Not loaded yet: creating the link loads it, if the kernel has it
+        let Ok(release) = std::fs::read_to_string("/proc/sys/kernel/osrelease") else {
+            return false;
+        };
+        let modules = std::path::Path::new("/lib/modules").join(release.trim());
+        ["modules.dep", "modules.builtin"].iter().any(|list| {
+            std::fs::read_to_string(modules.join(list))
+                .is_ok_and(|content| content.contains("/wireguard.ko"))
+        })
+    }
+
+    fn up(interface: &str, config: &WgConfig) -> Result<(), String> {
+        // Resolve first so a bad endpoint leaves nothing behind
+        let endpoints = config
+            .peers
+            .iter()
+            .map(|peer| peer.endpoint.as_deref().map(resolve_endpoint).transpose())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let route = open_route()?;
+        route
+            .request(&mut new_link_request(interface))
+            .map_err(|e| format!("Failed to create {interface}: {e}"))?;
+
+        let policy = policy_families(config);
+        let result = save_state(interface, &policy, policy_table(config))
+            .and_then(|()| configure(&route, interface, config, &endpoints, &policy));
+        if result.is_err() {
+            let _ = Self::down(interface);
+        }
+        result
+    }
+
+    fn down(interface: &str) -> Result<(), String> {
+        let route = open_route()?;
+        let state_path = crate::utils::get_wireguard_state_path(interface).ok();
+        let state = state_path
+            .as_ref()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .unwrap_or_default();
+
+        // Rules first: without the interface they would blackhole traffic
+        for (family, table) in parse_state(&state) {
+            for mut rule in rule_requests(libc::RTM_DELRULE, NLM_F_ACK, family, table) {
+                match route.request(&mut rule) {
+                    Ok(_) => {}
+                    Err(e) if e.raw_os_error() == Some(libc::ENOENT) => {}
+                    Err(e) => crate::logger::log(
+                        crate::logger::LogLevel::Warning,
+                        "WIREGUARD",
+                        format!("Failed to remove routing rule for {interface}: {e}"),
+                    ),
+                }
+            }
+        }
+
+        let result = match name_to_index(interface) {
+            Some(index) => route
+                .request(&mut link_request(libc::RTM_DELLINK, NLM_F_ACK, index, 0))
+                .map(|_| ())
+                .map_err(|e| format!("Failed to delete {interface}: {e}")),
+            None => Ok(()),
+        };
+        if result.is_ok() {
+            if let Some(path) = state_path {
+                let _ = std::fs::remove_file(path);
+            }
+        }
+        result
+    }
+
+    fn is_managed(interface: &str) -> bool {
+        crate::utils::get_wireguard_state_path(interface).is_ok_and(|p| p.exists())
+    }
+
+    fn device(interface: &str) -> Result<WgDevice, String> {
+        let (genl, family) = open_wireguard()?;
+        let mut request = genl_request(family, WG_CMD_GET_DEVICE, NLM_F_DUMP);
+        request.attr_str(WGDEVICE_A_IFNAME, interface);
+        let replies = genl
+            .request(&mut request)
+            .map_err(|e| format!("Failed to read {interface}: {e}"))?;
+        Ok(parse_device(&replies))
+    }
+
+    fn rehandshake(interface: &str) -> Result<(), String> {
+        let device = Self::device(interface)?;
+        let peers: Vec<(Key, SocketAddr)> = device
+            .peers
+            .iter()
+            .filter_map(|p| Some((p.public_key, p.endpoint?)))
+            .collect();
+        if peers.is_empty() {
+            return Err(format!("No peer endpoints on {interface}"));
+        }
+
+        let (genl, family) = open_wireguard()?;
+        let mut request = genl_request(family, WG_CMD_SET_DEVICE, NLM_F_ACK);
+        request
+            .attr_str(WGDEVICE_A_IFNAME, interface)
+            .begin_nested(WGDEVICE_A_PEERS);
+        for (key, endpoint) in peers {
+            request
+                .begin_nested(0)
+                .attr(WGPEER_A_PUBLIC_KEY, &key.0)
+                .attr(WGPEER_A_ENDPOINT, &encode_sockaddr(endpoint))
+                .end_nested();
+        }
+        request.end_nested();
+        genl.request(&mut request)
+            .map(|_| ())
+            .map_err(|e| format!("Failed to update {interface}: {e}"))
+    }
+}
+
+/// Everything after link creation; the caller deletes the link on error.
+fn configure(
+    route: &NetlinkSocket,
+    interface: &str,
+    config: &WgConfig,
+    endpoints: &[Option<SocketAddr>],
+    policy: &[u8],
+) -> Result<(), String> {
+    let index = name_to_index(interface).ok_or_else(|| format!("{interface} vanished"))?;
+    let table = policy_table(config);
+    let fwmark = if policy.is_empty() {
+        config.fwmark
+    } else {
+        Some(table)
+    };
+
+    let (genl, family) = open_wireguard()?;
+    genl.request(&mut set_device_request(
+        family, interface, config, fwmark, endpoints,
+    ))
+    .map_err(|e| format!("Failed to configure {interface}: {e}"))?;
+
+    for address in &config.addresses {
+        route
+            .request(&mut address_request(index, address.ip, address.prefix_len))
+            .map_err(|e| format!("Failed to add address {}: {e}", address.ip))?;
+    }
+
+    let mtu = config.mtu.unwrap_or(constants::WIREGUARD_DEFAULT_MTU);
+    let mut link = link_request(libc::RTM_NEWLINK, NLM_F_ACK, index, iff_up());
+    link.attr_u32(libc::IFLA_MTU, mtu);
+    route
+        .request(&mut link)
+        .map_err(|e| format!("Failed to bring {interface} up: {e}"))?;
+
+    if config.table == crate::vpn::wireguard::RouteTable::Off {
+        return Ok(());
+    }
+    for cidr in route_targets(config) {
+        let target = if cidr.prefix_len() == 0 {
+            table
+        } else {
+            u32::from(libc::RT_TABLE_MAIN)
+        };
+        route
+            .request(&mut route_request(index, cidr, target))
+            .map_err(|e| format!("Failed to add route {cidr}: {e}"))?;
+    }
+    for &family in policy {
+        for mut rule in rule_requests(
+            libc::RTM_NEWRULE,
+            NLM_F_CREATE | NLM_F_EXCL | NLM_F_ACK,
+            family,
+            table,
+        ) {
+            match route.request(&mut rule) {
+                Ok(_) => {}
+                // Left over from an earlier tunnel; identical, so keep it
+                Err(e) if e.raw_os_error() == Some(libc::EEXIST) => {}
+                Err(e) => return Err(format!("Failed to add routing rule: {e}")),
+            }
+        }
+        if family == AF_INET {
+            // Let replies to marked packets pass reverse path filtering
+            let _ = std::fs::write("/proc/sys/net/ipv4/conf/all/src_valid_mark", "1");
+        }
+    }
+    Ok(())
+}
+
+fn open_route() -> Result<NetlinkSocket, String> {
+    NetlinkSocket::open(libc::NETLINK_ROUTE, 0).map_err(|e| format!("netlink: {e}"))
+}
+
+fn open_wireguard() -> Result<(NetlinkSocket, u16), String> {
+    let genl =
+        NetlinkSocket::open(libc::NETLINK_GENERIC, 0).map_err(|e| format!("netlink: {e}"))?;
+    let family =
+        family_id(&genl).map_err(|e| format!("WireGuard kernel module unavailable: {e}"))?;
+    Ok((genl, family))
+}
+
+fn resolve_family() -> io::Result<u16> {
+    family_id(&NetlinkSocket::open(libc::NETLINK_GENERIC, 0)?)
+}
+
+/// Look up the dynamic id of the `wireguard` generic netlink family.
+fn family_id(genl: &NetlinkSocket) -> io::Result<u16> {
+    let mut request = genl_request(GENL_ID_CTRL, CTRL_CMD_GETFAMILY, NLM_F_ACK);
+    request.attr_str(CTRL_ATTR_FAMILY_NAME, WG_GENL_NAME);
+    genl.request(&mut request)?
+        .iter()
+        .filter_map(|body| body.get(GENL_HDR_LEN..))
+        .flat_map(attributes)
+        .find(|(kind, _)| *kind == CTRL_ATTR_FAMILY_ID)
+        .and_then(|(_, value)| read_u16(value, 0))
+        .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))
+}
+
+fn genl_request(family: u16, cmd: u8, flags: u16) -> MessageBuilder {
+    let version = if family == GENL_ID_CTRL {
+        1
+    } else {
+        WG_GENL_VERSION
+    };
+    let mut request = MessageBuilder::new(family, flags);
+    request.header(&[cmd, version, 0, 0]);
+    request
+}
+
+/// Mark of the tunnel's own packets and id of its policy table. Like
+/// `wg-quick`, one value serves both, so the encrypted packets skip the
+/// table that routes into the tunnel.
+fn policy_table(config: &WgConfig) -> u32 {
+    config
+        .fwmark
+        .filter(|&mark| mark != 0)
+        .unwrap_or(constants::WIREGUARD_ROUTE_TABLE)
+}
+
+/// Address families that route a default route through the policy table.
+fn policy_families(config: &WgConfig) -> Vec<u8> {
+    if config.table == crate::vpn::wireguard::RouteTable::Off {
+        return Vec::new();
+    }
+    let defaults: Vec<Cidr> = route_targets(config)
+        .into_iter()
+        .filter(|c| c.prefix_len() == 0)
+        .collect();
+    [AF_INET, AF_INET6]
+        .into_iter()
+        .filter(|&family| defaults.iter().any(|c| family_of(c.ip()) == family))
+        .collect()
+}
+
+/// Allowed IPs of all peers, deduplicated, most specific first (as `wg-quick`
+/// adds them).
+fn route_targets(config: &WgConfig) -> Vec<Cidr> {
+    let mut targets: Vec<Cidr> = Vec::new();
+    for cidr in config.peers.iter().flat_map(|p| &p.allowed_ips) {
+        if !targets.contains(cidr) {
+            targets.push(*cidr);
+        }
+    }
+    targets.sort_by_key(|c| std::cmp::Reverse(c.prefix_len()));
+    targets
+}
+
+fn family_of(ip: IpAddr) -> u8 {
+    if ip.is_ipv6() {
+        AF_INET6
+    } else {
+        AF_INET
+    }
+}
+
+fn ip_bytes(ip: IpAddr) -> Vec<u8> {
+    match ip {
+        IpAddr::V4(v4) => v4.octets().to_vec(),
+        IpAddr::V6(v6) => v6.octets().to_vec(),
+    }
+}
+
+// IFF_UP is a small positive flag
+#[allow(clippy::cast_sign_loss)]
+fn iff_up() -> u32 {
+    libc::IFF_UP as u32
+}
+
+fn resolve_endpoint(endpoint: &str) -> Result<SocketAddr, String> {
+    endpoint
+        .to_socket_addrs()
+        .map_err(|e| format!("Cannot resolve endpoint {endpoint}: {e}"))?
+        .next()
+        .ok_or_else(|| format!("Cannot resolve endpoint {endpoint}"))
+}
+
+/// `sockaddr_in` / `sockaddr_in6` as the kernel expects in `WGPEER_A_ENDPOINT`.
+fn encode_sockaddr(addr: SocketAddr) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(28);
+    buf.extend_from_slice(&u16::from(family_of(addr.ip())).to_ne_bytes());
+    buf.extend_from_slice(&addr.port().to_be_bytes());
+    match addr {
+        SocketAddr::V4(v4) => {
+            buf.extend_from_slice(&v4.ip().octets());
+            buf.extend_from_slice(&[0; 8]);
+        }
+        SocketAddr::V6(v6) => {
+            buf.extend_from_slice(&v6.flowinfo().to_be_bytes());
+            buf.extend_from_slice(&v6.ip().octets());
+            buf.extend_from_slice(&v6.scope_id().to_ne_bytes());
+        }
+    }
+    buf
+}
+
+fn decode_sockaddr(buf: &[u8]) -> Option<SocketAddr> {
+    let family = read_u16(buf, 0)?;
+    let port = u16::from_be_bytes(buf.get(2..4)?.try_into().ok()?);
+    if family == u16::from(AF_INET) {
+        let ip: [u8; 4] = buf.get(4..8)?.try_into().ok()?;
+        Some(SocketAddr::new(IpAddr::V4(Ipv4Addr::from(ip)), port))
+    } else if family == u16::from(AF_INET6) {
+        let ip: [u8; 16] = buf.get(8..24)?.try_into().ok()?;
+        Some(SocketAddr::new(IpAddr::V6(Ipv6Addr::from(ip)), port))
+    } else {
+        None
+    }
+}
+
+/// `struct ifinfomsg` for link `index` with `flags` set (and changed).
+fn link_request(kind: u16, flags: u16, index: u32, link_flags: u32) -> MessageBuilder {
+    let mut header = [0u8; 16];
+    header[4..8].copy_from_slice(&index.to_ne_bytes());
+    header[8..12].copy_from_slice(&link_flags.to_ne_bytes());
+    header[12..16].copy_from_slice(&link_flags.to_ne_bytes());
+    let mut request = MessageBuilder::new(kind, flags);
+    request.header(&header);
+    request
+}
+
+fn new_link_request(interface: &str) -> MessageBuilder {
+    let mut request = link_request(
+        libc::RTM_NEWLINK,
+        NLM_F_CREATE | NLM_F_EXCL | NLM_F_ACK,
+        0,
+        0,
+    );
+    request
+        .attr_str(libc::IFLA_IFNAME, interface)
+        .begin_nested(libc::IFLA_LINKINFO)
+        .attr_str(libc::IFLA_INFO_KIND, WG_GENL_NAME)
+        .end_nested();
+    request
+}
+
+fn set_device_request(
+    family: u16,
+    interface: &str,
+    config: &WgConfig,
+    fwmark: Option<u32>,
+    endpoints: &[Option<SocketAddr>],
+) -> MessageBuilder {
+    let mut request = genl_request(family, WG_CMD_SET_DEVICE, NLM_F_ACK);
+    request
+        .attr_str(WGDEVICE_A_IFNAME, interface)
+        .attr(WGDEVICE_A_PRIVATE_KEY, &config.private_key.0)
+        .attr_u32(WGDEVICE_A_FLAGS, WGDEVICE_F_REPLACE_PEERS);
+    if let Some(port) = config.listen_port {
+        request.attr_u16(WGDEVICE_A_LISTEN_PORT, port);
+    }
+    if let Some(mark) = fwmark {
+        request.attr_u32(WGDEVICE_A_FWMARK, mark);
+    }
+
+    request.begin_nested(WGDEVICE_A_PEERS);
+    for (peer, endpoint) in config.peers.iter().zip(endpoints) {
+        request
+            .begin_nested(0)
+            .attr(WGPEER_A_PUBLIC_KEY, &peer.public_key.0)
+            .attr_u32(WGPEER_A_FLAGS, WGPEER_F_REPLACE_ALLOWEDIPS);
+        if let Some(psk) = &peer.preshared_key {
+            request.attr(WGPEER_A_PRESHARED_KEY, &psk.0);
+        }
+        if let Some(endpoint) = endpoint {
+            request.attr(WGPEER_A_ENDPOINT, &encode_sockaddr(*endpoint));
+        }
+        if let Some(keepalive) = peer.persistent_keepalive {
+            request.attr_u16(WGPEER_A_PERSISTENT_KEEPALIVE_INTERVAL, keepalive);
+        }
+        request.begin_nested(WGPEER_A_ALLOWEDIPS);
+        for cidr in &peer.allowed_ips {
+            request
+                .begin_nested(0)
+                .attr_u16(WGALLOWEDIP_A_FAMILY, u16::from(family_of(cidr.ip())))
+                .attr(WGALLOWEDIP_A_IPADDR, &ip_bytes(cidr.ip()))
+                .attr_u8(WGALLOWEDIP_A_CIDR_MASK, cidr.prefix_len())
+                .end_nested();
+        }
+        request.end_nested().end_nested();
+    }
+    request.end_nested();
+    request
+}
+
+/// `struct ifaddrmsg` plus local/peer address, like `ip addr add`.
+fn address_request(index: u32, ip: IpAddr, prefix_len: u8) -> MessageBuilder {
+    let mut header = [0u8; 8];
+    header[0] = family_of(ip);
+    header[1] = prefix_len;
+    header[3] = libc::RT_SCOPE_UNIVERSE;
+    header[4..8].copy_from_slice(&index.to_ne_bytes());
+    let mut request = MessageBuilder::new(libc::RTM_NEWADDR, NLM_F_CREATE | NLM_F_EXCL | NLM_F_ACK);
+    request
+        .header(&header)
+        .attr(libc::IFA_LOCAL, &ip_bytes(ip))
+        .attr(libc::IFA_ADDRESS, &ip_bytes(ip));
+    request
+}
+
+/// `struct rtmsg` for a link-scope route to `dst` through link `index`.
+fn route_request(index: u32, dst: Cidr, table: u32) -> MessageBuilder {
+    let header = [
+        family_of(dst.ip()),
+        dst.prefix_len(),
+        0, // src_len
+        0, // tos
+        u8::try_from(table).unwrap_or(0),
+        libc::RTPROT_BOOT,
+        libc::RT_SCOPE_LINK,
+        libc::RTN_UNICAST,
+        0,
+        0,
+        0,
+        0, // flags
+    ];
+    let mut request =
+        MessageBuilder::new(libc::RTM_NEWROUTE, NLM_F_CREATE | NLM_F_EXCL | NLM_F_ACK);
+    request.header(&header);
+    if dst.prefix_len() > 0 {
+        request.attr(libc::RTA_DST, &ip_bytes(dst.ip()));
+    }
+    request
+        .attr_u32(libc::RTA_OIF, index)
+        .attr_u32(libc::RTA_TABLE, table);
+    request
+}
+
+/// The two rules `wg-quick` adds for a default route, as `kind` requests:
+/// `not fwmark <table> table <table>` and `table main suppress_prefixlength 0`.
+fn rule_requests(kind: u16, flags: u16, family: u8, table: u32) -> [MessageBuilder; 2] {
+    let header = |rule_flags: u32| {
+        let mut header = [0u8; 12];
+        header[0] = family;
+        header[7] = FR_ACT_TO_TBL;
+        header[8..12].copy_from_slice(&rule_flags.to_ne_bytes());
+        header
+    };
+
+    let mut to_tunnel = MessageBuilder::new(kind, flags);
+    to_tunnel
+        .header(&header(FIB_RULE_INVERT))
+        .attr_u32(FRA_FWMARK, table)
+        .attr_u32(FRA_TABLE, table);
+
+    let mut main_first = MessageBuilder::new(kind, flags);
+    main_first
+        .header(&header(0))
+        .attr_u32(FRA_SUPPRESS_PREFIXLEN, 0)
+        .attr_u32(FRA_TABLE, u32::from(libc::RT_TABLE_MAIN));
+
+    [to_tunnel, main_first]
+}
+
+/// Record the policy rules of `interface` (one `family table` line each).
+fn save_state(interface: &str, policy: &[u8], table: u32) -> Result<(), String> {
+    let lines: Vec<String> = policy
+        .iter()
+        .map(|family| format!("{family} {table}"))
+        .collect();
+    crate::utils::get_wireguard_state_path(interface)
+        .and_then(|path| crate::utils::write_user_file(&path, lines.join("\n")))
+        .map_err(|e| format!("Failed to save WireGuard state: {e}"))
+}
+
+fn parse_state(content: &str) -> Vec<(u8, u32)> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let (family, table) = line.split_once(' ')?;
+            Some((family.parse().ok()?, table.trim().parse().ok()?))
+        })
+        .collect()
+}
+
+/// Assemble the device from a `WG_CMD_GET_DEVICE` dump. Peers with many
+/// allowed IPs continue in the next message, repeating the public key.
+fn parse_device(replies: &[Vec<u8>]) -> WgDevice {
+    let mut device = WgDevice::default();
+    for body in replies {
+        let Some(attrs) = body.get(GENL_HDR_LEN..) else {
+            continue;
+        };
+        for (kind, value) in attributes(attrs) {
+            match kind {
+                WGDEVICE_A_PUBLIC_KEY => device.public_key = key(value),
+                WGDEVICE_A_LISTEN_PORT => device.listen_port = read_u16(value, 0).unwrap_or(0),
+                WGDEVICE_A_FWMARK => device.fwmark = read_u32(value, 0).unwrap_or(0),
+                WGDEVICE_A_PEERS => {
+                    for (_, peer) in attributes(value) {
+                        let Some(status) = parse_peer(peer) else {
+                            continue;
+                        };
+                        match device.peers.last_mut() {
+                            Some(last) if last.public_key == status.public_key => {
+                                last.allowed_ips.extend(status.allowed_ips);
+                            }
+                            _ => device.peers.push(status),
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    device
+}
+
+fn parse_peer(buf: &[u8]) -> Option<WgPeerStatus> {
+    let mut public_key = None;
+    let mut status = WgPeerStatus {
+        public_key: Key([0; 32]),
+        endpoint: None,
+        last_handshake: None,
+        rx_bytes: 0,
+        tx_bytes: 0,
+        allowed_ips: Vec::new(),
+    };
+    for (kind, value) in attributes(buf) {
+        match kind {
+            WGPEER_A_PUBLIC_KEY => public_key = key(value),
+            WGPEER_A_ENDPOINT => status.endpoint = decode_sockaddr(value),
+            WGPEER_A_LAST_HANDSHAKE_TIME => status.last_handshake = timespec(value),
+            WGPEER_A_RX_BYTES => status.rx_bytes = read_u64(value, 0).unwrap_or(0),
+            WGPEER_A_TX_BYTES => status.tx_bytes = read_u64(value, 0).unwrap_or(0),
+            WGPEER_A_ALLOWEDIPS => {
+                status
+                    .allowed_ips
+                    .extend(attributes(value).filter_map(|(_, ip)| parse_allowed_ip(ip)));
+            }
+            _ => {}
+        }
+    }
+    status.public_key = public_key?;
+    Some(status)
+}
+
+fn parse_allowed_ip(buf: &[u8]) -> Option<Cidr> {
+    let mut ip = None;
+    let mut mask = None;
+    for (kind, value) in attributes(buf) {
+        match kind {
+            WGALLOWEDIP_A_IPADDR => {
+                ip = match value.len() {
+                    4 => Some(IpAddr::V4(Ipv4Addr::from(<[u8; 4]>::try_from(value).ok()?))),
+                    16 => Some(IpAddr::V6(Ipv6Addr::from(
+                        <[u8; 16]>::try_from(value).ok()?,
+                    ))),
+                    _ => None,
+                };
+            }
+            WGALLOWEDIP_A_CIDR_MASK => mask = value.first().copied(),
+            _ => {}
+        }
+    }
+    Some(Cidr::new(ip?, mask?))
+}
+
+fn key(value: &[u8]) -> Option<Key> {
+    Some(Key(value.try_into().ok()?))
+}
+
+/// `struct __kernel_timespec`; all zero means no handshake yet.
+fn timespec(value: &[u8]) -> Option<SystemTime> {
+    let secs = read_u64(value, 0)?;
+    let nanos = read_u64(value, 8)?;
+    if secs == 0 && nanos == 0 {
+        return None;
+    }
+    let nanos = u32::try_from(nanos).unwrap_or(0);
+    SystemTime::UNIX_EPOCH.checked_add(Duration::new(secs, nanos))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::netlink::{messages, NLA_F_NESTED};
+    use super::*;
+
+    const CONFIG: &str = "\
+[Interface]
+PrivateKey = yAnz5TF+lXXJte14tji3zlMNq+hd2rYUIgJBgB3fBmk=
+Address = 10.64.0.2/32, fd00::2/128
+ListenPort = 51000
+
+[Peer]
+PublicKey = xTIBA5rboUvnH4htodjb6e697QjLERt1NAB4mZqp8Dg=
+PresharedKey = FpCyhws9cxwWoV4xELtfJvjJN+zQVRPISllRWgeopVE=
+Endpoint = 192.0.2.1:51820
+AllowedIPs = 0.0.0.0/0, 10.0.0.0/8
+PersistentKeepalive = 25
+";
+
+    /// Body of a finished request, as a reply would carry it.
+    fn body(mut builder: MessageBuilder) -> Vec<u8> {
+        let msg = builder.finish(1);
+        let body = messages(&msg).next().unwrap().body.to_vec();
+        body
+    }
+
+    fn find(buf: &[u8], kind: u16) -> Option<&[u8]> {
+        attributes(buf).find(|(k, _)| *k == kind).map(|(_, v)| v)
+    }
+
+    #[test]
+    fn test_set_device_request() {
+        let config = WgConfig::parse(CONFIG).unwrap();
+        let endpoint = "192.0.2.1:51820".parse().ok();
+        let request = set_device_request(
+            0x1b,
+            "wg0",
+            &config,
+            Some(constants::WIREGUARD_ROUTE_TABLE),
+            &[endpoint],
+        );
+        let body = body(request);
+        assert_eq!(&body[..2], &[WG_CMD_SET_DEVICE, WG_GENL_VERSION]);
+
+        let attrs = &body[GENL_HDR_LEN..];
+        assert_eq!(find(attrs, WGDEVICE_A_IFNAME), Some(&b"wg0\0"[..]));
+        assert_eq!(
+            find(attrs, WGDEVICE_A_PRIVATE_KEY),
+            Some(&config.private_key.0[..])
+        );
+        assert_eq!(
+            read_u16(find(attrs, WGDEVICE_A_LISTEN_PORT).unwrap(), 0),
+            Some(51000)
+        );
+        assert_eq!(
+            read_u32(find(attrs, WGDEVICE_A_FWMARK).unwrap(), 0),
+            Some(51820)
+        );
+
+        let peers = find(attrs, WGDEVICE_A_PEERS).unwrap();
+        let (_, peer) = attributes(peers).next().unwrap();
+        assert_eq!(
+            find(peer, WGPEER_A_PUBLIC_KEY),
+            Some(&config.peers[0].public_key.0[..])
+        );
+        assert_eq!(
+            decode_sockaddr(find(peer, WGPEER_A_ENDPOINT).unwrap()),
+            endpoint
+        );
+        assert_eq!(
+            read_u16(
+                find(peer, WGPEER_A_PERSISTENT_KEEPALIVE_INTERVAL).unwrap(),
+                0
+            ),
+            Some(25)
+        );
+        let allowed: Vec<Cidr> = attributes(find(peer, WGPEER_A_ALLOWEDIPS).unwrap())
+            .filter_map(|(_, ip)| parse_allowed_ip(ip))
+            .collect();
+        assert_eq!(allowed, config.peers[0].allowed_ips);
+    }
+
+    #[test]
+    fn test_new_link_request_nests_kind() {
+        let body = body(new_link_request("wg0"));
+        let attrs = &body[16..];
+        assert_eq!(find(attrs, libc::IFLA_IFNAME), Some(&b"wg0\0"[..]));
+        let info = find(attrs, libc::IFLA_LINKINFO).unwrap();
+        assert_eq!(find(info, libc::IFLA_INFO_KIND), Some(&b"wireguard\0"[..]));
+        // The nested flag is set on the wire but masked when parsing
+        assert_eq!(
+            read_u16(attrs, 8 + 2),
+            Some(libc::IFLA_LINKINFO | NLA_F_NESTED)
+        );
+    }
+
+    #[test]
+    fn test_route_and_rule_requests() {
+        let body_main = body(route_request(
+            3,
+            Cidr::parse("10.0.0.0/8").unwrap(),
+            u32::from(libc::RT_TABLE_MAIN),
+        ));
+        assert_eq!(&body_main[..2], &[AF_INET, 8]);
+        assert_eq!(
+            find(&body_main[12..], libc::RTA_DST),
+            Some(&[10, 0, 0, 0][..])
+        );
+        assert_eq!(
+            read_u32(find(&body_main[12..], libc::RTA_OIF).unwrap(), 0),
+            Some(3)
+        );
+
+        let body_default = body(route_request(3, Cidr::parse("::/0").unwrap(), 51820));
+        assert_eq!(body_default[0], AF_INET6);
+        assert!(find(&body_default[12..], libc::RTA_DST).is_none());
+        assert_eq!(
+            read_u32(find(&body_default[12..], libc::RTA_TABLE).unwrap(), 0),
+            Some(51820)
+        );
+
+        let [to_tunnel, main_first] = rule_requests(libc::RTM_NEWRULE, 0, AF_INET, 51820);
+        let to_tunnel = body(to_tunnel);
+        assert_eq!(read_u32(&to_tunnel, 8), Some(FIB_RULE_INVERT));
+        assert_eq!(
+            read_u32(find(&to_tunnel[12..], FRA_FWMARK).unwrap(), 0),
+            Some(51820)
+        );
+        let main_first = body(main_first);
+        assert_eq!(
+            read_u32(find(&main_first[12..], FRA_SUPPRESS_PREFIXLEN).unwrap(), 0),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn test_policy_table_follows_fwmark() {
+        let config = WgConfig::parse(CONFIG).unwrap();
+        assert_eq!(policy_table(&config), constants::WIREGUARD_ROUTE_TABLE);
+
+        let marked = CONFIG.replace("ListenPort = 51000", "ListenPort = 51000\nFwMark = 1234");
+        let config = WgConfig::parse(&marked).unwrap();
+        assert_eq!(config.fwmark, Some(1234));
+        let table = policy_table(&config);
+        assert_eq!(table, 1234);
+
+        let [to_tunnel, _] = rule_requests(libc::RTM_NEWRULE, 0, AF_INET, table);
+        let to_tunnel = body(to_tunnel);
+        assert_eq!(
+            read_u32(find(&to_tunnel[12..], FRA_FWMARK).unwrap(), 0),
+            Some(1234)
+        );
+        assert_eq!(
+            read_u32(find(&to_tunnel[12..], FRA_TABLE).unwrap(), 0),
+            Some(1234)
+        );
+    }
+
+    #[test]
+    fn test_policy_only_for_default_routes() {
+        let config = WgConfig::parse(CONFIG).unwrap();
+        assert_eq!(policy_families(&config), vec![AF_INET]);
+        let targets: Vec<String> = route_targets(&config)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(targets, vec!["10.0.0.0/8", "0.0.0.0/0"]);
+
+        let off =
+            WgConfig::parse(&CONFIG.replace("ListenPort", "Table = off\nListenPort")).unwrap();
+        assert!(policy_families(&off).is_empty());
+
+        let state = format!("{AF_INET} 51820\n{AF_INET6} 51820\ngarbage\n");
+        assert_eq!(
+            parse_state(&state),
+            vec![(AF_INET, 51820), (AF_INET6, 51820)]
+        );
+    }
+
+    #[test]
+    fn test_parse_device_merges_split_peers() {
+        let config = WgConfig::parse(CONFIG).unwrap();
+        let peer_key = config.peers[0].public_key;
+        let dump = |allowed: &str, with_stats: bool| {
+            let mut msg = genl_request(0x1b, WG_CMD_GET_DEVICE, 0);
+            msg.attr_u16(WGDEVICE_A_LISTEN_PORT, 51000)
+                .begin_nested(WGDEVICE_A_PEERS)
+                .begin_nested(0)
+                .attr(WGPEER_A_PUBLIC_KEY, &peer_key.0);
+            if with_stats {
+                let mut ts = 1_700_000_000u64.to_ne_bytes().to_vec();
+                ts.extend_from_slice(&0u64.to_ne_bytes());
+                msg.attr(WGPEER_A_LAST_HANDSHAKE_TIME, &ts)
+                    .attr(WGPEER_A_RX_BYTES, &1536u64.to_ne_bytes())
+                    .attr(WGPEER_A_TX_BYTES, &512u64.to_ne_bytes())
+                    .attr(
+                        WGPEER_A_ENDPOINT,
+                        &encode_sockaddr("[2001:db8::1]:51820".parse().unwrap()),
+                    );
+            }
+            let cidr = Cidr::parse(allowed).unwrap();
+            msg.begin_nested(WGPEER_A_ALLOWEDIPS)
+                .begin_nested(0)
+                .attr_u16(WGALLOWEDIP_A_FAMILY, u16::from(family_of(cidr.ip())))
+                .attr(WGALLOWEDIP_A_IPADDR, &ip_bytes(cidr.ip()))
+                .attr_u8(WGALLOWEDIP_A_CIDR_MASK, cidr.prefix_len())
+                .end_nested()
+                .end_nested()
+                .end_nested()
+                .end_nested();
+            body(msg)
+        };
+
+        let device = parse_device(&[dump("0.0.0.0/0", true), dump("::/0", false)]);
+        assert_eq!(device.listen_port, 51000);
+        assert_eq!(device.peers.len(), 1);
+        let peer = &device.peers[0];
+        assert_eq!(peer.allowed_ips.len(), 2);
+        assert_eq!(peer.rx_bytes, 1536);
+        assert_eq!(device.tx_bytes(), 512);
+        assert_eq!(peer.endpoint, Some("[2001:db8::1]:51820".parse().unwrap()));
+        assert_eq!(
+            device.last_handshake(),
+            SystemTime::UNIX_EPOCH.checked_add(Duration::from_secs(1_700_000_000))
+        );
+    }
+}
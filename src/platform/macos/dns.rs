@@ -1,7 +1,9 @@
 //! macOS DNS resolver using scutil and networksetup.
 
 use crate::constants;
-use crate::platform::DnsResolver;
+use crate::core::dns::{DnsRestore, ServiceDns};
+use crate::platform::{DnsConfigurator, DnsResolver};
+use std::process::Command;
 
 /// macOS DNS resolution via scutil --dns, networksetup, and /etc/resolv.conf.
 pub struct MacDns;
@@ -15,6 +17,92 @@ impl DnsResolver for MacDns {
     }
 }
 
+/// Overrides are applied to every enabled network service, since macOS picks
+/// the resolver of the primary service rather than of the tunnel.
+impl DnsConfigurator for MacDns {
+    fn apply_dns(
+        _vpn_interface: &str,
+        servers: &[String],
+        search: &[String],
+    ) -> Result<DnsRestore, String> {
+        let list = networksetup(&["-listallnetworkservices"])?;
+        let mut services = Vec::new();
+        for name in parse_services(&list) {
+            let previous = ServiceDns {
+                servers: parse_list(&networksetup(&["-getdnsservers", &name])?),
+                search: parse_list(&networksetup(&["-getsearchdomains", &name])?),
+                name,
+            };
+            if !servers.is_empty() {
+                set_list(&previous.name, "-setdnsservers", servers)?;
+            }
+            if !search.is_empty() {
+                set_list(&previous.name, "-setsearchdomains", search)?;
+            }
+            services.push(previous);
+        }
+        Ok(DnsRestore::Networksetup { services })
+    }
+
+    fn restore_dns(restore: &DnsRestore) -> Result<(), String> {
+        let DnsRestore::Networksetup { services } = restore else {
+            return Err("Only networksetup changes can be restored on macOS".to_string());
+        };
+        for service in services {
+            set_list(&service.name, "-setdnsservers", &service.servers)?;
+            set_list(&service.name, "-setsearchdomains", &service.search)?;
+        }
+        Ok(())
+    }
+}
+
+fn networksetup(args: &[&str]) -> Result<String, String> {
+    let output = Command::new("networksetup")
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run networksetup: {e}"))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+/// Set a service's DNS servers or search domains; an empty list resets to DHCP.
+fn set_list(service: &str, flag: &str, values: &[String]) -> Result<(), String> {
+    let mut args = vec![flag, service];
+    if values.is_empty() {
+        args.push("Empty");
+    } else {
+        args.extend(values.iter().map(String::as_str));
+    }
+    networksetup(&args).map(|_| ())
+}
+
+/// Enabled services from `networksetup -listallnetworkservices`.
+fn parse_services(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .skip(1) // "An asterisk (*) denotes that a network service is disabled."
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('*'))
+        .map(String::from)
+        .collect()
+}
+
+/// Values from `-getdnsservers` / `-getsearchdomains` (empty when unset).
+fn parse_list(output: &str) -> Vec<String> {
+    if output.contains("aren't any") {
+        return Vec::new();
+    }
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(String::from)
+        .collect()
+}
+
 /// Try to get DNS from /etc/resolv.conf
 fn try_get_dns_resolv_conf() -> Option<String> {
     let content = std::fs::read_to_string(constants::RESOLV_CONF_PATH).ok()?;
@@ -32,10 +120,7 @@ fn try_get_dns_resolv_conf() -> Option<String> {
 
 /// Try to get DNS from scutil (macOS)
 fn try_get_dns_scutil() -> Option<String> {
-    let output = std::process::Command::new("scutil")
-        .args(["--dns"])
-        .output()
-        .ok()?;
+    let output = Command::new("scutil").args(["--dns"]).output().ok()?;
 
     if !output.status.success() {
         return None;
@@ -58,7 +143,7 @@ fn try_get_dns_scutil() -> Option<String> {
 
 /// Try to get DNS from networksetup (macOS)
 fn try_get_dns_networksetup() -> Option<String> {
-    let output = std::process::Command::new("networksetup")
+    let output = Command::new("networksetup")
         .args(["-listallnetworkservices"])
         .output()
         .ok()?;
@@ -70,7 +155,7 @@ fn try_get_dns_networksetup() -> Option<String> {
     let stdout = String::from_utf8_lossy(&output.stdout);
     for service in ["Wi-Fi", "Ethernet", "USB 10/100/1000 LAN"] {
         if stdout.contains(service) {
-            if let Ok(dns_output) = std::process::Command::new("networksetup")
+            if let Ok(dns_output) = Command::new("networksetup")
                 .args(["-getdnsservers", service])
                 .output()
             {
@@ -84,3 +169,20 @@ fn try_get_dns_networksetup() -> Option<String> {
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_services_skips_header_and_disabled() {
+        let out = "An asterisk (*) denotes that a network service is disabled.\nWi-Fi\n*Bluetooth PAN\nThunderbolt Bridge\n";
+        assert_eq!(parse_services(out), vec!["Wi-Fi", "Thunderbolt Bridge"]);
+    }
+
+    #[test]
+    fn test_parse_list() {
+        assert!(parse_list("There aren't any DNS Servers set on Wi-Fi.\n").is_empty());
+        assert_eq!(parse_list("1.1.1.1\n1.0.0.1\n"), vec!["1.1.1.1", "1.0.0.1"]);
+    }
+}
@@ -1,13 +1,13 @@
 //! macOS pf (Packet Filter) firewall implementation for kill switch.
 
+use crate::config::KillSwitchConfig;
 use crate::constants;
-use crate::core::killswitch::{KillSwitchError, Result};
+use crate::core::killswitch::{KillSwitchError, PortProtocol, Result, RulePlan};
 use crate::logger::{self, LogLevel};
 use crate::platform::Firewall;
 use std::fmt::Write as FmtWrite;
 use std::fs;
 use std::io::Write as IoWrite;
-use std::os::unix::fs::OpenOptionsExt;
 use std::process::Command;
 
 /// macOS pf-based firewall implementation.
@@ -15,34 +15,103 @@ pub struct PfFirewall;
 
 impl PfFirewall {
     /// Generate pf rules that block all traffic except VPN.
-    pub fn generate_pf_rules(vpn_interface: &str, vpn_server_ip: Option<&str>) -> String {
+    ///
+    /// `block all` covers IPv6 too; link-local and neighbor discovery are let
+    /// through unless `options.block_ipv6` drops IPv6 entirely.
+    pub fn generate_pf_rules(
+        vpn_interface: &str,
+        vpn_server_ip: Option<&str>,
+        options: &KillSwitchConfig,
+    ) -> String {
         let mut rules = format!(
-            r"# Vortix Kill Switch Rules - Auto-generated
+            r#"# Vortix Kill Switch Rules - Auto-generated
 # DO NOT EDIT - Will be overwritten
 
 # Default: block all
 block all
 
+# Outside-DNS block (empty unless a profile enables it)
+anchor "{dns_anchor}"
+
 # Allow loopback
 pass quick on lo0 all
+"#,
+            dns_anchor = constants::PF_DNS_ANCHOR,
+        );
+
+        if options.block_ipv6 {
+            rules.push_str("\n# Block IPv6 entirely\nblock drop quick inet6 all\n");
+        }
 
-# Allow local network (RFC1918)
+        if !options.block_lan {
+            rules.push_str(
+                r"
+# Allow local network (RFC1918, IPv6 link-local)
 pass out quick to 192.168.0.0/16
 pass in quick from 192.168.0.0/16
 pass out quick to 10.0.0.0/8
 pass in quick from 10.0.0.0/8
 pass out quick to 172.16.0.0/12
 pass in quick from 172.16.0.0/12
+pass out quick inet6 to { fe80::/10, ff02::/16 }
+pass in quick inet6 from fe80::/10
+",
+            );
+        }
 
-# Allow DHCP
+        rules.push_str(
+            r"
+# Allow DHCP, DHCPv6 and IPv6 neighbor discovery
 pass out quick proto udp from any port 68 to any port 67
 pass in quick proto udp from any port 67 to any port 68
-
-# Allow all traffic on VPN interface
-pass quick on {vpn_interface} all
-"
+pass out quick inet6 proto udp from any port 546 to any port 547
+pass in quick inet6 proto udp from any port 547 to any port 546
+pass quick inet6 proto icmp6 all icmp6-type { routersol, routeradv, neighbrsol, neighbradv }
+",
         );
 
+        let cidrs = options.allowed_cidrs();
+        let ports = options.allowed_ports();
+        if !cidrs.is_empty() || !ports.is_empty() {
+            rules.push_str("\n# Configured exceptions\n");
+        }
+        for net in cidrs {
+            writeln!(rules, "pass out quick to {net}").unwrap();
+        }
+        for port in ports {
+            match (port.protocol, port.ports) {
+                (PortProtocol::Icmp, _) => {
+                    rules.push_str("pass out quick inet proto icmp all\n");
+                    rules.push_str("pass out quick inet6 proto icmp6 all\n");
+                }
+                (protocol, None) => {
+                    writeln!(rules, "pass out quick proto {} all", protocol.as_str()).unwrap();
+                }
+                (protocol, Some((lo, hi))) if lo == hi => {
+                    writeln!(
+                        rules,
+                        "pass out quick proto {} to any port {lo}",
+                        protocol.as_str()
+                    )
+                    .unwrap();
+                }
+                (protocol, Some((lo, hi))) => {
+                    writeln!(
+                        rules,
+                        "pass out quick proto {} to any port {lo}:{hi}",
+                        protocol.as_str()
+                    )
+                    .unwrap();
+                }
+            }
+        }
+
+        writeln!(
+            rules,
+            "\n# Allow all traffic on VPN interface\npass quick on {vpn_interface} all"
+        )
+        .unwrap();
+
         if let Some(ip) = vpn_server_ip {
             writeln!(
                 rules,
@@ -53,10 +122,103 @@ pass quick on {vpn_interface} all
 
         rules
     }
+
+    /// Plan that writes the pf rules and loads them.
+    ///
+    /// `pfctl -e` fails when pf is already enabled, so it may fail.
+    pub fn pf_plan(
+        vpn_interface: &str,
+        vpn_server_ip: Option<&str>,
+        options: &KillSwitchConfig,
+    ) -> RulePlan {
+        let mut plan = RulePlan::new("pf");
+        plan.write_file(
+            constants::PF_CONF_PATH,
+            Self::generate_pf_rules(vpn_interface, vpn_server_ip, options),
+        );
+        plan.run("pfctl", &["-f", constants::PF_CONF_PATH]);
+        plan.try_run("pfctl", &["-e"]);
+        plan
+    }
+
+    /// Differences between the live pf state (`pfctl -s info`, `pfctl -s
+    /// rules`) and the kill switch rules.
+    ///
+    /// pf rewrites rules when loading them (adding `flags S/SA keep state`
+    /// and the like), so only the rules the kill switch cannot work without
+    /// are checked.
+    pub fn pf_drift(
+        info: &str,
+        rules: &str,
+        vpn_interface: &str,
+        options: &KillSwitchConfig,
+    ) -> Vec<String> {
+        let mut drift = Vec::new();
+        if !info
+            .lines()
+            .any(|l| l.trim_start().starts_with("Status: Enabled"))
+        {
+            drift.push("pf: packet filter is disabled".to_string());
+        }
+
+        let has = |prefix: &str| rules.lines().any(|l| l.trim().starts_with(prefix));
+        if !has("block drop all") {
+            drift.push("pf: default block rule is missing".to_string());
+        }
+        if !has(&format!("pass quick on {vpn_interface} all")) {
+            drift.push(format!("pf: pass rule for {vpn_interface} is missing"));
+        }
+        if options.block_ipv6 && !has("block drop quick inet6 all") {
+            drift.push("pf: IPv6 block rule is missing".to_string());
+        }
+        drift
+    }
+
+    /// Generate anchor rules that drop port 53 traffic outside the tunnel,
+    /// over IPv4 and IPv6.
+    pub fn generate_dns_block_rules(vpn_interface: &str) -> String {
+        format!(
+            "pass out quick on lo0 all\n\
+             pass out quick on {vpn_interface} all\n\
+             block drop out quick inet proto {{ udp tcp }} from any to any port 53\n\
+             block drop out quick inet6 proto {{ udp tcp }} from any to any port 53\n"
+        )
+    }
 }
 
 impl Firewall for PfFirewall {
-    fn enable_blocking(vpn_interface: &str, vpn_server_ip: Option<&str>) -> Result<()> {
+    fn plan_blocking(
+        vpn_interface: &str,
+        vpn_server_ip: Option<&str>,
+        options: &KillSwitchConfig,
+    ) -> Result<RulePlan> {
+        Ok(Self::pf_plan(vpn_interface, vpn_server_ip, options))
+    }
+
+    fn verify_blocking(
+        vpn_interface: &str,
+        _vpn_server_ip: Option<&str>,
+        options: &KillSwitchConfig,
+    ) -> Result<Vec<String>> {
+        if !crate::utils::is_root() {
+            return Err(KillSwitchError::NotRoot);
+        }
+
+        let info = Command::new("pfctl").args(["-s", "info"]).output()?;
+        let rules = Command::new("pfctl").args(["-s", "rules"]).output()?;
+        Ok(Self::pf_drift(
+            &String::from_utf8_lossy(&info.stdout),
+            &String::from_utf8_lossy(&rules.stdout),
+            vpn_interface,
+            options,
+        ))
+    }
+
+    fn enable_blocking(
+        vpn_interface: &str,
+        vpn_server_ip: Option<&str>,
+        options: &KillSwitchConfig,
+    ) -> Result<()> {
         logger::log(
             LogLevel::Info,
             "FIREWALL",
@@ -78,44 +240,13 @@ impl Firewall for PfFirewall {
             return Err(KillSwitchError::NotRoot);
         }
 
-        let rules = Self::generate_pf_rules(vpn_interface, vpn_server_ip);
-        let mut file = fs::OpenOptions::new()
-            .write(true)
-            .create(true)
-            .truncate(true)
-            .mode(0o600) // Root-only read/write — prevents symlink attacks
-            .open(constants::PF_CONF_PATH)?;
-        file.write_all(rules.as_bytes())?;
-        logger::log(
-            LogLevel::Debug,
-            "FIREWALL",
-            format!("Wrote pf rules to {}", constants::PF_CONF_PATH),
-        );
-
-        let output = Command::new("pfctl")
-            .args(["-f", constants::PF_CONF_PATH])
-            .output()?;
-        if !output.status.success() {
-            let err = String::from_utf8_lossy(&output.stderr).to_string();
+        if let Err(e) = Self::plan_blocking(vpn_interface, vpn_server_ip, options)?.execute() {
             logger::log(
                 LogLevel::Error,
                 "FIREWALL",
-                format!("pfctl -f failed: {err}"),
+                format!("Failed to load pf rules: {e}"),
             );
-            return Err(KillSwitchError::CommandFailed(err));
-        }
-
-        let output = Command::new("pfctl").args(["-e"]).output()?;
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            if !stderr.contains("enabled") {
-                logger::log(
-                    LogLevel::Error,
-                    "FIREWALL",
-                    format!("pfctl -e failed: {stderr}"),
-                );
-                return Err(KillSwitchError::CommandFailed(stderr.to_string()));
-            }
+            return Err(e);
         }
 
         logger::log(
@@ -161,6 +292,50 @@ impl Firewall for PfFirewall {
         );
         Ok(())
     }
+
+    fn enable_dns_block(vpn_interface: &str) -> Result<()> {
+        if !crate::utils::is_root() {
+            return Err(KillSwitchError::NotRoot);
+        }
+
+        let rules = Self::generate_dns_block_rules(vpn_interface);
+        let mut child = Command::new("pfctl")
+            .args(["-a", constants::PF_DNS_ANCHOR, "-f", "-"])
+            .stdin(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(rules.as_bytes())?;
+        }
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            let err = String::from_utf8_lossy(&output.stderr).to_string();
+            return Err(KillSwitchError::CommandFailed(err));
+        }
+
+        // Make sure pf is evaluating rules at all ("already enabled" is fine)
+        let _ = Command::new("pfctl").args(["-e"]).output()?;
+
+        logger::log(
+            LogLevel::Info,
+            "FIREWALL",
+            format!("Blocking DNS outside '{vpn_interface}'"),
+        );
+        Ok(())
+    }
+
+    fn disable_dns_block() -> Result<()> {
+        if !crate::utils::is_root() {
+            return Err(KillSwitchError::NotRoot);
+        }
+
+        let _ = Command::new("pfctl")
+            .args(["-a", constants::PF_DNS_ANCHOR, "-F", "all"])
+            .output()?;
+
+        logger::log(LogLevel::Info, "FIREWALL", "DNS block removed");
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -169,7 +344,8 @@ mod tests {
 
     #[test]
     fn test_generate_pf_rules_with_server() {
-        let rules = PfFirewall::generate_pf_rules("utun3", Some("1.2.3.4"));
+        let rules =
+            PfFirewall::generate_pf_rules("utun3", Some("1.2.3.4"), &KillSwitchConfig::default());
         assert!(rules.contains("block all"));
         assert!(rules.contains("pass quick on lo0"));
         assert!(rules.contains("192.168.0.0/16"));
@@ -177,11 +353,101 @@ mod tests {
         assert!(rules.contains("pass quick on utun3"));
     }
 
+    #[test]
+    fn test_pf_plan() {
+        let plan = PfFirewall::pf_plan("utun3", None, &KillSwitchConfig::default());
+        assert_eq!(plan.backend, "pf");
+        assert_eq!(
+            plan.commands(),
+            vec![
+                format!("pfctl -f {}", constants::PF_CONF_PATH),
+                "pfctl -e".to_string()
+            ]
+        );
+        assert!(plan.to_string().contains("pass quick on utun3 all"));
+    }
+
+    #[test]
+    fn test_pf_drift() {
+        let info = "Status: Enabled for 0 days 00:10:02           Debug: Urgent\n";
+        let rules = "block drop all\n\
+                     pass quick on lo0 all flags S/SA keep state\n\
+                     pass quick on utun3 all flags S/SA keep state\n";
+        let options = KillSwitchConfig::default();
+        assert!(PfFirewall::pf_drift(info, rules, "utun3", &options).is_empty());
+
+        assert_eq!(
+            PfFirewall::pf_drift("Status: Disabled\n", "", "utun3", &options),
+            vec![
+                "pf: packet filter is disabled",
+                "pf: default block rule is missing",
+                "pf: pass rule for utun3 is missing",
+            ]
+        );
+        assert_eq!(
+            PfFirewall::pf_drift(info, rules, "utun3", &block_ipv6_options()),
+            vec!["pf: IPv6 block rule is missing"]
+        );
+    }
+
+    fn block_ipv6_options() -> KillSwitchConfig {
+        KillSwitchConfig {
+            block_ipv6: true,
+            ..KillSwitchConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_generate_dns_block_rules() {
+        let rules = PfFirewall::generate_dns_block_rules("utun3");
+        assert!(rules.contains("pass out quick on utun3 all"));
+        assert!(
+            rules.contains("block drop out quick inet proto { udp tcp } from any to any port 53")
+        );
+        assert!(
+            rules.contains("block drop out quick inet6 proto { udp tcp } from any to any port 53")
+        );
+        // The tunnel passes come first; pf's quick rules stop at the first match
+        let pass = rules.find("pass out quick on utun3 all").unwrap();
+        let block = rules.find("block drop out quick inet ").unwrap();
+        assert!(pass < block);
+    }
+
     #[test]
     fn test_generate_pf_rules_without_server() {
-        let rules = PfFirewall::generate_pf_rules("utun3", None);
+        let rules = PfFirewall::generate_pf_rules("utun3", None, &KillSwitchConfig::default());
         assert!(rules.contains("block all"));
         assert!(rules.contains("pass quick on utun3"));
         assert!(!rules.contains("1.2.3.4"));
+        assert!(rules.contains("neighbrsol"));
+        assert!(!rules.contains("block drop quick inet6 all"));
+    }
+
+    #[test]
+    fn test_generate_pf_rules_block_ipv6() {
+        let options = KillSwitchConfig {
+            block_ipv6: true,
+            ..KillSwitchConfig::default()
+        };
+        let rules = PfFirewall::generate_pf_rules("utun3", None, &options);
+        let block = rules.find("block drop quick inet6 all").unwrap();
+        let tunnel = rules.find("pass quick on utun3").unwrap();
+        assert!(block < tunnel);
+    }
+
+    #[test]
+    fn test_generate_pf_rules_block_lan_with_exceptions() {
+        let options = KillSwitchConfig {
+            block_lan: true,
+            allow_cidrs: vec!["100.64.0.0/10".to_string()],
+            allow_ports: vec!["udp/5353".to_string(), "tcp/8000-8100".to_string()],
+            ..KillSwitchConfig::default()
+        };
+        let rules = PfFirewall::generate_pf_rules("utun3", None, &options);
+        assert!(!rules.contains("192.168.0.0/16"));
+        assert!(rules.contains("pass out quick proto udp from any port 68 to any port 67"));
+        assert!(rules.contains("pass out quick to 100.64.0.0/10"));
+        assert!(rules.contains("pass out quick proto udp to any port 5353"));
+        assert!(rules.contains("pass out quick proto tcp to any port 8000:8100"));
     }
 }
@@ -1,14 +1,13 @@
 //! macOS network statistics via `netstat -ib`.
 
-use crate::platform::NetworkStatsProvider;
+use crate::platform::{InterfaceBytes, NetworkStatsProvider};
 
 /// macOS network stats using `netstat -ib`.
 pub struct MacNetworkStats;
 
 impl NetworkStatsProvider for MacNetworkStats {
-    fn get_total_bytes() -> (u64, u64) {
-        let mut total_in: u64 = 0;
-        let mut total_out: u64 = 0;
+    fn get_interface_bytes() -> InterfaceBytes {
+        let mut counters = InterfaceBytes::new();
 
         let timeout = std::time::Duration::from_secs(crate::constants::CMD_TIMEOUT_SECS);
         if let Some(output) = crate::utils::run_with_timeout(
@@ -32,14 +31,16 @@ impl NetworkStatsProvider for MacNetworkStats {
                     _ => (6, 9),
                 }
             } else {
-                return (total_in, total_out);
+                return counters;
             };
 
             for line in lines {
                 let parts: Vec<&str> = line.split_whitespace().collect();
                 if parts.len() > ibytes_idx.max(obytes_idx) {
-                    let iface = parts[0];
-                    if iface.starts_with("lo") {
+                    // One row per address repeats the interface's counters;
+                    // the first (`<Link#n>`) row is enough
+                    let iface = parts[0].trim_end_matches('*');
+                    if counters.contains_key(iface) {
                         continue;
                     }
 
@@ -52,8 +53,7 @@ impl NetworkStatsProvider for MacNetworkStats {
                             if let (Ok(ibytes), Ok(obytes)) =
                                 (ibytes_str.parse::<u64>(), obytes_str.parse::<u64>())
                             {
-                                total_in += ibytes;
-                                total_out += obytes;
+                                counters.insert(iface.to_string(), (ibytes, obytes));
                             }
                         }
                     }
@@ -61,6 +61,21 @@ impl NetworkStatsProvider for MacNetworkStats {
             }
         }
 
-        (total_in, total_out)
+        counters
+    }
+
+    fn get_uplink_interface() -> Option<String> {
+        // `OpenVPN`'s def1 half routes and `WireGuard`'s 0/1 + 128/1 routes
+        // leave the exact default route on the physical interface
+        let timeout = std::time::Duration::from_secs(crate::constants::CMD_TIMEOUT_SECS);
+        let output = crate::utils::run_with_timeout(
+            std::process::Command::new("route").args(["-n", "get", "default"]),
+            timeout,
+        )?;
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("interface:"))
+            .map(|iface| iface.trim().to_string())
+            .filter(|iface| !iface.starts_with("utun"))
     }
 }
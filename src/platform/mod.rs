@@ -5,7 +5,7 @@
 //!
 //! Supported platforms:
 //! - macOS: pf firewall, netstat -ib, ifconfig, scutil/networksetup
-//! - Linux: iptables/nftables, /proc/net/dev, ip addr, resolvectl
+//! - Linux: iptables/nftables, /proc/net/dev, ip addr, resolvectl, netlink
 
 #[cfg(target_os = "linux")]
 pub mod linux;
@@ -15,7 +15,11 @@ pub mod macos;
 #[cfg(not(any(target_os = "macos", target_os = "linux")))]
 compile_error!("Vortix currently only supports macOS and Linux");
 
-use crate::core::killswitch::Result as KsResult;
+use crate::config::KillSwitchConfig;
+use crate::core::dns::DnsRestore;
+use crate::core::killswitch::{Result as KsResult, RulePlan};
+#[cfg(target_os = "linux")]
+use crate::vpn::wireguard::{WgConfig, WgDevice};
 
 // Re-export platform constants from the centralized constants module for convenience.
 pub use crate::constants::DEFAULT_VPN_INTERFACE;
@@ -27,19 +31,70 @@ pub use crate::constants::KILLSWITCH_EMERGENCY_MSG;
 ///
 /// Implementations block all non-VPN traffic when enabled.
 pub trait Firewall {
+    /// Build the plan `enable_blocking` executes. Detects the backend but
+    /// changes nothing, so it needs no root.
+    fn plan_blocking(
+        vpn_interface: &str,
+        vpn_server_ip: Option<&str>,
+        options: &KillSwitchConfig,
+    ) -> KsResult<RulePlan>;
+
+    /// Read back the live rules and list how they differ from the plan
+    /// (empty when intact).
+    fn verify_blocking(
+        vpn_interface: &str,
+        vpn_server_ip: Option<&str>,
+        options: &KillSwitchConfig,
+    ) -> KsResult<Vec<String>>;
+
     /// Enable kill switch by loading restrictive firewall rules.
-    fn enable_blocking(vpn_interface: &str, vpn_server_ip: Option<&str>) -> KsResult<()>;
+    fn enable_blocking(
+        vpn_interface: &str,
+        vpn_server_ip: Option<&str>,
+        options: &KillSwitchConfig,
+    ) -> KsResult<()>;
 
     /// Disable kill switch by flushing firewall rules.
     fn disable_blocking() -> KsResult<()>;
+
+    /// Drop port 53 traffic that does not leave through `vpn_interface`.
+    fn enable_dns_block(vpn_interface: &str) -> KsResult<()>;
+
+    /// Remove the rules installed by `enable_dns_block`.
+    fn disable_dns_block() -> KsResult<()>;
+}
+
+/// Byte counters (in, out) keyed by interface name.
+pub type InterfaceBytes = std::collections::HashMap<String, (u64, u64)>;
+
+/// Whether `name` is the loopback interface (`lo` on Linux, `lo0` on macOS).
+#[must_use]
+pub fn is_loopback(name: &str) -> bool {
+    name == "lo" || name == "lo0"
 }
 
 /// Network statistics collection.
 ///
 /// Implementations read per-interface byte counters to calculate throughput.
 pub trait NetworkStatsProvider {
-    /// Get total bytes (in, out) across all non-loopback interfaces.
-    fn get_total_bytes() -> (u64, u64);
+    /// Get bytes (in, out) of every interface, including loopback.
+    fn get_interface_bytes() -> InterfaceBytes;
+
+    /// Name of the physical interface holding the default route, i.e. the
+    /// uplink a tunnel's encrypted traffic leaves through.
+    fn get_uplink_interface() -> Option<String>;
+}
+
+/// Byte counters of every interface, read with this platform's
+/// [`NetworkStatsProvider`].
+#[must_use]
+pub fn interface_bytes() -> InterfaceBytes {
+    #[cfg(target_os = "macos")]
+    type Stats = macos::network::MacNetworkStats;
+    #[cfg(target_os = "linux")]
+    type Stats = linux::network::LinuxNetworkStats;
+
+    Stats::get_interface_bytes()
 }
 
 /// VPN interface detection.
@@ -59,6 +114,65 @@ pub trait InterfaceDetector {
     fn get_interface_info(interface: &str) -> (String, String);
 }
 
+/// Network change notifications.
+///
+/// Implementations block on OS notifications for link, address and route
+/// changes, so callers run them on a dedicated thread.
+pub trait NetworkWatcher {
+    /// Block and call `on_change` with the affected interface names after
+    /// each burst of changes. Returns once `on_change` returns `false`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the notification source cannot be opened or fails.
+    fn watch(on_change: &mut dyn FnMut(&[String]) -> bool) -> std::io::Result<()>;
+}
+
+/// Native `WireGuard` device control.
+///
+/// Implementations configure the kernel module directly instead of running
+/// `wg`/`wg-quick`, and report device state as structured data.
+#[cfg(target_os = "linux")]
+pub trait WireGuardControl {
+    /// Whether the kernel module is loaded or can be loaded on demand.
+    fn is_available() -> bool;
+
+    /// Create `interface` and configure it from `config`: keys, peers,
+    /// addresses and routes. Nothing is left behind on failure.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an endpoint cannot be resolved or the kernel
+    /// rejected a step.
+    fn up(interface: &str, config: &WgConfig) -> Result<(), String>;
+
+    /// Remove `interface` and the routing rules `up` added for it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the interface could not be deleted.
+    fn down(interface: &str) -> Result<(), String>;
+
+    /// Whether `interface` was brought up by `up` (and not by `wg-quick`).
+    fn is_managed(interface: &str) -> bool;
+
+    /// Read keys, peers and traffic counters of `interface`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `interface` is not a `WireGuard` device or the
+    /// caller lacks `CAP_NET_ADMIN`.
+    fn device(interface: &str) -> Result<WgDevice, String>;
+
+    /// Re-apply each peer's endpoint so the next packet starts a fresh
+    /// handshake over the current route.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the device has no peer endpoints or cannot be updated.
+    fn rehandshake(interface: &str) -> Result<(), String>;
+}
+
 /// DNS resolver information.
 ///
 /// Implementations query the system for the active DNS server.
@@ -67,6 +181,28 @@ pub trait DnsResolver {
     fn get_dns_server() -> Option<String>;
 }
 
+/// Resolver configuration for per-profile DNS overrides.
+pub trait DnsConfigurator {
+    /// Point the system resolver at `servers` / `search` for the tunnel on
+    /// `vpn_interface`. Returns how to undo the change.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no supported resolver backend accepted the change.
+    fn apply_dns(
+        vpn_interface: &str,
+        servers: &[String],
+        search: &[String],
+    ) -> Result<DnsRestore, String>;
+
+    /// Undo a change made by `apply_dns`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the previous configuration could not be written back.
+    fn restore_dns(restore: &DnsRestore) -> Result<(), String>;
+}
+
 /// Platform-appropriate install hint for a package.
 #[cfg(target_os = "macos")]
 pub fn install_hint(pkg: &str) -> String {
@@ -50,6 +50,32 @@ impl KillSwitchState {
     pub const fn is_blocking(self) -> bool {
         matches!(self, Self::Blocking)
     }
+
+    /// Compute the state the kill switch should be in for `mode`, given
+    /// whether a tunnel is up and the state it is in right now.
+    ///
+    /// Auto mode keeps blocking after a drop until the VPN is back;
+    /// strict mode blocks whenever the VPN is down.
+    #[must_use]
+    pub const fn target(mode: KillSwitchMode, connected: bool, current: Self) -> Self {
+        match mode {
+            KillSwitchMode::Off => Self::Disabled,
+            KillSwitchMode::Auto => {
+                if !connected && matches!(current, Self::Blocking) {
+                    Self::Blocking
+                } else {
+                    Self::Armed // Show intent even if disconnected
+                }
+            }
+            KillSwitchMode::AlwaysOn => {
+                if connected {
+                    Self::Armed
+                } else {
+                    Self::Blocking
+                }
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -69,4 +95,17 @@ mod tests {
         assert!(!KillSwitchState::Armed.is_blocking());
         assert!(KillSwitchState::Blocking.is_blocking());
     }
+
+    #[test]
+    fn test_target_state() {
+        use KillSwitchMode::{AlwaysOn, Auto, Off};
+        use KillSwitchState::{Armed, Blocking, Disabled};
+
+        assert_eq!(KillSwitchState::target(Off, false, Blocking), Disabled);
+        assert_eq!(KillSwitchState::target(Auto, false, Armed), Armed);
+        assert_eq!(KillSwitchState::target(Auto, false, Blocking), Blocking);
+        assert_eq!(KillSwitchState::target(Auto, true, Blocking), Armed);
+        assert_eq!(KillSwitchState::target(AlwaysOn, false, Armed), Blocking);
+        assert_eq!(KillSwitchState::target(AlwaysOn, true, Blocking), Armed);
+    }
 }
@@ -5,14 +5,20 @@
 //! - `profile`: VPN profile configuration and protocol types
 //! - `ui`: UI-specific state like focus, input mode, and toasts
 //! - `killswitch`: Kill switch mode and state
+//! - `reconnect`: Automatic reconnect attempts and backoff
 
 mod connection;
 mod killswitch;
 mod profile;
+mod reconnect;
 mod ui;
 
 // Re-export all types for easy access
 pub use connection::{ConnectionState, DetailedConnectionInfo};
 pub use killswitch::{KillSwitchMode, KillSwitchState};
-pub use profile::{Protocol, VpnProfile};
-pub use ui::{AuthField, FocusedPanel, InputMode, Toast, ToastType, DISMISS_DURATION};
+pub use profile::{ProfileLabels, ProfileSection, Protocol, VpnProfile};
+pub use reconnect::{backoff_delay, ReconnectState};
+pub use ui::{
+    AuthField, FocusedPanel, FormField, InputMode, ProfileForm, SidebarRow, Toast, ToastType,
+    DISMISS_DURATION,
+};
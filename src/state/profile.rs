@@ -1,5 +1,6 @@
 //! VPN profile and protocol types.
 
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::time::SystemTime;
 
@@ -37,4 +38,192 @@ pub struct VpnProfile {
     pub config_path: PathBuf,
     /// Last time this profile was used.
     pub last_used: Option<SystemTime>,
+    /// Group, tags, favorite flag and quick slot (stored in `metadata.json`).
+    pub labels: ProfileLabels,
+}
+
+impl VpnProfile {
+    /// Sidebar section this profile is listed under.
+    #[must_use]
+    pub fn section(&self) -> ProfileSection {
+        if self.labels.favorite {
+            ProfileSection::Favorites
+        } else if let Some(group) = &self.labels.group {
+            ProfileSection::Group(group.clone())
+        } else {
+            ProfileSection::Ungrouped
+        }
+    }
+
+    /// How well `query` matches this profile's name, location, protocol and
+    /// tags. Every word of the query has to match one of them; name matches
+    /// count double. `None` if the profile doesn't match.
+    #[must_use]
+    pub fn search_score(&self, query: &str) -> Option<u32> {
+        let protocol = self.protocol.to_string();
+        query.split_whitespace().try_fold(0, |total, term| {
+            let name = crate::utils::fuzzy_score(&self.name, term).map(|s| s * 2);
+            let other = std::iter::once(self.location.as_str())
+                .chain(std::iter::once(protocol.as_str()))
+                .chain(self.labels.tags.iter().map(String::as_str))
+                .filter_map(|field| crate::utils::fuzzy_score(field, term))
+                .max();
+            Some(total + name.max(other)?)
+        })
+    }
+
+    /// Whether the profile carries tag `scope` or its location contains it
+    /// (case-insensitive). A blank scope takes in every profile.
+    #[must_use]
+    pub fn in_scope(&self, scope: &str) -> bool {
+        let scope = scope.trim();
+        scope.is_empty()
+            || self
+                .labels
+                .tags
+                .iter()
+                .any(|t| t.eq_ignore_ascii_case(scope.trim_start_matches('#')))
+            || self.location.to_lowercase().contains(&scope.to_lowercase())
+    }
+}
+
+/// How the user organized a profile in the sidebar.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileLabels {
+    /// Sidebar group (`None` = ungrouped).
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub group: Option<String>,
+    /// Free-form tags, shown next to the name.
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub tags: Vec<String>,
+    /// Listed under Favorites, ahead of every group.
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    pub favorite: bool,
+    /// Number key (1-9) that connects this profile.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub quick_slot: Option<u8>,
+}
+
+impl ProfileLabels {
+    /// Parse a comma-separated tag list: trimmed, a leading `#` dropped,
+    /// empty and repeated entries skipped.
+    #[must_use]
+    pub fn parse_tags(input: &str) -> Vec<String> {
+        let mut tags: Vec<String> = Vec::new();
+        for tag in input.split(',') {
+            let tag = tag.trim().trim_start_matches('#').trim();
+            if !tag.is_empty() && !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                tags.push(tag.to_string());
+            }
+        }
+        tags
+    }
+
+    /// One-line description for the activity log.
+    #[must_use]
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if self.favorite {
+            parts.push("favorite".to_string());
+        }
+        if let Some(group) = &self.group {
+            parts.push(format!("group {group}"));
+        }
+        if !self.tags.is_empty() {
+            parts.push(format!("tags {}", self.tags.join(", ")));
+        }
+        if parts.is_empty() {
+            "no labels".to_string()
+        } else {
+            parts.join("; ")
+        }
+    }
+}
+
+/// A sidebar section. Sorts in display order: favorites, then groups by
+/// name, then profiles without a group.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ProfileSection {
+    Favorites,
+    Group(String),
+    Ungrouped,
+}
+
+impl ProfileSection {
+    /// Header text.
+    #[must_use]
+    pub fn label(&self) -> &str {
+        match self {
+            Self::Favorites => "★ Favorites",
+            Self::Group(name) => name,
+            Self::Ungrouped => "Ungrouped",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tags() {
+        assert_eq!(
+            ProfileLabels::parse_tags(" #work, p2p,,Work , streaming "),
+            vec!["work", "p2p", "streaming"]
+        );
+        assert!(ProfileLabels::parse_tags(" , ").is_empty());
+    }
+
+    #[test]
+    fn test_search_score_matches_every_word() {
+        let profile = VpnProfile {
+            name: "de-fra-01".to_string(),
+            protocol: Protocol::OpenVPN,
+            location: "Frankfurt".to_string(),
+            config_path: PathBuf::from("/tmp/de-fra-01.ovpn"),
+            last_used: None,
+            labels: ProfileLabels {
+                tags: vec!["streaming".to_string()],
+                ..ProfileLabels::default()
+            },
+        };
+        assert!(profile.search_score("fra").is_some());
+        assert!(profile.search_score("openvpn stream").is_some());
+        assert!(profile.search_score("fra wireguard").is_none());
+        assert_eq!(profile.search_score("  "), Some(0));
+    }
+
+    #[test]
+    fn test_in_scope_by_tag_or_location() {
+        let profile = VpnProfile {
+            name: "de-fra-01".to_string(),
+            protocol: Protocol::WireGuard,
+            location: "Frankfurt, DE".to_string(),
+            config_path: PathBuf::from("/tmp/de-fra-01.conf"),
+            last_used: None,
+            labels: ProfileLabels {
+                tags: vec!["Streaming".to_string()],
+                ..ProfileLabels::default()
+            },
+        };
+        assert!(profile.in_scope(""));
+        assert!(profile.in_scope("#streaming"));
+        assert!(profile.in_scope("frankfurt"));
+        assert!(!profile.in_scope("stream"));
+        assert!(!profile.in_scope("Paris"));
+    }
+
+    #[test]
+    fn test_section_order() {
+        let mut sections = [
+            ProfileSection::Ungrouped,
+            ProfileSection::Group("Work".to_string()),
+            ProfileSection::Favorites,
+            ProfileSection::Group("Home".to_string()),
+        ];
+        sections.sort();
+        assert_eq!(sections[0], ProfileSection::Favorites);
+        assert_eq!(sections[1].label(), "Home");
+        assert_eq!(sections[3], ProfileSection::Ungrouped);
+    }
 }
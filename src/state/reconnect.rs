@@ -0,0 +1,104 @@
+//! Reconnect supervisor state.
+//!
+//! Tracks automatic reconnect attempts after an unexpected drop.
+
+use std::time::{Duration, Instant};
+
+/// An automatic reconnect in progress.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReconnectState {
+    /// Profile being reconnected.
+    pub profile: String,
+    /// Current attempt number (1-based).
+    pub attempt: u32,
+    /// When the current attempt fires. `None` while it is in flight.
+    pub next_at: Option<Instant>,
+    /// Interface of the dropped tunnel, kept open by the kill switch while retrying.
+    pub interface: String,
+    /// Server IP of the dropped tunnel, kept reachable by the kill switch while retrying.
+    pub server_ip: Option<String>,
+}
+
+impl ReconnectState {
+    /// Whole seconds until the next attempt (0 when due or in flight).
+    #[must_use]
+    pub fn remaining_secs(&self) -> u64 {
+        self.next_at.map_or(0, |at| {
+            let left = at.saturating_duration_since(Instant::now());
+            // Round up so the countdown never shows 0 before the attempt fires
+            left.as_secs() + u64::from(left.subsec_nanos() > 0)
+        })
+    }
+}
+
+/// Delay before reconnect `attempt` (1-based): `base * 2^(attempt-1)` capped at `max`,
+/// then spread by ±`jitter_pct`% using `entropy` as the random source.
+#[must_use]
+pub fn backoff_delay(
+    attempt: u32,
+    base: Duration,
+    max: Duration,
+    jitter_pct: u64,
+    entropy: u64,
+) -> Duration {
+    let exp = attempt.saturating_sub(1).min(31);
+    let delay = base.saturating_mul(1 << exp).min(max);
+
+    let millis = u64::try_from(delay.as_millis()).unwrap_or(u64::MAX);
+    let spread = millis.saturating_mul(jitter_pct.min(100)) / 100;
+    if spread == 0 {
+        return delay;
+    }
+    // Map entropy onto [-spread, +spread]
+    let offset = entropy % (2 * spread + 1);
+    Duration::from_millis(millis - spread + offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: Duration = Duration::from_secs(2);
+    const MAX: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn test_backoff_doubles_without_jitter() {
+        let delays: Vec<u64> = (1..=5)
+            .map(|n| backoff_delay(n, BASE, MAX, 0, 12345).as_secs())
+            .collect();
+        assert_eq!(delays, vec![2, 4, 8, 16, 32]);
+    }
+
+    #[test]
+    fn test_backoff_capped_at_max() {
+        assert_eq!(backoff_delay(6, BASE, MAX, 0, 0), MAX);
+        assert_eq!(backoff_delay(u32::MAX, BASE, MAX, 0, 0), MAX);
+    }
+
+    #[test]
+    fn test_backoff_jitter_bounds() {
+        // 20% of 4s = ±800ms
+        let low = backoff_delay(2, BASE, MAX, 20, 0);
+        let high = backoff_delay(2, BASE, MAX, 20, 1600);
+        assert_eq!(low, Duration::from_millis(3200));
+        assert_eq!(high, Duration::from_millis(4800));
+        for entropy in [1, 999, 123_456_789, u64::MAX] {
+            let d = backoff_delay(2, BASE, MAX, 20, entropy);
+            assert!(d >= low && d <= high, "{d:?} out of range");
+        }
+    }
+
+    #[test]
+    fn test_remaining_secs() {
+        let mut state = ReconnectState {
+            profile: "work".to_string(),
+            attempt: 1,
+            next_at: None,
+            interface: String::new(),
+            server_ip: None,
+        };
+        assert_eq!(state.remaining_secs(), 0);
+        state.next_at = Some(Instant::now() + Duration::from_millis(2500));
+        assert_eq!(state.remaining_secs(), 3);
+    }
+}
@@ -1,6 +1,7 @@
 //! UI state types.
 
-use super::Protocol;
+use super::{ProfileSection, Protocol};
+use crate::core::openvpn_mgmt::Challenge;
 use std::time::{Duration, Instant};
 
 /// Duration for toast notifications to remain visible.
@@ -29,10 +30,52 @@ pub enum AuthField {
     Username,
     /// Password text input (masked).
     Password,
+    /// Challenge answer (OTP code), masked unless the challenge allows echo.
+    Otp,
     /// "Save credentials" checkbox.
     SaveCheckbox,
 }
 
+impl AuthField {
+    /// Fields shown for `challenge`, in Tab order. A dynamic challenge
+    /// follows accepted credentials and asks only for the answer.
+    #[must_use]
+    pub fn order(challenge: Option<&Challenge>) -> &'static [AuthField] {
+        match challenge {
+            None => &[Self::Username, Self::Password, Self::SaveCheckbox],
+            Some(c) if c.is_dynamic() => &[Self::Otp],
+            Some(_) => &[
+                Self::Username,
+                Self::Password,
+                Self::Otp,
+                Self::SaveCheckbox,
+            ],
+        }
+    }
+}
+
+/// Per-profile settings edited through [`InputMode::ProfileForm`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ProfileForm {
+    /// Split tunnel policy (include, exclude, bypass LAN).
+    SplitTunnel,
+    /// DNS override (servers, search domains, block outside DNS).
+    Dns,
+    /// Sidebar labels (group, tags, favorite).
+    Tags,
+}
+
+/// Which field is focused in a profile settings form.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FormField {
+    /// First text input.
+    First,
+    /// Second text input.
+    Second,
+    /// Checkbox.
+    Checkbox,
+}
+
 /// Current input mode determining keyboard behavior.
 #[derive(Clone, Debug, PartialEq, Default)]
 pub enum InputMode {
@@ -81,6 +124,12 @@ pub enum InputMode {
         password: String,
         /// Cursor position in the password field.
         password_cursor: usize,
+        /// Second factor `OpenVPN` asked for along with the credentials.
+        challenge: Option<Challenge>,
+        /// Challenge answer input.
+        otp: String,
+        /// Cursor position in the challenge answer field.
+        otp_cursor: usize,
         /// Which field is currently focused.
         focused_field: AuthField,
         /// Whether to persist credentials for future sessions.
@@ -88,6 +137,93 @@ pub enum InputMode {
         /// Whether to auto-connect after submitting (false = save-only mode).
         connect_after: bool,
     },
+    /// Two-field + checkbox editor for per-profile settings.
+    ProfileForm {
+        /// Which settings are being edited.
+        form: ProfileForm,
+        /// Index of the profile being edited.
+        profile_idx: usize,
+        /// Name of the profile (for display).
+        profile_name: String,
+        /// First comma-separated list.
+        first: String,
+        /// Cursor position in the first field.
+        first_cursor: usize,
+        /// Second comma-separated list.
+        second: String,
+        /// Cursor position in the second field.
+        second_cursor: usize,
+        /// Checkbox state.
+        checked: bool,
+        /// Which field is currently focused.
+        focused_field: FormField,
+    },
+    /// Profile search: the sidebar only lists profiles matching `query`,
+    /// best match first.
+    Search {
+        /// Text the profiles are matched against.
+        query: String,
+        /// Cursor position in the query.
+        cursor: usize,
+    },
+    /// Quick slot picker: the next digit binds the profile to that slot.
+    QuickSlot {
+        /// Index of the profile being bound.
+        profile_idx: usize,
+        /// Name of the profile (for display).
+        profile_name: String,
+    },
+    /// Server benchmark: probes the profiles in scope, then offers to
+    /// connect the fastest.
+    Benchmark {
+        /// Tag or location the benchmark is limited to (empty = every profile).
+        scope: String,
+        /// Cursor position in the scope.
+        cursor: usize,
+        /// Whether probes are in flight.
+        running: bool,
+        /// Index of the selected result.
+        selected: usize,
+    },
+    /// Vault passphrase prompt, shown on start and when connecting an
+    /// encrypted profile while the vault is locked.
+    VaultUnlock {
+        /// Passphrase input.
+        passphrase: String,
+        /// Cursor position in the passphrase.
+        cursor: usize,
+        /// Why the last attempt failed.
+        error: Option<String>,
+        /// Profile to connect once unlocked.
+        connect: Option<String>,
+    },
+    /// Session history browser.
+    History {
+        /// Text the records are filtered by.
+        filter: String,
+        /// Cursor position in the filter.
+        cursor: usize,
+        /// Index of the selected record among the matching ones.
+        selected: usize,
+    },
+}
+
+/// One line of the profile sidebar.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SidebarRow {
+    /// Section header, shown once profiles are grouped or favorited.
+    Header {
+        /// The section this header opens.
+        section: ProfileSection,
+        /// Index of the section's first profile.
+        first: usize,
+        /// Number of profiles in the section.
+        count: usize,
+        /// Whether the section's profiles are hidden.
+        collapsed: bool,
+    },
+    /// A profile, by index into `App::profiles`.
+    Profile(usize),
 }
 
 /// Types of toast notifications for color coding.
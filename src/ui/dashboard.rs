@@ -1,4 +1,8 @@
-use crate::app::{App, AuthField, ConnectionState, InputMode, Protocol};
+use crate::app::{
+    App, AuthField, ConnectionState, FormField, InputMode, ProfileForm, Protocol, SidebarRow,
+};
+use crate::core::metrics::Metric;
+use crate::core::openvpn_mgmt::Challenge;
 use ratatui::{
     layout::{Alignment, Constraint, Flex, Layout, Rect},
     style::{Color, Modifier, Style},
@@ -6,7 +10,7 @@ use ratatui::{
     widgets::{
         canvas::{Canvas, Line as CanvasLine},
         Block, Borders, Cell, Clear, Paragraph, Row, Scrollbar, ScrollbarOrientation,
-        ScrollbarState, Table,
+        ScrollbarState, Table, TableState,
     },
     Frame,
 };
@@ -114,6 +118,9 @@ fn render_overlays(frame: &mut Frame, app: &mut App) {
             username_cursor,
             password,
             password_cursor,
+            challenge,
+            otp,
+            otp_cursor,
             focused_field,
             save_credentials,
             connect_after,
@@ -121,15 +128,60 @@ fn render_overlays(frame: &mut Frame, app: &mut App) {
         } => render_auth_overlay(
             frame,
             profile_name,
-            username,
-            *username_cursor,
-            password,
-            *password_cursor,
+            (username, *username_cursor),
+            (password, *password_cursor),
+            challenge.as_ref().map(|c| (c, otp.as_str(), *otp_cursor)),
             focused_field,
             *save_credentials,
             *connect_after,
         ),
-        InputMode::Normal => {}
+        InputMode::ProfileForm {
+            form,
+            profile_name,
+            first,
+            first_cursor,
+            second,
+            second_cursor,
+            checked,
+            focused_field,
+            ..
+        } => render_profile_form_overlay(
+            frame,
+            *form,
+            profile_name,
+            (first, *first_cursor),
+            (second, *second_cursor),
+            *checked,
+            *focused_field,
+        ),
+        InputMode::History {
+            filter,
+            cursor,
+            selected,
+        } => super::overlays::history::render(frame, app, filter, *cursor, *selected),
+        InputMode::Benchmark {
+            scope,
+            cursor,
+            running,
+            selected,
+        } => super::overlays::benchmark::render(frame, app, scope, *cursor, *running, *selected),
+        InputMode::QuickSlot { profile_name, .. } => {
+            render_quick_slot_overlay(frame, app, profile_name);
+        }
+        InputMode::VaultUnlock {
+            passphrase,
+            cursor,
+            error,
+            connect,
+        } => render_vault_unlock_overlay(
+            frame,
+            passphrase,
+            *cursor,
+            error.as_deref(),
+            connect.as_deref(),
+        ),
+        // Drawn inside the sidebar
+        InputMode::Search { .. } | InputMode::Normal => {}
     }
 
     if app.show_config {
@@ -245,14 +297,55 @@ fn render_import_overlay(frame: &mut Frame, path: &str, cursor: usize) {
     frame.render_widget(Paragraph::new(text).alignment(Alignment::Left), inner);
 }
 
+/// One line of a text input field, with a block cursor when focused.
+fn text_input_line(text: &str, cursor: usize, is_focused: bool, mask: bool) -> Line<'static> {
+    let display_text: String = if mask {
+        "\u{25CF}".repeat(text.len()) // ● characters
+    } else {
+        text.to_string()
+    };
+
+    let before: String = display_text.chars().take(cursor).collect();
+    let cursor_char: String = display_text
+        .chars()
+        .nth(cursor)
+        .map_or_else(|| "\u{2588}".to_string(), |c| c.to_string()); // █
+    let after: String = display_text.chars().skip(cursor + 1).collect();
+
+    let prompt_style = if is_focused {
+        Style::default().fg(theme::ACCENT_PRIMARY)
+    } else {
+        Style::default().fg(theme::TEXT_SECONDARY)
+    };
+
+    if is_focused {
+        Line::from(vec![
+            Span::styled(" > ", prompt_style),
+            Span::styled(before, Style::default().fg(theme::TEXT_PRIMARY)),
+            Span::styled(
+                cursor_char,
+                Style::default()
+                    .fg(theme::ACCENT_SECONDARY)
+                    .add_modifier(Modifier::REVERSED)
+                    .add_modifier(Modifier::SLOW_BLINK),
+            ),
+            Span::styled(after, Style::default().fg(theme::TEXT_PRIMARY)),
+        ])
+    } else {
+        Line::from(vec![
+            Span::styled("   ", prompt_style),
+            Span::styled(display_text, Style::default().fg(theme::INACTIVE)),
+        ])
+    }
+}
+
 #[allow(clippy::too_many_arguments, clippy::too_many_lines)]
 fn render_auth_overlay(
     frame: &mut Frame,
     profile_name: &str,
-    username: &str,
-    username_cursor: usize,
-    password: &str,
-    password_cursor: usize,
+    (username, username_cursor): (&str, usize),
+    (password, password_cursor): (&str, usize),
+    challenge: Option<(&Challenge, &str, usize)>,
     focused_field: &AuthField,
     save_credentials: bool,
     connect_after: bool,
@@ -292,56 +385,6 @@ fn render_auth_overlay(
     let inner = block.inner(popup_area);
     frame.render_widget(block, popup_area);
 
-    // Build text cursor helper
-    let make_cursor_line =
-        |text: &str, cursor: usize, is_focused: bool, mask: bool| -> Line<'static> {
-            let display_text: String = if mask {
-                "\u{25CF}".repeat(text.len()) // ● characters
-            } else {
-                text.to_string()
-            };
-
-            let before: String = display_text.chars().take(cursor).collect();
-            let cursor_char: String = display_text
-                .chars()
-                .nth(cursor)
-                .map_or_else(|| "\u{2588}".to_string(), |c| c.to_string()); // █
-            let after: String = display_text.chars().skip(cursor + 1).collect();
-
-            let prompt_style = if is_focused {
-                Style::default().fg(theme::ACCENT_PRIMARY)
-            } else {
-                Style::default().fg(theme::TEXT_SECONDARY)
-            };
-
-            if is_focused {
-                Line::from(vec![
-                    Span::styled(" > ", prompt_style),
-                    Span::styled(before, Style::default().fg(theme::TEXT_PRIMARY)),
-                    Span::styled(
-                        cursor_char,
-                        Style::default()
-                            .fg(theme::ACCENT_SECONDARY)
-                            .add_modifier(Modifier::REVERSED)
-                            .add_modifier(Modifier::SLOW_BLINK),
-                    ),
-                    Span::styled(after, Style::default().fg(theme::TEXT_PRIMARY)),
-                ])
-            } else {
-                let full_text: String = if mask && !text.is_empty() {
-                    "\u{25CF}".repeat(text.len())
-                } else if text.is_empty() {
-                    String::new()
-                } else {
-                    text.to_string()
-                };
-                Line::from(vec![
-                    Span::styled("   ", prompt_style),
-                    Span::styled(full_text, Style::default().fg(theme::INACTIVE)),
-                ])
-            }
-        };
-
     // Checkbox display
     let checkbox_focused = *focused_field == AuthField::SaveCheckbox;
     let checkbox_icon = if save_credentials { "[x]" } else { "[ ]" };
@@ -358,7 +401,17 @@ fn render_auth_overlay(
         Style::default().fg(theme::TEXT_SECONDARY)
     };
 
-    let text = vec![
+    let label_style = |field: AuthField| {
+        if *focused_field == field {
+            Style::default()
+                .fg(theme::TEXT_PRIMARY)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme::TEXT_SECONDARY)
+        }
+    };
+
+    let mut text = vec![
         Line::from(""),
         Line::from(vec![
             Span::styled("  Profile: ", Style::default().fg(theme::TEXT_SECONDARY)),
@@ -370,50 +423,304 @@ fn render_auth_overlay(
             ),
             Span::styled(" (OpenVPN)", Style::default().fg(theme::TEXT_SECONDARY)),
         ]),
-        Line::from(""),
+    ];
+    for field in AuthField::order(challenge.map(|(c, _, _)| c)) {
+        text.push(Line::from(""));
+        let focused = focused_field == field;
+        match field {
+            AuthField::Username => {
+                text.push(Line::from(Span::styled(
+                    "  Username:",
+                    label_style(AuthField::Username),
+                )));
+                text.push(text_input_line(username, username_cursor, focused, false));
+            }
+            AuthField::Password => {
+                text.push(Line::from(Span::styled(
+                    "  Password:",
+                    label_style(AuthField::Password),
+                )));
+                text.push(text_input_line(password, password_cursor, focused, true));
+            }
+            AuthField::Otp => {
+                let Some((challenge, otp, otp_cursor)) = challenge else {
+                    continue;
+                };
+                text.push(Line::from(Span::styled(
+                    format!("  {}:", challenge.text.trim_end_matches(':')),
+                    label_style(AuthField::Otp),
+                )));
+                text.push(text_input_line(otp, otp_cursor, focused, !challenge.echo));
+            }
+            AuthField::SaveCheckbox => text.push(Line::from(vec![
+                Span::styled(format!("  {checkbox_icon} "), checkbox_style),
+                Span::styled("Save credentials for future sessions", checkbox_label_style),
+            ])),
+        }
+    }
+
+    frame.render_widget(Paragraph::new(text).alignment(Alignment::Left), inner);
+}
+
+#[allow(clippy::too_many_lines)]
+fn render_profile_form_overlay(
+    frame: &mut Frame,
+    form: ProfileForm,
+    profile_name: &str,
+    (first, first_cursor): (&str, usize),
+    (second, second_cursor): (&str, usize),
+    checked: bool,
+    focused_field: FormField,
+) {
+    let (title, first_label, second_label, checkbox_label) = match form {
+        ProfileForm::SplitTunnel => (
+            constants::TITLE_SPLIT_TUNNEL,
+            "  Route through VPN (CIDRs/hosts, empty = all):",
+            "  Bypass VPN (CIDRs/hosts):",
+            "Bypass LAN (private and link-local ranges)",
+        ),
+        ProfileForm::Dns => (
+            constants::TITLE_DNS_OVERRIDE,
+            "  DNS servers (empty = VPN default):",
+            "  Search domains:",
+            "Block DNS outside the tunnel (port 53)",
+        ),
+        ProfileForm::Tags => (
+            constants::TITLE_PROFILE_LABELS,
+            "  Group (empty = ungrouped):",
+            "  Tags (comma-separated):",
+            "Favorite (listed first)",
+        ),
+    };
+    let note = match form {
+        ProfileForm::SplitTunnel | ProfileForm::Dns => {
+            "  Applied on the next connect. The profile file is not modified."
+        }
+        ProfileForm::Tags => "  Only changes how the sidebar lists this profile.",
+    };
+
+    let area = frame.area();
+    let popup_layout = Layout::vertical([
+        Constraint::Percentage(25),
+        Constraint::Percentage(50),
+        Constraint::Percentage(25),
+    ])
+    .split(area);
+
+    let popup_area = Layout::horizontal([
+        Constraint::Percentage(20),
+        Constraint::Percentage(60),
+        Constraint::Percentage(20),
+    ])
+    .split(popup_layout[1])[1];
+
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::ACCENT_PRIMARY))
+        .title(title)
+        .title_bottom(Line::from(constants::TITLE_PROFILE_FORM_FOOTER).centered());
+
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let label = |text: &'static str, field: FormField| {
         Line::from(Span::styled(
-            "  Username:",
-            if *focused_field == AuthField::Username {
+            text,
+            if focused_field == field {
                 Style::default()
                     .fg(theme::TEXT_PRIMARY)
                     .add_modifier(Modifier::BOLD)
             } else {
                 Style::default().fg(theme::TEXT_SECONDARY)
             },
-        )),
-        make_cursor_line(
-            username,
-            username_cursor,
-            *focused_field == AuthField::Username,
+        ))
+    };
+
+    let checkbox_focused = focused_field == FormField::Checkbox;
+    let checkbox_icon = if checked { "[x]" } else { "[ ]" };
+    let checkbox_style = if checkbox_focused {
+        Style::default()
+            .fg(theme::ACCENT_PRIMARY)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(theme::TEXT_SECONDARY)
+    };
+
+    let text = vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("  Profile: ", Style::default().fg(theme::TEXT_SECONDARY)),
+            Span::styled(
+                profile_name.to_string(),
+                Style::default()
+                    .fg(theme::ACCENT_PRIMARY)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ]),
+        Line::from(""),
+        label(first_label, FormField::First),
+        text_input_line(
+            first,
+            first_cursor,
+            focused_field == FormField::First,
             false,
         ),
         Line::from(""),
-        Line::from(Span::styled(
-            "  Password:",
-            if *focused_field == AuthField::Password {
-                Style::default()
-                    .fg(theme::TEXT_PRIMARY)
-                    .add_modifier(Modifier::BOLD)
-            } else {
-                Style::default().fg(theme::TEXT_SECONDARY)
-            },
-        )),
-        make_cursor_line(
-            password,
-            password_cursor,
-            *focused_field == AuthField::Password,
-            true,
+        label(second_label, FormField::Second),
+        text_input_line(
+            second,
+            second_cursor,
+            focused_field == FormField::Second,
+            false,
         ),
         Line::from(""),
         Line::from(vec![
             Span::styled(format!("  {checkbox_icon} "), checkbox_style),
-            Span::styled("Save credentials for future sessions", checkbox_label_style),
+            Span::styled(
+                checkbox_label,
+                if checkbox_focused {
+                    Style::default().fg(theme::TEXT_PRIMARY)
+                } else {
+                    Style::default().fg(theme::TEXT_SECONDARY)
+                },
+            ),
         ]),
+        Line::from(""),
+        Line::from(Span::styled(note, Style::default().fg(theme::INACTIVE))),
     ];
 
     frame.render_widget(Paragraph::new(text).alignment(Alignment::Left), inner);
 }
 
+/// Quick slot picker: which profile each number key connects.
+fn render_vault_unlock_overlay(
+    frame: &mut Frame,
+    passphrase: &str,
+    cursor: usize,
+    error: Option<&str>,
+    connect: Option<&str>,
+) {
+    let area = frame.area();
+    let popup_layout = Layout::vertical([
+        Constraint::Fill(1),
+        Constraint::Length(10),
+        Constraint::Fill(1),
+    ])
+    .split(area);
+
+    let popup_area = Layout::horizontal([
+        Constraint::Percentage(25),
+        Constraint::Percentage(50),
+        Constraint::Percentage(25),
+    ])
+    .split(popup_layout[1])[1];
+
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::ACCENT_PRIMARY))
+        .title(constants::TITLE_VAULT_UNLOCK)
+        .title_bottom(Line::from(constants::TITLE_VAULT_UNLOCK_FOOTER).centered());
+
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let reason = match connect {
+        Some(name) => format!("  '{name}' is encrypted. Vault passphrase:"),
+        None => "  Profiles and credentials are encrypted. Vault passphrase:".to_string(),
+    };
+    let mut text = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            reason,
+            Style::default().fg(theme::TEXT_SECONDARY),
+        )),
+        Line::from(""),
+        text_input_line(passphrase, cursor, true, true),
+    ];
+    if let Some(error) = error {
+        text.push(Line::from(""));
+        text.push(Line::from(Span::styled(
+            format!("  {error}"),
+            Style::default().fg(theme::ERROR),
+        )));
+    }
+
+    frame.render_widget(Paragraph::new(text).alignment(Alignment::Left), inner);
+}
+
+fn render_quick_slot_overlay(frame: &mut Frame, app: &App, profile_name: &str) {
+    let area = frame.area();
+    let popup_layout = Layout::vertical([
+        Constraint::Fill(1),
+        Constraint::Length(15),
+        Constraint::Fill(1),
+    ])
+    .split(area);
+
+    let popup_area = Layout::horizontal([
+        Constraint::Percentage(25),
+        Constraint::Percentage(50),
+        Constraint::Percentage(25),
+    ])
+    .split(popup_layout[1])[1];
+
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::ACCENT_PRIMARY))
+        .title(constants::TITLE_QUICK_SLOT)
+        .title_bottom(Line::from(constants::TITLE_QUICK_SLOT_FOOTER).centered());
+
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let mut text = vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("  Bind ", Style::default().fg(theme::TEXT_SECONDARY)),
+            Span::styled(
+                profile_name.to_string(),
+                Style::default()
+                    .fg(theme::ACCENT_PRIMARY)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(" to key:", Style::default().fg(theme::TEXT_SECONDARY)),
+        ]),
+        Line::from(""),
+    ];
+    for slot in 1..=9 {
+        let bound = app
+            .profiles
+            .iter()
+            .find(|p| p.labels.quick_slot == Some(slot));
+        let (name, style) = match bound {
+            Some(p) if p.name == profile_name => (
+                p.name.clone(),
+                Style::default()
+                    .fg(theme::ACCENT_PRIMARY)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Some(p) => (p.name.clone(), Style::default().fg(theme::TEXT_PRIMARY)),
+            None => ("—".to_string(), Style::default().fg(theme::INACTIVE)),
+        };
+        text.push(Line::from(vec![
+            Span::styled(
+                format!("  [{slot}] "),
+                Style::default().fg(theme::TEXT_SECONDARY),
+            ),
+            Span::styled(name, style),
+        ]));
+    }
+
+    frame.render_widget(Paragraph::new(text).alignment(Alignment::Left), inner);
+}
+
+#[allow(clippy::too_many_lines)]
 fn render_cockpit_header(frame: &mut Frame, app: &App, area: Rect) {
     let (status_text, color, profile_name, _location_text, _iface_text, since) =
         get_connection_info(app);
@@ -422,6 +729,19 @@ fn render_cockpit_header(frame: &mut Frame, app: &App, area: Rect) {
 
     // Build header based on connection state
     let line = match &app.connection_state {
+        ConnectionState::Disconnected if app.reconnect.is_some() => {
+            // Waiting out the backoff: show the countdown instead of the real IP
+            Line::from(vec![
+                Span::styled(
+                    status_text,
+                    Style::default().fg(color).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(" │ ", Style::default().fg(theme::NORD_POLAR_NIGHT_4)),
+                get_reconnect_indicator(app),
+                Span::styled(" │", Style::default().fg(theme::NORD_POLAR_NIGHT_4)),
+                ks_indicator,
+            ])
+        }
         ConnectionState::Disconnected => {
             // When disconnected, show "Real IP" label to clarify
             Line::from(vec![
@@ -439,7 +759,7 @@ fn render_cockpit_header(frame: &mut Frame, app: &App, area: Rect) {
         }
         ConnectionState::Connecting { .. } | ConnectionState::Disconnecting { .. } => {
             // Transitional states - show profile name
-            Line::from(vec![
+            let mut spans = vec![
                 Span::styled(
                     status_text,
                     Style::default().fg(color).add_modifier(Modifier::BOLD),
@@ -448,9 +768,20 @@ fn render_cockpit_header(frame: &mut Frame, app: &App, area: Rect) {
                     format!(" ({profile_name})"),
                     Style::default().fg(theme::TEXT_SECONDARY),
                 ),
-                Span::styled(" │", Style::default().fg(theme::NORD_POLAR_NIGHT_4)),
-                ks_indicator,
-            ])
+            ];
+            if app.reconnect.is_some() {
+                spans.push(Span::styled(
+                    " │ ",
+                    Style::default().fg(theme::NORD_POLAR_NIGHT_4),
+                ));
+                spans.push(get_reconnect_indicator(app));
+            }
+            spans.push(Span::styled(
+                " │",
+                Style::default().fg(theme::NORD_POLAR_NIGHT_4),
+            ));
+            spans.push(ks_indicator);
+            Line::from(spans)
         }
         ConnectionState::Connected { .. } => {
             // Connected - show VPN IP, uptime, and quality
@@ -568,6 +899,26 @@ fn get_connection_info(
     }
 }
 
+/// Get reconnect indicator for the header bar: a countdown while waiting out
+/// the backoff, the attempt number while it is in flight.
+fn get_reconnect_indicator(app: &App) -> Span<'static> {
+    let Some(rc) = &app.reconnect else {
+        return Span::raw("");
+    };
+    let max = app.config.reconnect_max_attempts;
+    let text = if rc.next_at.is_some() {
+        format!(
+            "↻ Reconnecting '{}' {}/{max} in {}s",
+            utils::truncate(&rc.profile, 15),
+            rc.attempt,
+            rc.remaining_secs()
+        )
+    } else {
+        format!("↻ Attempt {}/{max}", rc.attempt)
+    };
+    Span::styled(text, Style::default().fg(theme::WARNING))
+}
+
 /// Get kill switch indicator for the header bar.
 /// Self-explanatory labels: KS:Off, KS:Auto, KS:Strict, KS:BLOCK
 fn get_killswitch_indicator(app: &App) -> Span<'static> {
@@ -610,7 +961,7 @@ fn render_profiles_sidebar(frame: &mut Frame, app: &mut App, area: Rect) {
         .border_style(border_style)
         .title(" Profiles ");
 
-    let inner = block.inner(area);
+    let mut inner = block.inner(area);
     frame.render_widget(block, area);
 
     if app.profiles.is_empty() {
@@ -621,6 +972,20 @@ fn render_profiles_sidebar(frame: &mut Frame, app: &mut App, area: Rect) {
         return;
     }
 
+    // Search bar on the first line while searching
+    if let InputMode::Search { query, cursor } = &app.input_mode {
+        let [bar, rest] =
+            Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(inner);
+        let mut line = text_input_line(query, *cursor, true, false);
+        line.spans[0] = Span::styled(" / ", Style::default().fg(theme::ACCENT_PRIMARY));
+        line.spans.push(Span::styled(
+            format!("  {}/{}", app.search_matches().len(), app.profiles.len()),
+            Style::default().fg(Color::DarkGray),
+        ));
+        frame.render_widget(Paragraph::new(line), bar);
+        inner = rest;
+    }
+
     let (active_profile, active_color) = match &app.connection_state {
         ConnectionState::Connected { profile, .. } => (Some(profile.clone()), theme::SUCCESS),
         ConnectionState::Connecting { profile, .. }
@@ -628,87 +993,169 @@ fn render_profiles_sidebar(frame: &mut Frame, app: &mut App, area: Rect) {
         ConnectionState::Disconnected => (None, Color::Reset),
     };
 
-    let items: Vec<Row> = app
-        .profiles
-        .iter()
-        .enumerate()
-        .map(|(idx, p)| {
-            let is_selected = app.profile_list_state.selected() == Some(idx);
-            let is_active = active_profile.as_ref() == Some(&p.name);
-            let is_never_used = p.last_used.is_none();
-
-            // Status indicator — color matches connection state (green=connected, yellow=transitioning)
-            let (status_char, status_color) = if is_active {
-                ("●", active_color)
-            } else {
-                (" ", Color::Reset)
-            };
+    let rows = app.sidebar_rows();
+    let selected = app.profile_list_state.selected();
+    // The selected profile's row, or its section header while folded
+    let selected_row = selected.and_then(|sel| {
+        rows.iter().position(|row| match row {
+            SidebarRow::Profile(idx) => *idx == sel,
+            SidebarRow::Header {
+                first,
+                count,
+                collapsed: true,
+                ..
+            } => (*first..first + count).contains(&sel),
+            SidebarRow::Header { .. } => false,
+        })
+    });
+    let row_count = rows.len();
+    if rows.is_empty() {
+        frame.render_widget(
+            Paragraph::new("No matching profiles").alignment(Alignment::Center),
+            inner,
+        );
+        return;
+    }
 
-            let name_style = if is_selected {
-                Style::default()
-                    .fg(theme::ROW_SELECTED_FG)
-                    .add_modifier(Modifier::BOLD)
-            } else if is_active {
-                Style::default().fg(active_color)
-            } else if is_never_used {
-                Style::default().fg(Color::DarkGray)
-            } else {
-                Style::default().fg(theme::INACTIVE)
-            };
+    let items: Vec<Row> =
+        rows.into_iter()
+            .enumerate()
+            .map(|(row_idx, row)| {
+                let is_selected = selected_row == Some(row_idx);
+                let row_style = if is_selected {
+                    Style::default().bg(theme::ROW_SELECTED_BG)
+                } else {
+                    Style::default()
+                };
 
-            // Protocol indicator
-            let proto_icon = match p.protocol {
-                crate::app::Protocol::WireGuard => "W",
-                crate::app::Protocol::OpenVPN => "O",
-            };
-            let proto_color = if is_active {
-                active_color
-            } else if is_selected {
-                theme::ACCENT_PRIMARY
-            } else {
-                theme::TEXT_SECONDARY
-            };
+                let idx = match row {
+                    SidebarRow::Header {
+                        section,
+                        count,
+                        collapsed,
+                        ..
+                    } => {
+                        let arrow = if collapsed { "▸" } else { "▾" };
+                        let header_style = if is_selected {
+                            Style::default()
+                                .fg(theme::ROW_SELECTED_FG)
+                                .add_modifier(Modifier::BOLD)
+                        } else {
+                            Style::default()
+                                .fg(theme::ACCENT_SECONDARY)
+                                .add_modifier(Modifier::BOLD)
+                        };
+                        return Row::new(vec![
+                            Cell::from(Span::styled(arrow, header_style)),
+                            Cell::from(""),
+                            Cell::from(Line::from(vec![
+                                Span::styled(section.label().to_string(), header_style),
+                                Span::styled(
+                                    format!(" ({count})"),
+                                    Style::default().fg(Color::DarkGray),
+                                ),
+                            ])),
+                        ])
+                        .style(row_style);
+                    }
+                    SidebarRow::Profile(idx) => idx,
+                };
+                let p = &app.profiles[idx];
+                let is_active = active_profile.as_ref() == Some(&p.name);
+                let is_never_used = p.last_used.is_none();
 
-            // Last used time
-            let time_str = if let Some(last_used) = p.last_used {
-                let relative = utils::format_relative_time(last_used);
-                if !relative.ends_with("ago") && !relative.is_empty() {
-                    format!("{relative} ago")
+                // Status indicator — color matches connection state (green=connected, yellow=transitioning)
+                let (status_char, status_color) = if is_active {
+                    ("●", active_color)
                 } else {
-                    relative
-                }
-            } else {
-                "never".to_string()
-            };
+                    (" ", Color::Reset)
+                };
 
-            let row_style = if is_selected {
-                Style::default().bg(theme::ROW_SELECTED_BG)
-            } else {
-                Style::default()
-            };
+                let name_style = if is_selected {
+                    Style::default()
+                        .fg(theme::ROW_SELECTED_FG)
+                        .add_modifier(Modifier::BOLD)
+                } else if is_active {
+                    Style::default().fg(active_color)
+                } else if is_never_used {
+                    Style::default().fg(Color::DarkGray)
+                } else {
+                    Style::default().fg(theme::INACTIVE)
+                };
 
-            // Create cells for each column
-            let status_cell =
-                Cell::from(Span::styled(status_char, Style::default().fg(status_color)));
-            let name_cell = Cell::from(Span::styled(p.name.clone(), name_style));
-            let proto_cell = Cell::from(Span::styled(proto_icon, Style::default().fg(proto_color)));
-            let time_cell =
-                Cell::from(Span::styled(time_str, Style::default().fg(Color::DarkGray)));
+                // Protocol indicator
+                let proto_icon = match p.protocol {
+                    crate::app::Protocol::WireGuard => "W",
+                    crate::app::Protocol::OpenVPN => "O",
+                };
+                let proto_color = if is_active {
+                    active_color
+                } else if is_selected {
+                    theme::ACCENT_PRIMARY
+                } else {
+                    theme::TEXT_SECONDARY
+                };
 
-            Row::new(vec![status_cell, name_cell, proto_cell, time_cell]).style(row_style)
-        })
-        .collect();
+                // Last used time
+                let time_str = if let Some(last_used) = p.last_used {
+                    let relative = utils::format_relative_time(last_used);
+                    if !relative.ends_with("ago") && !relative.is_empty() {
+                        format!("{relative} ago")
+                    } else {
+                        relative
+                    }
+                } else {
+                    "never".to_string()
+                };
+
+                // Create cells for each column
+                let status_cell =
+                    Cell::from(Span::styled(status_char, Style::default().fg(status_color)));
+                let slot_cell = Cell::from(Span::styled(
+                    p.labels
+                        .quick_slot
+                        .map(|n| n.to_string())
+                        .unwrap_or_default(),
+                    Style::default().fg(theme::ACCENT_SECONDARY),
+                ));
+                let mut name_spans = vec![Span::styled(p.name.clone(), name_style)];
+                name_spans.extend(p.labels.tags.iter().map(|tag| {
+                    Span::styled(format!(" #{tag}"), Style::default().fg(Color::DarkGray))
+                }));
+                let name_cell = Cell::from(Line::from(name_spans));
+                let proto_cell =
+                    Cell::from(Span::styled(proto_icon, Style::default().fg(proto_color)));
+                let time_cell =
+                    Cell::from(Span::styled(time_str, Style::default().fg(Color::DarkGray)));
+
+                Row::new(vec![
+                    status_cell,
+                    slot_cell,
+                    name_cell,
+                    proto_cell,
+                    time_cell,
+                ])
+                .style(row_style)
+            })
+            .collect();
 
     let table = Table::new(
         items,
         [
-            Constraint::Length(2),  // Status column (● or space)
-            Constraint::Min(8),     // Profile name (flexible)
+            Constraint::Length(2),  // Status column (● or space), fold arrow on headers
+            Constraint::Length(2),  // Quick slot (1-9)
+            Constraint::Min(8),     // Profile name and tags (flexible)
             Constraint::Length(3),  // Protocol (W/O)
             Constraint::Length(10), // Last used time
         ],
     );
-    frame.render_stateful_widget(table, inner, &mut app.profile_list_state);
+    // Rows include headers, so the table gets its own state; only the scroll
+    // offset is carried between frames.
+    let mut table_state = TableState::default()
+        .with_offset(app.profile_list_state.offset())
+        .with_selected(selected_row);
+    frame.render_stateful_widget(table, inner, &mut table_state);
+    *app.profile_list_state.offset_mut() = table_state.offset();
 
     // Scrollbar Logic
     let scrollbar = Scrollbar::default()
@@ -718,9 +1165,8 @@ fn render_profiles_sidebar(frame: &mut Frame, app: &mut App, area: Rect) {
         .style(Style::default().fg(theme::NORD_POLAR_NIGHT_4))
         .thumb_style(Style::default().fg(theme::ACCENT_PRIMARY));
 
-    let mut scrollbar_state =
-        ScrollbarState::new(app.profiles.len().saturating_sub(inner.height as usize))
-            .position(app.profile_list_state.selected().unwrap_or(0));
+    let mut scrollbar_state = ScrollbarState::new(row_count.saturating_sub(inner.height as usize))
+        .position(selected_row.unwrap_or(0));
 
     frame.render_stateful_widget(
         scrollbar,
@@ -741,16 +1187,37 @@ fn render_throughput_chart(frame: &mut Frame, app: &App, area: Rect) {
         Style::default().fg(theme::BORDER_DEFAULT)
     };
 
+    // While connected the chart shows the tunnel; the uplink is drawn behind it
+    let uplink = app
+        .uplink_interface
+        .as_deref()
+        .filter(|_| app.config.uplink_overlay && app.chart_interface.is_some());
+
+    let window = app.chart_window;
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let series = |metric: Metric| app.metrics.series(window, metric, now);
+    let down = series(Metric::Down);
+    let up = series(Metric::Up);
+    let uplink_series = uplink.map(|_| [series(Metric::UplinkDown), series(Metric::UplinkUp)]);
+
     // Peak detection for dynamic Y-axis scaling (calculate first for title)
-    let max_down = app.down_history.iter().map(|(_, y)| *y).fold(0.0, f64::max);
-    let max_up = app.up_history.iter().map(|(_, y)| *y).fold(0.0, f64::max);
-    let peak = (max_down.max(max_up) * 1.2).max(1024.0 * 1024.0 * 0.5);
+    let mut max_all = series_max(&down).max(series_max(&up));
+    for s in uplink_series.iter().flatten() {
+        max_all = max_all.max(series_max(s));
+    }
+    let peak = (max_all * 1.2).max(1024.0 * 1024.0 * 0.5);
     let peak_label = format!(" Peak: {:.1} MB/s ", peak / 1024.0 / 1024.0);
 
-    let block = Block::default()
+    let title = match &app.chart_interface {
+        Some(iface) => format!(" Tunnel Throughput ({iface}) · {} ", window.label()),
+        None => format!(" Network Throughput · {} ", window.label()),
+    };
+    let mut block = Block::default()
         .borders(Borders::ALL)
         .border_style(border_style)
-        .title(" Network Throughput ")
+        .title(title)
         .title(
             Line::from(Span::styled(
                 peak_label,
@@ -758,12 +1225,38 @@ fn render_throughput_chart(frame: &mut Frame, app: &App, area: Rect) {
             ))
             .right_aligned(),
         );
+    if let Some(iface) = uplink {
+        let dim = Style::default().fg(theme::NORD_POLAR_NIGHT_4);
+        block = block.title_bottom(
+            Line::from(vec![
+                Span::styled(format!(" ┄ Uplink {iface}: "), dim),
+                Span::styled("↓", Style::default().fg(theme::NORD_FROST_3)),
+                Span::styled(utils::format_bytes_speed(app.current_uplink_down), dim),
+                Span::styled(" ↑", Style::default().fg(theme::NORD_GREEN)),
+                Span::styled(
+                    format!("{} ", utils::format_bytes_speed(app.current_uplink_up)),
+                    dim,
+                ),
+            ])
+            .right_aligned(),
+        );
+    }
 
     let inner = block.inner(area);
     frame.render_widget(block, area);
 
-    // Layout: Stats (Top) | Chart (Bottom)
-    let chunks = Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).split(inner);
+    // Layout: Stats (Top) | Throughput | Latency & loss (when there is room)
+    let quality_height = if inner.height >= 12 {
+        inner.height / 3
+    } else {
+        0
+    };
+    let chunks = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Min(0),
+        Constraint::Length(quality_height),
+    ])
+    .split(inner);
 
     // 1. Render Numeric Stats (Top row) - Removed redundant ping, added session totals
 
@@ -809,45 +1302,160 @@ fn render_throughput_chart(frame: &mut Frame, app: &App, area: Rect) {
         chunks[0],
     );
 
+    #[allow(clippy::cast_precision_loss)]
+    let x_max = (window.points() - 1) as f64;
     let canvas = Canvas::default()
         .block(Block::default())
-        .x_bounds([0.0, 60.0])
+        .x_bounds([0.0, x_max])
         .y_bounds([0.0, peak])
         .paint(|ctx| {
-            // Draw Streams
-            for i in 0..app.down_history.len() - 1 {
-                let y1 = app.down_history[i].1;
-                let y2 = app.down_history[i + 1].1;
-
-                // Skip drawing if both points are zero to avoid messy Braille dots
-                if y1 > 0.0 || y2 > 0.0 {
-                    ctx.draw(&CanvasLine {
-                        x1: app.down_history[i].0,
-                        y1,
-                        x2: app.down_history[i + 1].0,
-                        y2,
-                        color: theme::ACCENT_PRIMARY, // Frost Blue
-                    });
-                }
-            }
-            for i in 0..app.up_history.len() - 1 {
-                let y1 = app.up_history[i].1;
-                let y2 = app.up_history[i + 1].1;
-
-                // Skip drawing if both points are zero to avoid messy Braille dots
-                if y1 > 0.0 || y2 > 0.0 {
-                    ctx.draw(&CanvasLine {
-                        x1: app.up_history[i].0,
-                        y1,
-                        x2: app.up_history[i + 1].0,
-                        y2,
-                        color: theme::SUCCESS, // Aurora Green
-                    });
+            // Uplink first, so the tunnel streams are drawn over it. The space
+            // between the two is traffic that bypasses the tunnel.
+            if let Some(uplink_series) = &uplink_series {
+                for s in uplink_series {
+                    draw_series(ctx, s, 1.0, theme::NORD_POLAR_NIGHT_4);
                 }
+                ctx.layer();
             }
+
+            // Draw Streams
+            draw_series(ctx, &down, 1.0, theme::ACCENT_PRIMARY); // Frost Blue
+            draw_series(ctx, &up, 1.0, theme::SUCCESS); // Aurora Green
         });
 
     frame.render_widget(canvas, chunks[1]);
+
+    if quality_height > 0 {
+        render_quality_chart(frame, app, &series, x_max, chunks[2]);
+    }
+}
+
+/// Latency (with jitter) and packet loss below the throughput chart. Loss
+/// has its own scale; both peaks are shown in the title.
+fn render_quality_chart(
+    frame: &mut Frame,
+    app: &App,
+    series: &dyn Fn(Metric) -> Vec<Option<f64>>,
+    x_max: f64,
+    area: Rect,
+) {
+    let latency = series(Metric::Latency);
+    let jitter = series(Metric::Jitter);
+    let loss = series(Metric::Loss);
+    let latency_peak = (series_max(&latency) * 1.2).max(50.0);
+    let loss_peak = (series_max(&loss) * 1.2).max(5.0);
+
+    let dim = Style::default().fg(theme::NORD_POLAR_NIGHT_4);
+    let value = Style::default().fg(theme::TEXT_PRIMARY);
+    let title = Line::from(vec![
+        Span::styled(" Latency ", Style::default().fg(theme::WARNING)),
+        Span::styled(format!("{}ms", app.latency_ms), value),
+        Span::styled(format!(" (max {latency_peak:.0}ms)"), dim),
+        Span::styled(" · Loss ", Style::default().fg(theme::ERROR)),
+        Span::styled(format!("{:.1}%", app.packet_loss), value),
+        Span::styled(format!(" (max {loss_peak:.0}%)"), dim),
+        Span::styled(" · Jitter ", Style::default().fg(theme::TEXT_SECONDARY)),
+        Span::styled(format!("{}ms ", app.jitter_ms), value),
+    ]);
+
+    let canvas = Canvas::default()
+        .block(
+            Block::default()
+                .borders(Borders::TOP)
+                .border_style(dim)
+                .title(title),
+        )
+        .x_bounds([0.0, x_max])
+        .y_bounds([0.0, 1.0])
+        .paint(|ctx| {
+            draw_series(ctx, &jitter, latency_peak, theme::TEXT_SECONDARY);
+            draw_series(ctx, &latency, latency_peak, theme::WARNING);
+            draw_series(ctx, &loss, loss_peak, theme::ERROR);
+        });
+    frame.render_widget(canvas, area);
+}
+
+/// Largest measured value of a chart series.
+fn series_max(series: &[Option<f64>]) -> f64 {
+    series.iter().flatten().copied().fold(0.0, f64::max)
+}
+
+/// Draw a series as connected segments, divided by `scale`. Slots without
+/// a measurement break the line.
+fn draw_series(
+    ctx: &mut ratatui::widgets::canvas::Context,
+    series: &[Option<f64>],
+    scale: f64,
+    color: ratatui::style::Color,
+) {
+    for (i, pair) in series.windows(2).enumerate() {
+        let (Some(y1), Some(y2)) = (pair[0], pair[1]) else {
+            continue;
+        };
+        // Skip drawing if both points are zero to avoid messy Braille dots
+        if y1 > 0.0 || y2 > 0.0 {
+            #[allow(clippy::cast_precision_loss)]
+            let x1 = i as f64;
+            ctx.draw(&CanvasLine {
+                x1,
+                y1: y1 / scale,
+                x2: x1 + 1.0,
+                y2: y2 / scale,
+                color,
+            });
+        }
+    }
+}
+
+/// Resolvers seen by the active DNS leak test, below the DNS check.
+fn push_dns_leak_lines(audit: &mut Vec<Line<'static>>, app: &App, max_val: usize) {
+    const MAX_SHOWN: usize = 3;
+
+    let label = |text: &'static str| Span::styled(text, Style::default().fg(theme::TEXT_SECONDARY));
+    match &app.dns_leak {
+        Some(report) if !report.resolvers.is_empty() => {
+            for (i, resolver) in report.resolvers.iter().take(MAX_SHOWN).enumerate() {
+                let text = if resolver.asn.is_empty() {
+                    resolver.ip.clone()
+                } else {
+                    format!("{} {}", resolver.ip, resolver.asn)
+                };
+                audit.push(Line::from(vec![
+                    label(if i == 0 {
+                        "  Resolver: "
+                    } else {
+                        "            "
+                    }),
+                    Span::styled(
+                        utils::truncate(&text, max_val),
+                        Style::default().fg(if resolver.leaking {
+                            theme::ERROR
+                        } else {
+                            Color::DarkGray
+                        }),
+                    ),
+                ]));
+            }
+            if report.resolvers.len() > MAX_SHOWN {
+                audit.push(Line::from(vec![
+                    label("            "),
+                    Span::styled(
+                        format!("+{} more", report.resolvers.len() - MAX_SHOWN),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                ]));
+            }
+        }
+        Some(_) => audit.push(Line::from(vec![
+            label("  Leak test: "),
+            Span::styled("no resolvers seen", Style::default().fg(theme::WARNING)),
+        ])),
+        None if !app.config.dns_leak_api.is_empty() => audit.push(Line::from(vec![
+            label("  Leak test: "),
+            Span::styled("Running...", Style::default().fg(Color::DarkGray)),
+        ])),
+        None => {}
+    }
 }
 
 #[allow(clippy::too_many_lines)]
@@ -896,7 +1504,11 @@ fn render_security_guard(frame: &mut Frame, app: &App, area: Rect) {
     }
 
     // Connected - show security checklist with visual indicators
-    let dns_leaking = utils::is_private_ip(&app.dns_server);
+    // A completed leak test beats the configured-resolver heuristic
+    let dns_leaking = app.dns_leak.as_ref().map_or_else(
+        || app.is_dns_leaking(&app.dns_server),
+        crate::core::dns_leak::DnsLeakReport::is_leaking,
+    );
 
     // Check if IP is actually masked (different from real IP captured when disconnected)
     let ip_status = match &app.real_ip {
@@ -1026,6 +1638,7 @@ fn render_security_guard(frame: &mut Frame, app: &App, area: Rect) {
             Span::styled(dns_provider, Style::default().fg(Color::DarkGray)),
         ]));
     }
+    push_dns_leak_lines(&mut audit, app, max_val);
 
     audit.push(Line::from(""));
 
@@ -0,0 +1,193 @@
+//! Fastest server overlay
+
+use crate::app::{App, ConnectionState};
+use crate::core::benchmark::{ProbeMethod, ProbeResult};
+use crate::theme;
+use ratatui::{
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table, TableState},
+    Frame,
+};
+
+/// Render the benchmark: scope input, then the ranked servers once probed.
+pub fn render(
+    frame: &mut Frame,
+    app: &App,
+    scope: &str,
+    cursor: usize,
+    running: bool,
+    selected: usize,
+) {
+    let area = centered_rect(80, 70, frame.area());
+    frame.render_widget(Clear, area);
+
+    let hints = if app.benchmark.is_empty() {
+        " [Esc] Close  [Enter] Benchmark  Type a tag or location "
+    } else {
+        " [Esc] Close  [↑/↓] Select  [Enter] Connect  Type to change scope "
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::BORDER_FOCUSED))
+        .title(" Fastest Server ")
+        .title_bottom(Line::from(hints).centered());
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let [scope_area, status_area, table_area] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Length(2),
+        Constraint::Min(3),
+    ])
+    .areas(inner);
+
+    frame.render_widget(Paragraph::new(scope_line(scope, cursor)), scope_area);
+    frame.render_widget(
+        Paragraph::new(status_line(app, scope, running)),
+        status_area,
+    );
+
+    if app.benchmark.is_empty() {
+        return;
+    }
+
+    let header = Row::new(
+        ["#", "Profile", "Server", "Via", "RTT", "Loss", "Score"]
+            .map(|h| Cell::from(Span::styled(h, Style::default().fg(theme::TEXT_SECONDARY)))),
+    );
+    let rows: Vec<Row> = app
+        .benchmark
+        .iter()
+        .enumerate()
+        .map(|(rank, r)| result_row(rank, r))
+        .collect();
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(3), // Rank
+            Constraint::Min(12),   // Profile
+            Constraint::Min(18),   // Server
+            Constraint::Length(4), // Method
+            Constraint::Length(7), // RTT
+            Constraint::Length(6), // Loss
+            Constraint::Min(7),    // Score or error
+        ],
+    )
+    .header(header)
+    .row_highlight_style(
+        Style::default()
+            .bg(theme::ROW_SELECTED_BG)
+            .fg(theme::ROW_SELECTED_FG)
+            .add_modifier(Modifier::BOLD),
+    );
+    let mut state = TableState::default().with_selected(Some(selected));
+    frame.render_stateful_widget(table, table_area, &mut state);
+}
+
+fn scope_line(scope: &str, cursor: usize) -> Line<'static> {
+    let before = scope.chars().take(cursor).collect::<String>();
+    let cursor_char = scope
+        .chars()
+        .nth(cursor)
+        .map_or_else(|| "█".to_string(), |c| c.to_string());
+    let after = scope.chars().skip(cursor + 1).collect::<String>();
+    Line::from(vec![
+        Span::styled(
+            "Tag or location > ",
+            Style::default().fg(theme::TEXT_SECONDARY),
+        ),
+        Span::styled(before, Style::default().fg(theme::TEXT_PRIMARY)),
+        Span::styled(
+            cursor_char,
+            Style::default()
+                .fg(theme::ACCENT_SECONDARY)
+                .add_modifier(Modifier::REVERSED),
+        ),
+        Span::styled(after, Style::default().fg(theme::TEXT_PRIMARY)),
+    ])
+}
+
+/// What the benchmark is doing, and a caveat when a tunnel is up.
+fn status_line(app: &App, scope: &str, running: bool) -> Vec<Line<'static>> {
+    let dim = Style::default().fg(theme::TEXT_SECONDARY);
+    let in_scope = app.profiles.iter().filter(|p| p.in_scope(scope)).count();
+    let mut lines = vec![if running {
+        Line::from(Span::styled(
+            format!("Probing {in_scope} server(s)..."),
+            Style::default().fg(theme::WARNING),
+        ))
+    } else if app.benchmark.is_empty() {
+        Line::from(Span::styled(
+            format!("{in_scope} profile(s) in scope (blank = all)"),
+            dim,
+        ))
+    } else {
+        let reachable = app.benchmark.iter().filter(|r| r.is_reachable()).count();
+        Line::from(Span::styled(
+            format!(
+                "{reachable} of {} server(s) reachable, fastest first",
+                app.benchmark.len()
+            ),
+            dim,
+        ))
+    }];
+    if !matches!(app.connection_state, ConnectionState::Disconnected) {
+        lines.push(Line::from(Span::styled(
+            "Measured through the active tunnel",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+    lines
+}
+
+fn result_row(rank: usize, r: &ProbeResult) -> Row<'static> {
+    let dash = || "-".to_string();
+    let last = match (r.score(), r.method) {
+        (Some(score), _) => Cell::from(score.to_string()),
+        (None, Some(ProbeMethod::Udp)) => Cell::from(Span::styled(
+            "no reply",
+            Style::default().fg(theme::TEXT_SECONDARY),
+        )),
+        (None, _) => Cell::from(Span::styled(
+            r.error.clone(),
+            Style::default().fg(theme::ERROR),
+        )),
+    };
+    let color = if rank == 0 && r.score().is_some() {
+        theme::SUCCESS
+    } else if r.is_reachable() {
+        theme::TEXT_PRIMARY
+    } else {
+        theme::TEXT_SECONDARY
+    };
+    Row::new(vec![
+        Cell::from(format!("{}", rank + 1)),
+        Cell::from(r.profile.clone()),
+        Cell::from(if r.endpoint.is_empty() {
+            dash()
+        } else {
+            r.endpoint.clone()
+        }),
+        Cell::from(r.method.map_or_else(dash, |m| m.to_string())),
+        Cell::from(r.rtt_ms.map_or_else(dash, |ms| format!("{ms}ms"))),
+        Cell::from(if r.method == Some(ProbeMethod::Icmp) {
+            format!("{:.0}%", r.loss_pct)
+        } else {
+            dash()
+        }),
+        last,
+    ])
+    .style(Style::default().fg(color))
+}
+
+/// Create a centered rectangle
+fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let vertical = Layout::vertical([Constraint::Percentage(percent_y)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Percentage(percent_x)]).flex(Flex::Center);
+
+    let [area] = vertical.areas(area);
+    let [area] = horizontal.areas(area);
+    area
+}
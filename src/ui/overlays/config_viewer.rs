@@ -9,8 +9,8 @@ use ratatui::{
     widgets::{Block, Borders, Clear, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState},
     Frame,
 };
-use std::fs;
 use std::path::PathBuf;
+use zeroize::Zeroizing;
 
 /// Render config file viewer overlay
 pub fn render(frame: &mut Frame, app: &App) {
@@ -20,24 +20,25 @@ pub fn render(frame: &mut Frame, app: &App) {
     frame.render_widget(Clear, area);
 
     // Get the current profile's config path
-    let (config_content, profile_name, config_path): (String, String, PathBuf) =
+    // The viewer shows the decrypted profile; keep it zeroized on drop
+    let (config_content, profile_name, config_path): (Zeroizing<String>, String, PathBuf) =
         if let Some(idx) = app.profile_list_state.selected() {
             if let Some(profile) = app.profiles.get(idx) {
-                let content = match fs::read_to_string(&profile.config_path) {
+                let content = match crate::core::vault::read_to_string(&profile.config_path) {
                     Ok(c) => c,
-                    Err(e) => format!("Error reading config: {e}"),
+                    Err(e) => Zeroizing::new(format!("Error reading config: {e}")),
                 };
                 (content, profile.name.clone(), profile.config_path.clone())
             } else {
                 (
-                    "No profile selected".to_string(),
+                    Zeroizing::new("No profile selected".to_string()),
                     String::new(),
                     PathBuf::new(),
                 )
             }
         } else {
             (
-                "No profile selected".to_string(),
+                Zeroizing::new("No profile selected".to_string()),
                 String::new(),
                 PathBuf::new(),
             )
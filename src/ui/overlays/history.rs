@@ -0,0 +1,217 @@
+//! Session history overlay
+
+use crate::app::App;
+use crate::core::history::{EndReason, SessionRecord};
+use crate::theme;
+use crate::utils;
+use ratatui::{
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table, TableState},
+    Frame,
+};
+use std::time::{Duration, UNIX_EPOCH};
+
+/// Render the session history browser: filter input, totals, the matching
+/// sessions (newest first) and details of the selected one.
+pub fn render(frame: &mut Frame, app: &App, filter: &str, cursor: usize, selected: usize) {
+    let area = centered_rect(90, 85, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::BORDER_FOCUSED))
+        .title(" Session History ")
+        .title_bottom(Line::from(" [Esc] Close  [↑/↓] Select  Type to filter ").centered());
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let [filter_area, summary_area, table_area, detail_area] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Length(2),
+        Constraint::Min(3),
+        Constraint::Length(2),
+    ])
+    .areas(inner);
+
+    let records = app.filtered_history(filter);
+    frame.render_widget(Paragraph::new(filter_line(filter, cursor)), filter_area);
+    frame.render_widget(Paragraph::new(summary_line(&records)), summary_area);
+
+    if records.is_empty() {
+        let text = if app.history.is_empty() {
+            "No sessions recorded yet"
+        } else {
+            "No sessions match the filter"
+        };
+        frame.render_widget(
+            Paragraph::new(Span::styled(text, Style::default().fg(Color::DarkGray))),
+            table_area,
+        );
+        return;
+    }
+
+    let header = Row::new(
+        [
+            "Started", "Profile", "Duration", "Ended", "Down", "Up", "Latency", "Loss", "Exit IP",
+            "Location",
+        ]
+        .map(|h| Cell::from(Span::styled(h, Style::default().fg(theme::TEXT_SECONDARY)))),
+    );
+    let rows: Vec<Row> = records.iter().map(|r| record_row(r)).collect();
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(16), // Started
+            Constraint::Min(10),    // Profile
+            Constraint::Length(8),  // Duration
+            Constraint::Length(7),  // End reason
+            Constraint::Length(10), // Down
+            Constraint::Length(10), // Up
+            Constraint::Length(7),  // Latency
+            Constraint::Length(6),  // Loss
+            Constraint::Length(15), // Exit IP
+            Constraint::Min(10),    // Location
+        ],
+    )
+    .header(header)
+    .row_highlight_style(
+        Style::default()
+            .bg(theme::ROW_SELECTED_BG)
+            .fg(theme::ROW_SELECTED_FG)
+            .add_modifier(Modifier::BOLD),
+    );
+    let mut state = TableState::default().with_selected(Some(selected));
+    frame.render_stateful_widget(table, table_area, &mut state);
+
+    if let Some(record) = records.get(selected) {
+        frame.render_widget(Paragraph::new(detail_lines(record)), detail_area);
+    }
+}
+
+fn filter_line(filter: &str, cursor: usize) -> Line<'static> {
+    let before = filter.chars().take(cursor).collect::<String>();
+    let cursor_char = filter
+        .chars()
+        .nth(cursor)
+        .map_or_else(|| "█".to_string(), |c| c.to_string());
+    let after = filter.chars().skip(cursor + 1).collect::<String>();
+    Line::from(vec![
+        Span::styled("Filter > ", Style::default().fg(theme::TEXT_SECONDARY)),
+        Span::styled(before, Style::default().fg(theme::TEXT_PRIMARY)),
+        Span::styled(
+            cursor_char,
+            Style::default()
+                .fg(theme::ACCENT_SECONDARY)
+                .add_modifier(Modifier::REVERSED),
+        ),
+        Span::styled(after, Style::default().fg(theme::TEXT_PRIMARY)),
+    ])
+}
+
+/// Totals over the matching sessions.
+fn summary_line(records: &[&SessionRecord]) -> Line<'static> {
+    let connected: u64 = records
+        .iter()
+        .filter(|r| matches!(r.end_reason, EndReason::User | EndReason::Drop))
+        .map(|r| r.duration_secs())
+        .sum();
+    let rx: u64 = records.iter().map(|r| r.rx_bytes).sum();
+    let tx: u64 = records.iter().map(|r| r.tx_bytes).sum();
+    let count = |reason: EndReason| records.iter().filter(|r| r.end_reason == reason).count();
+    let dim = Style::default().fg(theme::TEXT_SECONDARY);
+    Line::from(vec![
+        Span::styled(format!("{} sessions", records.len()), dim),
+        Span::styled("  ·  connected ", dim),
+        Span::raw(utils::format_duration_secs(connected)),
+        Span::styled("  ·  ↓ ", dim),
+        Span::raw(utils::format_bytes(rx)),
+        Span::styled("  ↑ ", dim),
+        Span::raw(utils::format_bytes(tx)),
+        Span::styled("  ·  drops ", dim),
+        Span::styled(
+            count(EndReason::Drop).to_string(),
+            Style::default().fg(theme::WARNING),
+        ),
+        Span::styled("  failed ", dim),
+        Span::styled(
+            (count(EndReason::Error) + count(EndReason::Timeout)).to_string(),
+            Style::default().fg(theme::ERROR),
+        ),
+    ])
+}
+
+fn record_row(r: &SessionRecord) -> Row<'static> {
+    let started = UNIX_EPOCH + Duration::from_secs(r.started_at);
+    let reason_color = match r.end_reason {
+        EndReason::User => theme::TEXT_SECONDARY,
+        EndReason::Drop => theme::WARNING,
+        EndReason::Timeout | EndReason::Error => theme::ERROR,
+    };
+    let or_dash = |s: &str| {
+        if s.is_empty() {
+            "-".to_string()
+        } else {
+            s.to_string()
+        }
+    };
+    Row::new(vec![
+        Cell::from(utils::format_system_datetime_local(started)),
+        Cell::from(r.profile.clone()),
+        Cell::from(utils::format_duration_secs(r.duration_secs())),
+        Cell::from(Span::styled(
+            r.end_reason.to_string(),
+            Style::default().fg(reason_color),
+        )),
+        Cell::from(utils::format_bytes(r.rx_bytes)),
+        Cell::from(utils::format_bytes(r.tx_bytes)),
+        Cell::from(
+            r.avg_latency_ms
+                .map_or_else(|| "-".to_string(), |ms| format!("{ms}ms")),
+        ),
+        Cell::from(
+            r.avg_loss_pct
+                .map_or_else(|| "-".to_string(), |pct| format!("{pct:.1}%")),
+        ),
+        Cell::from(or_dash(&r.exit_ip)),
+        Cell::from(or_dash(&r.location)),
+    ])
+    .style(Style::default().fg(theme::TEXT_PRIMARY))
+}
+
+/// Protocol, exact times and the error (if any) of the selected session.
+fn detail_lines(r: &SessionRecord) -> Vec<Line<'static>> {
+    let started = UNIX_EPOCH + Duration::from_secs(r.started_at);
+    let ended = UNIX_EPOCH + Duration::from_secs(r.ended_at);
+    let dim = Style::default().fg(theme::TEXT_SECONDARY);
+    let mut lines = vec![Line::from(vec![
+        Span::styled(
+            format!("{} ", r.profile),
+            Style::default().fg(theme::ACCENT_PRIMARY),
+        ),
+        Span::styled(format!("[{}]  ", r.protocol), dim),
+        Span::raw(format!(
+            "{} → {}",
+            utils::format_system_datetime_local(started),
+            utils::format_system_datetime_local(ended)
+        )),
+    ])];
+    if !r.error.is_empty() {
+        lines.push(Line::from(Span::styled(
+            r.error.clone(),
+            Style::default().fg(theme::ERROR),
+        )));
+    }
+    lines
+}
+
+/// Create a centered rectangle
+fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let vertical = Layout::vertical([Constraint::Percentage(percent_y)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Percentage(percent_x)]).flex(Flex::Center);
+
+    let [area] = vertical.areas(area);
+    let [area] = horizontal.areas(area);
+    area
+}
@@ -1,5 +1,7 @@
 //! UI overlay modules
 
 pub mod action_menu;
+pub mod benchmark;
 pub mod config_viewer;
+pub mod history;
 pub mod toast;
@@ -1,6 +1,6 @@
 //! Footer widget with context-aware keybinding hints
 
-use crate::app::App;
+use crate::app::{App, InputMode};
 use ratatui::{
     layout::Rect,
     style::{Color, Modifier, Style},
@@ -23,6 +23,18 @@ pub fn render_dashboard(frame: &mut Frame, app: &App, area: Rect) {
         return;
     }
 
+    // Profile search keeps the dashboard visible, so it gets its own hints
+    if matches!(app.input_mode, InputMode::Search { .. }) {
+        let hints = vec![
+            ("type", "Filter"),
+            ("↑↓", "Select"),
+            ("Enter", "Connect"),
+            ("Esc", "Close"),
+        ];
+        render_hints(frame, area, &hints, Some("Search"));
+        return;
+    }
+
     // Determine focused panel name for display
     let panel_name = match &app.focused_panel {
         crate::app::FocusedPanel::Sidebar => "Profiles",
@@ -35,16 +47,19 @@ pub fn render_dashboard(frame: &mut Frame, app: &App, area: Rect) {
     // Build essential global hints
     let mut hints = Vec::new();
 
-    // Only show 1-9 hint if there are profiles
-    if !app.profiles.is_empty() {
+    // Only show 1-9 hint once a profile is bound to a quick slot
+    if app.profiles.iter().any(|p| p.labels.quick_slot.is_some()) {
         hints.push(("1-9", "Quick Connect"));
     }
 
     hints.extend_from_slice(&[
+        ("/", "Search"),
         ("i", "Import"),
         ("d", "Disconnect"),
         ("Tab", "Panel"),
         ("K", "Kill Switch"),
+        ("H", "History"),
+        ("B", "Fastest"),
         ("x", "Menu"),
         ("q", "Quit"),
     ]);
@@ -9,11 +9,8 @@
 /// This avoids silent failures if `id` is unavailable or fails.
 #[must_use]
 #[cfg(unix)]
-#[allow(unsafe_code)]
 pub fn is_root() -> bool {
-    // SAFETY: geteuid() is a simple syscall that returns the effective user ID.
-    // It has no side effects and always succeeds.
-    unsafe { libc::geteuid() == 0 }
+    effective_uid() == 0
 }
 
 /// Check if the current process is running as root (UID 0)
@@ -26,25 +23,104 @@ pub fn is_root() -> bool {
     false
 }
 
+/// Effective user ID of the current process.
+#[must_use]
+#[cfg(unix)]
+#[allow(unsafe_code)]
+pub fn effective_uid() -> u32 {
+    // SAFETY: geteuid() is a simple syscall that returns the effective user ID.
+    // It has no side effects and always succeeds.
+    unsafe { libc::geteuid() }
+}
+
+/// Effective user ID of the current process (always `0`: non-Unix platforms
+/// have no user IDs).
+#[must_use]
+#[cfg(not(unix))]
+pub fn effective_uid() -> u32 {
+    0
+}
+
+/// User ID of the process on the other end of a Unix socket.
+///
+/// # Errors
+///
+/// Returns the OS error if the peer's credentials can't be read.
+#[cfg(target_os = "linux")]
+#[allow(unsafe_code, clippy::cast_possible_truncation)]
+pub fn peer_uid(stream: &std::os::unix::net::UnixStream) -> std::io::Result<u32> {
+    use std::os::unix::io::AsRawFd;
+
+    let mut cred = libc::ucred {
+        pid: 0,
+        uid: 0,
+        gid: 0,
+    };
+    let mut len = std::mem::size_of::<libc::ucred>() as libc::socklen_t;
+    // SAFETY: SO_PEERCRED writes at most `len` bytes into `cred`, which is a
+    // live, correctly sized `ucred`; the fd stays open for the call.
+    let rc = unsafe {
+        libc::getsockopt(
+            stream.as_raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_PEERCRED,
+            std::ptr::addr_of_mut!(cred).cast(),
+            &mut len,
+        )
+    };
+    if rc == 0 {
+        Ok(cred.uid)
+    } else {
+        Err(std::io::Error::last_os_error())
+    }
+}
+
+/// User ID of the process on the other end of a Unix socket.
+///
+/// # Errors
+///
+/// Returns the OS error if the peer's credentials can't be read.
+#[cfg(all(unix, not(target_os = "linux")))]
+#[allow(unsafe_code)]
+pub fn peer_uid(stream: &std::os::unix::net::UnixStream) -> std::io::Result<u32> {
+    use std::os::unix::io::AsRawFd;
+
+    let (mut uid, mut gid) = (0, 0);
+    // SAFETY: getpeereid only writes the two ids we pass by reference.
+    let rc = unsafe { libc::getpeereid(stream.as_raw_fd(), &mut uid, &mut gid) };
+    if rc == 0 {
+        Ok(uid)
+    } else {
+        Err(std::io::Error::last_os_error())
+    }
+}
+
 /// Run a system command with a timeout.
 ///
 /// Spawns the command and polls for completion. If the command doesn't
 /// finish within `timeout`, the child process is killed and `None` is
 /// returned. This prevents the UI from freezing when system commands
 /// hang (e.g. `lsof` or `netstat` with no network).
-#[cfg(target_os = "macos")]
 pub fn run_with_timeout(
     cmd: &mut std::process::Command,
     timeout: std::time::Duration,
 ) -> Option<std::process::Output> {
     use std::process::Stdio;
 
-    let mut child = cmd
+    let child = cmd
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
         .spawn()
         .ok()?;
+    wait_with_timeout(child, timeout)
+}
 
+/// Wait for a spawned child and collect its output, killing it if it
+/// doesn't finish within `timeout`.
+pub fn wait_with_timeout(
+    mut child: std::process::Child,
+    timeout: std::time::Duration,
+) -> Option<std::process::Output> {
     let deadline = std::time::Instant::now() + timeout;
     loop {
         match child.try_wait() {
@@ -88,6 +164,36 @@ pub fn write_user_file(path: &std::path::Path, contents: impl AsRef<[u8]>) -> st
     Ok(())
 }
 
+/// Create `path` with mode 600 and write `contents`, replacing any existing
+/// file rather than writing through it (or through a symlink in its place).
+///
+/// # Errors
+///
+/// Returns an error if the file can't be created or written.
+#[cfg(unix)]
+pub fn write_private(path: &std::path::Path, contents: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+    use std::os::unix::fs::OpenOptionsExt;
+
+    let _ = std::fs::remove_file(path);
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)?;
+    file.write_all(contents)
+}
+
+/// Create `path` and write `contents` (non-Unix fallback, no mode).
+///
+/// # Errors
+///
+/// Returns an error if the file can't be created or written.
+#[cfg(not(unix))]
+pub fn write_private(path: &std::path::Path, contents: &[u8]) -> std::io::Result<()> {
+    std::fs::write(path, contents)
+}
+
 /// Formats bytes per second into a human-readable string.
 ///
 /// # Arguments
@@ -115,6 +221,23 @@ pub fn format_bytes_speed(bytes: u64) -> String {
     }
 }
 
+/// Byte count the way `wg show` prints it (`"1.50 MiB"`).
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.2} {}", UNITS[unit])
+}
+
 /// Checks if an IP address belongs to a private network range (RFC1918).
 ///
 /// # Arguments
@@ -177,6 +300,58 @@ pub fn get_profiles_dir() -> std::io::Result<std::path::PathBuf> {
     Ok(path)
 }
 
+/// Returns the daemon control socket path.
+///
+/// Creates `~/.config/vortix/run/` if it doesn't exist.
+///
+/// # Errors
+///
+/// Returns an error if directory creation fails.
+pub fn get_daemon_socket_path() -> std::io::Result<std::path::PathBuf> {
+    let run_dir = get_app_config_dir()?.join(crate::constants::OPENVPN_RUN_DIR);
+
+    if !run_dir.exists() {
+        create_user_dir(&run_dir)?;
+    }
+
+    Ok(run_dir.join(crate::constants::DAEMON_SOCKET_NAME))
+}
+
+/// Returns the path of a profile copy rendered with its split tunnel policy.
+///
+/// Creates `~/.config/vortix/run/split/` if it doesn't exist.
+///
+/// # Errors
+///
+/// Returns an error if directory creation fails.
+pub fn get_split_config_path(file_name: &str) -> std::io::Result<std::path::PathBuf> {
+    let split_dir = get_app_config_dir()?
+        .join(crate::constants::OPENVPN_RUN_DIR)
+        .join(crate::constants::SPLIT_TUNNEL_DIR);
+
+    if !split_dir.exists() {
+        create_user_dir(&split_dir)?;
+    }
+
+    Ok(split_dir.join(file_name))
+}
+
+/// Returns the native `WireGuard` state file for `interface`.
+///
+/// Creates `~/.config/vortix/run/wireguard/` if it doesn't exist.
+#[cfg(target_os = "linux")]
+pub fn get_wireguard_state_path(interface: &str) -> std::io::Result<std::path::PathBuf> {
+    let state_dir = get_app_config_dir()?
+        .join(crate::constants::OPENVPN_RUN_DIR)
+        .join(crate::constants::WIREGUARD_NATIVE_DIR);
+
+    if !state_dir.exists() {
+        create_user_dir(&state_dir)?;
+    }
+
+    Ok(state_dir.join(format!("{interface}.state")))
+}
+
 /// Returns the `OpenVPN` runtime directory path for a given profile.
 ///
 /// Creates `~/.config/vortix/run/` if it doesn't exist.
@@ -219,6 +394,20 @@ pub fn cleanup_openvpn_run_files(profile_name: &str) {
         let _ = std::fs::remove_file(&pid_path);
         let _ = std::fs::remove_file(&log_path);
     }
+    if let Ok(mgmt_path) = get_openvpn_mgmt_path(profile_name) {
+        let _ = std::fs::remove_file(&mgmt_path);
+    }
+}
+
+/// Returns the `OpenVPN` management socket path for a profile, next to its
+/// pid and log files.
+///
+/// # Errors
+///
+/// Returns an error if directory creation fails.
+pub fn get_openvpn_mgmt_path(profile_name: &str) -> std::io::Result<std::path::PathBuf> {
+    let (pid_path, _) = get_openvpn_run_paths(profile_name)?;
+    Ok(pid_path.with_extension("sock"))
 }
 
 /// Reads the PID from an `OpenVPN` pid file.
@@ -257,49 +446,71 @@ pub fn get_openvpn_auth_path(profile_name: &str) -> std::io::Result<std::path::P
     Ok(auth_dir.join(format!("{safe_name}.auth")))
 }
 
-/// Writes `OpenVPN` credentials to a file (username on line 1, password on line 2).
-///
-/// The file is created with `chmod 600` (owner read/write only).
+/// Saves `OpenVPN` credentials in the configured credential store: the
+/// keyring, or an auth file (see [`write_openvpn_auth_to_file`]).
 ///
 /// # Errors
 ///
-/// Returns an error if file write or permission setting fails.
-#[cfg(unix)]
+/// Returns an error if the keyring refused the item or the file can't be written.
 pub fn write_openvpn_auth_file(
     profile_name: &str,
     username: &str,
     password: &str,
-) -> std::io::Result<std::path::PathBuf> {
-    use std::os::unix::fs::PermissionsExt;
-
-    let auth_path = get_openvpn_auth_path(profile_name)?;
-    write_user_file(&auth_path, format!("{username}\n{password}\n"))?;
-
-    let mut perms = std::fs::metadata(&auth_path)?.permissions();
-    perms.set_mode(0o600);
-    std::fs::set_permissions(&auth_path, perms)?;
+) -> std::io::Result<()> {
+    use crate::core::secrets::{self, CredentialStore};
 
-    Ok(auth_path)
+    match secrets::store() {
+        CredentialStore::Keyring => {
+            secrets::save(profile_name, username, password).map_err(std::io::Error::other)
+        }
+        CredentialStore::File => {
+            write_openvpn_auth_to_file(profile_name, username, password).map(|_| ())
+        }
+    }
 }
 
-/// Writes `OpenVPN` credentials to a file (non-Unix fallback, no chmod).
-#[cfg(not(unix))]
-pub fn write_openvpn_auth_file(
+/// Writes `OpenVPN` credentials to a file (username on line 1, password on line 2).
+///
+/// The file is created owner-only (mode 600 on Unix) and is encrypted when
+/// the vault is enabled.
+///
+/// # Errors
+///
+/// Returns an error if the vault is locked or the file can't be written.
+pub fn write_openvpn_auth_to_file(
     profile_name: &str,
     username: &str,
     password: &str,
 ) -> std::io::Result<std::path::PathBuf> {
     let auth_path = get_openvpn_auth_path(profile_name)?;
-    write_user_file(&auth_path, format!("{username}\n{password}\n"))?;
+    let plain = zeroize::Zeroizing::new(format!("{username}\n{password}\n"));
+    let sealed = crate::core::vault::seal_for_disk(plain.as_bytes(), &auth_path)?;
+    // Created owner-only, so the password is never readable by others
+    write_private(&auth_path, &sealed)?;
+    crate::config::fix_ownership(&auth_path);
     Ok(auth_path)
 }
 
-/// Reads saved `OpenVPN` credentials from the auth file.
+/// Reads saved `OpenVPN` credentials from the configured credential store.
 ///
-/// Returns `Some((username, password))` if a valid auth file exists.
+/// Returns `Some((username, password))` if the store holds a valid pair.
+/// An unreachable keyring counts as no saved credentials.
 pub fn read_openvpn_saved_auth(profile_name: &str) -> Option<(String, String)> {
+    use crate::core::secrets::{self, CredentialStore};
+
+    match secrets::store() {
+        CredentialStore::Keyring => secrets::load(profile_name).ok().flatten(),
+        CredentialStore::File => read_openvpn_auth_from_file(profile_name),
+    }
+}
+
+/// Reads saved `OpenVPN` credentials from the auth file.
+///
+/// Returns `Some((username, password))` if a valid auth file exists (and
+/// the vault is unlocked, if the file is encrypted).
+pub fn read_openvpn_auth_from_file(profile_name: &str) -> Option<(String, String)> {
     let auth_path = get_openvpn_auth_path(profile_name).ok()?;
-    let content = std::fs::read_to_string(&auth_path).ok()?;
+    let content = crate::core::vault::read_to_string(&auth_path).ok()?;
     let mut lines = content.lines();
     let username = lines.next()?.to_string();
     let password = lines.next()?.to_string();
@@ -309,11 +520,15 @@ pub fn read_openvpn_saved_auth(profile_name: &str) -> Option<(String, String)> {
     Some((username, password))
 }
 
-/// Deletes the saved `OpenVPN` auth credentials file for a profile.
+/// Deletes the saved `OpenVPN` credentials of a profile: the auth file and,
+/// with the keyring store, the keyring item.
 pub fn delete_openvpn_auth_file(profile_name: &str) {
     if let Ok(auth_path) = get_openvpn_auth_path(profile_name) {
         let _ = std::fs::remove_file(&auth_path);
     }
+    if crate::core::secrets::store() == crate::core::secrets::CredentialStore::Keyring {
+        let _ = crate::core::secrets::delete(profile_name);
+    }
 }
 
 /// Checks whether an `OpenVPN` config file contains `auth-user-pass` without a file argument.
@@ -324,7 +539,7 @@ pub fn delete_openvpn_auth_file(profile_name: &str) {
 /// - The directive has a file path argument (`auth-user-pass /path/to/file`)
 /// - The directive is commented out (`# auth-user-pass`)
 pub fn openvpn_config_needs_auth(config_path: &std::path::Path) -> bool {
-    let Ok(content) = std::fs::read_to_string(config_path) else {
+    let Ok(content) = crate::core::vault::read_to_string(config_path) else {
         return false;
     };
 
@@ -352,6 +567,52 @@ pub fn openvpn_config_needs_auth(config_path: &std::path::Path) -> bool {
     false
 }
 
+/// Reads the `static-challenge "<text>" <echo> [scrv1|concat]` directive of
+/// an `OpenVPN` config: the second factor asked for with the password.
+///
+/// Returns `None` if the directive is absent, commented out or malformed.
+pub fn openvpn_static_challenge(
+    config_path: &std::path::Path,
+) -> Option<crate::core::openvpn_mgmt::Challenge> {
+    use crate::core::openvpn_mgmt::{Challenge, ChallengeKind};
+
+    let content = crate::core::vault::read_to_string(config_path).ok()?;
+    let args = content.lines().find_map(|line| {
+        let rest = line
+            .trim()
+            .strip_prefix(crate::constants::OVPN_STATIC_CHALLENGE)?;
+        rest.starts_with(char::is_whitespace).then_some(rest.trim())
+    })?;
+
+    // The text is one word or a double-quoted string with `\"` escapes
+    let (text, rest) = if let Some(quoted) = args.strip_prefix('"') {
+        let mut text = String::new();
+        let mut chars = quoted.char_indices();
+        let end = loop {
+            match chars.next()? {
+                (_, '\\') => text.push(chars.next()?.1),
+                (i, '"') => break i + 1,
+                (_, c) => text.push(c),
+            }
+        };
+        (text, &quoted[end..])
+    } else {
+        let (word, rest) = args.split_once(char::is_whitespace)?;
+        (word.to_string(), rest)
+    };
+    let mut rest = rest.split_whitespace();
+    let echo = match rest.next()? {
+        "0" => false,
+        "1" => true,
+        _ => return None,
+    };
+    let kind = match rest.next() {
+        Some("concat") => ChallengeKind::Concat,
+        _ => ChallengeKind::Static,
+    };
+    Some(Challenge { text, echo, kind })
+}
+
 /// Truncates a string to a maximum number of characters.
 ///
 /// If the string exceeds `max_chars`, it is truncated and "..." is appended.
@@ -388,8 +649,18 @@ pub fn format_system_time_local(time: std::time::SystemTime) -> String {
 }
 
 #[cfg(unix)]
-#[allow(unsafe_code)]
 fn format_system_time_inner(time: std::time::SystemTime) -> Option<String> {
+    let tm = local_tm(time)?;
+    Some(format!(
+        "{:02}:{:02}:{:02}",
+        tm.tm_hour, tm.tm_min, tm.tm_sec
+    ))
+}
+
+/// Breaks `time` down into local calendar fields.
+#[cfg(unix)]
+#[allow(unsafe_code)]
+fn local_tm(time: std::time::SystemTime) -> Option<libc::tm> {
     let secs = time
         .duration_since(std::time::SystemTime::UNIX_EPOCH)
         .ok()?
@@ -405,11 +676,33 @@ fn format_system_time_inner(time: std::time::SystemTime) -> Option<String> {
     if result.is_null() {
         return None;
     }
+    Some(tm)
+}
 
-    Some(format!(
-        "{:02}:{:02}:{:02}",
-        tm.tm_hour, tm.tm_min, tm.tm_sec
-    ))
+/// Converts a `SystemTime` into a local `YYYY-MM-DD HH:MM` string, for
+/// timestamps that may lie days back (session history).
+#[cfg(unix)]
+#[must_use]
+pub fn format_system_datetime_local(time: std::time::SystemTime) -> String {
+    local_tm(time).map_or_else(
+        || "-".to_string(),
+        |tm| {
+            format!(
+                "{:04}-{:02}-{:02} {:02}:{:02}",
+                tm.tm_year + 1900,
+                tm.tm_mon + 1,
+                tm.tm_mday,
+                tm.tm_hour,
+                tm.tm_min
+            )
+        },
+    )
+}
+
+#[cfg(not(unix))]
+#[must_use]
+pub fn format_system_datetime_local(time: std::time::SystemTime) -> String {
+    format_system_time_local(time)
 }
 
 #[cfg(not(unix))]
@@ -423,6 +716,21 @@ fn format_system_time_inner(time: std::time::SystemTime) -> Option<String> {
         .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
 }
 
+/// Formats a duration in seconds with its two largest units
+/// (e.g. `45s`, `12m 30s`, `1h 05m`, `2d 03h`).
+#[must_use]
+pub fn format_duration_secs(secs: u64) -> String {
+    if secs < 60 {
+        format!("{secs}s")
+    } else if secs < 3600 {
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    } else if secs < 86_400 {
+        format!("{}h {:02}m", secs / 3600, secs % 3600 / 60)
+    } else {
+        format!("{}d {:02}h", secs / 86_400, secs % 86_400 / 3600)
+    }
+}
+
 /// Formats a `SystemTime` into a compact relative time string (e.g., 1s, 2m, 3h, 4d).
 pub fn format_relative_time(time: std::time::SystemTime) -> String {
     let now = std::time::SystemTime::now();
@@ -484,8 +792,104 @@ fn home_dir_from_passwd() -> Option<std::path::PathBuf> {
     None
 }
 
+/// Session bus address of the real user, and the uid/gid to talk to it as.
+///
+/// Under sudo the environment no longer points at the user's bus, and the
+/// bus only accepts its owner, so the call has to run as `SUDO_UID`.
+#[must_use]
+#[cfg(target_os = "linux")]
+pub fn session_bus() -> Option<(String, u32, u32)> {
+    use std::os::unix::fs::MetadataExt;
+
+    if !is_root() {
+        if let Ok(address) = std::env::var("DBUS_SESSION_BUS_ADDRESS") {
+            let me = std::fs::metadata("/proc/self").ok()?;
+            return Some((address, me.uid(), me.gid()));
+        }
+    }
+    let (uid, gid) = if is_root() {
+        let id = |var| std::env::var(var).ok()?.parse::<u32>().ok();
+        (id("SUDO_UID")?, id("SUDO_GID")?)
+    } else {
+        let me = std::fs::metadata("/proc/self").ok()?;
+        (me.uid(), me.gid())
+    };
+    let socket = format!("/run/user/{uid}/bus");
+    std::path::Path::new(&socket)
+        .exists()
+        .then(|| (format!("unix:path={socket}"), uid, gid))
+}
+
+#[must_use]
+#[cfg(not(target_os = "linux"))]
+pub fn session_bus() -> Option<(String, u32, u32)> {
+    None
+}
+
+/// Entries of a comma- or whitespace-separated list typed into a form field.
+pub fn split_list(field: &str) -> impl Iterator<Item = &str> {
+    field
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|s| !s.is_empty())
+}
+
+/// Host part of a `host:port` endpoint, with IPv6 brackets removed
+/// (`[2001:db8::1]:51820` → `2001:db8::1`). A bare address is returned as is.
+pub fn endpoint_host(endpoint: &str) -> &str {
+    let endpoint = endpoint.trim();
+    if let Some(rest) = endpoint.strip_prefix('[') {
+        return rest.split(']').next().unwrap_or(rest);
+    }
+    if endpoint.parse::<std::net::IpAddr>().is_ok() {
+        return endpoint;
+    }
+    endpoint.rsplit_once(':').map_or(endpoint, |(host, _)| host)
+}
+
+/// Whether `s` is a syntactically valid DNS name (not an IP address).
+pub fn is_hostname(s: &str) -> bool {
+    !s.is_empty()
+        && s.len() <= 253
+        && s.contains(|c: char| c.is_ascii_alphabetic())
+        && s.split('.').all(|label| {
+            !label.is_empty()
+                && !label.starts_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+}
+
+/// Case-insensitive fuzzy match of `pattern` against `text`: every
+/// character of the pattern must appear in order. Returns `None` when it
+/// doesn't, otherwise a score that rewards consecutive characters, matches at
+/// the start of words and an exact substring.
+pub fn fuzzy_score(text: &str, pattern: &str) -> Option<u32> {
+    let text_lower = text.to_lowercase();
+    let pattern_lower = pattern.to_lowercase();
+    let haystack: Vec<char> = text_lower.chars().collect();
+
+    let mut score = 0;
+    let mut from = 0;
+    let mut previous: Option<usize> = None;
+    for wanted in pattern_lower.chars() {
+        let found = from + haystack[from..].iter().position(|&c| c == wanted)?;
+        score += 1;
+        if previous.is_some_and(|p| p + 1 == found) {
+            score += 4;
+        }
+        if found == 0 || !haystack[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        previous = Some(found);
+        from = found + 1;
+    }
+    if text_lower.contains(&pattern_lower) {
+        score += 10;
+    }
+    Some(score)
+}
+
 /// Profile metadata for persistence
-#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Default, serde::Serialize, serde::Deserialize)]
 pub struct ProfileMetadata {
     #[serde(
         with = "systemtime_serde",
@@ -493,6 +897,12 @@ pub struct ProfileMetadata {
         default
     )]
     pub last_used: Option<std::time::SystemTime>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub split_tunnel: Option<crate::vpn::split::SplitTunnel>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub dns: Option<crate::core::dns::DnsOverride>,
+    #[serde(flatten)]
+    pub labels: crate::state::ProfileLabels,
 }
 
 mod systemtime_serde {
@@ -633,6 +1043,21 @@ mod tests {
         assert_eq!(format_bytes_speed(100_000_000), "100.0 MB/s");
     }
 
+    #[test]
+    fn test_format_bytes_binary_units() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.50 KiB");
+        assert_eq!(format_bytes(5 * 1024 * 1024 * 1024), "5.00 GiB");
+    }
+
+    #[test]
+    fn test_format_duration_secs_two_units() {
+        assert_eq!(format_duration_secs(45), "45s");
+        assert_eq!(format_duration_secs(750), "12m 30s");
+        assert_eq!(format_duration_secs(3900), "1h 05m");
+        assert_eq!(format_duration_secs(2 * 86_400 + 3 * 3600 + 59), "2d 03h");
+    }
+
     #[test]
     fn test_truncate_short_string() {
         assert_eq!(truncate("hello", 10), "hello");
@@ -698,6 +1123,15 @@ mod tests {
         assert_eq!(format_relative_time(future), "now");
     }
 
+    #[test]
+    fn test_endpoint_host() {
+        assert_eq!(endpoint_host("1.2.3.4:51820"), "1.2.3.4");
+        assert_eq!(endpoint_host("[2001:db8::1]:51820"), "2001:db8::1");
+        assert_eq!(endpoint_host("2001:db8::1"), "2001:db8::1");
+        assert_eq!(endpoint_host("vpn.example.com:1194"), "vpn.example.com");
+        assert_eq!(endpoint_host("1.2.3.4"), "1.2.3.4");
+    }
+
     #[test]
     fn test_is_private_ip_class_a() {
         assert!(is_private_ip("10.0.0.1"));
@@ -846,6 +1280,35 @@ mod tests {
         assert!(!openvpn_config_needs_auth(&path));
     }
 
+    #[test]
+    fn test_openvpn_static_challenge() {
+        use crate::core::openvpn_mgmt::ChallengeKind;
+
+        let dir = std::env::temp_dir().join("vortix_test_static_challenge");
+        let _ = std::fs::create_dir_all(&dir);
+        let path = dir.join("test.ovpn");
+
+        std::fs::write(
+            &path,
+            "client\n# static-challenge \"Old\" 1\nstatic-challenge \"Enter \\\"OTP\\\" code\" 1\n",
+        )
+        .unwrap();
+        let challenge = openvpn_static_challenge(&path).unwrap();
+        assert_eq!(challenge.text, "Enter \"OTP\" code");
+        assert!(challenge.echo);
+        assert_eq!(challenge.kind, ChallengeKind::Static);
+
+        std::fs::write(&path, "static-challenge PIN 0 concat\n").unwrap();
+        let challenge = openvpn_static_challenge(&path).unwrap();
+        assert_eq!(challenge.text, "PIN");
+        assert!(!challenge.echo);
+        assert_eq!(challenge.kind, ChallengeKind::Concat);
+
+        std::fs::write(&path, "auth-user-pass\nstatic-challenge \"No flag\"\n").unwrap();
+        assert!(openvpn_static_challenge(&path).is_none());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     // === OpenVPN auth file write/read tests ===
 
     #[test]
@@ -854,7 +1317,7 @@ mod tests {
         // Write
         let result = write_openvpn_auth_file(name, "myuser", "mypass");
         assert!(result.is_ok());
-        let path = result.unwrap();
+        let path = get_openvpn_auth_path(name).unwrap();
         assert!(path.exists());
 
         // Read
@@ -877,7 +1340,7 @@ mod tests {
         let name = "test_auth_perms";
         let result = write_openvpn_auth_file(name, "user", "pass");
         assert!(result.is_ok());
-        let path = result.unwrap();
+        let path = get_openvpn_auth_path(name).unwrap();
 
         let perms = std::fs::metadata(&path).unwrap().permissions();
         assert_eq!(perms.mode() & 0o777, 0o600);
@@ -905,4 +1368,19 @@ mod tests {
 
         delete_openvpn_auth_file(name);
     }
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert!(fuzzy_score("Germany Frankfurt", "gfra").is_some());
+        assert!(fuzzy_score("Germany Frankfurt", "xyz").is_none());
+        assert!(fuzzy_score("ab", "abc").is_none());
+        // Substrings and word starts beat scattered letters
+        let tight = fuzzy_score("us-nyc-01", "nyc").unwrap();
+        let loose = fuzzy_score("north-yorkshire-city", "nyc").unwrap();
+        assert!(tight > loose);
+        assert_eq!(
+            fuzzy_score("WireGuard", "WIRE"),
+            fuzzy_score("wireguard", "wire")
+        );
+    }
 }
@@ -1,8 +1,13 @@
 //! VPN profile import functionality
 
+pub mod split;
+#[cfg(target_os = "linux")]
+pub mod wireguard;
+
 use crate::constants;
+use crate::core::vault;
 use crate::logger::{self, LogLevel};
-use crate::state::{Protocol, VpnProfile};
+use crate::state::{ProfileLabels, Protocol, VpnProfile};
 use std::fs;
 use std::os::unix::fs::PermissionsExt;
 use std::path::{Path, PathBuf};
@@ -92,7 +97,10 @@ pub fn import_profile(path: &Path) -> Result<VpnProfile, String> {
         .unwrap_or(&name)
         .to_string();
 
-    fs::copy(path, &dest_path).map_err(|e| {
+    // Stored encrypted when the vault is enabled
+    let stored = vault::seal_for_disk(content.as_bytes(), &dest_path)
+        .map_err(|e| format!("Failed to store profile: {e}"))?;
+    crate::utils::write_user_file(&dest_path, stored).map_err(|e| {
         logger::log(
             LogLevel::Error,
             "IMPORT",
@@ -126,6 +134,7 @@ pub fn import_profile(path: &Path) -> Result<VpnProfile, String> {
         location,
         config_path: dest_path,
         last_used: None,
+        labels: ProfileLabels::default(),
     })
 }
 
@@ -179,7 +188,6 @@ fn parse_wireguard_config(content: &str, path: &Path) -> Result<(String, String)
     let mut has_private_key = false;
     let mut has_address = false;
     let mut has_public_key = false;
-    let mut endpoint = String::new();
     let mut in_peer = false;
 
     for line in content.lines() {
@@ -202,12 +210,6 @@ fn parse_wireguard_config(content: &str, path: &Path) -> Result<(String, String)
                 "privatekey" if !in_peer => has_private_key = true,
                 "address" if !in_peer => has_address = true,
                 "publickey" if in_peer => has_public_key = true,
-                "endpoint" if in_peer && endpoint.is_empty() => {
-                    // Use original (non-lowered) value for the endpoint
-                    if let Some((_, orig_val)) = trimmed.split_once('=') {
-                        endpoint = orig_val.trim().split(':').next().unwrap_or("").to_string();
-                    }
-                }
                 _ => {}
             }
             // Also check non-lowered for PrivateKey detection (some generators use mixed case)
@@ -225,7 +227,7 @@ fn parse_wireguard_config(content: &str, path: &Path) -> Result<(String, String)
     if !has_public_key {
         missing.push("PublicKey (in [Peer])");
     }
-    if endpoint.is_empty() {
+    if wireguard_peer_endpoint(content).is_none() {
         missing.push("Endpoint (in [Peer])");
     }
 
@@ -252,7 +254,6 @@ fn parse_openvpn_config(content: &str, path: &Path) -> Result<(String, String),
         .unwrap_or("unknown")
         .to_string();
 
-    let mut server = String::new();
     let mut has_openvpn_structure = false;
 
     // Known OpenVPN directives (presence of any confirms this is an OpenVPN config)
@@ -284,14 +285,6 @@ fn parse_openvpn_config(content: &str, path: &Path) -> Result<(String, String),
         let trimmed = line.trim();
         let lower_line = trimmed.to_lowercase();
 
-        // Check for remote directive
-        if server.is_empty() && lower_line.starts_with("remote ") {
-            let parts: Vec<&str> = trimmed.split_whitespace().collect();
-            if parts.len() >= 2 {
-                server = parts[1].to_string();
-            }
-        }
-
         // Check for any OpenVPN directive
         if !has_openvpn_structure
             && (lower_line == "client"
@@ -302,7 +295,7 @@ fn parse_openvpn_config(content: &str, path: &Path) -> Result<(String, String),
         }
     }
 
-    if server.is_empty() {
+    if openvpn_remote(content).is_none() {
         return Err("No 'remote' directive found in OpenVPN config".to_string());
     }
 
@@ -317,6 +310,116 @@ fn parse_openvpn_config(content: &str, path: &Path) -> Result<(String, String),
     Ok((name, location))
 }
 
+/// The first non-empty `Endpoint` (`host:port`) in a `WireGuard` config's
+/// `[Peer]` sections.
+fn wireguard_peer_endpoint(content: &str) -> Option<&str> {
+    let mut in_peer = false;
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            in_peer = trimmed.eq_ignore_ascii_case("[peer]");
+            continue;
+        }
+        if let Some((key, value)) = trimmed.split_once('=') {
+            if in_peer && key.trim().eq_ignore_ascii_case("endpoint") && !value.trim().is_empty() {
+                return Some(value.trim());
+            }
+        }
+    }
+    None
+}
+
+/// Arguments of the first `remote` directive in an `OpenVPN` config: the
+/// host, then the optional port and protocol.
+fn openvpn_remote(content: &str) -> Option<Vec<&str>> {
+    content.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        parts
+            .next()
+            .is_some_and(|d| d.eq_ignore_ascii_case("remote"))
+            .then(|| parts.collect::<Vec<_>>())
+            .filter(|args| !args.is_empty())
+    })
+}
+
+/// Where a profile's tunnel connects to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Endpoint {
+    /// Server hostname or IP address.
+    pub host: String,
+    pub port: u16,
+    /// Whether the tunnel runs over TCP (`OpenVPN` `proto tcp`) rather than UDP.
+    pub tcp: bool,
+}
+
+impl std::fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let transport = if self.tcp { "tcp" } else { "udp" };
+        if self.host.contains(':') {
+            write!(f, "[{}]:{}/{transport}", self.host, self.port)
+        } else {
+            write!(f, "{}:{}/{transport}", self.host, self.port)
+        }
+    }
+}
+
+/// Read the server endpoint from a profile's config file.
+///
+/// # Errors
+///
+/// Returns an error if the file can't be read or names no server.
+pub fn profile_endpoint(profile: &VpnProfile) -> Result<Endpoint, String> {
+    let content = vault::read_to_string(&profile.config_path)
+        .map_err(|e| format!("Failed to read {}: {e}", profile.config_path.display()))?;
+    match profile.protocol {
+        Protocol::WireGuard => wireguard_endpoint(&content),
+        Protocol::OpenVPN => openvpn_endpoint(&content),
+    }
+    .ok_or_else(|| format!("No server endpoint in '{}'", profile.name))
+}
+
+fn wireguard_endpoint(content: &str) -> Option<Endpoint> {
+    let raw = wireguard_peer_endpoint(content)?;
+    let host = crate::utils::endpoint_host(raw);
+    let port = raw
+        .rsplit_once(':')
+        .filter(|_| host != raw)
+        .and_then(|(_, port)| port.parse().ok())
+        .unwrap_or(constants::WIREGUARD_DEFAULT_PORT);
+    Some(Endpoint {
+        host: host.to_string(),
+        port,
+        tcp: false,
+    })
+}
+
+fn openvpn_endpoint(content: &str) -> Option<Endpoint> {
+    let remote = openvpn_remote(content)?;
+    // `port` and `proto` directives apply when the remote line leaves them out
+    let directive = |name: &str| {
+        content.lines().find_map(|line| {
+            let mut parts = line.split_whitespace();
+            parts
+                .next()
+                .is_some_and(|d| d.eq_ignore_ascii_case(name))
+                .then(|| parts.next())
+                .flatten()
+        })
+    };
+    let port = remote
+        .get(1)
+        .copied()
+        .or_else(|| directive("port"))
+        .and_then(|p| p.parse().ok())
+        .unwrap_or(constants::OPENVPN_DEFAULT_PORT);
+    let proto = remote.get(2).copied().or_else(|| directive("proto"));
+    Some(Endpoint {
+        host: remote[0].to_string(),
+        port,
+        tcp: proto.is_some_and(|p| p.to_lowercase().starts_with("tcp")),
+    })
+}
+
 /// Derive location from profile name
 ///
 /// IMPORTANT: This is a best-effort heuristic based on common naming patterns.
@@ -432,6 +535,28 @@ fn derive_location_from_name(name: &str) -> String {
     "Unknown".to_string()
 }
 
+/// Stand-in for an encrypted profile while the vault is locked, built from
+/// the file name alone (`.conf` is assumed to be `WireGuard`).
+fn locked_profile(path: &Path, ext: &str) -> VpnProfile {
+    let name = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("unknown")
+        .to_string();
+    VpnProfile {
+        location: derive_location_from_name(&name),
+        name,
+        protocol: if ext == "ovpn" {
+            Protocol::OpenVPN
+        } else {
+            Protocol::WireGuard
+        },
+        config_path: path.to_path_buf(),
+        last_used: None,
+        labels: ProfileLabels::default(),
+    }
+}
+
 /// Get the profiles directory, creating it if needed
 pub fn get_profiles_dir() -> Result<PathBuf, String> {
     crate::utils::get_profiles_dir().map_err(|e| format!("Failed to get profiles directory: {e}"))
@@ -459,7 +584,9 @@ pub fn load_profiles() -> Vec<VpnProfile> {
             if path.is_file() {
                 let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
                 if ext == "conf" || ext == "ovpn" {
-                    if let Ok(content) = fs::read_to_string(&path) {
+                    if vault::is_sealed(&path) && !vault::is_unlocked() {
+                        profiles.push(locked_profile(&path, ext));
+                    } else if let Ok(content) = vault::read_to_string(&path) {
                         // Detect protocol: .ovpn is always OpenVPN, .conf uses content detection
                         let protocol = if ext == "ovpn" {
                             Protocol::OpenVPN
@@ -494,6 +621,7 @@ pub fn load_profiles() -> Vec<VpnProfile> {
                                     location,
                                     config_path: path.clone(),
                                     last_used: None,
+                                    labels: ProfileLabels::default(),
                                 });
                             }
                             Err(e) => {
@@ -561,6 +689,16 @@ mod tests {
         assert_eq!(derive_location_from_name("desktop-server"), "Unknown");
     }
 
+    #[test]
+    fn test_locked_profile_from_file_name() {
+        let wg = locked_profile(Path::new("/p/de-berlin.conf"), "conf");
+        assert_eq!(wg.name, "de-berlin");
+        assert_eq!(wg.location, "Germany");
+        assert_eq!(wg.protocol, Protocol::WireGuard);
+        let ovpn = locked_profile(Path::new("/p/office.ovpn"), "ovpn");
+        assert_eq!(ovpn.protocol, Protocol::OpenVPN);
+    }
+
     #[test]
     fn test_derive_location_asia() {
         assert_eq!(derive_location_from_name("jp-01"), "Japan");
@@ -651,6 +789,52 @@ proto udp
         assert!(result.unwrap_err().contains("No 'remote' directive"));
     }
 
+    #[test]
+    fn test_wireguard_endpoint() {
+        let config = "[Interface]\nPrivateKey = x\n[Peer]\nEndpoint = vpn.example.com:4500\n";
+        assert_eq!(
+            wireguard_endpoint(config),
+            Some(Endpoint {
+                host: "vpn.example.com".to_string(),
+                port: 4500,
+                tcp: false,
+            })
+        );
+        let config = "[Peer]\nEndpoint = [2001:db8::1]:51821\n";
+        let endpoint = wireguard_endpoint(config).unwrap();
+        assert_eq!(
+            (endpoint.host.as_str(), endpoint.port),
+            ("2001:db8::1", 51821)
+        );
+        assert_eq!(endpoint.to_string(), "[2001:db8::1]:51821/udp");
+        // Endpoint outside [Peer] doesn't count
+        assert_eq!(
+            wireguard_endpoint("[Interface]\nEndpoint = 1.2.3.4:1\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_openvpn_endpoint_directives() {
+        let config = "client\nproto tcp-client\nport 443\nremote de1.example.com\n";
+        assert_eq!(
+            openvpn_endpoint(config),
+            Some(Endpoint {
+                host: "de1.example.com".to_string(),
+                port: 443,
+                tcp: true,
+            })
+        );
+        // The remote line wins over the defaults
+        let config = "client\nremote 10.0.0.1 1195 udp\nremote 10.0.0.2\n";
+        assert_eq!(
+            openvpn_endpoint(config).unwrap().to_string(),
+            "10.0.0.1:1195/udp"
+        );
+        let config = "client\nremote 10.0.0.1\n";
+        assert_eq!(openvpn_endpoint(config).unwrap().port, 1194);
+    }
+
     #[test]
     fn test_import_profile_nonexistent_file() {
         let path = std::path::Path::new("/nonexistent/path/file.conf");
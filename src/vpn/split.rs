@@ -0,0 +1,633 @@
+//! Per-profile split tunneling.
+//!
+//! The policy lives in the profile metadata file; the stored profile is never
+//! modified. At connect time the policy is resolved into CIDRs and rendered
+//! into a temporary `WireGuard` config with rewritten `AllowedIPs`, or into
+//! extra `OpenVPN` `route` / `route-nopull` options.
+
+use crate::logger::{self, LogLevel};
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Write as _};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+
+/// Private, link-local and unique-local ranges skipped by "bypass LAN".
+const LAN_RANGES: &[&str] = &[
+    "10.0.0.0/8",
+    "172.16.0.0/12",
+    "192.168.0.0/16",
+    "169.254.0.0/16",
+    "fc00::/7",
+    "fe80::/10",
+];
+
+/// Split tunnel policy for a single profile.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SplitTunnel {
+    /// CIDRs or hostnames routed through the tunnel. Empty means "everything
+    /// the profile already routes".
+    pub include: Vec<String>,
+    /// CIDRs or hostnames that always bypass the tunnel.
+    pub exclude: Vec<String>,
+    /// Keep private and link-local ranges off the tunnel.
+    pub bypass_lan: bool,
+}
+
+impl SplitTunnel {
+    /// Build a policy from the comma/whitespace separated editor fields.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first entry that is neither a CIDR, an IP nor a hostname.
+    pub fn from_fields(include: &str, exclude: &str, bypass_lan: bool) -> Result<Self, String> {
+        let split = |field: &str| -> Result<Vec<String>, String> {
+            crate::utils::split_list(field)
+                .map(|entry| {
+                    if Cidr::parse(entry).is_some() || crate::utils::is_hostname(entry) {
+                        Ok(entry.to_string())
+                    } else {
+                        Err(format!("Invalid CIDR or hostname: '{entry}'"))
+                    }
+                })
+                .collect()
+        };
+        Ok(Self {
+            include: split(include)?,
+            exclude: split(exclude)?,
+            bypass_lan,
+        })
+    }
+
+    /// Whether the policy leaves the profile unchanged.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty() && !self.bypass_lan
+    }
+
+    /// One-line summary for logs and the editor.
+    #[must_use]
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.include.is_empty() {
+            parts.push(format!("include {}", self.include.join(", ")));
+        }
+        if !self.exclude.is_empty() {
+            parts.push(format!("exclude {}", self.exclude.join(", ")));
+        }
+        if self.bypass_lan {
+            parts.push("bypass LAN".to_string());
+        }
+        if parts.is_empty() {
+            "full tunnel".to_string()
+        } else {
+            parts.join("; ")
+        }
+    }
+
+    /// Resolve hostnames and expand the LAN toggle into concrete routes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an entry is malformed or a hostname cannot be resolved.
+    pub fn resolve(&self) -> Result<Routes, String> {
+        let mut include = Vec::new();
+        for entry in &self.include {
+            include.extend(resolve_entry(entry)?);
+        }
+        let mut exclude = Vec::new();
+        for entry in &self.exclude {
+            exclude.extend(resolve_entry(entry)?);
+        }
+        if self.bypass_lan {
+            exclude.extend(LAN_RANGES.iter().filter_map(|r| Cidr::parse(r)));
+        }
+        Ok(Routes { include, exclude })
+    }
+}
+
+/// A policy resolved to concrete networks.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Routes {
+    /// Networks routed through the tunnel (empty = keep the profile's own).
+    pub include: Vec<Cidr>,
+    /// Networks that must bypass the tunnel.
+    pub exclude: Vec<Cidr>,
+}
+
+/// An IPv4 or IPv6 network. Host bits are always zero.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cidr {
+    addr: u128,
+    len: u8,
+    v6: bool,
+}
+
+impl Cidr {
+    /// Parse `a.b.c.d/n`, `x::y/n` or a bare address (host route).
+    #[must_use]
+    pub fn parse(s: &str) -> Option<Self> {
+        let (ip, len) = match s.trim().split_once('/') {
+            Some((ip, len)) => (ip, Some(len.parse::<u8>().ok()?)),
+            None => (s.trim(), None),
+        };
+        let ip: IpAddr = ip.parse().ok()?;
+        let max = if ip.is_ipv6() { 128 } else { 32 };
+        let len = len.unwrap_or(max);
+        (len <= max).then(|| Self::new(ip, len))
+    }
+
+    /// Network of `ip` with a `len`-bit prefix (host bits are cleared).
+    #[must_use]
+    pub fn new(ip: IpAddr, len: u8) -> Self {
+        let (addr, v6) = match ip {
+            IpAddr::V4(v4) => (u128::from(u32::from(v4)), false),
+            IpAddr::V6(v6) => (u128::from(v6), true),
+        };
+        let mut cidr = Self { addr, len, v6 };
+        cidr.addr &= cidr.mask();
+        cidr
+    }
+
+    fn max_len(self) -> u8 {
+        if self.v6 {
+            128
+        } else {
+            32
+        }
+    }
+
+    fn mask(self) -> u128 {
+        let full = if self.v6 {
+            u128::MAX
+        } else {
+            u128::from(u32::MAX)
+        };
+        if self.len == 0 {
+            0
+        } else {
+            (full << (self.max_len() - self.len)) & full
+        }
+    }
+
+    /// Whether this is an IPv6 network.
+    #[must_use]
+    pub fn is_ipv6(self) -> bool {
+        self.v6
+    }
+
+    /// Whether `other` lies entirely inside this network.
+    #[must_use]
+    pub fn contains(self, other: Self) -> bool {
+        self.v6 == other.v6 && self.len <= other.len && other.addr & self.mask() == self.addr
+    }
+
+    /// The two halves of this network (`None` for a host route).
+    fn halves(self) -> Option<(Self, Self)> {
+        if self.len >= self.max_len() {
+            return None;
+        }
+        let len = self.len + 1;
+        let bit = 1u128 << (self.max_len() - len);
+        Some((
+            Self { len, ..self },
+            Self {
+                addr: self.addr | bit,
+                len,
+                v6: self.v6,
+            },
+        ))
+    }
+
+    /// Prefix length in bits.
+    #[must_use]
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    pub fn prefix_len(self) -> u8 {
+        self.len
+    }
+
+    /// Network address.
+    #[must_use]
+    pub fn ip(self) -> IpAddr {
+        if self.v6 {
+            IpAddr::V6(Ipv6Addr::from(self.addr))
+        } else {
+            #[allow(clippy::cast_possible_truncation)]
+            IpAddr::V4(Ipv4Addr::from(self.addr as u32))
+        }
+    }
+
+    /// Dotted netmask for `OpenVPN`'s `route` directive (IPv4 only).
+    fn netmask(self) -> String {
+        #[allow(clippy::cast_possible_truncation)]
+        Ipv4Addr::from(self.mask() as u32).to_string()
+    }
+}
+
+impl fmt::Display for Cidr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.ip(), self.len)
+    }
+}
+
+/// Remove `hole` from every network in `set`, splitting networks that only
+/// partially overlap it into the smallest covering set of prefixes.
+#[must_use]
+pub fn subtract(set: &[Cidr], hole: Cidr) -> Vec<Cidr> {
+    let mut out = Vec::new();
+    for &net in set {
+        if hole.contains(net) {
+            continue;
+        }
+        if !net.contains(hole) {
+            out.push(net);
+            continue;
+        }
+        // Walk down towards the hole, keeping the sibling half at each level
+        let mut cur = net;
+        while let Some((lo, hi)) = cur.halves() {
+            let (keep, next) = if lo.contains(hole) {
+                (hi, lo)
+            } else {
+                (lo, hi)
+            };
+            out.push(keep);
+            if next == hole {
+                break;
+            }
+            cur = next;
+        }
+    }
+    out
+}
+
+/// Networks for one `WireGuard` peer: `base` minus every excluded range.
+#[must_use]
+pub fn allowed_ips(base: &[Cidr], exclude: &[Cidr]) -> Vec<Cidr> {
+    exclude
+        .iter()
+        .fold(base.to_vec(), |set, &hole| subtract(&set, hole))
+}
+
+/// Rewrite the `AllowedIPs` of each `[Peer]` section according to `routes`.
+///
+/// The include list replaces the first peer's `AllowedIPs`; other peers keep
+/// their own. Excludes are removed from every peer.
+#[must_use]
+pub fn render_wireguard(content: &str, routes: &Routes) -> String {
+    // First pass: collect the AllowedIPs of every peer
+    let mut peers: Vec<Vec<Cidr>> = Vec::new();
+    let mut in_peer = false;
+    for line in content.lines() {
+        let lower = line.trim().to_lowercase();
+        if lower.starts_with('[') {
+            in_peer = lower == "[peer]";
+            if in_peer {
+                peers.push(Vec::new());
+            }
+            continue;
+        }
+        if let (true, Some(value)) = (in_peer, wg_value(line, "allowedips")) {
+            if let Some(peer) = peers.last_mut() {
+                peer.extend(value.split(',').filter_map(Cidr::parse));
+            }
+        }
+    }
+
+    // Second pass: drop the old lines and emit the new set after each [Peer]
+    let mut out = String::with_capacity(content.len());
+    let mut peer_idx = 0;
+    in_peer = false;
+    for line in content.lines() {
+        let lower = line.trim().to_lowercase();
+        if lower.starts_with('[') {
+            in_peer = lower == "[peer]";
+            out.push_str(line);
+            out.push('\n');
+            if in_peer {
+                let base = if peer_idx == 0 && !routes.include.is_empty() {
+                    &routes.include
+                } else {
+                    &peers[peer_idx]
+                };
+                let ips = allowed_ips(base, &routes.exclude);
+                if !ips.is_empty() {
+                    let list: Vec<String> = ips.iter().map(ToString::to_string).collect();
+                    let _ = writeln!(out, "AllowedIPs = {}", list.join(", "));
+                }
+                peer_idx += 1;
+            }
+            continue;
+        }
+        if in_peer && wg_value(line, "allowedips").is_some() {
+            continue;
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    out
+}
+
+/// Extra `OpenVPN` arguments implementing `routes`.
+///
+/// Includes switch to `route-nopull` and route only the listed networks via
+/// the tunnel; excludes are routed via the pre-VPN gateway.
+#[must_use]
+pub fn openvpn_args(routes: &Routes) -> Vec<String> {
+    let mut args = Vec::new();
+    if !routes.include.is_empty() {
+        args.push("--route-nopull".to_string());
+    }
+    let mut push_route = |net: &Cidr, via_tunnel: bool| {
+        if net.is_ipv6() {
+            args.push("--route-ipv6".to_string());
+            args.push(net.to_string());
+            if !via_tunnel {
+                args.push("net_gateway_ipv6".to_string());
+            }
+        } else {
+            args.push("--route".to_string());
+            args.push(net.ip().to_string());
+            args.push(net.netmask());
+            args.push(
+                if via_tunnel {
+                    "vpn_gateway"
+                } else {
+                    "net_gateway"
+                }
+                .to_string(),
+            );
+        }
+    };
+    for net in &routes.include {
+        push_route(net, true);
+    }
+    for net in &routes.exclude {
+        push_route(net, false);
+    }
+    args
+}
+
+/// Load the split tunnel policy stored for a profile, if any.
+#[must_use]
+pub fn for_profile(config_path: &Path) -> Option<SplitTunnel> {
+    let key = config_path.to_string_lossy().to_string();
+    crate::utils::load_profile_metadata()
+        .ok()?
+        .remove(&key)?
+        .split_tunnel
+        .filter(|p| !p.is_empty())
+}
+
+/// Write a copy of a `WireGuard` profile with the policy applied.
+///
+/// The copy keeps the profile's file name so `wg-quick` derives the same
+/// interface name; the caller removes it once the tunnel is up.
+///
+/// # Errors
+///
+/// Returns an error if the profile is invalid, a hostname cannot be resolved,
+/// or the copy cannot be written.
+pub fn prepare_wireguard(config_path: &Path, policy: &SplitTunnel) -> Result<PathBuf, String> {
+    let content = crate::core::vault::read_to_string(config_path)
+        .map_err(|e| format!("Failed to read profile: {e}"))?;
+    super::parse_wireguard_config(&content, config_path)?;
+
+    let mut routes = policy.resolve()?;
+    // Never route the handshake itself into the tunnel
+    routes.exclude.extend(
+        wireguard_endpoints(&content)
+            .iter()
+            .flat_map(|h| resolve_host(h)),
+    );
+
+    let file_name = config_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or("Invalid profile path")?;
+    let rendered = render_wireguard(&content, &routes);
+    // The rendered copy of an encrypted profile goes to the vault's tmpfs dir
+    let path = if crate::core::vault::is_sealed(config_path) {
+        let path =
+            crate::core::vault::runtime_copy_path(config_path).ok_or("Invalid profile path")?;
+        crate::core::vault::write_runtime_file(&path, rendered.as_bytes())?;
+        path
+    } else {
+        let path = crate::utils::get_split_config_path(file_name)
+            .map_err(|e| format!("Failed to create run directory: {e}"))?;
+        crate::utils::write_private(&path, rendered.as_bytes())
+            .map_err(|e| format!("Failed to write split tunnel config: {e}"))?;
+        path
+    };
+
+    logger::log(
+        LogLevel::Info,
+        "SPLIT",
+        format!("Applying split tunnel: {}", policy.summary()),
+    );
+    Ok(path)
+}
+
+/// Resolve a policy into `OpenVPN` arguments.
+///
+/// # Errors
+///
+/// Returns an error if the profile is invalid or a hostname cannot be resolved.
+pub fn prepare_openvpn(config_path: &Path, policy: &SplitTunnel) -> Result<Vec<String>, String> {
+    let content = crate::core::vault::read_to_string(config_path)
+        .map_err(|e| format!("Failed to read profile: {e}"))?;
+    super::parse_openvpn_config(&content, config_path)?;
+
+    let args = openvpn_args(&policy.resolve()?);
+    logger::log(
+        LogLevel::Info,
+        "SPLIT",
+        format!("Applying split tunnel: {}", policy.summary()),
+    );
+    Ok(args)
+}
+
+/// Value of `key = value` if `line` sets `key` (case-insensitive).
+fn wg_value<'a>(line: &'a str, key: &str) -> Option<&'a str> {
+    let (k, v) = line.split_once('=')?;
+    k.trim().eq_ignore_ascii_case(key).then(|| v.trim())
+}
+
+/// Host part of every `Endpoint` in a `WireGuard` config.
+fn wireguard_endpoints(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .filter_map(|l| wg_value(l, "endpoint"))
+        .map(|ep| crate::utils::endpoint_host(ep).to_string())
+        .collect()
+}
+
+fn resolve_entry(entry: &str) -> Result<Vec<Cidr>, String> {
+    if let Some(cidr) = Cidr::parse(entry) {
+        return Ok(vec![cidr]);
+    }
+    if !crate::utils::is_hostname(entry) {
+        return Err(format!("Invalid CIDR or hostname: '{entry}'"));
+    }
+    let hosts = resolve_host(entry);
+    if hosts.is_empty() {
+        return Err(format!("Cannot resolve '{entry}'"));
+    }
+    Ok(hosts)
+}
+
+/// Host routes for every address `host` resolves to.
+fn resolve_host(host: &str) -> Vec<Cidr> {
+    if let Ok(ip) = host.parse::<IpAddr>() {
+        return vec![Cidr::new(ip, if ip.is_ipv6() { 128 } else { 32 })];
+    }
+    let mut out: Vec<Cidr> = (host, 0)
+        .to_socket_addrs()
+        .map(|addrs| {
+            addrs
+                .map(|a| Cidr::new(a.ip(), if a.is_ipv6() { 128 } else { 32 }))
+                .collect()
+        })
+        .unwrap_or_default();
+    out.dedup();
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cidrs(list: &[&str]) -> Vec<Cidr> {
+        list.iter().map(|s| Cidr::parse(s).unwrap()).collect()
+    }
+
+    fn strings(list: &[Cidr]) -> Vec<String> {
+        list.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_cidr_parse_normalizes_host_bits() {
+        assert_eq!(Cidr::parse("10.1.2.3/8").unwrap().to_string(), "10.0.0.0/8");
+        assert_eq!(Cidr::parse("1.1.1.1").unwrap().to_string(), "1.1.1.1/32");
+        assert_eq!(
+            Cidr::parse("2001:db8::1/32").unwrap().to_string(),
+            "2001:db8::/32"
+        );
+        assert!(Cidr::parse("10.0.0.0/33").is_none());
+        assert!(Cidr::parse("example.com").is_none());
+    }
+
+    #[test]
+    fn test_subtract_splits_default_route() {
+        let out = subtract(&cidrs(&["0.0.0.0/0"]), Cidr::parse("128.0.0.0/2").unwrap());
+        assert_eq!(strings(&out), vec!["0.0.0.0/1", "192.0.0.0/2"]);
+    }
+
+    #[test]
+    fn test_subtract_disjoint_and_covering() {
+        let set = cidrs(&["10.0.0.0/8", "::/0"]);
+        assert_eq!(
+            strings(&subtract(&set, Cidr::parse("192.168.0.0/16").unwrap())),
+            vec!["10.0.0.0/8", "::/0"]
+        );
+        assert_eq!(
+            strings(&subtract(&set, Cidr::parse("10.0.0.0/7").unwrap())),
+            vec!["::/0"]
+        );
+    }
+
+    #[test]
+    fn test_allowed_ips_complement_covers_everything_else() {
+        let ips = allowed_ips(&cidrs(&["0.0.0.0/0"]), &cidrs(&["10.0.0.0/8", "1.2.3.4"]));
+        // Address space left = 2^32 - 2^24 - 1
+        let total: u64 = ips.iter().map(|c| 1u64 << (32 - c.len)).sum();
+        assert_eq!(total, (1u64 << 32) - (1 << 24) - 1);
+        for hole in ["10.0.0.1", "1.2.3.4"] {
+            let hole = Cidr::parse(hole).unwrap();
+            assert!(!ips.iter().any(|c| c.contains(hole)));
+        }
+    }
+
+    #[test]
+    fn test_from_fields_validates_entries() {
+        let policy =
+            SplitTunnel::from_fields("10.0.0.0/8, intranet.example.com", "1.1.1.1", true).unwrap();
+        assert_eq!(policy.include, vec!["10.0.0.0/8", "intranet.example.com"]);
+        assert_eq!(policy.exclude, vec!["1.1.1.1"]);
+        assert!(SplitTunnel::from_fields("10.0.0.0/99", "", false).is_err());
+        assert!(SplitTunnel::from_fields("", "", false).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_render_wireguard_rewrites_allowed_ips_only() {
+        let content = "[Interface]\nPrivateKey = abc\nAddress = 10.2.0.2/32\n\n\
+                       [Peer]\nPublicKey = xyz\nAllowedIPs = 0.0.0.0/0\nEndpoint = 1.2.3.4:51820\n";
+        let routes = Routes {
+            include: Vec::new(),
+            exclude: cidrs(&["0.0.0.0/1"]),
+        };
+        let out = render_wireguard(content, &routes);
+        assert!(out.contains("PrivateKey = abc\n"));
+        assert!(out.contains("Endpoint = 1.2.3.4:51820\n"));
+        assert!(out.contains("[Peer]\nAllowedIPs = 128.0.0.0/1\nPublicKey = xyz\n"));
+        assert_eq!(out.matches("AllowedIPs").count(), 1);
+    }
+
+    #[test]
+    fn test_render_wireguard_include_replaces_first_peer() {
+        let content = "[Peer]\nAllowedIPs = 0.0.0.0/0, ::/0\n[Peer]\nAllowedIPs = 10.9.0.0/16\n";
+        let routes = Routes {
+            include: cidrs(&["192.168.50.0/24"]),
+            exclude: cidrs(&["10.9.1.0/24"]),
+        };
+        let out = render_wireguard(content, &routes);
+        let lines: Vec<&str> = out
+            .lines()
+            .filter(|l| l.starts_with("AllowedIPs"))
+            .collect();
+        assert_eq!(lines[0], "AllowedIPs = 192.168.50.0/24");
+        assert!(!lines[1].contains("10.9.0.0/16"));
+        assert!(lines[1].contains("10.9.0.0/24"));
+    }
+
+    #[test]
+    fn test_openvpn_args() {
+        let routes = Routes {
+            include: cidrs(&["10.0.0.0/8"]),
+            exclude: cidrs(&["10.1.0.0/16", "fc00::/7"]),
+        };
+        assert_eq!(
+            openvpn_args(&routes),
+            vec![
+                "--route-nopull",
+                "--route",
+                "10.0.0.0",
+                "255.0.0.0",
+                "vpn_gateway",
+                "--route",
+                "10.1.0.0",
+                "255.255.0.0",
+                "net_gateway",
+                "--route-ipv6",
+                "fc00::/7",
+                "net_gateway_ipv6",
+            ]
+        );
+        // Exclude-only keeps the server-pushed routes
+        let routes = Routes {
+            include: Vec::new(),
+            exclude: cidrs(&["1.1.1.1"]),
+        };
+        assert_eq!(openvpn_args(&routes)[0], "--route");
+    }
+
+    #[test]
+    fn test_wireguard_endpoints() {
+        let content =
+            "[Peer]\nEndpoint = vpn.example.com:51820\n[Peer]\nEndpoint = [2001:db8::1]:51820\n";
+        assert_eq!(
+            wireguard_endpoints(content),
+            vec!["vpn.example.com", "2001:db8::1"]
+        );
+    }
+}
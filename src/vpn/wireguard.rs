@@ -0,0 +1,463 @@
+//! `WireGuard` profile model for the native (netlink) backend.
+//!
+//! Parses a `wg-quick` style config into structured data and describes the
+//! device state read back from the kernel. Directives the native backend
+//! does not implement (hooks, custom routing tables, `SaveConfig`) are
+//! collected in [`WgConfig::unsupported`] so the caller can fall back to
+//! `wg-quick` instead of silently ignoring them.
+
+use super::split::Cidr;
+use std::net::IpAddr;
+use std::time::{Duration, SystemTime};
+
+/// A 32-byte Curve25519 key (private, public or preshared).
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Key(pub [u8; 32]);
+
+impl Key {
+    /// Decode the base64 form used in configs and `wg show`.
+    #[must_use]
+    pub fn from_base64(s: &str) -> Option<Self> {
+        let bytes = base64_decode(s.trim())?;
+        Some(Self(bytes.try_into().ok()?))
+    }
+
+    /// Base64 form, as printed by `wg show`.
+    #[must_use]
+    pub fn to_base64(self) -> String {
+        base64_encode(&self.0)
+    }
+}
+
+// Never print key material by accident
+impl std::fmt::Debug for Key {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Key(..)")
+    }
+}
+
+/// An interface address with its prefix (`10.64.0.2/24`). Unlike a
+/// [`Cidr`], the host bits are kept.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InterfaceAddress {
+    pub ip: IpAddr,
+    pub prefix_len: u8,
+}
+
+impl InterfaceAddress {
+    /// Parse `ip/len` or a bare address (`/32` or `/128`).
+    #[must_use]
+    pub fn parse(s: &str) -> Option<Self> {
+        let (ip, len) = match s.trim().split_once('/') {
+            Some((ip, len)) => (ip, Some(len.parse::<u8>().ok()?)),
+            None => (s.trim(), None),
+        };
+        let ip: IpAddr = ip.parse().ok()?;
+        let max = if ip.is_ipv6() { 128 } else { 32 };
+        let prefix_len = len.unwrap_or(max);
+        (prefix_len <= max).then_some(Self { ip, prefix_len })
+    }
+}
+
+/// Routing mode of the `Table` directive.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RouteTable {
+    /// Routes for every allowed IP; default routes via a fwmark policy table.
+    #[default]
+    Auto,
+    /// No routes at all (`Table = off`).
+    Off,
+}
+
+/// One `[Peer]` section.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WgPeer {
+    pub public_key: Key,
+    pub preshared_key: Option<Key>,
+    /// `host:port`, resolved when the device is configured.
+    pub endpoint: Option<String>,
+    pub allowed_ips: Vec<Cidr>,
+    pub persistent_keepalive: Option<u16>,
+}
+
+/// A parsed `wg-quick` config.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WgConfig {
+    pub private_key: Key,
+    pub addresses: Vec<InterfaceAddress>,
+    pub listen_port: Option<u16>,
+    pub fwmark: Option<u32>,
+    pub mtu: Option<u32>,
+    /// `DNS` entries that are addresses.
+    pub dns_servers: Vec<String>,
+    /// `DNS` entries that are search domains.
+    pub dns_search: Vec<String>,
+    pub table: RouteTable,
+    pub peers: Vec<WgPeer>,
+    /// Directives present in the config that the native backend cannot
+    /// honor, e.g. `PostUp`.
+    pub unsupported: Vec<String>,
+}
+
+impl WgConfig {
+    /// Parse a `wg-quick` config.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for malformed keys, addresses or numbers, or a
+    /// missing `PrivateKey`.
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let mut private_key = None;
+        let mut config = Self {
+            private_key: Key([0; 32]),
+            addresses: Vec::new(),
+            listen_port: None,
+            fwmark: None,
+            mtu: None,
+            dns_servers: Vec::new(),
+            dns_search: Vec::new(),
+            table: RouteTable::Auto,
+            peers: Vec::new(),
+            unsupported: Vec::new(),
+        };
+        let mut in_peer = false;
+
+        for line in content.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            match line.to_ascii_lowercase().as_str() {
+                "[interface]" => {
+                    in_peer = false;
+                    continue;
+                }
+                "[peer]" => {
+                    in_peer = true;
+                    config.peers.push(WgPeer {
+                        public_key: Key([0; 32]),
+                        preshared_key: None,
+                        endpoint: None,
+                        allowed_ips: Vec::new(),
+                        persistent_keepalive: None,
+                    });
+                    continue;
+                }
+                _ => {}
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(format!("Invalid line in WireGuard config: '{line}'"));
+            };
+            let key = key.trim().to_ascii_lowercase();
+            let value = value.trim();
+
+            if let (true, Some(peer)) = (in_peer, config.peers.last_mut()) {
+                match key.as_str() {
+                    "publickey" => peer.public_key = parse_key(value, "PublicKey")?,
+                    "presharedkey" => peer.preshared_key = Some(parse_key(value, "PresharedKey")?),
+                    "endpoint" => peer.endpoint = Some(value.to_string()),
+                    "allowedips" => peer.allowed_ips.extend(parse_cidrs(value)?),
+                    "persistentkeepalive" if value.eq_ignore_ascii_case("off") => {}
+                    "persistentkeepalive" => {
+                        peer.persistent_keepalive = Some(parse_num(value, "PersistentKeepalive")?);
+                    }
+                    _ => config.unsupported.push(key),
+                }
+                continue;
+            }
+
+            match key.as_str() {
+                "privatekey" => private_key = Some(parse_key(value, "PrivateKey")?),
+                "address" => {
+                    for entry in crate::utils::split_list(value) {
+                        let address = InterfaceAddress::parse(entry)
+                            .ok_or_else(|| format!("Invalid address '{entry}'"))?;
+                        config.addresses.push(address);
+                    }
+                }
+                "listenport" => config.listen_port = Some(parse_num(value, "ListenPort")?),
+                "fwmark" if value.eq_ignore_ascii_case("off") => {}
+                "fwmark" => config.fwmark = Some(parse_fwmark(value)?),
+                "mtu" => config.mtu = Some(parse_num(value, "MTU")?),
+                "dns" => {
+                    for entry in crate::utils::split_list(value) {
+                        if entry.parse::<IpAddr>().is_ok() {
+                            config.dns_servers.push(entry.to_string());
+                        } else {
+                            config.dns_search.push(entry.to_string());
+                        }
+                    }
+                }
+                "table" if value.eq_ignore_ascii_case("auto") => config.table = RouteTable::Auto,
+                "table" if value.eq_ignore_ascii_case("off") => config.table = RouteTable::Off,
+                "saveconfig" if value.eq_ignore_ascii_case("false") => {}
+                _ => config.unsupported.push(key),
+            }
+        }
+
+        config.private_key = private_key.ok_or("Missing PrivateKey in WireGuard config")?;
+        Ok(config)
+    }
+}
+
+/// Device state as reported by the kernel.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WgDevice {
+    pub public_key: Option<Key>,
+    pub listen_port: u16,
+    pub fwmark: u32,
+    pub peers: Vec<WgPeerStatus>,
+}
+
+/// One peer of a running device.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WgPeerStatus {
+    pub public_key: Key,
+    pub endpoint: Option<std::net::SocketAddr>,
+    /// `None` until the first handshake completes.
+    pub last_handshake: Option<SystemTime>,
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+    pub allowed_ips: Vec<Cidr>,
+}
+
+impl WgDevice {
+    /// Total bytes received over all peers.
+    #[must_use]
+    pub fn rx_bytes(&self) -> u64 {
+        self.peers.iter().map(|p| p.rx_bytes).sum()
+    }
+
+    /// Total bytes sent over all peers.
+    #[must_use]
+    pub fn tx_bytes(&self) -> u64 {
+        self.peers.iter().map(|p| p.tx_bytes).sum()
+    }
+
+    /// Most recent handshake of any peer.
+    #[must_use]
+    pub fn last_handshake(&self) -> Option<SystemTime> {
+        self.peers.iter().filter_map(|p| p.last_handshake).max()
+    }
+}
+
+/// Handshake age the way `wg show` prints it (`"1 minute, 5 seconds ago"`).
+#[must_use]
+pub fn format_handshake_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    if secs == 0 {
+        return "Now".to_string();
+    }
+    let parts = [
+        (secs / 86_400, "day"),
+        (secs / 3600 % 24, "hour"),
+        (secs / 60 % 60, "minute"),
+        (secs % 60, "second"),
+    ];
+    let text: Vec<String> = parts
+        .iter()
+        .filter(|(n, _)| *n > 0)
+        .map(|(n, unit)| format!("{n} {unit}{}", if *n == 1 { "" } else { "s" }))
+        .collect();
+    format!("{} ago", text.join(", "))
+}
+
+/// Parse a handshake age printed by [`format_handshake_age`] or `wg show`
+/// back into seconds. `None` for anything else (e.g. `OpenVPN` details).
+#[must_use]
+pub fn parse_handshake_age(text: &str) -> Option<u64> {
+    if text == "Now" {
+        return Some(0);
+    }
+    let mut secs = 0;
+    for part in text.strip_suffix(" ago")?.split(", ") {
+        let (n, unit) = part.split_once(' ')?;
+        let n: u64 = n.parse().ok()?;
+        secs += n * match unit.trim_end_matches('s') {
+            "day" => 86_400,
+            "hour" => 3600,
+            "minute" => 60,
+            "second" => 1,
+            _ => return None,
+        };
+    }
+    Some(secs)
+}
+
+fn parse_key(value: &str, field: &str) -> Result<Key, String> {
+    Key::from_base64(value).ok_or_else(|| format!("Invalid {field} in WireGuard config"))
+}
+
+fn parse_cidrs(value: &str) -> Result<Vec<Cidr>, String> {
+    crate::utils::split_list(value)
+        .map(|entry| Cidr::parse(entry).ok_or_else(|| format!("Invalid address '{entry}'")))
+        .collect()
+}
+
+fn parse_num<T: std::str::FromStr>(value: &str, field: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("Invalid {field} '{value}' in WireGuard config"))
+}
+
+fn parse_fwmark(value: &str) -> Result<u32, String> {
+    match value.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => value.parse().ok(),
+    }
+    .ok_or_else(|| format!("Invalid FwMark '{value}' in WireGuard config"))
+}
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn base64_decode(s: &str) -> Option<Vec<u8>> {
+    let s = s.trim_end_matches('=');
+    let mut out = Vec::with_capacity(s.len() * 3 / 4);
+    let mut acc = 0u32;
+    let mut bits = 0;
+    for c in s.bytes() {
+        let value = BASE64.iter().position(|b| *b == c)?;
+        acc = (acc << 6) | u32::try_from(value).ok()?;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push(u8::try_from((acc >> bits) & 0xff).ok()?);
+        }
+    }
+    Some(out)
+}
+
+fn base64_encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(char::from(BASE64[(n >> (18 - 6 * i)) as usize & 63]));
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PRIVATE: &str = "yAnz5TF+lXXJte14tji3zlMNq+hd2rYUIgJBgB3fBmk=";
+    const PUBLIC: &str = "xTIBA5rboUvnH4htodjb6e697QjLERt1NAB4mZqp8Dg=";
+
+    #[test]
+    fn test_key_base64_round_trip() {
+        let key = Key::from_base64(PRIVATE).unwrap();
+        assert_eq!(key.to_base64(), PRIVATE);
+        assert!(Key::from_base64("c2hvcnQ=").is_none());
+        assert!(Key::from_base64("not base64!").is_none());
+        assert_eq!(format!("{key:?}"), "Key(..)");
+    }
+
+    #[test]
+    fn test_parse_full_config() {
+        let content = format!(
+            "[Interface]
+PrivateKey = {PRIVATE}
+Address = 10.64.0.2/32, fc00:bbbb::2/128
+DNS = 10.64.0.1, corp.example
+ListenPort = 51820
+FwMark = 0xca6c
+MTU = 1380
+
+[Peer]
+PublicKey = {PUBLIC}
+PresharedKey = {PRIVATE}
+Endpoint = vpn.example.com:51820
+AllowedIPs = 0.0.0.0/0, ::/0 # full tunnel
+PersistentKeepalive = 25
+"
+        );
+        let config = WgConfig::parse(&content).unwrap();
+        assert_eq!(config.private_key.to_base64(), PRIVATE);
+        assert_eq!(
+            config.addresses[1],
+            InterfaceAddress {
+                ip: "fc00:bbbb::2".parse().unwrap(),
+                prefix_len: 128
+            }
+        );
+        assert_eq!(config.dns_servers, vec!["10.64.0.1"]);
+        assert_eq!(config.dns_search, vec!["corp.example"]);
+        assert_eq!(config.listen_port, Some(51820));
+        assert_eq!(config.fwmark, Some(0xca6c));
+        assert_eq!(config.mtu, Some(1380));
+        assert_eq!(config.table, RouteTable::Auto);
+        assert!(config.unsupported.is_empty());
+
+        let peer = &config.peers[0];
+        assert_eq!(peer.public_key.to_base64(), PUBLIC);
+        assert!(peer.preshared_key.is_some());
+        assert_eq!(peer.endpoint.as_deref(), Some("vpn.example.com:51820"));
+        assert_eq!(peer.allowed_ips[1].to_string(), "::/0");
+        assert_eq!(peer.persistent_keepalive, Some(25));
+    }
+
+    #[test]
+    fn test_parse_reports_unsupported_directives() {
+        let content = format!(
+            "[Interface]\nPrivateKey = {PRIVATE}\nPostUp = iptables -A FORWARD\nTable = 1234\n"
+        );
+        let config = WgConfig::parse(&content).unwrap();
+        assert_eq!(config.unsupported, vec!["postup", "table"]);
+
+        let off = format!("[Interface]\nPrivateKey = {PRIVATE}\nTable = off\nSaveConfig = false\n");
+        let config = WgConfig::parse(&off).unwrap();
+        assert_eq!(config.table, RouteTable::Off);
+        assert!(config.unsupported.is_empty());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(WgConfig::parse("[Interface]\nAddress = 10.0.0.2/32\n").is_err());
+        assert!(
+            WgConfig::parse(&format!("[Interface]\nPrivateKey = {PRIVATE}\nMTU = big\n")).is_err()
+        );
+        assert!(WgConfig::parse("[Interface]\nPrivateKey = short\n").is_err());
+        assert_eq!(
+            InterfaceAddress::parse("10.0.0.2/24")
+                .unwrap()
+                .ip
+                .to_string(),
+            "10.0.0.2"
+        );
+        assert!(InterfaceAddress::parse("10.0.0.2/33").is_none());
+    }
+
+    #[test]
+    fn test_format_handshake_age_like_wg_show() {
+        assert_eq!(format_handshake_age(Duration::ZERO), "Now");
+        assert_eq!(
+            format_handshake_age(Duration::from_secs(65)),
+            "1 minute, 5 seconds ago"
+        );
+        assert_eq!(
+            format_handshake_age(Duration::from_secs(2 * 86_400 + 3600)),
+            "2 days, 1 hour ago"
+        );
+    }
+
+    #[test]
+    fn test_parse_handshake_age_roundtrip() {
+        for secs in [0, 1, 65, 3600, 2 * 86_400 + 3661] {
+            let text = format_handshake_age(Duration::from_secs(secs));
+            assert_eq!(parse_handshake_age(&text), Some(secs), "{text}");
+        }
+        assert_eq!(parse_handshake_age("Cipher: AES-256-GCM"), None);
+        assert_eq!(parse_handshake_age(""), None);
+    }
+}